@@ -1,5 +1,5 @@
 use nazmc_data_pool::PoolIdx;
-use nazmc_diagnostics::span::{Span, SpanCursor};
+use nazmc_diagnostics::span::Span;
 use thin_vec::ThinVec;
 
 #[derive(Clone)]
@@ -22,6 +22,10 @@ pub enum ItemKind {
     UnitStruct,
     TupleStruct(TupleStruct),
     FieldsStruct(FieldsStruct),
+    Enum(Enum),
+    Const(Const),
+    /// The static has the same declaration of the constant, but it has a single location in the memory
+    Static(Const),
     Fn(Fn),
 }
 
@@ -70,7 +74,7 @@ pub enum Type {
     Lambda(ThinVec<Type>, Box<Type>),
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum VisModifier {
     Default,
     Public,
@@ -87,6 +91,30 @@ pub struct FieldsStruct {
     pub fields: ThinVec<(VisModifier, ASTId, Type)>,
 }
 
+#[derive(Clone)]
+pub struct Enum {
+    pub variants: ThinVec<Variant>,
+}
+
+#[derive(Clone)]
+pub struct Variant {
+    pub name: ASTId,
+    pub kind: VariantKind,
+}
+
+#[derive(Clone)]
+pub enum VariantKind {
+    Unit,
+    Tuple(ThinVec<Type>),
+    Fields(ThinVec<(ASTId, Type)>),
+}
+
+#[derive(Clone)]
+pub struct Const {
+    pub typ: Type,
+    pub expr: Expr,
+}
+
 #[derive(Clone)]
 pub struct Fn {
     pub params: ThinVec<(ASTId, Type)>,
@@ -105,7 +133,10 @@ pub enum Stm {
     Let(Box<LetStm>),
     LetMut(Box<LetStm>),
     While(Box<(Expr, Scope)>),
+    /// The body is executed once before checking the condition
+    DoWhile(Box<(Scope, Expr)>),
     If(Box<IfExpr>),
+    When(Box<WhenExpr>),
     Expr(Box<Expr>),
 }
 
@@ -136,6 +167,7 @@ pub enum ExprKind {
     ArrayElemnts(ThinVec<Expr>),
     ArrayElemntsSized(Box<ArrayElementsSizedExpr>),
     If(Box<IfExpr>),
+    When(Box<WhenExpr>),
     Lambda(Box<LambdaExpr>),
     UnaryOp(Box<UnaryOpExpr>),
     BinaryOp(Box<BinaryOpExpr>),
@@ -153,7 +185,7 @@ pub enum LiteralExpr {
     Num(NumKind),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum NumKind {
     F4(f32),
     F8(f64),
@@ -216,6 +248,59 @@ pub struct IfExpr {
     pub else_: Option<Box<Scope>>,
 }
 
+#[derive(Clone)]
+pub struct WhenExpr {
+    pub expr: Expr,
+    pub arms: ThinVec<WhenArm>,
+}
+
+#[derive(Clone)]
+pub struct WhenArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Scope,
+}
+
+#[derive(Clone)]
+pub struct Pattern {
+    pub span: Span,
+    pub kind: PatternKind,
+}
+
+#[derive(Clone)]
+pub enum PatternKind {
+    /// `_`
+    Wildcard,
+    /// Binds the matched value to a new local
+    Binding(ASTId),
+    /// A literal or a negated number literal
+    Literal(Box<Expr>),
+    Range(Box<RangePattern>),
+    Tuple(ThinVec<Pattern>),
+    UnitStruct(Box<PkgPathWithItem>),
+    TupleStruct(Box<TupleStructPattern>),
+    FieldsStruct(Box<FieldsStructPattern>),
+}
+
+#[derive(Clone)]
+pub struct RangePattern {
+    pub start: Expr,
+    pub op: BinOp,
+    pub end: Expr,
+}
+
+#[derive(Clone)]
+pub struct TupleStructPattern {
+    pub path: PkgPathWithItem,
+    pub patterns: ThinVec<Pattern>,
+}
+
+#[derive(Clone)]
+pub struct FieldsStructPattern {
+    pub path: PkgPathWithItem,
+    pub fields: ThinVec<(ASTId, Pattern)>,
+}
+
 #[derive(Clone)]
 pub struct LambdaExpr {
     pub params: ThinVec<Binding>,
@@ -229,7 +314,7 @@ pub struct UnaryOpExpr {
     pub expr: Expr,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum UnaryOp {
     Minus,
     LNot,
@@ -242,12 +327,13 @@ pub enum UnaryOp {
 #[derive(Clone)]
 pub struct BinaryOpExpr {
     pub op: BinOp,
-    pub op_span_cursor: SpanCursor,
+    /// The byte offset of the operator
+    pub op_span_lo: u32,
     pub left: Expr,
     pub right: Expr,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum BinOp {
     LOr,
     LAnd,
@@ -0,0 +1,654 @@
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Access {
+    /// Reading a value which is copied, or reading through a pointer
+    Read,
+    Move,
+    Write,
+    /// Reading and writing by a compound assignment (e.g. `+=`)
+    Update,
+    Borrow(LoanKind),
+}
+
+/// Whether writing to a place or borrowing it by `#متغير` is allowed
+#[derive(Clone, Copy)]
+enum Mutability {
+    Local(usize),
+    /// The place is reached by dereferencing a pointer, the bool is whether it is mutable
+    ThroughPtr(bool),
+    /// The place is a temporary value
+    Temp,
+    /// The place is in a static, which is never changed
+    Static(usize),
+}
+
+struct PlaceInfo {
+    /// The path of the place, or `None` if it is not rooted at a local (e.g. `*ر` or `ف().س`)
+    place: Option<Place>,
+    mutability: Mutability,
+}
+
+impl<'a> BorrowChecker<'a> {
+    /// Checks the expression as a value and returns the loans which the value holds
+    pub(crate) fn check_expr(&mut self, expr: &Expr, state: &mut State) -> Vec<usize> {
+        self.update_pos(expr);
+
+        if self.is_place(expr) {
+            let ty = self.types.expr_ty(expr);
+            let access = if self.is_copy(ty) {
+                Access::Read
+            } else {
+                Access::Move
+            };
+            let has_refs = self.has_refs(ty);
+
+            let place = self.access(expr, access, state);
+
+            return match place {
+                Some(place) if has_refs => self.held_loans(place.local, state),
+                _ => vec![],
+            };
+        }
+
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PARENS => self.check_expr(&exprs.parens[index].expr, state),
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                let loans = match call.path.resolved {
+                    ResolvedPath::Builtin(_) => {
+                        for arg in &call.args {
+                            self.check_operand(arg, state);
+                        }
+                        return vec![];
+                    }
+                    ResolvedPath::Local(local_idx) => {
+                        let loans = self.check_args(&call.args, state);
+                        let span = call.path.item.span;
+                        self.check_access(&Place::local(local_idx), Access::Read, span, state);
+                        loans
+                    }
+                    _ => self.check_args(&call.args, state),
+                };
+                self.returned_loans(expr, loans)
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                self.check_expr(&call.on, state);
+                let loans = self.check_args(&call.args, state);
+                self.returned_loans(expr, loans)
+            }
+            ExprKindAndIndex::TUPLE_STRUCT => {
+                self.check_exprs(&exprs.tuple_structs[index].args, state)
+            }
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                let mut loans = vec![];
+                for field in &exprs.fields_structs[index].fields {
+                    loans.extend(self.check_expr(&field.expr, state));
+                }
+                loans
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                self.check_exprs(&exprs.array_elements[index].elements, state)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                self.check_expr(&exprs.array_elements_sized[index].repeat, state)
+            }
+            ExprKindAndIndex::TUPLE_EXPR => self.check_exprs(&exprs.tuples[index].elements, state),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                self.check_expr(&exprs.returns[index].expr_to_return, state);
+                state.diverged = true;
+                vec![]
+            }
+            ExprKindAndIndex::RETURN => {
+                state.diverged = true;
+                vec![]
+            }
+            ExprKindAndIndex::BREAK_WITH_VALUE | ExprKindAndIndex::BREAK => {
+                if expr.kind_and_index.kind() == ExprKindAndIndex::BREAK_WITH_VALUE {
+                    self.check_expr(&exprs.breaks[index].expr_to_break, state);
+                }
+                if let Some(ctx) = self.loops.last_mut() {
+                    ctx.breaks.push(state.clone());
+                }
+                state.diverged = true;
+                vec![]
+            }
+            ExprKindAndIndex::CONTINUE => {
+                if let Some(ctx) = self.loops.last_mut() {
+                    ctx.continues.push(state.clone());
+                }
+                state.diverged = true;
+                vec![]
+            }
+            ExprKindAndIndex::IF_EXPR => self.check_if(&exprs.ifs[index], state),
+            ExprKindAndIndex::WHEN_EXPR => self.check_when(&exprs.whens[index], state),
+            ExprKindAndIndex::LAMBDA_EXPR => {
+                let lambda = &exprs.lambdas[index];
+                let loops = std::mem::take(&mut self.loops);
+                let diverged = state.diverged;
+
+                for param in &lambda.params {
+                    self.bind(&param.kind, &[], false, state);
+                }
+                self.check_scope(lambda.body, state);
+
+                state.diverged = diverged;
+                self.loops = loops;
+                vec![]
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                match unary.op {
+                    UnaryOp::Borrow => {
+                        self.check_borrow(expr, &unary.expr, LoanKind::Shared, state)
+                    }
+                    UnaryOp::BorrowMut => {
+                        self.check_borrow(expr, &unary.expr, LoanKind::Mut, state)
+                    }
+                    _ => {
+                        self.check_expr(&unary.expr, state);
+                        vec![]
+                    }
+                }
+            }
+            ExprKindAndIndex::BIN_EXPR => self.check_bin_expr(&exprs.bin_exprs[index], state),
+            _ => vec![],
+        }
+    }
+
+    fn check_exprs(&mut self, exprs: &[Expr], state: &mut State) -> Vec<usize> {
+        let mut loans = vec![];
+        for expr in exprs {
+            loans.extend(self.check_expr(expr, state));
+        }
+        loans
+    }
+
+    /// Checks the call args, a `#متغير` reference passed to a call is borrowed again instead of being moved
+    fn check_args(&mut self, args: &[Expr], state: &mut State) -> Vec<usize> {
+        let mut loans = vec![];
+
+        for arg in args {
+            if self.is_place(arg) && matches!(self.types.expr_ty(arg), Ty::RefMut(_)) {
+                self.update_pos(arg);
+                if let Some(place) = self.access(arg, Access::Read, state) {
+                    loans.extend(self.held_loans(place.local, state));
+                }
+            } else {
+                loans.extend(self.check_expr(arg, state));
+            }
+        }
+
+        loans
+    }
+
+    /// Checks the matched value and the arms from the same state and joins their states,
+    /// and returns the loans of all the arms values
+    ///
+    /// The matched place is moved if any pattern binds a part of it which is not copied,
+    /// otherwise it is only read
+    pub(crate) fn check_when(&mut self, when_expr: &WhenExpr, state: &mut State) -> Vec<usize> {
+        let expr = &when_expr.expr;
+
+        let value_loans = if self.is_place(expr) {
+            self.update_pos(expr);
+            let ty = self.types.expr_ty(expr);
+            let has_refs = self.has_refs(ty);
+            let access = if when_expr
+                .arms
+                .iter()
+                .any(|arm| self.binds_non_copy(&arm.pattern))
+            {
+                Access::Move
+            } else {
+                Access::Read
+            };
+
+            match self.access(expr, access, state) {
+                Some(place) if has_refs => self.held_loans(place.local, state),
+                _ => vec![],
+            }
+        } else {
+            self.check_expr(expr, state)
+        };
+
+        let mut loans = vec![];
+        let mut arms = vec![];
+
+        for arm in &when_expr.arms {
+            let mut arm_state = state.clone();
+            self.bind_pattern(&arm.pattern, &value_loans, &mut arm_state);
+            if let Some(guard) = &arm.guard {
+                self.check_expr(guard, &mut arm_state);
+            }
+            loans.extend(self.check_scope(arm.body, &mut arm_state));
+            arms.push(arm_state);
+        }
+
+        if let Some(joined) = arms.into_iter().reduce(State::join) {
+            *state = joined;
+        }
+
+        loans
+    }
+
+    fn binds_non_copy(&self, pattern: &Pattern) -> bool {
+        match &pattern.kind {
+            PatternKind::Binding(local_idx) => !self.is_copy(&self.types.locals[*local_idx]),
+            PatternKind::Tuple(patterns) | PatternKind::TupleStruct(_, patterns) => {
+                patterns.iter().any(|pattern| self.binds_non_copy(pattern))
+            }
+            PatternKind::FieldsStruct(_, fields) => fields
+                .iter()
+                .any(|(_, pattern)| self.binds_non_copy(pattern)),
+            _ => false,
+        }
+    }
+
+    /// The returned value of a call may hold the loans of its args if it could hold references
+    fn returned_loans(&self, call: &Expr, loans: Vec<usize>) -> Vec<usize> {
+        if self.has_refs(self.types.expr_ty(call)) {
+            loans
+        } else {
+            vec![]
+        }
+    }
+
+    /// Checks an expression whose value is only read (e.g. the operands of comparisons)
+    fn check_operand(&mut self, expr: &Expr, state: &mut State) {
+        if self.is_place(expr) {
+            self.update_pos(expr);
+            self.access(expr, Access::Read, state);
+        } else {
+            self.check_expr(expr, state);
+        }
+    }
+
+    fn check_borrow(
+        &mut self,
+        expr: &Expr,
+        borrowed: &Expr,
+        kind: LoanKind,
+        state: &mut State,
+    ) -> Vec<usize> {
+        if !self.is_place(borrowed) {
+            return self.check_expr(borrowed, state);
+        }
+
+        let has_refs = self.has_refs(self.types.expr_ty(borrowed));
+
+        let Some(place) = self.access_with_span(borrowed, Access::Borrow(kind), expr.span, state)
+        else {
+            return vec![];
+        };
+
+        // The new reference holds the loans of the borrowed value as it could reach them
+        let mut loans = if has_refs {
+            self.held_loans(place.local, state)
+        } else {
+            vec![]
+        };
+
+        loans.push(self.new_loan(place, kind, expr.span, state));
+
+        loans
+    }
+
+    fn check_bin_expr(&mut self, bin: &BinExpr, state: &mut State) -> Vec<usize> {
+        match bin.op {
+            BinOp::Assign => {
+                let loans = self.check_expr(&bin.right, state);
+                self.update_pos(&bin.left);
+                if let Some(place) = self.access(&bin.left, Access::Write, state) {
+                    for loan_idx in loans {
+                        self.loans[loan_idx].holders.push(place.local);
+                    }
+                }
+            }
+            BinOp::PlusAssign
+            | BinOp::MinusAssign
+            | BinOp::TimesAssign
+            | BinOp::DivAssign
+            | BinOp::ModAssign
+            | BinOp::BAndAssign
+            | BinOp::BOrAssign
+            | BinOp::XorAssign
+            | BinOp::ShlAssign
+            | BinOp::ShrAssign => {
+                self.check_expr(&bin.right, state);
+                self.update_pos(&bin.left);
+                self.access(&bin.left, Access::Update, state);
+            }
+            BinOp::EqualEqual | BinOp::NotEqual | BinOp::GE | BinOp::GT | BinOp::LE | BinOp::LT => {
+                self.check_operand(&bin.left, state);
+                self.check_operand(&bin.right, state);
+            }
+            _ => {
+                self.check_expr(&bin.left, state);
+                self.check_expr(&bin.right, state);
+            }
+        }
+
+        vec![]
+    }
+
+    /// Checks accessing a place expression and returns its path if it's rooted at a local
+    fn access(&mut self, expr: &Expr, access: Access, state: &mut State) -> Option<Place> {
+        self.access_with_span(expr, access, expr.span, state)
+    }
+
+    fn access_with_span(
+        &mut self,
+        expr: &Expr,
+        access: Access,
+        span: Span,
+        state: &mut State,
+    ) -> Option<Place> {
+        let info = self.check_place(expr, state);
+
+        if matches!(
+            access,
+            Access::Write | Access::Update | Access::Borrow(LoanKind::Mut)
+        ) {
+            self.check_mutability(&info, access, span, state);
+        }
+
+        let place = info.place?;
+        self.check_access(&place, access, span, state);
+        Some(place)
+    }
+
+    /// Checks the sub-expressions of the place expression without accessing it
+    fn check_place(&mut self, expr: &Expr, state: &mut State) -> PlaceInfo {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                ResolvedPath::Local(local_idx) => PlaceInfo {
+                    place: Some(Place::local(local_idx)),
+                    mutability: Mutability::Local(local_idx),
+                },
+                ResolvedPath::Static(static_idx) => PlaceInfo {
+                    place: None,
+                    mutability: Mutability::Static(static_idx),
+                },
+                _ => unreachable!(),
+            },
+            ExprKindAndIndex::PARENS => self.check_place(&exprs.parens[index].expr, state),
+            ExprKindAndIndex::FIELD => {
+                let field = &exprs.fields[index];
+                let mut info = self.check_projected(&field.on, state);
+                if let Some(place) = &mut info.place {
+                    place.projections.push(Projection::Field(field.name.id));
+                }
+                info
+            }
+            ExprKindAndIndex::INDEX => {
+                let index = &exprs.indexes[index];
+                let mut info = self.check_projected(&index.on, state);
+                self.check_expr(&index.idx, state);
+                if let Some(place) = &mut info.place {
+                    place.projections.push(Projection::Index);
+                }
+                info
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let ptr = &exprs.unary_exprs[index].expr;
+                let is_mut = matches!(self.types.expr_ty(ptr), Ty::RefMut(_) | Ty::PtrMut(_));
+                self.check_operand(ptr, state);
+                PlaceInfo {
+                    place: None,
+                    mutability: Mutability::ThroughPtr(is_mut),
+                }
+            }
+            _ => {
+                self.check_expr(expr, state);
+                PlaceInfo {
+                    place: None,
+                    mutability: Mutability::Temp,
+                }
+            }
+        }
+    }
+
+    /// Checks the expression whose field or element is accessed, the pointers are dereferenced automatically
+    fn check_projected(&mut self, on: &Expr, state: &mut State) -> PlaceInfo {
+        let mut ty = self.types.expr_ty(on);
+
+        if ty.pointee().is_none() {
+            return self.check_place(on, state);
+        }
+
+        let mut is_mut = true;
+        while let Some(pointee) = ty.pointee() {
+            is_mut = matches!(ty, Ty::RefMut(_) | Ty::PtrMut(_));
+            ty = pointee;
+        }
+
+        self.check_operand(on, state);
+
+        PlaceInfo {
+            place: None,
+            mutability: Mutability::ThroughPtr(is_mut),
+        }
+    }
+
+    fn check_mutability(
+        &mut self,
+        info: &PlaceInfo,
+        access: Access,
+        span: Span,
+        state: &mut State,
+    ) {
+        if let Mutability::Local(local_idx) = info.mutability {
+            self.mutated[local_idx] = true;
+        }
+
+        match info.mutability {
+            Mutability::Local(local_idx) if !self.nir.locals[local_idx].is_mut => {
+                let place = info.place.as_ref().unwrap();
+                let name = self.fmt_place(place);
+
+                if access == Access::Write
+                    && place.projections.is_empty()
+                    && self.deferred[local_idx]
+                {
+                    match state.assigned.iter().find(|(local, _)| *local == local_idx) {
+                        Some((_, first_span)) => {
+                            let msg = format!("لا يمكن الإسناد مرتين إلى `{}` لأنه غير متغير", name);
+                            let first_span = *first_span;
+                            let help = self.mut_help(local_idx);
+                            if let Some(diagnostic) = self.add_conflict_err(
+                                msg,
+                                span,
+                                "لا يمكن الإسناد مرة ثانية".to_string(),
+                                first_span,
+                                "أول إسناد هنا".to_string(),
+                            ) {
+                                diagnostic.chain(help);
+                            }
+                        }
+                        None => state.assigned.push((local_idx, span)),
+                    }
+                    return;
+                }
+
+                let (msg, label) = if access == Access::Borrow(LoanKind::Mut) {
+                    (
+                        format!("لا يمكن استعارة `{}` كمتغير لأنه غير متغير", name),
+                        "لا يمكن استعارته كمتغير",
+                    )
+                } else {
+                    (
+                        format!("لا يمكن الإسناد إلى `{}` لأنه غير متغير", name),
+                        "لا يمكن الإسناد إليه",
+                    )
+                };
+
+                let decl_span = self.nir.locals[local_idx].name.span;
+                let help = self.mut_help(local_idx);
+
+                if let Some(diagnostic) = self.add_conflict_err(
+                    msg,
+                    span,
+                    label.to_string(),
+                    decl_span,
+                    "تم تعريفه هنا بدون `متغير`".to_string(),
+                ) {
+                    diagnostic.chain(help);
+                }
+            }
+            Mutability::Static(static_idx) => {
+                let name = &self.id_pool[self.nir.statics[static_idx].name.id];
+                let (msg, label) = if access == Access::Borrow(LoanKind::Mut) {
+                    (
+                        format!("لا يمكن استعارة المتغير المشترك `{}` كمتغير", name),
+                        "لا يمكن استعارته كمتغير",
+                    )
+                } else {
+                    (
+                        format!("لا يمكن الإسناد إلى المتغير المشترك `{}`", name),
+                        "لا يمكن الإسناد إليه",
+                    )
+                };
+                self.add_err(msg, span, vec![label.to_string()]);
+            }
+            Mutability::ThroughPtr(false) => {
+                let msg = if access == Access::Borrow(LoanKind::Mut) {
+                    "لا يمكن الاستعارة كمتغير عبر مرجع غير متغير"
+                } else {
+                    "لا يمكن الإسناد عبر مرجع غير متغير"
+                };
+                self.add_err(
+                    msg.to_string(),
+                    span,
+                    vec!["المرجع ليس `#متغير`".to_string()],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn mut_help(&self, local_idx: usize) -> Diagnostic {
+        let local = &self.nir.locals[local_idx];
+        let mut code_window = CodeWindow::new(local.name.span);
+        code_window.mark_help(local.name.span, vec![]);
+        Diagnostic::help(
+            format!(
+                "أضف `متغير` لتعريف `{}` كمتغير",
+                &self.id_pool[local.name.id]
+            ),
+            vec![code_window],
+        )
+    }
+
+    /// Reports accessing a moved place or a place conflicting with an alive loan,
+    /// and applies the effects of the access
+    fn check_access(&mut self, place: &Place, access: Access, span: Span, state: &mut State) {
+        if access != Access::Write {
+            if let Some((_, move_span)) =
+                state.moved.iter().find(|(moved, _)| moved.overlaps(place))
+            {
+                let msg = format!("لا يمكن استخدام `{}` بعد نقله", self.fmt_place(place));
+                let move_span = *move_span;
+                if move_span == span {
+                    // It's moved by the same expression in an earlier iteration of a loop
+                    self.add_err(
+                        msg,
+                        span,
+                        vec!["تم نقله هنا في تكرار سابق للحلقة".to_string()],
+                    );
+                } else {
+                    self.add_conflict_err(
+                        msg,
+                        span,
+                        "تم استخدامه هنا بعد نقله".to_string(),
+                        move_span,
+                        "تم نقله هنا".to_string(),
+                    );
+                }
+            }
+        }
+
+        let conflicting = state.loans.iter().copied().find(|&loan_idx| {
+            let loan = &self.loans[loan_idx];
+            loan.place.overlaps(place)
+                && match access {
+                    Access::Read | Access::Borrow(LoanKind::Shared) => loan.kind == LoanKind::Mut,
+                    _ => true,
+                }
+        });
+
+        if let Some(loan_idx) = conflicting {
+            let loan = &self.loans[loan_idx];
+            let name = self.fmt_place(place);
+
+            let (msg, label) = match access {
+                Access::Read => (
+                    format!("لا يمكن استخدام `{}` لأنه مستعار كمتغير", name),
+                    "تم استخدامه هنا",
+                ),
+                Access::Move => (format!("لا يمكن نقل `{}` لأنه مستعار", name), "تم نقله هنا"),
+                Access::Write | Access::Update => (
+                    format!("لا يمكن الإسناد إلى `{}` لأنه مستعار", name),
+                    "تم الإسناد إليه هنا",
+                ),
+                Access::Borrow(LoanKind::Shared) => (
+                    format!("لا يمكن استعارة `{}` لأنه مستعار كمتغير", name),
+                    "تمت استعارته هنا",
+                ),
+                Access::Borrow(LoanKind::Mut) if loan.kind == LoanKind::Mut => (
+                    format!("لا يمكن استعارة `{}` كمتغير أكثر من مرة في نفس الوقت", name),
+                    "تمت استعارته كمتغير مرة أخرى هنا",
+                ),
+                Access::Borrow(LoanKind::Mut) => (
+                    format!("لا يمكن استعارة `{}` كمتغير لأنه مستعار", name),
+                    "تمت استعارته كمتغير هنا",
+                ),
+            };
+
+            let loan_label = if loan.kind == LoanKind::Mut {
+                "تمت استعارته كمتغير أولاً هنا"
+            } else {
+                "تمت استعارته أولاً هنا"
+            };
+
+            let loan_span = loan.span;
+
+            self.add_conflict_err(
+                msg,
+                span,
+                label.to_string(),
+                loan_span,
+                loan_label.to_string(),
+            );
+        }
+
+        match access {
+            Access::Move => state.moved.push((place.clone(), span)),
+            Access::Write => state.moved.retain(|(moved, _)| !place.contains(moved)),
+            _ => {}
+        }
+    }
+
+    fn is_place(&self, expr: &Expr) -> bool {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => matches!(
+                exprs.paths[index].resolved,
+                ResolvedPath::Local(_) | ResolvedPath::Static(_)
+            ),
+            ExprKindAndIndex::FIELD | ExprKindAndIndex::INDEX => true,
+            ExprKindAndIndex::UNARY_EXPR => exprs.unary_exprs[index].op == UnaryOp::Deref,
+            ExprKindAndIndex::PARENS => self.is_place(&exprs.parens[index].expr),
+            _ => false,
+        }
+    }
+}
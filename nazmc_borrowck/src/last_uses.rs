@@ -0,0 +1,237 @@
+use nazmc_nir::*;
+
+/// The byte offset of the end of a span, the uses in a function are ordered by it as they are in the same file
+pub(crate) type Pos = u32;
+
+#[derive(Default)]
+struct Loop {
+    /// The locals used inside the loop
+    used: Vec<usize>,
+    /// The end of the last expression inside the loop
+    end: Pos,
+}
+
+/// Finds the position of the last use of each local, a local used inside a loop but bound outside it
+/// is used again in the next iteration, so its last use is at least the end of the loop
+pub(crate) struct LastUsesCollector<'a> {
+    nir: &'a NIR,
+    last_uses: Vec<Pos>,
+    /// The count of the loops around each local when it is bound
+    depths: Vec<usize>,
+    loops: Vec<Loop>,
+}
+
+impl<'a> LastUsesCollector<'a> {
+    pub(crate) fn new(nir: &'a NIR) -> Self {
+        Self {
+            nir,
+            last_uses: vec![Pos::default(); nir.locals.len()],
+            depths: vec![0; nir.locals.len()],
+            loops: vec![],
+        }
+    }
+
+    pub(crate) fn collect(mut self) -> Vec<Pos> {
+        for f in &self.nir.fns {
+            self.collect_scope(f.body);
+        }
+        self.last_uses
+    }
+
+    fn use_local(&mut self, local_idx: usize, pos: Pos) {
+        self.last_uses[local_idx] = self.last_uses[local_idx].max(pos);
+        if let Some(l) = self.loops.last_mut() {
+            l.used.push(local_idx);
+        }
+    }
+
+    fn collect_binding_kind(&mut self, kind: &BindingKind) {
+        match kind {
+            BindingKind::Local(local_idx) => self.depths[*local_idx] = self.loops.len(),
+            BindingKind::TupleDestruction(kinds, _) => {
+                for kind in kinds {
+                    self.collect_binding_kind(kind);
+                }
+            }
+        }
+    }
+
+    fn collect_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Binding(local_idx) => self.depths[*local_idx] = self.loops.len(),
+            PatternKind::Tuple(patterns) | PatternKind::TupleStruct(_, patterns) => {
+                for pattern in patterns {
+                    self.collect_pattern(pattern);
+                }
+            }
+            PatternKind::FieldsStruct(_, fields) => {
+                for (_, pattern) in fields {
+                    self.collect_pattern(pattern);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_scope(&mut self, scope: Scope) {
+        let scope = &self.nir.scopes[scope.index];
+
+        for stm in &scope.stms {
+            self.collect_stm(stm);
+        }
+
+        if let Some(expr) = &scope.return_expr {
+            self.collect_expr(expr);
+        }
+    }
+
+    fn collect_stm(&mut self, stm: &Stm) {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET | StmKindAndIndex::LET_MUT => {
+                let let_stm = if stm.kind_and_index.kind() == StmKindAndIndex::LET {
+                    &stms.lets[index]
+                } else {
+                    &stms.let_muts[index]
+                };
+                if let Some(expr) = &let_stm.assign {
+                    self.collect_expr(expr);
+                }
+                self.collect_binding_kind(&let_stm.binding.kind);
+            }
+            StmKindAndIndex::IF => self.collect_if(&stms.ifs[index]),
+            StmKindAndIndex::WHILE | StmKindAndIndex::DO_WHILE => {
+                let while_stm = if stm.kind_and_index.kind() == StmKindAndIndex::WHILE {
+                    &stms.whiles[index]
+                } else {
+                    &stms.do_whiles[index]
+                };
+
+                self.loops.push(Loop::default());
+                self.collect_expr(&while_stm.condition);
+                self.collect_scope(while_stm.scope);
+                let l = self.loops.pop().unwrap();
+
+                let depth = self.loops.len();
+                for &local_idx in &l.used {
+                    if self.depths[local_idx] <= depth {
+                        self.last_uses[local_idx] = self.last_uses[local_idx].max(l.end);
+                    }
+                }
+
+                if let Some(outer) = self.loops.last_mut() {
+                    outer.used.extend(l.used);
+                    outer.end = outer.end.max(l.end);
+                }
+            }
+            StmKindAndIndex::WHEN => self.collect_when(&stms.whens[index]),
+            StmKindAndIndex::EXPR => self.collect_expr(&stms.exprs[index]),
+            _ => unreachable!(),
+        }
+    }
+
+    fn collect_if(&mut self, if_expr: &IfExpr) {
+        self.collect_expr(&if_expr.if_.condition);
+        self.collect_scope(if_expr.if_.scope);
+
+        for else_if in &if_expr.else_ifs {
+            self.collect_expr(&else_if.condition);
+            self.collect_scope(else_if.scope);
+        }
+
+        if let Some(scope) = if_expr.else_ {
+            self.collect_scope(scope);
+        }
+    }
+
+    fn collect_when(&mut self, when_expr: &WhenExpr) {
+        self.collect_expr(&when_expr.expr);
+
+        for arm in &when_expr.arms {
+            self.collect_pattern(&arm.pattern);
+            if let Some(guard) = &arm.guard {
+                self.collect_expr(guard);
+            }
+            self.collect_scope(arm.body);
+        }
+    }
+
+    fn collect_exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.collect_expr(expr);
+        }
+    }
+
+    fn collect_expr(&mut self, expr: &Expr) {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        if let Some(l) = self.loops.last_mut() {
+            l.end = l.end.max(expr.span.hi);
+        }
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PARENS => self.collect_expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => {
+                if let ResolvedPath::Local(local_idx) = exprs.paths[index].resolved {
+                    self.use_local(local_idx, expr.span.hi);
+                }
+            }
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                if let ResolvedPath::Local(local_idx) = call.path.resolved {
+                    self.use_local(local_idx, expr.span.hi);
+                }
+                self.collect_exprs(&call.args);
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                self.collect_expr(&call.on);
+                self.collect_exprs(&call.args);
+            }
+            ExprKindAndIndex::TUPLE_STRUCT => self.collect_exprs(&exprs.tuple_structs[index].args),
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                for field in &exprs.fields_structs[index].fields {
+                    self.collect_expr(&field.expr);
+                }
+            }
+            ExprKindAndIndex::FIELD => self.collect_expr(&exprs.fields[index].on),
+            ExprKindAndIndex::INDEX => {
+                let index = &exprs.indexes[index];
+                self.collect_expr(&index.on);
+                self.collect_expr(&index.idx);
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                self.collect_exprs(&exprs.array_elements[index].elements)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                self.collect_expr(&exprs.array_elements_sized[index].repeat)
+            }
+            ExprKindAndIndex::TUPLE_EXPR => self.collect_exprs(&exprs.tuples[index].elements),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                self.collect_expr(&exprs.returns[index].expr_to_return)
+            }
+            ExprKindAndIndex::BREAK_WITH_VALUE => {
+                self.collect_expr(&exprs.breaks[index].expr_to_break)
+            }
+            ExprKindAndIndex::IF_EXPR => self.collect_if(&exprs.ifs[index]),
+            ExprKindAndIndex::WHEN_EXPR => self.collect_when(&exprs.whens[index]),
+            ExprKindAndIndex::LAMBDA_EXPR => {
+                let lambda = &exprs.lambdas[index];
+                for param in &lambda.params {
+                    self.collect_binding_kind(&param.kind);
+                }
+                self.collect_scope(lambda.body);
+            }
+            ExprKindAndIndex::UNARY_EXPR => self.collect_expr(&exprs.unary_exprs[index].expr),
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                self.collect_expr(&bin.left);
+                self.collect_expr(&bin.right);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use last_uses::{LastUsesCollector, Pos};
+use nazmc_data_pool::{Built, DataPool};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic, Lint, LintLevel, LintLevels};
+use nazmc_nir::*;
+use nazmc_typeck::{Ty, TypesTable};
+use state::{Loan, LoanKind, Place, Projection, State};
+
+mod exprs;
+mod last_uses;
+mod state;
+
+/// The states reaching the end and the start of a loop from `قطع` and `وصل`
+#[derive(Default)]
+struct LoopCtx {
+    breaks: Vec<State>,
+    continues: Vec<State>,
+}
+
+pub struct BorrowChecker<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    nir: &'a NIR,
+    types: &'a TypesTable,
+    lint_levels: LintLevels,
+    diagnostics: Vec<Diagnostic>,
+    /// The spans and the messages of the reported errors, the loops are checked twice
+    /// so the same error could be found again
+    reported: Vec<(Span, String)>,
+    /// The position of the last use of each local
+    last_uses: Vec<Pos>,
+    /// Whether each local is declared without a value, so it could be assigned once without `متغير`
+    deferred: Vec<bool>,
+    /// Whether each local is assigned or borrowed by `#متغير`, to find the locals which don't need `متغير`
+    mutated: Vec<bool>,
+    /// The loans of the body being checked
+    loans: Vec<Loan>,
+    /// The loops around the expression being checked in the current function or lambda
+    loops: Vec<LoopCtx>,
+    /// The end of the last checked expression
+    pos: Pos,
+    /// The file index of the item being checked
+    current_file_idx: usize,
+}
+
+impl<'a> BorrowChecker<'a> {
+    pub fn new(
+        id_pool: &'a DataPool<Built>,
+        nir: &'a NIR,
+        types: &'a TypesTable,
+        lint_levels: LintLevels,
+    ) -> Self {
+        Self {
+            id_pool,
+            nir,
+            types,
+            lint_levels,
+            diagnostics: vec![],
+            reported: vec![],
+            last_uses: vec![],
+            deferred: vec![false; nir.locals.len()],
+            mutated: vec![false; nir.locals.len()],
+            loans: vec![],
+            loops: vec![],
+            pos: Pos::default(),
+            current_file_idx: 0,
+        }
+    }
+
+    /// Returns the errors and the warnings of the borrows and the moves
+    pub fn check(mut self) -> Vec<Diagnostic> {
+        let nir = self.nir;
+
+        self.last_uses = LastUsesCollector::new(nir).collect();
+
+        for f in &nir.fns {
+            self.current_file_idx = f.file_idx;
+            self.loans.clear();
+            self.pos = Pos::default();
+            self.check_scope(f.body, &mut State::default());
+        }
+
+        self.check_unused_muts();
+
+        self.diagnostics
+    }
+
+    fn check_unused_muts(&mut self) {
+        let level = self.lint_levels.get(Lint::UnusedMut);
+
+        if level == LintLevel::Allow {
+            return;
+        }
+
+        for (local_idx, local) in self.nir.locals.iter().enumerate() {
+            if !local.is_mut || self.mutated[local_idx] {
+                continue;
+            }
+
+            let name = &self.id_pool[local.name.id];
+            let msg = format!("لا حاجة لتعريف `{}` كمتغير", name);
+            let mut code_window = CodeWindow::new(local.name.span);
+            code_window.mark_lint(
+                level,
+                local.name.span,
+                vec!["لم يتم تغييره أبدا".to_string()],
+            );
+            let mut diagnostic = Diagnostic::lint(Lint::UnusedMut, level, msg, vec![code_window]);
+            diagnostic.chain(Diagnostic::help(
+                "احذف `متغير` من تعريفه".to_string(),
+                vec![],
+            ));
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Checks the scope and returns the loans of its return value
+    fn check_scope(&mut self, scope: Scope, state: &mut State) -> Vec<usize> {
+        let scope = &self.nir.scopes[scope.index];
+
+        for stm in &scope.stms {
+            self.check_stm(stm, state);
+            self.release_loans(state);
+        }
+
+        match &scope.return_expr {
+            Some(expr) => self.check_expr(expr, state),
+            None => vec![],
+        }
+    }
+
+    /// Releases the loans whose references will not be used anymore after the current position
+    fn release_loans(&mut self, state: &mut State) {
+        state.loans.retain(|&loan_idx| {
+            self.loans[loan_idx]
+                .holders
+                .iter()
+                .any(|&local_idx| self.last_uses[local_idx] > self.pos)
+        });
+    }
+
+    fn check_stm(&mut self, stm: &Stm, state: &mut State) {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET | StmKindAndIndex::LET_MUT => {
+                let let_stm = if stm.kind_and_index.kind() == StmKindAndIndex::LET {
+                    &stms.lets[index]
+                } else {
+                    &stms.let_muts[index]
+                };
+                let loans = match &let_stm.assign {
+                    Some(expr) => self.check_expr(expr, state),
+                    None => vec![],
+                };
+                self.bind(
+                    &let_stm.binding.kind,
+                    &loans,
+                    let_stm.assign.is_none(),
+                    state,
+                );
+            }
+            StmKindAndIndex::IF => {
+                self.check_if(&stms.ifs[index], state);
+            }
+            StmKindAndIndex::WHILE => self.check_while(&stms.whiles[index], state),
+            StmKindAndIndex::DO_WHILE => self.check_do_while(&stms.do_whiles[index], state),
+            StmKindAndIndex::WHEN => {
+                self.check_when(&stms.whens[index], state);
+            }
+            StmKindAndIndex::EXPR => {
+                self.check_expr(&stms.exprs[index], state);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Binds new locals, so they are not moved nor assigned anymore, and they hold the loans of the bound value
+    fn bind(&mut self, kind: &BindingKind, loans: &[usize], deferred: bool, state: &mut State) {
+        match kind {
+            BindingKind::Local(local_idx) => {
+                let local_idx = *local_idx;
+                state.moved.retain(|(place, _)| place.local != local_idx);
+                state.assigned.retain(|(local, _)| *local != local_idx);
+                self.deferred[local_idx] = deferred;
+                for &loan_idx in loans {
+                    self.loans[loan_idx].holders.push(local_idx);
+                }
+            }
+            BindingKind::TupleDestruction(kinds, _) => {
+                for kind in kinds {
+                    self.bind(kind, loans, deferred, state);
+                }
+            }
+        }
+    }
+
+    /// Binds the locals of the pattern, they hold the loans of the matched value
+    fn bind_pattern(&mut self, pattern: &Pattern, loans: &[usize], state: &mut State) {
+        match &pattern.kind {
+            PatternKind::Binding(local_idx) => {
+                self.bind(&BindingKind::Local(*local_idx), loans, false, state)
+            }
+            PatternKind::Tuple(patterns) | PatternKind::TupleStruct(_, patterns) => {
+                for pattern in patterns {
+                    self.bind_pattern(pattern, loans, state);
+                }
+            }
+            PatternKind::FieldsStruct(_, fields) => {
+                for (_, pattern) in fields {
+                    self.bind_pattern(pattern, loans, state);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the branches from the same state and joins their states,
+    /// and returns the loans of all the branches values
+    fn check_if(&mut self, if_expr: &IfExpr, state: &mut State) -> Vec<usize> {
+        let mut loans = vec![];
+        let mut branches = vec![];
+
+        for ConditionalScope { condition, scope } in
+            std::iter::once(&if_expr.if_).chain(&if_expr.else_ifs)
+        {
+            self.check_expr(condition, state);
+            let mut branch = state.clone();
+            loans.extend(self.check_scope(*scope, &mut branch));
+            branches.push(branch);
+        }
+
+        match if_expr.else_ {
+            Some(scope) => {
+                let mut branch = state.clone();
+                loans.extend(self.check_scope(scope, &mut branch));
+                branches.push(branch);
+            }
+            None => branches.push(state.clone()),
+        }
+
+        *state = branches.into_iter().reduce(State::join).unwrap();
+
+        loans
+    }
+
+    /// Checks the loop twice, the second time starts from the states reaching the start of the loop
+    /// from the end of the first iteration, so moving a value in an iteration is found in the next one
+    fn check_while(&mut self, while_stm: &ConditionalScope, state: &mut State) {
+        let mut start = state.clone();
+        let mut exit = State::default();
+
+        for _ in 0..2 {
+            let mut body = start.clone();
+
+            self.check_expr(&while_stm.condition, &mut body);
+
+            exit = body.clone();
+
+            self.loops.push(LoopCtx::default());
+            self.check_scope(while_stm.scope, &mut body);
+            let ctx = self.loops.pop().unwrap();
+
+            let end = ctx.continues.into_iter().fold(body, State::join);
+            start = start.join(end);
+            exit = ctx.breaks.into_iter().fold(exit, State::join);
+        }
+
+        *state = exit;
+    }
+
+    /// Checks the loop twice like `طالما`, the body is reached first from the state before the loop,
+    /// and the condition is checked at the end of each iteration
+    fn check_do_while(&mut self, do_while_stm: &ConditionalScope, state: &mut State) {
+        let mut start = state.clone();
+        let mut exit = State::default();
+
+        for _ in 0..2 {
+            let mut body = start.clone();
+
+            self.loops.push(LoopCtx::default());
+            self.check_scope(do_while_stm.scope, &mut body);
+            let ctx = self.loops.pop().unwrap();
+
+            let mut end = ctx.continues.into_iter().fold(body, State::join);
+            self.check_expr(&do_while_stm.condition, &mut end);
+
+            start = start.join(end.clone());
+            exit = ctx.breaks.into_iter().fold(end, State::join);
+        }
+
+        *state = exit;
+    }
+
+    fn fmt_place(&self, place: &Place) -> String {
+        let mut s = self.id_pool[self.nir.locals[place.local].name.id].to_string();
+
+        for projection in &place.projections {
+            match projection {
+                Projection::Field(id) => {
+                    s.push('.');
+                    s.push_str(&self.id_pool[*id]);
+                }
+                Projection::Index => s.push_str("[_]"),
+            }
+        }
+
+        s
+    }
+
+    fn add_err(&mut self, msg: String, span: Span, labels: Vec<String>) {
+        if self.is_reported(span, &msg) {
+            return;
+        }
+
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_error(span, labels);
+        self.diagnostics
+            .push(Diagnostic::error(msg, vec![code_window]));
+    }
+
+    /// Reports an error with the span of the access which caused it and the span of the earlier access
+    /// which it conflicts with
+    fn add_conflict_err(
+        &mut self,
+        msg: String,
+        span: Span,
+        label: String,
+        earlier_span: Span,
+        earlier_label: String,
+    ) -> Option<&mut Diagnostic> {
+        if self.is_reported(span, &msg) {
+            return None;
+        }
+
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_secondary(earlier_span, vec![earlier_label]);
+        code_window.mark_error(span, vec![label]);
+        self.diagnostics
+            .push(Diagnostic::error(msg, vec![code_window]));
+        self.diagnostics.last_mut()
+    }
+
+    fn is_reported(&mut self, span: Span, msg: &str) -> bool {
+        if self
+            .reported
+            .iter()
+            .any(|(reported_span, reported_msg)| *reported_span == span && reported_msg == msg)
+        {
+            return true;
+        }
+
+        self.reported.push((span, msg.to_string()));
+        false
+    }
+
+    /// Returns whether the values of the type are copied instead of moved
+    fn is_copy(&self, ty: &Ty) -> bool {
+        match ty {
+            Ty::RefMut(_) | Ty::Slice(_) => false,
+            Ty::UnitStruct(_) | Ty::TupleStruct(_) | Ty::FieldsStruct(_) | Ty::Enum(_) => false,
+            Ty::Tuple(types) => types.iter().all(|ty| self.is_copy(ty)),
+            Ty::Array(ty, _) => self.is_copy(ty),
+            _ => true,
+        }
+    }
+
+    /// Returns whether the values of the type could hold references
+    fn has_refs(&self, ty: &Ty) -> bool {
+        match ty {
+            Ty::Ref(_) | Ty::RefMut(_) | Ty::Ptr(_) | Ty::PtrMut(_) => true,
+            Ty::Tuple(types) => types.iter().any(|ty| self.has_refs(ty)),
+            Ty::Array(ty, _) | Ty::Slice(ty) => self.has_refs(ty),
+            Ty::TupleStruct(idx) => self.types.tuple_structs[*idx]
+                .iter()
+                .any(|ty| self.has_refs(ty)),
+            Ty::FieldsStruct(idx) => self.types.fields_structs[*idx]
+                .iter()
+                .any(|ty| self.has_refs(ty)),
+            Ty::Enum(idx) => self.types.enums[*idx]
+                .iter()
+                .flatten()
+                .any(|ty| self.has_refs(ty)),
+            _ => false,
+        }
+    }
+
+    /// Returns the alive loans held by the local
+    fn held_loans(&self, local_idx: usize, state: &State) -> Vec<usize> {
+        state
+            .loans
+            .iter()
+            .copied()
+            .filter(|&loan_idx| self.loans[loan_idx].holders.contains(&local_idx))
+            .collect()
+    }
+
+    fn update_pos(&mut self, expr: &Expr) {
+        self.pos = self.pos.max(expr.span.hi);
+    }
+
+    fn new_loan(&mut self, place: Place, kind: LoanKind, span: Span, state: &mut State) -> usize {
+        let loan_idx = self.loans.len();
+        self.loans.push(Loan {
+            place,
+            kind,
+            span,
+            holders: vec![],
+        });
+        state.loans.push(loan_idx);
+        loan_idx
+    }
+}
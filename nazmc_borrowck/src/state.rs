@@ -0,0 +1,100 @@
+use nazmc_data_pool::PoolIdx;
+use nazmc_diagnostics::span::Span;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Projection {
+    Field(PoolIdx),
+    /// Any element of an array, the indices are not known before running
+    Index,
+}
+
+/// A memory location rooted at a local without dereferencing any pointer
+#[derive(Clone, PartialEq)]
+pub(crate) struct Place {
+    pub(crate) local: usize,
+    pub(crate) projections: Vec<Projection>,
+}
+
+impl Place {
+    pub(crate) fn local(local: usize) -> Self {
+        Self {
+            local,
+            projections: vec![],
+        }
+    }
+
+    /// Returns whether the two places share some memory, which is when one of them contains the other
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        self.local == other.local
+            && self
+                .projections
+                .iter()
+                .zip(&other.projections)
+                .all(|(a, b)| a == b)
+    }
+
+    /// Returns whether the other place is this place or a part of it
+    pub(crate) fn contains(&self, other: &Self) -> bool {
+        self.projections.len() <= other.projections.len() && self.overlaps(other)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum LoanKind {
+    /// Borrowed by `#`
+    Shared,
+    /// Borrowed by `#متغير`
+    Mut,
+}
+
+pub(crate) struct Loan {
+    pub(crate) place: Place,
+    pub(crate) kind: LoanKind,
+    pub(crate) span: Span,
+    /// The locals which hold the reference, the loan is alive until the last use of them
+    pub(crate) holders: Vec<usize>,
+}
+
+/// What is known at some point of a body, it's the union of what is known from all the paths reaching it
+#[derive(Clone, Default)]
+pub(crate) struct State {
+    /// The places which may be moved with the spans where they are moved
+    pub(crate) moved: Vec<(Place, Span)>,
+    /// The locals declared without `متغير` and without a value which may be assigned,
+    /// with the spans of the assignments
+    pub(crate) assigned: Vec<(usize, Span)>,
+    /// The indices of the loans which may be alive
+    pub(crate) loans: Vec<usize>,
+    /// Whether this point is never reached (e.g. after `أرجع`)
+    pub(crate) diverged: bool,
+}
+
+impl State {
+    pub(crate) fn join(mut self, other: Self) -> Self {
+        if self.diverged {
+            return other;
+        } else if other.diverged {
+            return self;
+        }
+
+        for moved in other.moved {
+            if !self.moved.iter().any(|(place, _)| *place == moved.0) {
+                self.moved.push(moved);
+            }
+        }
+
+        for assigned in other.assigned {
+            if !self.assigned.iter().any(|(local, _)| *local == assigned.0) {
+                self.assigned.push(assigned);
+            }
+        }
+
+        for loan in other.loans {
+            if !self.loans.contains(&loan) {
+                self.loans.push(loan);
+            }
+        }
+
+        self
+    }
+}
@@ -0,0 +1,202 @@
+use nazmc_nir::*;
+
+/// Collects the locals used in a lambda body and the locals bound inside it,
+/// the captured locals are the used ones which are not bound inside the lambda
+pub(crate) struct LocalsCollector<'a> {
+    nir: &'a NIR,
+    pub(crate) used: Vec<usize>,
+    pub(crate) bound: Vec<usize>,
+}
+
+impl<'a> LocalsCollector<'a> {
+    pub(crate) fn new(nir: &'a NIR) -> Self {
+        Self {
+            nir,
+            used: vec![],
+            bound: vec![],
+        }
+    }
+
+    /// Returns the sorted locals captured by the lambda
+    pub(crate) fn captures(mut self, lambda: &LambdaExpr) -> Vec<usize> {
+        self.collect_lambda(lambda);
+
+        let mut captures = self.used;
+        captures.retain(|local_idx| !self.bound.contains(local_idx));
+        captures.sort_unstable();
+        captures.dedup();
+        captures
+    }
+
+    fn collect_lambda(&mut self, lambda: &LambdaExpr) {
+        for param in &lambda.params {
+            self.collect_binding_kind(&param.kind);
+        }
+        self.collect_scope(lambda.body);
+    }
+
+    fn collect_binding_kind(&mut self, kind: &BindingKind) {
+        match kind {
+            BindingKind::Local(local_idx) => self.bound.push(*local_idx),
+            BindingKind::TupleDestruction(kinds, _) => {
+                for kind in kinds {
+                    self.collect_binding_kind(kind);
+                }
+            }
+        }
+    }
+
+    fn collect_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Binding(local_idx) => self.bound.push(*local_idx),
+            PatternKind::Tuple(patterns) | PatternKind::TupleStruct(_, patterns) => {
+                for pattern in patterns {
+                    self.collect_pattern(pattern);
+                }
+            }
+            PatternKind::FieldsStruct(_, fields) => {
+                for (_, pattern) in fields {
+                    self.collect_pattern(pattern);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_scope(&mut self, scope: Scope) {
+        let scope = &self.nir.scopes[scope.index];
+
+        for stm in &scope.stms {
+            self.collect_stm(stm);
+        }
+
+        if let Some(expr) = &scope.return_expr {
+            self.collect_expr(expr);
+        }
+    }
+
+    fn collect_stm(&mut self, stm: &Stm) {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET | StmKindAndIndex::LET_MUT => {
+                let let_stm = if stm.kind_and_index.kind() == StmKindAndIndex::LET {
+                    &stms.lets[index]
+                } else {
+                    &stms.let_muts[index]
+                };
+                if let Some(expr) = &let_stm.assign {
+                    self.collect_expr(expr);
+                }
+                self.collect_binding_kind(&let_stm.binding.kind);
+            }
+            StmKindAndIndex::IF => self.collect_if(&stms.ifs[index]),
+            StmKindAndIndex::WHILE | StmKindAndIndex::DO_WHILE => {
+                let while_stm = if stm.kind_and_index.kind() == StmKindAndIndex::WHILE {
+                    &stms.whiles[index]
+                } else {
+                    &stms.do_whiles[index]
+                };
+                self.collect_expr(&while_stm.condition);
+                self.collect_scope(while_stm.scope);
+            }
+            StmKindAndIndex::WHEN => self.collect_when(&stms.whens[index]),
+            StmKindAndIndex::EXPR => self.collect_expr(&stms.exprs[index]),
+            _ => unreachable!(),
+        }
+    }
+
+    fn collect_if(&mut self, if_expr: &IfExpr) {
+        self.collect_expr(&if_expr.if_.condition);
+        self.collect_scope(if_expr.if_.scope);
+
+        for else_if in &if_expr.else_ifs {
+            self.collect_expr(&else_if.condition);
+            self.collect_scope(else_if.scope);
+        }
+
+        if let Some(scope) = if_expr.else_ {
+            self.collect_scope(scope);
+        }
+    }
+
+    fn collect_when(&mut self, when_expr: &WhenExpr) {
+        self.collect_expr(&when_expr.expr);
+
+        for arm in &when_expr.arms {
+            self.collect_pattern(&arm.pattern);
+            if let Some(guard) = &arm.guard {
+                self.collect_expr(guard);
+            }
+            self.collect_scope(arm.body);
+        }
+    }
+
+    fn collect_exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.collect_expr(expr);
+        }
+    }
+
+    fn collect_expr(&mut self, expr: &Expr) {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PARENS => self.collect_expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => {
+                if let ResolvedPath::Local(local_idx) = exprs.paths[index].resolved {
+                    self.used.push(local_idx);
+                }
+            }
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                if let ResolvedPath::Local(local_idx) = call.path.resolved {
+                    self.used.push(local_idx);
+                }
+                self.collect_exprs(&call.args);
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                self.collect_expr(&call.on);
+                self.collect_exprs(&call.args);
+            }
+            ExprKindAndIndex::TUPLE_STRUCT => self.collect_exprs(&exprs.tuple_structs[index].args),
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                for field in &exprs.fields_structs[index].fields {
+                    self.collect_expr(&field.expr);
+                }
+            }
+            ExprKindAndIndex::FIELD => self.collect_expr(&exprs.fields[index].on),
+            ExprKindAndIndex::INDEX => {
+                let index = &exprs.indexes[index];
+                self.collect_expr(&index.on);
+                self.collect_expr(&index.idx);
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                self.collect_exprs(&exprs.array_elements[index].elements)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                self.collect_expr(&exprs.array_elements_sized[index].repeat)
+            }
+            ExprKindAndIndex::TUPLE_EXPR => self.collect_exprs(&exprs.tuples[index].elements),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                self.collect_expr(&exprs.returns[index].expr_to_return)
+            }
+            ExprKindAndIndex::BREAK_WITH_VALUE => {
+                self.collect_expr(&exprs.breaks[index].expr_to_break)
+            }
+            ExprKindAndIndex::IF_EXPR => self.collect_if(&exprs.ifs[index]),
+            ExprKindAndIndex::WHEN_EXPR => self.collect_when(&exprs.whens[index]),
+            ExprKindAndIndex::LAMBDA_EXPR => self.collect_lambda(&exprs.lambdas[index]),
+            ExprKindAndIndex::UNARY_EXPR => self.collect_expr(&exprs.unary_exprs[index].expr),
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                self.collect_expr(&bin.left);
+                self.collect_expr(&bin.right);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,761 @@
+use crate::{c_primitive, is_unit, mangle::c_str_lit, ops_suffix, CodeGen};
+use nazmc_diagnostics::span::Span;
+use nazmc_nir::*;
+use nazmc_typeck::{ConstValue, Ty};
+
+impl<'a> CodeGen<'a> {
+    /// Generates the expression and returns a C expression of its value, the side effects
+    /// are written as statements before it so the expressions are evaluated from left to right
+    pub(crate) fn expr(&mut self, expr: &Expr) -> String {
+        let nir = self.nir;
+        let types = self.types;
+        let exprs = &nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT => "0".to_string(),
+            ExprKindAndIndex::LITERAL => self.literal(&exprs.literals[index], expr),
+            ExprKindAndIndex::PARENS => self.expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                ResolvedPath::Local(local_idx) => {
+                    // Copy the value, so it will not be affected by the next expressions
+                    let lvalue = self.ctx().locals[&local_idx].clone();
+                    self.tmp(&types.locals[local_idx], lvalue)
+                }
+                ResolvedPath::Fn(fn_idx) => {
+                    let c_type = self.c_type(types.expr_ty(expr));
+                    let wrapper = self.fn_wrapper(fn_idx);
+                    format!("({}){{{}, NULL}}", c_type, wrapper)
+                }
+                ResolvedPath::UnitStruct(idx) => {
+                    format!("({}){{0}}", self.c_type(&Ty::UnitStruct(idx)))
+                }
+                ResolvedPath::Const(idx) => {
+                    self.const_value(&types.consts_values[idx], &types.consts[idx])
+                }
+                ResolvedPath::Static(idx) => {
+                    let name = self.static_name(idx);
+                    self.tmp(&types.statics[idx], name)
+                }
+                ResolvedPath::TupleStruct(_)
+                | ResolvedPath::FieldsStruct(_)
+                | ResolvedPath::Enum(_)
+                | ResolvedPath::Variant(..)
+                | ResolvedPath::Builtin(_) => {
+                    unreachable!("The type checker rejects using them as values")
+                }
+            },
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                match call.path.resolved {
+                    ResolvedPath::Fn(fn_idx) => {
+                        let args = self.args(&call.args, &types.fns_signatures[fn_idx].0);
+                        let value = format!("{}({})", self.fn_name(fn_idx), args.join(", "));
+                        self.tmp(types.expr_ty(expr), value)
+                    }
+                    ResolvedPath::Builtin(builtin) => {
+                        let arg_ty = types.expr_ty(&call.args[0]);
+                        let arg = self.expr_as(&call.args[0], arg_ty);
+                        let print = self.print_call(arg_ty, &arg);
+                        self.line(format!("{};", print));
+                        if builtin == BuiltinFn::PrintLn {
+                            self.line("putchar('\\n');");
+                        }
+                        "0".to_string()
+                    }
+                    ResolvedPath::Local(local_idx) => {
+                        let Ty::Lambda(params, _) = &types.locals[local_idx] else {
+                            unreachable!()
+                        };
+                        let args = self.args(&call.args, params);
+                        let lvalue = self.ctx().locals[&local_idx].clone();
+                        let on = self.tmp(&types.locals[local_idx], lvalue);
+                        self.call_lambda(&on, args, types.expr_ty(expr))
+                    }
+                    _ => unreachable!("The type checker only accepts calling fns and lambdas"),
+                }
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                let on_ty = types.expr_ty(&call.on);
+                let on = self.expr(&call.on);
+                let on = self.tmp(on_ty, on);
+                let Ty::Lambda(params, _) = on_ty else {
+                    unreachable!("The type checker only accepts calling fns and lambdas")
+                };
+                let args = self.args(&call.args, params);
+                self.call_lambda(&on, args, types.expr_ty(expr))
+            }
+            ExprKindAndIndex::UNIT_STRUCT => match exprs.unit_structs[index].resolved {
+                ResolvedPath::UnitStruct(idx) => {
+                    format!("({}){{0}}", self.c_type(&Ty::UnitStruct(idx)))
+                }
+                ResolvedPath::Variant(enum_idx, variant_idx) => {
+                    self.variant_literal(enum_idx, variant_idx, vec![])
+                }
+                _ => unreachable!(),
+            },
+            ExprKindAndIndex::TUPLE_STRUCT => {
+                let tuple_struct = &exprs.tuple_structs[index];
+                match tuple_struct.path.resolved {
+                    ResolvedPath::TupleStruct(idx) => {
+                        let args = self.args(&tuple_struct.args, &types.tuple_structs[idx]);
+                        let c_type = self.c_type(&Ty::TupleStruct(idx));
+                        compound_literal(&c_type, &args)
+                    }
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        let args =
+                            self.args(&tuple_struct.args, &types.enums[enum_idx][variant_idx]);
+                        let values = args
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, arg)| format!("._{} = {}", i, arg))
+                            .collect();
+                        self.variant_literal(enum_idx, variant_idx, values)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                let fields_struct = &exprs.fields_structs[index];
+                let (names, fields_types): (Vec<_>, _) = match fields_struct.path.resolved {
+                    ResolvedPath::FieldsStruct(idx) => (
+                        nir.fields_structs[idx]
+                            .fields
+                            .iter()
+                            .map(|(_, name, _)| name.id)
+                            .collect(),
+                        &types.fields_structs[idx],
+                    ),
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        let VariantKind::Fields(fields) =
+                            &nir.enums[enum_idx].variants[variant_idx].kind
+                        else {
+                            unreachable!()
+                        };
+                        (
+                            fields.iter().map(|(name, _)| name.id).collect(),
+                            &types.enums[enum_idx][variant_idx],
+                        )
+                    }
+                    _ => unreachable!(),
+                };
+
+                // The fields are evaluated in the order they are written
+                let mut values = vec![];
+                for field in &fields_struct.fields {
+                    let field_idx = names.iter().position(|id| *id == field.name.id).unwrap();
+                    let value = self.expr_as(&field.expr, &fields_types[field_idx]);
+                    values.push(format!(".{} = {}", self.field_name(field.name.id), value));
+                }
+
+                match fields_struct.path.resolved {
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        self.variant_literal(enum_idx, variant_idx, values)
+                    }
+                    resolved => {
+                        let ResolvedPath::FieldsStruct(idx) = resolved else {
+                            unreachable!()
+                        };
+                        let c_type = self.c_type(&Ty::FieldsStruct(idx));
+                        compound_literal(&c_type, &values)
+                    }
+                }
+            }
+            ExprKindAndIndex::FIELD | ExprKindAndIndex::INDEX => {
+                let lvalue = self.place(expr);
+                self.tmp(types.expr_ty(expr), lvalue)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                let ty = types.expr_ty(expr);
+                let Ty::Array(element_ty, _) = ty else {
+                    unreachable!()
+                };
+                let mut elements = vec![];
+                for element in &exprs.array_elements[index].elements {
+                    elements.push(self.expr_as(element, element_ty));
+                }
+                let c_type = self.c_type(ty);
+                if elements.is_empty() {
+                    format!("({}){{0}}", c_type)
+                } else {
+                    format!("({}){{{{{}}}}}", c_type, elements.join(", "))
+                }
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let ty = types.expr_ty(expr);
+                let Ty::Array(element_ty, size) = ty else {
+                    unreachable!()
+                };
+                let repeat = self.expr_as(&exprs.array_elements_sized[index].repeat, element_ty);
+                let c_type = self.c_type(ty);
+                let name = self.tmp_name();
+                self.line(format!("{} {};", c_type, name));
+                self.line(format!(
+                    "for (uintptr_t i = 0; i < {}; i++) {}.elems[i] = {};",
+                    size, name, repeat
+                ));
+                name
+            }
+            ExprKindAndIndex::TUPLE_EXPR => {
+                let ty = types.expr_ty(expr);
+                let Ty::Tuple(elements_types) = ty else {
+                    unreachable!()
+                };
+                let elements = self.args(&exprs.tuples[index].elements, elements_types);
+                if elements.is_empty() {
+                    return "0".to_string();
+                }
+                let c_type = self.c_type(ty);
+                compound_literal(&c_type, &elements)
+            }
+            ExprKindAndIndex::RETURN => {
+                self.line("return 0;");
+                "0".to_string()
+            }
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                let return_ty = self.ctx().return_ty.clone();
+                let value = self.expr_as(&exprs.returns[index].expr_to_return, &return_ty);
+                self.line(format!("return {};", value));
+                "0".to_string()
+            }
+            ExprKindAndIndex::BREAK => {
+                self.line("break;");
+                "0".to_string()
+            }
+            ExprKindAndIndex::CONTINUE => {
+                self.line("continue;");
+                "0".to_string()
+            }
+            ExprKindAndIndex::IF_EXPR => {
+                let ty = types.expr_ty(expr);
+                if is_unit(ty) {
+                    self.gen_if(&exprs.ifs[index], None);
+                    return "0".to_string();
+                }
+                let c_type = self.c_type(ty);
+                let name = self.tmp_name();
+                self.line(format!("{} {};", c_type, name));
+                self.gen_if(&exprs.ifs[index], Some((name.as_str(), ty)));
+                name
+            }
+            ExprKindAndIndex::WHEN_EXPR => {
+                let ty = types.expr_ty(expr);
+                if is_unit(ty) {
+                    self.gen_when(&exprs.whens[index], None);
+                    return "0".to_string();
+                }
+                let c_type = self.c_type(ty);
+                let name = self.tmp_name();
+                self.line(format!("{} {};", c_type, name));
+                self.gen_when(&exprs.whens[index], Some((name.as_str(), ty)));
+                name
+            }
+            ExprKindAndIndex::LAMBDA_EXPR => self.lambda(index, types.expr_ty(expr)),
+            ExprKindAndIndex::UNARY_EXPR => self.unary_expr(&exprs.unary_exprs[index], expr),
+            ExprKindAndIndex::BIN_EXPR => self.bin_expr(&exprs.bin_exprs[index], expr),
+            _ => unreachable!("The type checker rejects the other expressions"),
+        }
+    }
+
+    /// Generates the expression as a value of the expected type, the expressions that never
+    /// complete have no value, so an uninitialized temporary of that type is given instead
+    pub(crate) fn expr_as(&mut self, expr: &Expr, ty: &Ty) -> String {
+        let value = self.expr(expr);
+
+        if *self.types.expr_ty(expr) != Ty::Never || is_unit(ty) {
+            return value;
+        }
+
+        let c_type = self.c_type(ty);
+        let name = self.tmp_name();
+        self.line(format!("{} {};", c_type, name));
+        name
+    }
+
+    fn args(&mut self, args: &[Expr], types: &[Ty]) -> Vec<String> {
+        args.iter()
+            .zip(types)
+            .map(|(arg, ty)| self.expr_as(arg, ty))
+            .collect()
+    }
+
+    /// Returns the C compound literal of the enum with the tag of the variant and the initializers of its fields
+    fn variant_literal(
+        &mut self,
+        enum_idx: usize,
+        variant_idx: usize,
+        values: Vec<String>,
+    ) -> String {
+        let c_type = self.c_type(&Ty::Enum(enum_idx));
+        if values.is_empty() {
+            format!("({}){{.tag = {}}}", c_type, variant_idx)
+        } else {
+            format!(
+                "({}){{.tag = {}, .v.v{} = {{{}}}}}",
+                c_type,
+                variant_idx,
+                variant_idx,
+                values.join(", ")
+            )
+        }
+    }
+
+    fn call_lambda(&mut self, on: &str, args: Vec<String>, return_ty: &Ty) -> String {
+        let mut args = args;
+        args.insert(0, format!("{}.env", on));
+        self.tmp(return_ty, format!("{}.fn({})", on, args.join(", ")))
+    }
+
+    fn literal(&mut self, literal: &LiteralExpr, expr: &Expr) -> String {
+        match literal {
+            LiteralExpr::Str(idx) => {
+                let s = &self.str_pool[*idx];
+                format!("(nazm_str){{{}, {}}}", c_str_lit(s), s.len())
+            }
+            LiteralExpr::Char(ch) => format!("{}u", *ch as u32),
+            LiteralExpr::Bool(b) => b.to_string(),
+            LiteralExpr::Num(num) => match *num {
+                NumKind::F4(f) => format!("{:?}f", f),
+                NumKind::F8(f) => format!("{:?}", f),
+                NumKind::I(i) => int_literal(PrimitiveType::I, i as i128),
+                NumKind::I1(i) => int_literal(PrimitiveType::I1, i as i128),
+                NumKind::I2(i) => int_literal(PrimitiveType::I2, i as i128),
+                NumKind::I4(i) => int_literal(PrimitiveType::I4, i as i128),
+                NumKind::I8(i) => int_literal(PrimitiveType::I8, i as i128),
+                NumKind::U(u) => int_literal(PrimitiveType::U, u as i128),
+                NumKind::U1(u) => int_literal(PrimitiveType::U1, u as i128),
+                NumKind::U2(u) => int_literal(PrimitiveType::U2, u as i128),
+                NumKind::U4(u) => int_literal(PrimitiveType::U4, u as i128),
+                NumKind::U8(u) => int_literal(PrimitiveType::U8, u as i128),
+                NumKind::UnspecifiedInt(u) => match self.types.expr_ty(expr) {
+                    Ty::Primitive(PrimitiveType::F4) => format!("{:?}f", u as f32),
+                    Ty::Primitive(PrimitiveType::F8) => format!("{:?}", u as f64),
+                    Ty::Primitive(p) => int_literal(*p, u as i128),
+                    _ => unreachable!(),
+                },
+                NumKind::UnspecifiedFloat(f) => match self.types.expr_ty(expr) {
+                    Ty::Primitive(PrimitiveType::F4) => format!("{:?}f", f as f32),
+                    _ => format!("{:?}", f),
+                },
+            },
+        }
+    }
+
+    /// Returns a C expression of the value computed at compile time
+    pub(crate) fn const_value(&mut self, value: &ConstValue, ty: &Ty) -> String {
+        match (value, ty) {
+            (ConstValue::Unit, _) => "0".to_string(),
+            (ConstValue::Int(i, p), _) => int_literal(*p, *i),
+            (ConstValue::Float(f, _), _) if f.is_nan() => "NAN".to_string(),
+            (ConstValue::Float(f, _), _) if f.is_infinite() => {
+                let sign = if *f < 0.0 { "-" } else { "" };
+                format!("{}INFINITY", sign)
+            }
+            (ConstValue::Float(f, PrimitiveType::F4), _) => format!("{:?}f", *f as f32),
+            (ConstValue::Float(f, _), _) => format!("{:?}", f),
+            (ConstValue::Bool(b), _) => b.to_string(),
+            (ConstValue::Char(ch), _) => format!("{}u", *ch as u32),
+            (ConstValue::Str(idx), _) => {
+                let s = &self.str_pool[*idx];
+                format!("(nazm_str){{{}, {}}}", c_str_lit(s), s.len())
+            }
+            (ConstValue::Tuple(values), Ty::Tuple(types)) => {
+                if values.is_empty() {
+                    return "0".to_string();
+                }
+                let values = values
+                    .iter()
+                    .zip(types)
+                    .map(|(value, ty)| self.const_value(value, ty))
+                    .collect::<Vec<_>>();
+                let c_type = self.c_type(ty);
+                compound_literal(&c_type, &values)
+            }
+            (ConstValue::Array(values), Ty::Array(element_ty, _)) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.const_value(value, element_ty))
+                    .collect::<Vec<_>>();
+                let c_type = self.c_type(ty);
+                if values.is_empty() {
+                    format!("({}){{0}}", c_type)
+                } else {
+                    format!("({}){{{{{}}}}}", c_type, values.join(", "))
+                }
+            }
+            _ => unreachable!("The type checker rejects the invalid constants"),
+        }
+    }
+
+    /// Generates the expression as a C lvalue, the expressions which are not places
+    /// are evaluated to temporaries
+    fn place(&mut self, expr: &Expr) -> String {
+        let types = self.types;
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                ResolvedPath::Local(local_idx) => self.ctx().locals[&local_idx].clone(),
+                ResolvedPath::Static(idx) => self.static_name(idx),
+                _ => self.expr(expr),
+            },
+            ExprKindAndIndex::PARENS => self.place(&exprs.parens[index].expr),
+            ExprKindAndIndex::FIELD => {
+                let field = &exprs.fields[index];
+                let on = self.place(&field.on);
+                let (on, _) = auto_deref(on, types.expr_ty(&field.on));
+                format!("{}.{}", on, self.field_name(field.name.id))
+            }
+            ExprKindAndIndex::INDEX => {
+                let index_expr = &exprs.indexes[index];
+                let on = self.place(&index_expr.on);
+                let (on, on_ty) = auto_deref(on, types.expr_ty(&index_expr.on));
+                let idx = self.expr(&index_expr.idx);
+                let is_negative = match types.expr_ty(&index_expr.idx) {
+                    Ty::Primitive(p) if p.is_signed_int() => format!("{} < 0", idx),
+                    _ => "0".to_string(),
+                };
+                let (len, elements) = match on_ty {
+                    Ty::Array(_, size) => (size.to_string(), "elems"),
+                    _ => (format!("{}.len", on), "ptr"),
+                };
+                let loc = self.loc(index_expr.idx.span);
+                let checked = self.tmp(
+                    &Ty::Primitive(PrimitiveType::U),
+                    format!(
+                        "nazm_check_index((long long){}, {}, {}, {})",
+                        idx, is_negative, len, loc
+                    ),
+                );
+                format!("{}.{}[{}]", on, elements, checked)
+            }
+            ExprKindAndIndex::UNARY_EXPR if exprs.unary_exprs[index].op == UnaryOp::Deref => {
+                let inner = &exprs.unary_exprs[index].expr;
+                let value = self.expr(inner);
+                match types.expr_ty(inner).pointee() {
+                    // The references to `متن` are the values of the strings
+                    Some(Ty::Primitive(PrimitiveType::Str)) => value,
+                    _ => format!("(*{})", value),
+                }
+            }
+            _ => {
+                let value = self.expr(expr);
+                self.tmp(types.expr_ty(expr), value)
+            }
+        }
+    }
+
+    fn lambda(&mut self, lambda_idx: usize, ty: &Ty) -> String {
+        let fn_name = self.gen_lambda_fn(lambda_idx, ty);
+        let captures = self.captures[lambda_idx].clone();
+        let env = self.tmp_name();
+
+        // Each lambda value has its own environment, so the lambdas are only equal to their copies
+        if captures.is_empty() {
+            self.line(format!("void *{} = nazm_alloc(0);", env));
+        } else {
+            self.line(format!(
+                "nazm_env_{0} *{1} = (nazm_env_{0} *)nazm_alloc(sizeof(nazm_env_{0}));",
+                lambda_idx, env
+            ));
+            for local_idx in captures {
+                let ptr = self.ctx().boxes[&local_idx].clone();
+                self.line(format!("{}->l{} = {};", env, local_idx, ptr));
+            }
+        }
+
+        let c_type = self.c_type(ty);
+        self.tmp(ty, format!("({}){{{}, {}}}", c_type, fn_name, env))
+    }
+
+    fn unary_expr(&mut self, unary: &UnaryExpr, expr: &Expr) -> String {
+        let types = self.types;
+        let ty = types.expr_ty(expr);
+
+        match unary.op {
+            UnaryOp::Minus | UnaryOp::BNot if *types.expr_ty(&unary.expr) == Ty::Never => {
+                self.expr(&unary.expr);
+                "0".to_string()
+            }
+            UnaryOp::Minus => {
+                let Ty::Primitive(p) = ty else { unreachable!() };
+
+                // The negated literal may be the min value which has no positive value to negate
+                if p.is_signed_int()
+                    && unary.expr.kind_and_index.kind() == ExprKindAndIndex::LITERAL
+                {
+                    let index = unary.expr.kind_and_index.index();
+                    if let LiteralExpr::Num(NumKind::UnspecifiedInt(u)) =
+                        self.nir.exprs.literals[index]
+                    {
+                        return int_literal(*p, -(u as i128));
+                    }
+                }
+
+                let value = self.expr(&unary.expr);
+                if p.is_float() {
+                    self.tmp(ty, format!("-{}", value))
+                } else if p.is_signed_int() {
+                    let loc = self.loc(expr.span);
+                    self.tmp(
+                        ty,
+                        format!("nazm_neg_{}({}, {})", ops_suffix(*p), value, loc),
+                    )
+                } else {
+                    unreachable!("The type checker rejects negating unsigned ints")
+                }
+            }
+            UnaryOp::LNot => {
+                let value = self.expr_as(&unary.expr, &Ty::bool());
+                self.tmp(ty, format!("!{}", value))
+            }
+            UnaryOp::BNot => {
+                let value = self.expr(&unary.expr);
+                let c_type = self.c_type(ty);
+                self.tmp(ty, format!("({})~{}", c_type, value))
+            }
+            UnaryOp::Deref => {
+                let lvalue = self.place(expr);
+                self.tmp(ty, lvalue)
+            }
+            UnaryOp::Borrow | UnaryOp::BorrowMut => {
+                let inner_ty = types.expr_ty(&unary.expr);
+
+                if *inner_ty == Ty::Primitive(PrimitiveType::Str) {
+                    return self.place(&unary.expr);
+                }
+
+                if self.is_place(&unary.expr) {
+                    let lvalue = self.place(&unary.expr);
+                    return self.tmp(ty, format!("&{}", lvalue));
+                }
+
+                // Borrowing a temporary value, so it is moved to the heap to outlive its scope
+                let value = self.expr_as(&unary.expr, inner_ty);
+                let c_type = self.c_type(inner_ty);
+                let ptr = self.tmp(ty, format!("({0} *)nazm_alloc(sizeof({0}))", c_type));
+                self.line(format!("*{} = {};", ptr, value));
+                ptr
+            }
+        }
+    }
+
+    fn bin_expr(&mut self, bin: &BinExpr, expr: &Expr) -> String {
+        let types = self.types;
+        let left_ty = types.expr_ty(&bin.left);
+        let right_ty = types.expr_ty(&bin.right);
+
+        match bin.op {
+            BinOp::LOr | BinOp::LAnd => {
+                let left = self.expr_as(&bin.left, &Ty::bool());
+                let name = self.tmp(&Ty::bool(), left);
+                // Short circuit
+                if bin.op == BinOp::LOr {
+                    self.line(format!("if (!{}) {{", name));
+                } else {
+                    self.line(format!("if ({}) {{", name));
+                }
+                self.ctx().indent += 1;
+                let right = self.expr_as(&bin.right, &Ty::bool());
+                self.line(format!("{} = {};", name, right));
+                self.ctx().indent -= 1;
+                self.line("}");
+                name
+            }
+            BinOp::EqualEqual | BinOp::NotEqual | BinOp::GE | BinOp::GT | BinOp::LE | BinOp::LT => {
+                let left = self.expr(&bin.left);
+                if *left_ty == Ty::Never {
+                    return "0".to_string();
+                }
+                let right = self.expr_as(&bin.right, left_ty);
+                let cmp = self.cmp_call(left_ty, &left, &right);
+                let ordering = self.tmp_name();
+                self.line(format!("int {} = {};", ordering, cmp));
+                let value = match bin.op {
+                    BinOp::EqualEqual => format!("{} == 0", ordering),
+                    BinOp::NotEqual => format!("{} != 0", ordering),
+                    BinOp::GE => format!("({0} == 1 || {0} == 0)", ordering),
+                    BinOp::GT => format!("{} == 1", ordering),
+                    BinOp::LE => format!("({0} == -1 || {0} == 0)", ordering),
+                    _ => format!("{} == -1", ordering),
+                };
+                self.tmp(&Ty::bool(), value)
+            }
+            BinOp::OpenOpenRange
+            | BinOp::CloseOpenRange
+            | BinOp::OpenCloseRange
+            | BinOp::CloseCloseRange => {
+                unreachable!("The type checker rejects ranges as values")
+            }
+            BinOp::Assign => {
+                let value = self.expr_as(&bin.right, left_ty);
+                let lvalue = self.place(&bin.left);
+                self.line(format!("{} = {};", lvalue, value));
+                "0".to_string()
+            }
+            BinOp::PlusAssign
+            | BinOp::MinusAssign
+            | BinOp::TimesAssign
+            | BinOp::DivAssign
+            | BinOp::ModAssign
+            | BinOp::BAndAssign
+            | BinOp::BOrAssign
+            | BinOp::XorAssign
+            | BinOp::ShlAssign
+            | BinOp::ShrAssign => {
+                let right = self.expr_as(&bin.right, right_ty);
+                let lvalue = self.place(&bin.left);
+                let ptr = self.tmp(
+                    &Ty::PtrMut(Box::new(left_ty.clone())),
+                    format!("&{}", lvalue),
+                );
+                let op = match bin.op {
+                    BinOp::PlusAssign => BinOp::Plus,
+                    BinOp::MinusAssign => BinOp::Minus,
+                    BinOp::TimesAssign => BinOp::Times,
+                    BinOp::DivAssign => BinOp::Div,
+                    BinOp::ModAssign => BinOp::Mod,
+                    BinOp::BAndAssign => BinOp::BAnd,
+                    BinOp::BOrAssign => BinOp::BOr,
+                    BinOp::XorAssign => BinOp::Xor,
+                    BinOp::ShlAssign => BinOp::Shl,
+                    _ => BinOp::Shr,
+                };
+                let value = self.arithmetic(
+                    op,
+                    left_ty,
+                    &format!("(*{})", ptr),
+                    &right,
+                    right_ty,
+                    expr.span,
+                );
+                self.line(format!("*{} = {};", ptr, value));
+                "0".to_string()
+            }
+            op => {
+                let left = self.expr(&bin.left);
+                if *left_ty == Ty::Never {
+                    return "0".to_string();
+                }
+                let right = if matches!(op, BinOp::Shl | BinOp::Shr) {
+                    self.expr_as(&bin.right, right_ty)
+                } else {
+                    self.expr_as(&bin.right, left_ty)
+                };
+                let value = self.arithmetic(op, left_ty, &left, &right, right_ty, expr.span);
+                self.tmp(left_ty, value)
+            }
+        }
+    }
+
+    /// Returns the C expression of the arithmetic operation, the int operations
+    /// are checked like the interpreter and report the errors at the span
+    fn arithmetic(
+        &mut self,
+        op: BinOp,
+        ty: &Ty,
+        left: &str,
+        right: &str,
+        right_ty: &Ty,
+        span: Span,
+    ) -> String {
+        let Ty::Primitive(p) = ty else {
+            unreachable!("The type checker only accepts numbers in arithmetic operations")
+        };
+
+        if p.is_float() {
+            return match op {
+                BinOp::Plus => format!("{} + {}", left, right),
+                BinOp::Minus => format!("{} - {}", left, right),
+                BinOp::Times => format!("{} * {}", left, right),
+                BinOp::Div => format!("{} / {}", left, right),
+                BinOp::Mod if *p == PrimitiveType::F4 => format!("fmodf({}, {})", left, right),
+                BinOp::Mod => format!("fmod({}, {})", left, right),
+                _ => unreachable!(),
+            };
+        }
+
+        let suffix = ops_suffix(*p);
+        let c_type = c_primitive(*p);
+
+        match op {
+            BinOp::BAnd => return format!("({})({} & {})", c_type, left, right),
+            BinOp::BOr => return format!("({})({} | {})", c_type, left, right),
+            BinOp::Xor => return format!("({})({} ^ {})", c_type, left, right),
+            _ => {}
+        }
+
+        let loc = self.loc(span);
+
+        match op {
+            BinOp::Plus => format!("nazm_add_{}({}, {}, {})", suffix, left, right, loc),
+            BinOp::Minus => format!("nazm_sub_{}({}, {}, {})", suffix, left, right, loc),
+            BinOp::Times => format!("nazm_mul_{}({}, {}, {})", suffix, left, right, loc),
+            BinOp::Div => format!("nazm_div_{}({}, {}, {})", suffix, left, right, loc),
+            BinOp::Mod => format!("nazm_rem_{}({}, {}, {})", suffix, left, right, loc),
+            BinOp::Shl | BinOp::Shr => {
+                let is_negative = match right_ty {
+                    Ty::Primitive(p) if p.is_signed_int() => format!("{} < 0", right),
+                    _ => "0".to_string(),
+                };
+                let name = if op == BinOp::Shl { "shl" } else { "shr" };
+                format!(
+                    "nazm_{}_{}({}, (long long){}, {}, {})",
+                    name, suffix, left, right, is_negative, loc
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_place(&self, expr: &Expr) -> bool {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => matches!(
+                exprs.paths[index].resolved,
+                ResolvedPath::Local(_) | ResolvedPath::Static(_)
+            ),
+            ExprKindAndIndex::FIELD | ExprKindAndIndex::INDEX => true,
+            ExprKindAndIndex::UNARY_EXPR => exprs.unary_exprs[index].op == UnaryOp::Deref,
+            ExprKindAndIndex::PARENS => self.is_place(&exprs.parens[index].expr),
+            _ => false,
+        }
+    }
+}
+
+/// Follows the pointers in the lvalue to the underlying value, like the type checker does,
+/// the references to `متن` and slices are kept as they are the values which are accessed
+fn auto_deref(mut lvalue: String, ty: &Ty) -> (String, &Ty) {
+    let mut ty = ty;
+    while let Some(pointee) = ty.pointee() {
+        if matches!(pointee, Ty::Slice(_) | Ty::Primitive(PrimitiveType::Str)) {
+            return (lvalue, pointee);
+        }
+        lvalue = format!("(*{})", lvalue);
+        ty = pointee;
+    }
+    (lvalue, ty)
+}
+
+fn compound_literal(c_type: &str, values: &[String]) -> String {
+    if values.is_empty() {
+        format!("({}){{0}}", c_type)
+    } else {
+        format!("({}){{{}}}", c_type, values.join(", "))
+    }
+}
+
+fn int_literal(p: PrimitiveType, value: i128) -> String {
+    let c_type = c_primitive(p);
+    if value == i64::MIN as i128 {
+        "INT64_MIN".to_string()
+    } else if value > i32::MAX as i128 {
+        format!("(({}){}ULL)", c_type, value)
+    } else {
+        format!("(({}){})", c_type, value)
+    }
+}
@@ -0,0 +1,928 @@
+use captures::LocalsCollector;
+use mangle::{c_str_lit, mangle_name, mangle_path};
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic, Numerals, SourceMap};
+use nazmc_nir::*;
+use nazmc_typeck::{Ty, TypesTable};
+use std::{collections::HashMap, fmt::Write};
+use thin_vec::ThinVec;
+
+mod captures;
+mod exprs;
+mod mangle;
+mod types;
+
+const PRELUDE: &str = include_str!("prelude.h");
+
+/// The messages used by the runtime errors in the prelude
+const MESSAGES: [(&str, &str); 5] = [
+    ("NAZM_MSG_ERROR", "خطأ"),
+    (
+        "NAZM_MSG_OVERFLOW",
+        "حدث فائض في العملية الحسابية للنوع `%s`",
+    ),
+    ("NAZM_MSG_DIV_BY_ZERO", "لا يمكن القسمة على صفر"),
+    (
+        "NAZM_MSG_BAD_SHIFT",
+        "لا يمكن إزاحة النوع `%s` بمقدار %s من البتات",
+    ),
+    (
+        "NAZM_MSG_INDEX_OUT_OF_BOUNDS",
+        "الفهرس %s خارج حدود المصفوفة ذات الطول %s",
+    ),
+];
+
+/// The state of the function or the lambda being generated
+struct FnCtx {
+    body: String,
+    indent: usize,
+    /// The count of the temporaries declared so far
+    tmps: usize,
+    /// The C lvalue of each local visible in the body
+    locals: HashMap<usize, String>,
+    /// The C pointer of each local visible in the body which is allocated on the heap
+    boxes: HashMap<usize, String>,
+    return_ty: Ty,
+}
+
+/// The checked program to translate with the pools and the files it refers to
+pub struct Program<'a> {
+    /// The pool used to preserve ids string values
+    pub id_pool: &'a DataPool<Built>,
+    /// The pool used to preserve the string literals values
+    pub str_pool: &'a DataPool<Built>,
+    /// The files of the spans, it is used in the locations of the runtime errors
+    pub source_map: &'a SourceMap,
+    pub packages_names: &'a [ThinVec<PoolIdx>],
+    pub packages_to_parsed_files: &'a [Vec<usize>],
+    pub nir: &'a NIR,
+    pub types: &'a TypesTable,
+}
+
+/// Translates the NIR into a single portable C source file
+pub struct CodeGen<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    /// The pool used to preserve the string literals values
+    str_pool: &'a DataPool<Built>,
+    /// The files of the spans, it is used in the locations of the runtime errors
+    source_map: &'a SourceMap,
+    /// The package path of each parsed file
+    files_packages: Vec<&'a [PoolIdx]>,
+    nir: &'a NIR,
+    types: &'a TypesTable,
+    /// The digits of the numbers in the output and in the runtime errors
+    numerals: Numerals,
+    /// The names of the generated C types of tuples, arrays, lambdas and slices,
+    /// with whether their definitions are generated
+    c_types: Vec<(Ty, String, bool)>,
+    /// Whether the struct definition is generated, with `None` if it is being generated
+    unit_structs_defined: Vec<Option<bool>>,
+    tuple_structs_defined: Vec<Option<bool>>,
+    fields_structs_defined: Vec<Option<bool>>,
+    enums_defined: Vec<Option<bool>>,
+    /// The types which have generated print functions
+    print_fns: Vec<Ty>,
+    /// The types which have generated compare functions
+    cmp_fns: Vec<Ty>,
+    /// Whether a function is used as a value, so it has a wrapper to be called as a lambda
+    fns_wrapped: Vec<bool>,
+    /// The sorted locals captured by each lambda
+    captures: Vec<Vec<usize>>,
+    /// Whether each local is allocated on the heap, which are the locals captured by any lambda
+    /// or borrowed, so the lambdas and the references could outlive the scopes of the locals
+    boxed: Vec<bool>,
+    ctxs: Vec<FnCtx>,
+    forward_decls: String,
+    type_defs: String,
+    protos: String,
+    fns_defs: String,
+}
+
+impl<'a> CodeGen<'a> {
+    pub fn new(program: Program<'a>, numerals: Numerals) -> Self {
+        let Program {
+            id_pool,
+            str_pool,
+            source_map,
+            packages_names,
+            packages_to_parsed_files,
+            nir,
+            types,
+        } = program;
+
+        let files_count = packages_to_parsed_files.iter().map(Vec::len).sum();
+        let mut files_packages: Vec<&[PoolIdx]> = vec![&[]; files_count];
+        for (pkg_idx, files) in packages_to_parsed_files.iter().enumerate() {
+            for file_idx in files {
+                files_packages[*file_idx] = &packages_names[pkg_idx];
+            }
+        }
+
+        Self {
+            id_pool,
+            str_pool,
+            source_map,
+            files_packages,
+            nir,
+            types,
+            numerals,
+            c_types: vec![],
+            unit_structs_defined: vec![Some(false); nir.unit_structs.len()],
+            tuple_structs_defined: vec![Some(false); nir.tuple_structs.len()],
+            fields_structs_defined: vec![Some(false); nir.fields_structs.len()],
+            enums_defined: vec![Some(false); nir.enums.len()],
+            print_fns: vec![],
+            cmp_fns: vec![],
+            fns_wrapped: vec![false; nir.fns.len()],
+            captures: vec![],
+            boxed: vec![false; nir.locals.len()],
+            ctxs: vec![],
+            forward_decls: String::new(),
+            type_defs: String::new(),
+            protos: String::new(),
+            fns_defs: String::new(),
+        }
+    }
+
+    /// Returns the C source of the whole program, or the errors if `البداية` is missing or invalid
+    pub fn generate(mut self) -> Result<String, Vec<Diagnostic>> {
+        let nir = self.nir;
+
+        let Some(main_idx) = nir.fns.iter().position(|f| f.name.id == PoolIdx::MAIN) else {
+            return Err(vec![Diagnostic::error(
+                "لم يتم العثور على الدالة `البداية` لبدء التنفيذ منها".to_string(),
+                vec![],
+            )]);
+        };
+
+        let main_fn = &nir.fns[main_idx];
+
+        if !main_fn.params.is_empty() {
+            let mut code_window = CodeWindow::new(main_fn.name.span);
+            code_window.mark_error(main_fn.name.span, vec![]);
+            return Err(vec![Diagnostic::error(
+                "يجب ألا تستقبل الدالة `البداية` أي مُعاملات".to_string(),
+                vec![code_window],
+            )]);
+        }
+
+        for lambda in &nir.exprs.lambdas {
+            let captures = LocalsCollector::new(nir).captures(lambda);
+            for local_idx in &captures {
+                self.boxed[*local_idx] = true;
+            }
+            self.captures.push(captures);
+        }
+
+        for unary in &nir.exprs.unary_exprs {
+            if matches!(unary.op, UnaryOp::Borrow | UnaryOp::BorrowMut) {
+                if let Some(local_idx) = self.place_root(&unary.expr) {
+                    self.boxed[local_idx] = true;
+                }
+            }
+        }
+
+        for (idx, s) in nir.unit_structs.iter().enumerate() {
+            let name = self.struct_name(s.file_idx, s.name.id);
+            writeln!(self.forward_decls, "typedef struct {0} {0};", name).unwrap();
+            self.define_unit_struct(idx);
+        }
+
+        for s in &nir.tuple_structs {
+            let name = self.struct_name(s.file_idx, s.name.id);
+            writeln!(self.forward_decls, "typedef struct {0} {0};", name).unwrap();
+        }
+
+        for s in &nir.fields_structs {
+            let name = self.struct_name(s.file_idx, s.name.id);
+            writeln!(self.forward_decls, "typedef struct {0} {0};", name).unwrap();
+        }
+
+        for e in &nir.enums {
+            let name = self.enum_name(e.file_idx, e.name.id);
+            writeln!(self.forward_decls, "typedef struct {0} {0};", name).unwrap();
+        }
+
+        for idx in 0..nir.tuple_structs.len() {
+            self.define_tuple_struct(idx);
+        }
+
+        for idx in 0..nir.fields_structs.len() {
+            self.define_fields_struct(idx);
+        }
+
+        for idx in 0..nir.enums.len() {
+            self.define_enum(idx);
+        }
+
+        for fn_idx in 0..nir.fns.len() {
+            self.gen_fn(fn_idx);
+        }
+
+        // The statics are initialized before running `البداية`,
+        // since the C initializers of the globals accept only constant expressions
+        let mut globals = String::new();
+        let mut statics_inits = String::new();
+
+        for (idx, value) in self.types.statics_values.iter().enumerate() {
+            let ty = &self.types.statics[idx];
+            let c_type = self.c_type(ty);
+            let name = self.static_name(idx);
+            let value = self.const_value(value, ty);
+            writeln!(globals, "static {} {};", c_type, name).unwrap();
+            writeln!(statics_inits, "    {} = {};", name, value).unwrap();
+        }
+
+        let mut out = String::new();
+
+        for (name, msg) in MESSAGES {
+            writeln!(out, "#define {} {}", name, c_str_lit(msg)).unwrap();
+        }
+
+        writeln!(
+            out,
+            "#define NAZM_ARABIC_INDIC_DIGITS {}",
+            (self.numerals == Numerals::ArabicIndic) as u8
+        )
+        .unwrap();
+
+        out.push('\n');
+        out.push_str(PRELUDE);
+        out.push('\n');
+
+        for p in PrimitiveType::ALL {
+            let (min, max) = match p {
+                PrimitiveType::I | PrimitiveType::I8 => ("INT64_MIN", "INT64_MAX"),
+                PrimitiveType::I1 => ("INT8_MIN", "INT8_MAX"),
+                PrimitiveType::I2 => ("INT16_MIN", "INT16_MAX"),
+                PrimitiveType::I4 => ("INT32_MIN", "INT32_MAX"),
+                PrimitiveType::U | PrimitiveType::U8 => ("0", "UINT64_MAX"),
+                PrimitiveType::U1 => ("0", "UINT8_MAX"),
+                PrimitiveType::U2 => ("0", "UINT16_MAX"),
+                PrimitiveType::U4 => ("0", "UINT32_MAX"),
+                _ => continue,
+            };
+            let c_type = c_primitive(p);
+            let name = c_str_lit(p.name());
+            let bits = int_bits(p);
+            if p.is_signed_int() {
+                let unsigned = c_type.replacen("int", "uint", 1);
+                writeln!(
+                    out,
+                    "NAZM_SIGNED_OPS({}, {}, {}, {}, {}, {}, {})",
+                    ops_suffix(p),
+                    c_type,
+                    unsigned,
+                    min,
+                    max,
+                    bits,
+                    name
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "NAZM_UNSIGNED_OPS({}, {}, {}, {}, {})",
+                    ops_suffix(p),
+                    c_type,
+                    max,
+                    bits,
+                    name
+                )
+                .unwrap();
+            }
+        }
+
+        out.push('\n');
+        out.push_str(&self.forward_decls);
+        out.push('\n');
+        out.push_str(&self.type_defs);
+        out.push('\n');
+        out.push_str(&globals);
+        out.push('\n');
+        out.push_str(&self.protos);
+        out.push('\n');
+        out.push_str(&self.fns_defs);
+
+        let main_name = self.fn_name(main_idx);
+        writeln!(
+            out,
+            "int main(void) {{\n{}    {}();\n    return 0;\n}}",
+            statics_inits, main_name
+        )
+        .unwrap();
+
+        Ok(out)
+    }
+
+    fn gen_fn(&mut self, fn_idx: usize) {
+        let f = &self.nir.fns[fn_idx];
+        let (params_tys, return_ty) = &self.types.fns_signatures[fn_idx];
+
+        let name = self.fn_name(fn_idx);
+        let return_type = self.c_type(return_ty);
+        let params = f
+            .params
+            .iter()
+            .zip(params_tys)
+            .map(|((local_idx, _), ty)| format!("{} p{}", self.c_type(ty), local_idx))
+            .collect::<Vec<_>>();
+        let params = if params.is_empty() {
+            "void".to_string()
+        } else {
+            params.join(", ")
+        };
+        let signature = format!("static {} {}({})", return_type, name, params);
+
+        writeln!(self.protos, "{};", signature).unwrap();
+
+        self.ctxs.push(FnCtx {
+            body: String::new(),
+            indent: 1,
+            tmps: 0,
+            locals: HashMap::new(),
+            boxes: HashMap::new(),
+            return_ty: return_ty.clone(),
+        });
+
+        for (local_idx, _) in &f.params {
+            self.declare_local(*local_idx, Some(format!("p{}", local_idx)));
+        }
+
+        self.gen_body(f.body);
+
+        let ctx = self.ctxs.pop().unwrap();
+        writeln!(self.fns_defs, "{} {{\n{}}}\n", signature, ctx.body).unwrap();
+    }
+
+    /// Generates the lambda function and returns its name
+    fn gen_lambda_fn(&mut self, lambda_idx: usize, lambda_ty: &Ty) -> String {
+        let lambda = &self.nir.exprs.lambdas[lambda_idx];
+        let Ty::Lambda(params_tys, return_ty) = lambda_ty else {
+            unreachable!()
+        };
+
+        let name = format!("nazm_lambda_fn_{}", lambda_idx);
+        let env_name = format!("nazm_env_{}", lambda_idx);
+        let captures = self.captures[lambda_idx].clone();
+
+        if !captures.is_empty() {
+            let mut fields = String::new();
+            for local_idx in &captures {
+                let c_type = self.c_type(&self.types.locals[*local_idx]);
+                writeln!(fields, "    {} *l{};", c_type, local_idx).unwrap();
+            }
+            writeln!(
+                self.type_defs,
+                "typedef struct {0} {{\n{1}}} {0};\n",
+                env_name, fields
+            )
+            .unwrap();
+        }
+
+        let return_type = self.c_type(return_ty);
+        let mut params = vec!["void *nazm_env".to_string()];
+        for (i, ty) in params_tys.iter().enumerate() {
+            params.push(format!("{} a{}", self.c_type(ty), i));
+        }
+        let signature = format!("static {} {}({})", return_type, name, params.join(", "));
+
+        writeln!(self.protos, "{};", signature).unwrap();
+
+        self.ctxs.push(FnCtx {
+            body: String::new(),
+            indent: 1,
+            tmps: 0,
+            locals: HashMap::new(),
+            boxes: HashMap::new(),
+            return_ty: *return_ty.clone(),
+        });
+
+        if captures.is_empty() {
+            self.line("(void)nazm_env;");
+        } else {
+            self.line(format!("{0} *env = ({0} *)nazm_env;", env_name));
+            for local_idx in captures {
+                let ptr = format!("env->l{}", local_idx);
+                let ctx = self.ctx();
+                ctx.locals.insert(local_idx, format!("(*{})", ptr));
+                ctx.boxes.insert(local_idx, ptr);
+            }
+        }
+
+        for (i, param) in lambda.params.iter().enumerate() {
+            self.bind(&param.kind, Some(format!("a{}", i)));
+        }
+
+        self.gen_body(lambda.body);
+
+        let ctx = self.ctxs.pop().unwrap();
+        writeln!(self.fns_defs, "{} {{\n{}}}\n", signature, ctx.body).unwrap();
+
+        name
+    }
+
+    /// Returns the name of the wrapper which calls the function like a lambda
+    fn fn_wrapper(&mut self, fn_idx: usize) -> String {
+        let name = format!("nazm_fn_wrapper_{}", fn_idx);
+
+        if self.fns_wrapped[fn_idx] {
+            return name;
+        }
+
+        self.fns_wrapped[fn_idx] = true;
+
+        let (params_tys, return_ty) = &self.types.fns_signatures[fn_idx];
+        let mut params = vec!["void *nazm_env".to_string()];
+        let mut args = vec![];
+        for (i, ty) in params_tys.iter().enumerate() {
+            params.push(format!("{} a{}", self.c_type(ty), i));
+            args.push(format!("a{}", i));
+        }
+        let signature = format!(
+            "static {} {}({})",
+            self.c_type(return_ty),
+            name,
+            params.join(", ")
+        );
+
+        writeln!(self.protos, "{};", signature).unwrap();
+        writeln!(
+            self.fns_defs,
+            "{} {{\n    (void)nazm_env;\n    return {}({});\n}}\n",
+            signature,
+            self.fn_name(fn_idx),
+            args.join(", ")
+        )
+        .unwrap();
+
+        name
+    }
+
+    /// Generates the body of the current function or lambda which returns its value
+    fn gen_body(&mut self, body: Scope) {
+        let return_ty = self.ctx().return_ty.clone();
+        let c_type = self.c_type(&return_ty);
+
+        self.line(format!("{} nazm_ret;", c_type));
+        self.gen_scope(body, Some(("nazm_ret", &return_ty)));
+        self.line("return nazm_ret;");
+    }
+
+    /// Generates the scope in a C block, and assigns its value to the target if it is given
+    fn gen_scope(&mut self, scope: Scope, target: Option<(&str, &Ty)>) {
+        let scope = &self.nir.scopes[scope.index];
+
+        self.line("{");
+        self.ctx().indent += 1;
+
+        for stm in &scope.stms {
+            self.gen_stm(stm);
+        }
+
+        match (&scope.return_expr, target) {
+            (Some(expr), Some((target, ty))) => {
+                let value = self.expr_as(expr, ty);
+                self.line(format!("{} = {};", target, value));
+            }
+            (Some(expr), None) => {
+                self.expr(expr);
+            }
+            (None, Some((target, Ty::Unit | Ty::Never))) => {
+                self.line(format!("{} = 0;", target));
+            }
+            (None, Some((target, Ty::Tuple(types)))) if types.is_empty() => {
+                self.line(format!("{} = 0;", target));
+            }
+            // The scope has no value of other types unless all its paths diverge
+            (None, Some(_)) => {}
+            (None, None) => {}
+        }
+
+        self.ctx().indent -= 1;
+        self.line("}");
+    }
+
+    fn gen_stm(&mut self, stm: &Stm) {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET => self.gen_let_stm(&stms.lets[index]),
+            StmKindAndIndex::LET_MUT => self.gen_let_stm(&stms.let_muts[index]),
+            StmKindAndIndex::IF => self.gen_if(&stms.ifs[index], None),
+            StmKindAndIndex::WHILE => {
+                let while_stm = &stms.whiles[index];
+                self.line("while (1) {");
+                self.ctx().indent += 1;
+                let condition = self.expr_as(&while_stm.condition, &Ty::bool());
+                self.line(format!("if (!{}) break;", condition));
+                self.gen_scope(while_stm.scope, None);
+                self.ctx().indent -= 1;
+                self.line("}");
+            }
+            StmKindAndIndex::DO_WHILE => {
+                // The condition is skipped in the first iteration only,
+                // so `وصل` goes to checking it like the other iterations
+                let do_while_stm = &stms.do_whiles[index];
+                let first = self.tmp_name();
+                self.line(format!("for (bool {0} = true; ; {0} = false) {{", first));
+                self.ctx().indent += 1;
+                self.line(format!("if (!{}) {{", first));
+                self.ctx().indent += 1;
+                let condition = self.expr_as(&do_while_stm.condition, &Ty::bool());
+                self.line(format!("if (!{}) break;", condition));
+                self.ctx().indent -= 1;
+                self.line("}");
+                self.gen_scope(do_while_stm.scope, None);
+                self.ctx().indent -= 1;
+                self.line("}");
+            }
+            StmKindAndIndex::WHEN => self.gen_when(&stms.whens[index], None),
+            StmKindAndIndex::EXPR => {
+                self.expr(&stms.exprs[index]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn gen_let_stm(&mut self, let_stm: &LetStm) {
+        let value = let_stm.assign.as_ref().map(|expr| {
+            let ty = self.binding_ty(&let_stm.binding.kind);
+            self.expr_as(expr, &ty)
+        });
+
+        self.bind(&let_stm.binding.kind, value);
+    }
+
+    /// Generates the `لو` chain, and assigns its value to the target if it is given
+    fn gen_if(&mut self, if_expr: &IfExpr, target: Option<(&str, &Ty)>) {
+        let condition = self.expr_as(&if_expr.if_.condition, &Ty::bool());
+        self.line(format!("if ({})", condition));
+        self.gen_scope(if_expr.if_.scope, target);
+
+        // The conditions of the else ifs may need statements before them,
+        // so each one is nested in the else block of the previous one
+        for else_if in &if_expr.else_ifs {
+            self.line("else {");
+            self.ctx().indent += 1;
+            let condition = self.expr_as(&else_if.condition, &Ty::bool());
+            self.line(format!("if ({})", condition));
+            self.gen_scope(else_if.scope, target);
+        }
+
+        if let Some(scope) = if_expr.else_ {
+            self.line("else");
+            self.gen_scope(scope, target);
+        }
+
+        for _ in &if_expr.else_ifs {
+            self.ctx().indent -= 1;
+            self.line("}");
+        }
+    }
+
+    /// Generates the `عندما` arms as blocks which jump to the next arm when their patterns
+    /// or guards don't match, and assigns the value of the matched arm to the target if it is given
+    ///
+    /// Jumps are used instead of nested ifs, so `قطع` and `وصل` in the arms still reach the loops
+    fn gen_when(&mut self, when_expr: &WhenExpr, target: Option<(&str, &Ty)>) {
+        let ty = self.types.expr_ty(&when_expr.expr).clone();
+        let value = self.expr_as(&when_expr.expr, &ty);
+        let value = self.tmp(&ty, value);
+        let end = format!("nazm_when_end_{}", self.tmp_name());
+
+        for arm in &when_expr.arms {
+            let next = format!("nazm_when_next_{}", self.tmp_name());
+
+            self.line("{");
+            self.ctx().indent += 1;
+
+            self.gen_pattern_test(&arm.pattern, &value, &ty, &next);
+
+            if let Some(guard) = &arm.guard {
+                let guard = self.expr_as(guard, &Ty::bool());
+                self.line(format!("if (!{}) goto {};", guard, next));
+            }
+
+            self.gen_scope(arm.body, target);
+            self.line(format!("goto {};", end));
+
+            self.ctx().indent -= 1;
+            self.line("}");
+            self.line(format!("{}:;", next));
+        }
+
+        self.line(format!("{}:;", end));
+    }
+
+    /// Jumps to the label if the value doesn't match the pattern, and declares the bound locals
+    fn gen_pattern_test(&mut self, pattern: &Pattern, value: &str, ty: &Ty, label: &str) {
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::UnitStruct(path) => self.gen_variant_test(path, value, label),
+            PatternKind::Binding(local_idx) => {
+                self.declare_local(*local_idx, Some(value.to_string()))
+            }
+            PatternKind::Literal(expr) => {
+                let literal = self.expr_as(expr, ty);
+                let cmp = self.cmp_call(ty, value, &literal);
+                self.line(format!("if ({} != 0) goto {};", cmp, label));
+            }
+            PatternKind::Range(range) => {
+                let start = self.expr_as(&range.start, ty);
+                let end = self.expr_as(&range.end, ty);
+                let after_start = self.cmp_call(ty, value, &start);
+                let before_end = self.cmp_call(ty, value, &end);
+                let (start_op, end_op) = match range.op {
+                    BinOp::OpenOpenRange => ("<=", ">="),
+                    BinOp::OpenCloseRange => ("<=", ">"),
+                    BinOp::CloseOpenRange => ("<", ">="),
+                    _ => ("<", ">"),
+                };
+                self.line(format!(
+                    "if ({} {} 0 || {} {} 0) goto {};",
+                    after_start, start_op, before_end, end_op, label
+                ));
+            }
+            PatternKind::Tuple(patterns) => {
+                let Ty::Tuple(types) = ty else {
+                    // The pattern `()` of the unit type
+                    return;
+                };
+                for (i, (pattern, ty)) in patterns.iter().zip(types).enumerate() {
+                    let element = format!("{}._{}", value, i);
+                    self.gen_pattern_test(pattern, &element, ty, label);
+                }
+            }
+            PatternKind::TupleStruct(path, patterns) => {
+                let (fields, types) = self.pattern_fields(path, value, ty);
+                self.gen_variant_test(path, value, label);
+                for ((pattern, field), ty) in patterns.iter().zip(fields).zip(&types) {
+                    self.gen_pattern_test(pattern, &field, ty, label);
+                }
+            }
+            PatternKind::FieldsStruct(path, fields) => {
+                let (values, types) = self.pattern_fields(path, value, ty);
+                let names = match path.resolved {
+                    ResolvedPath::FieldsStruct(idx) => self.nir.fields_structs[idx]
+                        .fields
+                        .iter()
+                        .map(|(_, name, _)| name.id)
+                        .collect::<Vec<_>>(),
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        match &self.nir.enums[enum_idx].variants[variant_idx].kind {
+                            VariantKind::Fields(fields) => {
+                                fields.iter().map(|(name, _)| name.id).collect()
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                    _ => unreachable!(
+                        "The type checker only accepts fields structs and fields variants in fields patterns"
+                    ),
+                };
+                self.gen_variant_test(path, value, label);
+                for (name, pattern) in fields {
+                    let field_idx = names.iter().position(|id| *id == name.id).unwrap();
+                    self.gen_pattern_test(pattern, &values[field_idx], &types[field_idx], label);
+                }
+            }
+        }
+    }
+
+    /// Jumps to the label if the path is resolved to a variant and the value is of another variant
+    fn gen_variant_test(&mut self, path: &ModPathWithItem, value: &str, label: &str) {
+        if let ResolvedPath::Variant(_, variant_idx) = path.resolved {
+            self.line(format!(
+                "if ({}.tag != {}) goto {};",
+                value, variant_idx, label
+            ));
+        }
+    }
+
+    /// Returns the C expressions and the types of the fields of the struct or the variant value
+    /// by the order of their declaration
+    fn pattern_fields(
+        &self,
+        path: &ModPathWithItem,
+        value: &str,
+        ty: &Ty,
+    ) -> (Vec<String>, ThinVec<Ty>) {
+        match (path.resolved, ty) {
+            (ResolvedPath::Variant(enum_idx, variant_idx), _) => {
+                let fields = self
+                    .variant_fields_names(enum_idx, variant_idx)
+                    .into_iter()
+                    .map(|name| format!("{}.v.v{}.{}", value, variant_idx, name))
+                    .collect();
+                (fields, self.types.enums[enum_idx][variant_idx].clone())
+            }
+            (_, Ty::TupleStruct(idx)) => {
+                let types = self.types.tuple_structs[*idx].clone();
+                let fields = (0..types.len())
+                    .map(|i| format!("{}._{}", value, i))
+                    .collect();
+                (fields, types)
+            }
+            (_, Ty::FieldsStruct(idx)) => {
+                let fields = self.nir.fields_structs[*idx]
+                    .fields
+                    .iter()
+                    .map(|(_, name, _)| format!("{}.{}", value, self.field_name(name.id)))
+                    .collect();
+                (fields, self.types.fields_structs[*idx].clone())
+            }
+            _ => unreachable!("The type checker only accepts struct patterns of structs and enums"),
+        }
+    }
+
+    /// Declares the locals of the binding with the value, destructuring the tuples
+    fn bind(&mut self, kind: &BindingKind, value: Option<String>) {
+        match kind {
+            BindingKind::Local(local_idx) => self.declare_local(*local_idx, value),
+            BindingKind::TupleDestruction(kinds, _) => {
+                for (i, kind) in kinds.iter().enumerate() {
+                    let element = value.as_ref().map(|value| format!("{}._{}", value, i));
+                    self.bind(kind, element);
+                }
+            }
+        }
+    }
+
+    fn declare_local(&mut self, local_idx: usize, value: Option<String>) {
+        let c_type = self.c_type(&self.types.locals[local_idx]);
+        let name = format!("l{}", local_idx);
+
+        if self.boxed[local_idx] {
+            self.line(format!(
+                "{0} *{1} = ({0} *)nazm_alloc(sizeof({0}));",
+                c_type, name
+            ));
+            if let Some(value) = value {
+                self.line(format!("*{} = {};", name, value));
+            }
+            let ctx = self.ctx();
+            ctx.locals.insert(local_idx, format!("(*{})", name));
+            ctx.boxes.insert(local_idx, name);
+        } else {
+            match value {
+                Some(value) => self.line(format!("{} {} = {};", c_type, name, value)),
+                None => self.line(format!("{} {};", c_type, name)),
+            }
+            self.ctx().locals.insert(local_idx, name);
+        }
+    }
+
+    fn binding_ty(&self, kind: &BindingKind) -> Ty {
+        match kind {
+            BindingKind::Local(local_idx) => self.types.locals[*local_idx].clone(),
+            BindingKind::TupleDestruction(kinds, _) => {
+                Ty::Tuple(kinds.iter().map(|kind| self.binding_ty(kind)).collect())
+            }
+        }
+    }
+
+    /// Returns the local whose value contains the place, if any
+    fn place_root(&self, expr: &Expr) -> Option<usize> {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                ResolvedPath::Local(local_idx) => Some(local_idx),
+                _ => None,
+            },
+            ExprKindAndIndex::PARENS => self.place_root(&exprs.parens[index].expr),
+            ExprKindAndIndex::FIELD => self.place_root(&exprs.fields[index].on),
+            ExprKindAndIndex::INDEX => self.place_root(&exprs.indexes[index].on),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn ctx(&mut self) -> &mut FnCtx {
+        self.ctxs.last_mut().unwrap()
+    }
+
+    /// Writes a line of C code in the current body
+    fn line(&mut self, line: impl AsRef<str>) {
+        let ctx = self.ctx();
+        for _ in 0..ctx.indent {
+            ctx.body.push_str("    ");
+        }
+        ctx.body.push_str(line.as_ref());
+        ctx.body.push('\n');
+    }
+
+    /// Declares a new temporary with the value and returns its name
+    fn tmp(&mut self, ty: &Ty, value: impl AsRef<str>) -> String {
+        let c_type = self.c_type(ty);
+        let name = self.tmp_name();
+        self.line(format!("{} {} = {};", c_type, name, value.as_ref()));
+        name
+    }
+
+    fn tmp_name(&mut self) -> String {
+        let ctx = self.ctx();
+        ctx.tmps += 1;
+        format!("t{}", ctx.tmps)
+    }
+
+    /// The location of the span as a C string literal, it is reported by the runtime errors
+    fn loc(&self, span: Span) -> String {
+        let file = &self.source_map[span.file_id];
+        let cursor = file.cursor(span.lo);
+        c_str_lit(&format!(
+            "{}:{}:{}",
+            file.path(),
+            self.numerals.fmt(cursor.line + 1),
+            self.numerals.fmt(cursor.col + 1)
+        ))
+    }
+
+    fn fn_name(&self, fn_idx: usize) -> String {
+        let f = &self.nir.fns[fn_idx];
+        self.item_name("nzF", f.file_idx, f.name.id)
+    }
+
+    fn static_name(&self, static_idx: usize) -> String {
+        let s = &self.nir.statics[static_idx];
+        self.item_name("nzG", s.file_idx, s.name.id)
+    }
+
+    fn struct_name(&self, file_idx: usize, id: PoolIdx) -> String {
+        self.item_name("nzS", file_idx, id)
+    }
+
+    fn enum_name(&self, file_idx: usize, id: PoolIdx) -> String {
+        self.item_name("nzE", file_idx, id)
+    }
+
+    fn item_name(&self, prefix: &str, file_idx: usize, id: PoolIdx) -> String {
+        let segments = self.files_packages[file_idx]
+            .iter()
+            .chain([&id])
+            .map(|id| &self.id_pool[*id]);
+        mangle_path(prefix, segments)
+    }
+
+    fn field_name(&self, id: PoolIdx) -> String {
+        format!("f_{}", mangle_name(&self.id_pool[id]))
+    }
+}
+
+/// Whether the type has no values other than `()`
+fn is_unit(ty: &Ty) -> bool {
+    match ty {
+        Ty::Unit | Ty::Never => true,
+        Ty::Tuple(types) => types.is_empty(),
+        _ => false,
+    }
+}
+
+/// The C type of the primitive type, the unsized `متن` is always used behind a reference
+fn c_primitive(p: PrimitiveType) -> &'static str {
+    match p {
+        PrimitiveType::I | PrimitiveType::I8 => "int64_t",
+        PrimitiveType::I1 => "int8_t",
+        PrimitiveType::I2 => "int16_t",
+        PrimitiveType::I4 => "int32_t",
+        PrimitiveType::U | PrimitiveType::U8 => "uint64_t",
+        PrimitiveType::U1 => "uint8_t",
+        PrimitiveType::U2 => "uint16_t",
+        PrimitiveType::U4 => "uint32_t",
+        PrimitiveType::F4 => "float",
+        PrimitiveType::F8 => "double",
+        PrimitiveType::Bool => "bool",
+        PrimitiveType::Char => "uint32_t",
+        PrimitiveType::Str => "nazm_str",
+    }
+}
+
+/// The suffix of the checked arithmetic functions of the int type in the prelude
+fn ops_suffix(p: PrimitiveType) -> &'static str {
+    match p {
+        PrimitiveType::I => "i",
+        PrimitiveType::I1 => "i1",
+        PrimitiveType::I2 => "i2",
+        PrimitiveType::I4 => "i4",
+        PrimitiveType::I8 => "i8",
+        PrimitiveType::U => "u",
+        PrimitiveType::U1 => "u1",
+        PrimitiveType::U2 => "u2",
+        PrimitiveType::U4 => "u4",
+        PrimitiveType::U8 => "u8",
+        _ => unreachable!("Not an int type"),
+    }
+}
+
+fn int_bits(p: PrimitiveType) -> u32 {
+    match p {
+        PrimitiveType::I1 | PrimitiveType::U1 => 8,
+        PrimitiveType::I2 | PrimitiveType::U2 => 16,
+        PrimitiveType::I4 | PrimitiveType::U4 => 32,
+        _ => 64,
+    }
+}
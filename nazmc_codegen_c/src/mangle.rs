@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+/// Mangles a name deterministically into a valid C identifier, the ASCII letters and digits
+/// are kept and any other char is replaced by its code point in hex between two underscores
+/// (e.g. `نقطة` becomes `_646__642__637__629_`)
+pub(crate) fn mangle_name(name: &str) -> String {
+    let mut mangled = String::with_capacity(name.len());
+
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            mangled.push(ch);
+        } else {
+            write!(mangled, "_{:x}_", ch as u32).unwrap();
+        }
+    }
+
+    mangled
+}
+
+/// Mangles the path of an item like the Itanium ABI, each mangled segment is prefixed
+/// by its length so different paths could never have the same mangled name,
+/// and `prefix` distinguishes the kinds of items
+pub(crate) fn mangle_path<'a>(prefix: &str, segments: impl IntoIterator<Item = &'a str>) -> String {
+    let mut mangled = prefix.to_string();
+
+    for segment in segments {
+        let segment = mangle_name(segment);
+        write!(mangled, "{}{}", segment.len(), segment).unwrap();
+    }
+
+    mangled
+}
+
+/// Writes the string as a C string literal, escaping all the bytes which are not printable ASCII
+pub(crate) fn c_str_lit(s: &str) -> String {
+    let mut lit = String::with_capacity(s.len() + 2);
+
+    lit.push('"');
+
+    for byte in s.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => write!(lit, "\\{}", byte as char).unwrap(),
+            b' '..=b'~' => lit.push(byte as char),
+            // Octal escapes have at most 3 digits, so the next char will never be a part of them
+            _ => write!(lit, "\\{:03o}", byte).unwrap(),
+        }
+    }
+
+    lit.push('"');
+
+    lit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mangle_name() {
+        assert_eq!(mangle_name("main"), "main");
+        assert_eq!(mangle_name("نقطة"), "_646__642__637__629_");
+        assert_eq!(mangle_name("جمع_2"), "_62c__645__639__5f_2");
+    }
+
+    #[test]
+    fn test_mangle_path() {
+        assert_eq!(mangle_path("nzF", ["أ", "ب"]), "nzF5_623_5_628_");
+        assert_ne!(
+            mangle_path("nzF", ["أب", "ت"]),
+            mangle_path("nzF", ["أ", "بت"])
+        );
+    }
+
+    #[test]
+    fn test_c_str_lit() {
+        assert_eq!(c_str_lit("a\"b\\"), r#""a\"b\\""#);
+        assert_eq!(c_str_lit("\n"), r#""\012""#);
+        assert_eq!(c_str_lit("س1"), r#""\330\2631""#);
+    }
+}
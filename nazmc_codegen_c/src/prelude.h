@@ -0,0 +1,233 @@
+/*
+ * The runtime support of the C code generated by nazmc, the messages (`NAZM_MSG_*`)
+ * and `NAZM_ARABIC_INDIC_DIGITS` are defined before it, and the ops macros are instantiated after it for each int type
+ */
+
+#include <math.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* The unit type `()`, it is also used for the type `!!` */
+typedef uint8_t nazm_unit;
+
+/* The references to `متن` */
+typedef struct nazm_str {
+    const char *ptr;
+    uintptr_t len;
+} nazm_str;
+
+/* Copies the formatted number to `out` with the selected digits, the decimal point becomes `٫` with the
+ * Eastern Arabic-Indic digits which are two bytes each in UTF-8, so `out` is twice as large as `num` */
+static const char *nazm_localize_num(const char *num, char *out) {
+    char *o = out;
+    for (; *num; num++) {
+        if (NAZM_ARABIC_INDIC_DIGITS && *num >= '0' && *num <= '9') {
+            *o++ = (char)0xD9;
+            *o++ = (char)(0xA0 + (*num - '0'));
+        } else if (NAZM_ARABIC_INDIC_DIGITS && *num == '.') {
+            *o++ = (char)0xD9;
+            *o++ = (char)0xAB;
+        } else {
+            *o++ = *num;
+        }
+    }
+    *o = '\0';
+    return out;
+}
+
+static void nazm_panic(const char *msg, const char *loc) {
+    fflush(stdout);
+    fprintf(stderr, NAZM_MSG_ERROR ": %s\n  --> %s\n", msg, loc);
+    exit(101);
+}
+
+static void nazm_overflow(const char *type_name, const char *loc) {
+    char msg[128];
+    snprintf(msg, sizeof msg, NAZM_MSG_OVERFLOW, type_name);
+    nazm_panic(msg, loc);
+}
+
+static void nazm_div_by_zero(const char *loc) {
+    nazm_panic(NAZM_MSG_DIV_BY_ZERO, loc);
+}
+
+static void nazm_bad_shift(const char *type_name, long long amount, const char *loc) {
+    char msg[160], num[32], amount_str[64];
+    snprintf(num, sizeof num, "%lld", amount);
+    snprintf(msg, sizeof msg, NAZM_MSG_BAD_SHIFT, type_name, nazm_localize_num(num, amount_str));
+    nazm_panic(msg, loc);
+}
+
+static uintptr_t nazm_check_index(long long idx, int is_negative, uintptr_t len, const char *loc) {
+    if (is_negative || (unsigned long long)idx >= len) {
+        char msg[160], num[32], idx_str[64], len_str[64];
+        snprintf(num, sizeof num, "%lld", idx);
+        nazm_localize_num(num, idx_str);
+        snprintf(num, sizeof num, "%llu", (unsigned long long)len);
+        snprintf(msg, sizeof msg, NAZM_MSG_INDEX_OUT_OF_BOUNDS, idx_str, nazm_localize_num(num, len_str));
+        nazm_panic(msg, loc);
+    }
+    return (uintptr_t)idx;
+}
+
+static void *nazm_alloc(size_t size) {
+    void *ptr = malloc(size ? size : 1);
+    if (!ptr) {
+        fputs("out of memory\n", stderr);
+        exit(101);
+    }
+    return ptr;
+}
+
+/* The checked arithmetic of the signed ints, they report the overflow like the interpreter */
+#define NAZM_SIGNED_OPS(N, T, UT, MIN, MAX, BITS, NAME)                                        \
+    static T nazm_add_##N(T a, T b, const char *loc) {                                         \
+        if ((b > 0 && a > MAX - b) || (b < 0 && a < MIN - b)) nazm_overflow(NAME, loc);        \
+        return (T)(a + b);                                                                     \
+    }                                                                                          \
+    static T nazm_sub_##N(T a, T b, const char *loc) {                                         \
+        if ((b < 0 && a > MAX + b) || (b > 0 && a < MIN + b)) nazm_overflow(NAME, loc);        \
+        return (T)(a - b);                                                                     \
+    }                                                                                          \
+    static T nazm_mul_##N(T a, T b, const char *loc) {                                         \
+        if (a > 0 ? (b > 0 ? a > MAX / b : b < MIN / a)                                        \
+                  : (b > 0 ? a < MIN / b : (a != 0 && b < MAX / a)))                           \
+            nazm_overflow(NAME, loc);                                                          \
+        return (T)(a * b);                                                                     \
+    }                                                                                          \
+    static T nazm_div_##N(T a, T b, const char *loc) {                                         \
+        if (b == 0) nazm_div_by_zero(loc);                                                     \
+        if (a == MIN && b == -1) nazm_overflow(NAME, loc);                                     \
+        return (T)(a / b);                                                                     \
+    }                                                                                          \
+    static T nazm_rem_##N(T a, T b, const char *loc) {                                         \
+        if (b == 0) nazm_div_by_zero(loc);                                                     \
+        if (b == -1) return 0; /* `MIN % -1` is undefined in C */                              \
+        return (T)(a % b);                                                                     \
+    }                                                                                          \
+    static T nazm_neg_##N(T a, const char *loc) {                                              \
+        if (a == MIN) nazm_overflow(NAME, loc);                                                \
+        return (T)-a;                                                                          \
+    }                                                                                          \
+    static T nazm_shl_##N(T a, long long b, int is_negative, const char *loc) {                \
+        if (is_negative || (unsigned long long)b >= BITS) nazm_bad_shift(NAME, b, loc);        \
+        return (T)(UT)((unsigned long long)(UT)a << b);                                        \
+    }                                                                                          \
+    static T nazm_shr_##N(T a, long long b, int is_negative, const char *loc) {                \
+        if (is_negative || (unsigned long long)b >= BITS) nazm_bad_shift(NAME, b, loc);        \
+        return (T)(a >> b);                                                                    \
+    }
+
+/* The checked arithmetic of the unsigned ints */
+#define NAZM_UNSIGNED_OPS(N, T, MAX, BITS, NAME)                                               \
+    static T nazm_add_##N(T a, T b, const char *loc) {                                         \
+        if (a > MAX - b) nazm_overflow(NAME, loc);                                             \
+        return (T)(a + b);                                                                     \
+    }                                                                                          \
+    static T nazm_sub_##N(T a, T b, const char *loc) {                                         \
+        if (a < b) nazm_overflow(NAME, loc);                                                   \
+        return (T)(a - b);                                                                     \
+    }                                                                                          \
+    static T nazm_mul_##N(T a, T b, const char *loc) {                                         \
+        if (b != 0 && a > MAX / b) nazm_overflow(NAME, loc);                                   \
+        return (T)(a * b);                                                                     \
+    }                                                                                          \
+    static T nazm_div_##N(T a, T b, const char *loc) {                                         \
+        if (b == 0) nazm_div_by_zero(loc);                                                     \
+        return (T)(a / b);                                                                     \
+    }                                                                                          \
+    static T nazm_rem_##N(T a, T b, const char *loc) {                                         \
+        if (b == 0) nazm_div_by_zero(loc);                                                     \
+        return (T)(a % b);                                                                     \
+    }                                                                                          \
+    static T nazm_shl_##N(T a, long long b, int is_negative, const char *loc) {                \
+        if (is_negative || (unsigned long long)b >= BITS) nazm_bad_shift(NAME, b, loc);        \
+        return (T)((unsigned long long)a << b);                                                \
+    }                                                                                          \
+    static T nazm_shr_##N(T a, long long b, int is_negative, const char *loc) {                \
+        if (is_negative || (unsigned long long)b >= BITS) nazm_bad_shift(NAME, b, loc);        \
+        return (T)(a >> b);                                                                    \
+    }
+
+/* Prints the formatted number with the selected digits */
+static void nazm_print_num(const char *num) {
+    char out[128];
+    fputs(nazm_localize_num(num, out), stdout);
+}
+
+static void nazm_print_int(long long i) {
+    char num[32];
+    snprintf(num, sizeof num, "%lld", i);
+    nazm_print_num(num);
+}
+
+static void nazm_print_uint(unsigned long long u) {
+    char num[32];
+    snprintf(num, sizeof num, "%llu", u);
+    nazm_print_num(num);
+}
+
+/* Prints the shortest representation which reads back to the same float, like the interpreter */
+static void nazm_print_float(double f, int is_f4) {
+    char buf[64];
+    double abs = f < 0 ? -f : f;
+    int is_exp = abs != 0 && (abs < 1e-4 || abs >= 1e16);
+    if (isnan(f)) {
+        fputs("NaN", stdout);
+        return;
+    }
+    if (isinf(f)) {
+        fputs(f > 0 ? "inf" : "-inf", stdout);
+        return;
+    }
+    for (int precision = is_exp ? 0 : 1; precision <= 17; precision++) {
+        snprintf(buf, sizeof buf, is_exp ? "%.*e" : "%.*f", precision, f);
+        if (is_f4 ? (float)strtod(buf, NULL) == (float)f : strtod(buf, NULL) == f) break;
+    }
+    if (is_exp) {
+        /* Write the exponent without the plus sign and the leading zeros */
+        char *e = strchr(buf, 'e');
+        int exp = atoi(e + 1);
+        snprintf(e, sizeof buf - (e - buf), "e%d", exp);
+    }
+    nazm_print_num(buf);
+}
+
+static void nazm_print_char(uint32_t ch) {
+    char buf[4];
+    int len;
+    if (ch < 0x80) {
+        buf[0] = (char)ch;
+        len = 1;
+    } else if (ch < 0x800) {
+        buf[0] = (char)(0xC0 | (ch >> 6));
+        buf[1] = (char)(0x80 | (ch & 0x3F));
+        len = 2;
+    } else if (ch < 0x10000) {
+        buf[0] = (char)(0xE0 | (ch >> 12));
+        buf[1] = (char)(0x80 | ((ch >> 6) & 0x3F));
+        buf[2] = (char)(0x80 | (ch & 0x3F));
+        len = 3;
+    } else {
+        buf[0] = (char)(0xF0 | (ch >> 18));
+        buf[1] = (char)(0x80 | ((ch >> 12) & 0x3F));
+        buf[2] = (char)(0x80 | ((ch >> 6) & 0x3F));
+        buf[3] = (char)(0x80 | (ch & 0x3F));
+        len = 4;
+    }
+    fwrite(buf, 1, len, stdout);
+}
+
+static void nazm_print_str(nazm_str s) { fwrite(s.ptr, 1, s.len, stdout); }
+
+/* Returns -1, 0 or 1 like the ordering of the values, or 2 if they are not ordered */
+#define NAZM_CMP(a, b) ((a) < (b) ? -1 : (a) > (b) ? 1 : (a) == (b) ? 0 : 2)
+
+static int nazm_cmp_str(nazm_str a, nazm_str b) {
+    int c = memcmp(a.ptr, b.ptr, a.len < b.len ? a.len : b.len);
+    if (c != 0) return c < 0 ? -1 : 1;
+    return NAZM_CMP(a.len, b.len);
+}
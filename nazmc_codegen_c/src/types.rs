@@ -0,0 +1,545 @@
+use crate::{c_primitive, mangle::c_str_lit, CodeGen};
+use nazmc_nir::{PrimitiveType, VariantKind};
+use nazmc_typeck::Ty;
+use std::fmt::Write;
+
+impl<'a> CodeGen<'a> {
+    /// Returns the C type of the type, and generates its definition if it is not generated yet
+    pub(crate) fn c_type(&mut self, ty: &Ty) -> String {
+        match ty {
+            Ty::UnitStruct(idx) => {
+                self.define_unit_struct(*idx);
+                self.c_type_name(ty)
+            }
+            Ty::TupleStruct(idx) => {
+                self.define_tuple_struct(*idx);
+                self.c_type_name(ty)
+            }
+            Ty::FieldsStruct(idx) => {
+                self.define_fields_struct(*idx);
+                self.c_type_name(ty)
+            }
+            Ty::Enum(idx) => {
+                self.define_enum(*idx);
+                self.c_type_name(ty)
+            }
+            Ty::Tuple(types) if !types.is_empty() => self.compound_type(ty, true),
+            Ty::Array(..) | Ty::Lambda(..) => self.compound_type(ty, true),
+            _ => self.c_type_name(ty),
+        }
+    }
+
+    /// Returns the C type of the type without generating its definition,
+    /// the definition is not needed behind pointers and in the signatures of lambdas
+    fn c_type_name(&mut self, ty: &Ty) -> String {
+        match ty {
+            Ty::Unit | Ty::Never => "nazm_unit".to_string(),
+            Ty::Primitive(p) => c_primitive(*p).to_string(),
+            Ty::UnitStruct(idx) => {
+                let s = &self.nir.unit_structs[*idx];
+                self.struct_name(s.file_idx, s.name.id)
+            }
+            Ty::TupleStruct(idx) => {
+                let s = &self.nir.tuple_structs[*idx];
+                self.struct_name(s.file_idx, s.name.id)
+            }
+            Ty::FieldsStruct(idx) => {
+                let s = &self.nir.fields_structs[*idx];
+                self.struct_name(s.file_idx, s.name.id)
+            }
+            Ty::Enum(idx) => {
+                let e = &self.nir.enums[*idx];
+                self.enum_name(e.file_idx, e.name.id)
+            }
+            Ty::Ptr(pointee) | Ty::Ref(pointee) | Ty::PtrMut(pointee) | Ty::RefMut(pointee) => {
+                match &**pointee {
+                    Ty::Primitive(PrimitiveType::Str) => "nazm_str".to_string(),
+                    Ty::Slice(_) => self.compound_type(pointee, true),
+                    pointee => format!("{} *", self.c_type_name(pointee)),
+                }
+            }
+            Ty::Tuple(types) if types.is_empty() => "nazm_unit".to_string(),
+            Ty::Slice(_) | Ty::Tuple(_) | Ty::Array(..) | Ty::Lambda(..) => {
+                self.compound_type(ty, false)
+            }
+            Ty::Infer(_) | Ty::Error => unreachable!("The type checker resolves all types"),
+        }
+    }
+
+    /// Returns the name of the C struct of the tuple, the array, the lambda or the slice type,
+    /// and generates its definition if `define` is true
+    fn compound_type(&mut self, ty: &Ty, define: bool) -> String {
+        let idx = match self.c_types.iter().position(|(t, _, _)| t == ty) {
+            Some(idx) => idx,
+            None => {
+                let kind = match ty {
+                    Ty::Tuple(_) => "tuple",
+                    Ty::Array(..) => "array",
+                    Ty::Lambda(..) => "lambda",
+                    _ => "slice",
+                };
+                let name = format!("nazm_{}_{}", kind, self.c_types.len());
+                writeln!(self.forward_decls, "typedef struct {0} {0};", name).unwrap();
+                self.c_types.push((ty.clone(), name, false));
+                self.c_types.len() - 1
+            }
+        };
+
+        let name = self.c_types[idx].1.clone();
+
+        if !define || self.c_types[idx].2 {
+            return name;
+        }
+
+        self.c_types[idx].2 = true;
+
+        let mut members = String::new();
+
+        match ty {
+            Ty::Tuple(types) => {
+                for (i, ty) in types.iter().enumerate() {
+                    writeln!(members, "    {} _{};", self.c_type(ty), i).unwrap();
+                }
+            }
+            Ty::Array(ty, size) => {
+                // C does not accept arrays of zero length
+                let size = (*size).max(1);
+                writeln!(members, "    {} elems[{}];", self.c_type(ty), size).unwrap();
+            }
+            Ty::Lambda(params, return_ty) => {
+                let mut params_types = vec!["void *".to_string()];
+                for ty in params {
+                    params_types.push(self.c_type_name(ty));
+                }
+                writeln!(
+                    members,
+                    "    {} (*fn)({});\n    void *env;",
+                    self.c_type_name(return_ty),
+                    params_types.join(", ")
+                )
+                .unwrap();
+            }
+            Ty::Slice(ty) => {
+                writeln!(
+                    members,
+                    "    {} *ptr;\n    uintptr_t len;",
+                    self.c_type_name(ty)
+                )
+                .unwrap();
+            }
+            _ => unreachable!(),
+        }
+
+        writeln!(self.type_defs, "struct {} {{\n{}}};\n", name, members).unwrap();
+
+        name
+    }
+
+    pub(crate) fn define_unit_struct(&mut self, idx: usize) {
+        if self.unit_structs_defined[idx] != Some(false) {
+            return;
+        }
+
+        let s = &self.nir.unit_structs[idx];
+        let name = self.struct_name(s.file_idx, s.name.id);
+
+        // C does not accept empty structs
+        writeln!(
+            self.type_defs,
+            "struct {} {{\n    nazm_unit _;\n}};\n",
+            name
+        )
+        .unwrap();
+
+        self.unit_structs_defined[idx] = Some(true);
+    }
+
+    pub(crate) fn define_tuple_struct(&mut self, idx: usize) {
+        if self.tuple_structs_defined[idx] != Some(false) {
+            return;
+        }
+
+        self.tuple_structs_defined[idx] = None;
+
+        let s = &self.nir.tuple_structs[idx];
+        let name = self.struct_name(s.file_idx, s.name.id);
+        let mut members = String::new();
+
+        for (i, ty) in self.types.tuple_structs[idx].iter().enumerate() {
+            writeln!(members, "    {} _{};", self.c_type(ty), i).unwrap();
+        }
+
+        if members.is_empty() {
+            members.push_str("    nazm_unit _;\n");
+        }
+
+        writeln!(self.type_defs, "struct {} {{\n{}}};\n", name, members).unwrap();
+
+        self.tuple_structs_defined[idx] = Some(true);
+    }
+
+    pub(crate) fn define_fields_struct(&mut self, idx: usize) {
+        if self.fields_structs_defined[idx] != Some(false) {
+            return;
+        }
+
+        self.fields_structs_defined[idx] = None;
+
+        let s = &self.nir.fields_structs[idx];
+        let name = self.struct_name(s.file_idx, s.name.id);
+        let mut members = String::new();
+
+        for ((_, field_name, _), ty) in s.fields.iter().zip(&self.types.fields_structs[idx]) {
+            let c_type = self.c_type(ty);
+            writeln!(
+                members,
+                "    {} {};",
+                c_type,
+                self.field_name(field_name.id)
+            )
+            .unwrap();
+        }
+
+        if members.is_empty() {
+            members.push_str("    nazm_unit _;\n");
+        }
+
+        writeln!(self.type_defs, "struct {} {{\n{}}};\n", name, members).unwrap();
+
+        self.fields_structs_defined[idx] = Some(true);
+    }
+
+    /// Defines the enum as a struct of the tag of the variant and a union of the fields of all variants,
+    /// the fields of each variant are in a struct named by the variant index
+    pub(crate) fn define_enum(&mut self, idx: usize) {
+        if self.enums_defined[idx] != Some(false) {
+            return;
+        }
+
+        self.enums_defined[idx] = None;
+
+        let e = &self.nir.enums[idx];
+        let name = self.enum_name(e.file_idx, e.name.id);
+        // C does not accept empty unions
+        let mut members = String::from("        nazm_unit _;\n");
+
+        for variant_idx in 0..e.variants.len() {
+            let names = self.variant_fields_names(idx, variant_idx);
+            if names.is_empty() {
+                continue;
+            }
+            members.push_str("        struct {\n");
+            for (name, ty) in names.iter().zip(&self.types.enums[idx][variant_idx]) {
+                let c_type = self.c_type(ty);
+                writeln!(members, "            {} {};", c_type, name).unwrap();
+            }
+            writeln!(members, "        }} v{};", variant_idx).unwrap();
+        }
+
+        writeln!(
+            self.type_defs,
+            "struct {} {{\n    uint32_t tag;\n    union {{\n{}    }} v;\n}};\n",
+            name, members
+        )
+        .unwrap();
+
+        self.enums_defined[idx] = Some(true);
+    }
+
+    /// The C names of the fields of the variant, they are `_0`, `_1`, ... for the tuple variants
+    pub(crate) fn variant_fields_names(&self, enum_idx: usize, variant_idx: usize) -> Vec<String> {
+        match &self.nir.enums[enum_idx].variants[variant_idx].kind {
+            VariantKind::Unit => vec![],
+            VariantKind::Tuple(types) => (0..types.len()).map(|i| format!("_{}", i)).collect(),
+            VariantKind::Fields(fields) => fields
+                .iter()
+                .map(|(name, _)| self.field_name(name.id))
+                .collect(),
+        }
+    }
+
+    /// Returns the C statement which prints the value like `اظهر` does in the interpreter
+    pub(crate) fn print_call(&mut self, ty: &Ty, value: &str) -> String {
+        match ty {
+            Ty::Unit | Ty::Never => "fputs(\"()\", stdout)".to_string(),
+            Ty::Tuple(types) if types.is_empty() => "fputs(\"()\", stdout)".to_string(),
+            Ty::Primitive(p) if p.is_signed_int() => format!("nazm_print_int({})", value),
+            Ty::Primitive(p) if p.is_unsigned_int() => format!("nazm_print_uint({})", value),
+            Ty::Primitive(PrimitiveType::F4) => format!("nazm_print_float({}, 1)", value),
+            Ty::Primitive(PrimitiveType::F8) => format!("nazm_print_float({}, 0)", value),
+            Ty::Primitive(PrimitiveType::Bool) => format!(
+                "fputs({} ? {} : {}, stdout)",
+                value,
+                c_str_lit("صحيح"),
+                c_str_lit("فاسد")
+            ),
+            Ty::Primitive(PrimitiveType::Char) => format!("nazm_print_char({})", value),
+            Ty::Primitive(_) => format!("nazm_print_str({})", value),
+            Ty::Ptr(pointee) | Ty::Ref(pointee) | Ty::PtrMut(pointee) | Ty::RefMut(pointee) => {
+                match &**pointee {
+                    Ty::Primitive(PrimitiveType::Str) => format!("nazm_print_str({})", value),
+                    Ty::Slice(_) => format!("{}({})", self.print_fn(pointee), value),
+                    pointee => self.print_call(pointee, &format!("(*{})", value)),
+                }
+            }
+            _ => format!("{}({})", self.print_fn(ty), value),
+        }
+    }
+
+    /// Returns the name of the generated function which prints the values of the compound type
+    fn print_fn(&mut self, ty: &Ty) -> String {
+        if let Some(idx) = self.print_fns.iter().position(|t| t == ty) {
+            return format!("nazm_print_{}", idx);
+        }
+
+        let name = format!("nazm_print_{}", self.print_fns.len());
+        self.print_fns.push(ty.clone());
+
+        // Slices are passed as their references
+        let c_type = match ty {
+            Ty::Slice(_) => self.c_type(&Ty::Ref(Box::new(ty.clone()))),
+            _ => self.c_type(ty),
+        };
+        let signature = format!("static void {}({} v)", name, c_type);
+        writeln!(self.protos, "{};", signature).unwrap();
+
+        let mut body = String::new();
+        let puts = |body: &mut String, s: &str| {
+            writeln!(body, "    fputs({}, stdout);", c_str_lit(s)).unwrap();
+        };
+
+        match ty {
+            Ty::UnitStruct(idx) => puts(
+                &mut body,
+                &self.id_pool[self.nir.unit_structs[*idx].name.id],
+            ),
+            Ty::TupleStruct(idx) => {
+                puts(
+                    &mut body,
+                    &self.id_pool[self.nir.tuple_structs[*idx].name.id],
+                );
+                puts(&mut body, "(");
+                for (i, ty) in self.types.tuple_structs[*idx].iter().enumerate() {
+                    if i != 0 {
+                        puts(&mut body, "، ");
+                    }
+                    let call = self.print_call(ty, &format!("v._{}", i));
+                    writeln!(body, "    {};", call).unwrap();
+                }
+                puts(&mut body, ")");
+            }
+            Ty::FieldsStruct(idx) => {
+                let s = &self.nir.fields_structs[*idx];
+                puts(&mut body, &self.id_pool[s.name.id]);
+                puts(&mut body, " { ");
+                for (i, ((_, field_name, _), ty)) in s
+                    .fields
+                    .iter()
+                    .zip(&self.types.fields_structs[*idx])
+                    .enumerate()
+                {
+                    if i != 0 {
+                        puts(&mut body, "، ");
+                    }
+                    puts(&mut body, &format!("{} = ", &self.id_pool[field_name.id]));
+                    let value = format!("v.{}", self.field_name(field_name.id));
+                    let call = self.print_call(ty, &value);
+                    writeln!(body, "    {};", call).unwrap();
+                }
+                puts(&mut body, " }");
+            }
+            Ty::Enum(idx) => {
+                let e = &self.nir.enums[*idx];
+                body.push_str("    switch (v.tag) {\n");
+                for (variant_idx, variant) in e.variants.iter().enumerate() {
+                    writeln!(body, "    case {}:", variant_idx).unwrap();
+                    puts(
+                        &mut body,
+                        &format!(
+                            "{}::{}",
+                            &self.id_pool[e.name.id], &self.id_pool[variant.name.id]
+                        ),
+                    );
+                    let names = self.variant_fields_names(*idx, variant_idx);
+                    let types = self.types.enums[*idx][variant_idx].clone();
+                    let (open, close) = match &variant.kind {
+                        VariantKind::Unit => {
+                            body.push_str("    break;\n");
+                            continue;
+                        }
+                        VariantKind::Tuple(_) => ("(", ")"),
+                        VariantKind::Fields(_) => (" { ", " }"),
+                    };
+                    puts(&mut body, open);
+                    for (i, (name, ty)) in names.iter().zip(&types).enumerate() {
+                        if i != 0 {
+                            puts(&mut body, "، ");
+                        }
+                        if let VariantKind::Fields(fields) = &variant.kind {
+                            puts(&mut body, &format!("{} = ", &self.id_pool[fields[i].0.id]));
+                        }
+                        let value = format!("v.v.v{}.{}", variant_idx, name);
+                        let call = self.print_call(ty, &value);
+                        writeln!(body, "    {};", call).unwrap();
+                    }
+                    puts(&mut body, close);
+                    body.push_str("    break;\n");
+                }
+                body.push_str("    }\n");
+            }
+            Ty::Tuple(types) => {
+                puts(&mut body, "(");
+                for (i, ty) in types.iter().enumerate() {
+                    if i != 0 {
+                        puts(&mut body, "، ");
+                    }
+                    let call = self.print_call(ty, &format!("v._{}", i));
+                    writeln!(body, "    {};", call).unwrap();
+                }
+                puts(&mut body, ")");
+            }
+            Ty::Array(elem_ty, _) | Ty::Slice(elem_ty) => {
+                let (len, element) = elements_access(ty, "v");
+                puts(&mut body, "[");
+                writeln!(body, "    for (uintptr_t i = 0; i < {}; i++) {{", len).unwrap();
+                writeln!(
+                    body,
+                    "        if (i != 0) fputs({}, stdout);",
+                    c_str_lit("، ")
+                )
+                .unwrap();
+                let call = self.print_call(elem_ty, &element);
+                writeln!(
+                    body,
+                    "        {};
+    }}",
+                    call
+                )
+                .unwrap();
+                puts(&mut body, "]");
+            }
+            Ty::Lambda(..) => puts(&mut body, "لامدا"),
+            _ => unreachable!("Only compound types have print functions"),
+        }
+
+        writeln!(self.fns_defs, "{} {{\n{}}}\n", signature, body).unwrap();
+
+        name
+    }
+
+    /// Returns the C expression which compares the two values like the interpreter,
+    /// it is -1, 0 or 1 like the ordering of the values, or 2 if they are not ordered
+    pub(crate) fn cmp_call(&mut self, ty: &Ty, a: &str, b: &str) -> String {
+        match ty {
+            Ty::Unit | Ty::Never | Ty::UnitStruct(_) => "0".to_string(),
+            Ty::Tuple(types) if types.is_empty() => "0".to_string(),
+            Ty::Primitive(PrimitiveType::Str) => format!("nazm_cmp_str({}, {})", a, b),
+            Ty::Primitive(_) => format!("NAZM_CMP({}, {})", a, b),
+            Ty::Ptr(pointee) | Ty::Ref(pointee) | Ty::PtrMut(pointee) | Ty::RefMut(pointee) => {
+                match &**pointee {
+                    Ty::Primitive(PrimitiveType::Str) => format!("nazm_cmp_str({}, {})", a, b),
+                    Ty::Slice(_) => format!("{}({}, {})", self.cmp_fn(pointee), a, b),
+                    pointee => self.cmp_call(pointee, &format!("(*{})", a), &format!("(*{})", b)),
+                }
+            }
+            _ => format!("{}({}, {})", self.cmp_fn(ty), a, b),
+        }
+    }
+
+    /// Returns the name of the generated function which compares the values of the compound type
+    fn cmp_fn(&mut self, ty: &Ty) -> String {
+        if let Some(idx) = self.cmp_fns.iter().position(|t| t == ty) {
+            return format!("nazm_cmp_{}", idx);
+        }
+
+        let name = format!("nazm_cmp_{}", self.cmp_fns.len());
+        self.cmp_fns.push(ty.clone());
+
+        // Slices are passed as their references
+        let c_type = match ty {
+            Ty::Slice(_) => self.c_type(&Ty::Ref(Box::new(ty.clone()))),
+            _ => self.c_type(ty),
+        };
+        let signature = format!("static int {}({1} a, {1} b)", name, c_type);
+        writeln!(self.protos, "{};", signature).unwrap();
+
+        let mut body = String::new();
+        match ty {
+            Ty::TupleStruct(idx) => {
+                let types = self.types.tuple_structs[*idx].clone();
+                let names = (0..types.len()).map(|i| format!("_{}", i)).collect();
+                self.cmp_elements(&mut body, &types, names);
+            }
+            Ty::FieldsStruct(idx) => {
+                let types = self.types.fields_structs[*idx].clone();
+                let names = self.nir.fields_structs[*idx]
+                    .fields
+                    .iter()
+                    .map(|(_, field_name, _)| self.field_name(field_name.id))
+                    .collect();
+                self.cmp_elements(&mut body, &types, names);
+            }
+            // The variants are ordered as they are declared in the enum
+            Ty::Enum(idx) => {
+                body.push_str("    if (a.tag != b.tag) return NAZM_CMP(a.tag, b.tag);\n");
+                if self.types.enums[*idx].iter().any(|types| !types.is_empty()) {
+                    body.push_str("    int c;\n");
+                }
+                body.push_str("    switch (a.tag) {\n");
+                for variant_idx in 0..self.nir.enums[*idx].variants.len() {
+                    writeln!(body, "    case {}:", variant_idx).unwrap();
+                    let names = self.variant_fields_names(*idx, variant_idx);
+                    let types = self.types.enums[*idx][variant_idx].clone();
+                    for (name, ty) in names.iter().zip(&types) {
+                        let a = format!("a.v.v{}.{}", variant_idx, name);
+                        let b = format!("b.v.v{}.{}", variant_idx, name);
+                        let call = self.cmp_call(ty, &a, &b);
+                        writeln!(body, "        if ((c = {}) != 0) return c;", call).unwrap();
+                    }
+                    body.push_str("        break;\n");
+                }
+                body.push_str("    }\n    return 0;\n");
+            }
+            Ty::Tuple(types) => {
+                let names = (0..types.len()).map(|i| format!("_{}", i)).collect();
+                self.cmp_elements(&mut body, types, names);
+            }
+            Ty::Array(elem_ty, _) | Ty::Slice(elem_ty) => {
+                let (a_len, a_element) = elements_access(ty, "a");
+                let (b_len, b_element) = elements_access(ty, "b");
+                let call = self.cmp_call(elem_ty, &a_element, &b_element);
+                writeln!(
+                    body,
+                    "    int c;\n    for (uintptr_t i = 0; i < {0} && i < {1}; i++) {{\n        if ((c = {2}) != 0) return c;\n    }}\n    return NAZM_CMP({0}, {1});",
+                    a_len, b_len, call
+                )
+                .unwrap();
+            }
+            // Lambdas are only equal to their copies
+            Ty::Lambda(..) => body.push_str("    return a.fn == b.fn && a.env == b.env ? 0 : 2;\n"),
+            _ => unreachable!("Only compound types have compare functions"),
+        }
+
+        writeln!(self.fns_defs, "{} {{\n{}}}\n", signature, body).unwrap();
+
+        name
+    }
+
+    /// Writes the body which compares the elements in order and returns the first unequal ordering
+    fn cmp_elements(&mut self, body: &mut String, types: &[Ty], names: Vec<String>) {
+        body.push_str("    int c;\n");
+        for (ty, name) in types.iter().zip(names) {
+            let call = self.cmp_call(ty, &format!("a.{}", name), &format!("b.{}", name));
+            writeln!(body, "    if ((c = {}) != 0) return c;", call).unwrap();
+        }
+        body.push_str("    return 0;\n");
+    }
+}
+
+/// Returns the C expressions of the length and the element at the index `i` of the array or the slice
+fn elements_access(ty: &Ty, value: &str) -> (String, String) {
+    match ty {
+        Ty::Array(_, size) => (size.to_string(), format!("{}.elems[i]", value)),
+        _ => (format!("{}.len", value), format!("{}.ptr[i]", value)),
+    }
+}
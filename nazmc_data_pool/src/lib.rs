@@ -8,6 +8,9 @@ pub struct PoolIdx(usize);
 impl PoolIdx {
     pub const MAIN: Self = Self(0);
     pub const LAMBDA_IMPLICIT_PARAM: Self = Self(1);
+    pub const PRINT: Self = Self(2);
+    pub const PRINTLN: Self = Self(3);
+    pub const UNDERSCORE: Self = Self(4);
 }
 
 mod private {
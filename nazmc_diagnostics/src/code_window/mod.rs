@@ -9,96 +9,178 @@ use itertools::Itertools;
 use owo_colors::{OwoColorize, Style};
 use painter::Painter;
 
-use crate::span::{Span, SpanCursor};
+use crate::{
+    span::{Span, SpanCursor},
+    FileId, Numerals, SourceFile, WithSourceMap,
+};
 
 mod painter;
 
-pub struct CodeWindow<'a> {
-    file_path: &'a str,
-    file_lines: &'a [String],
-    cursor: SpanCursor,
-    /// Map lines indecies and main depth line on them
-    code_lines: HashMap<usize, CodeLine<'a>>,
+pub struct CodeWindow {
+    /// The file of the code lines in the source map
+    pub(crate) file_id: FileId,
+    /// The byte offset where the code window is located
+    pub(crate) lo: u32,
+    /// The marks in the order they were added, their lines are computed from the file when the
+    /// code window is rendered
+    pub(crate) marks: Vec<Mark>,
+}
+
+/// A marked span with its labels as it was added to the code window
+pub struct Mark {
+    pub(crate) kind: MarkKind,
+    pub(crate) span: Span,
+    pub(crate) labels: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarkKind {
+    Error,
+    Warning,
+    Help,
+    Note,
+    Secondary,
+    Tertiary,
+}
+
+impl Mark {
+    pub fn kind(&self) -> MarkKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
-impl<'a> CodeWindow<'a> {
-    pub fn new(file_path: &'a str, file_lines: &'a [String], cursor: SpanCursor) -> Self {
+impl MarkKind {
+    fn sign_and_style(self) -> (char, Style) {
+        match self {
+            MarkKind::Error => ('^', Style::new().bold().red()),
+            MarkKind::Warning => ('^', Style::new().bold().yellow()),
+            MarkKind::Help => ('=', Style::new().bold().cyan()),
+            MarkKind::Note => ('~', Style::new().bold().green()),
+            MarkKind::Secondary => ('-', Style::new().bold().blue()),
+            MarkKind::Tertiary => ('*', Style::new().bold().bright_magenta()),
+        }
+    }
+}
+
+impl CodeWindow {
+    /// A code window of the file of the span, its location is displayed as the start of the span
+    pub fn new(span: Span) -> Self {
         Self {
-            file_path,
-            file_lines,
-            cursor,
-            code_lines: HashMap::new(),
+            file_id: span.file_id,
+            lo: span.lo,
+            marks: vec![],
         }
     }
 
     pub fn mark_error(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '^', Style::new().bold().red(), labels);
-        self
+        self.mark(MarkKind::Error, span, labels)
     }
 
     pub fn mark_warning(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '^', Style::new().bold().yellow(), labels);
-        self
+        self.mark(MarkKind::Warning, span, labels)
     }
 
     pub fn mark_help(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '=', Style::new().bold().cyan(), labels);
-        self
+        self.mark(MarkKind::Help, span, labels)
     }
 
     pub fn mark_note(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '~', Style::new().bold().green(), labels);
-        self
+        self.mark(MarkKind::Note, span, labels)
     }
 
     pub fn mark_secondary(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '-', Style::new().bold().blue(), labels);
-        self
+        self.mark(MarkKind::Secondary, span, labels)
     }
 
     pub fn mark_tertiary(&mut self, span: Span, labels: Vec<String>) -> &mut Self {
-        self.mark(span, '*', Style::new().bold().bright_magenta(), labels);
+        self.mark(MarkKind::Tertiary, span, labels)
+    }
+
+    fn mark(&mut self, kind: MarkKind, span: Span, labels: Vec<String>) -> &mut Self {
+        self.marks.push(Mark { kind, span, labels });
         self
     }
 
-    fn mark(&mut self, span: Span, sign: char, style: Style, labels: Vec<String>) -> &mut Self {
-        let start_line = span.start.line;
-        let start_col = span.start.col;
-        let end_line = span.end.line;
-        let end_col = span.end.col;
+    /// Maps the indecies of the marked lines to their markers, the lines and the columns of the
+    /// marks are computed from the file
+    fn code_lines(&self, file: &SourceFile) -> HashMap<usize, CodeLine<'static>> {
+        let mut code_lines = HashMap::new();
 
-        if start_line == end_line {
-            self.code_lines
-                .entry(start_line)
-                .or_insert(CodeLine::default())
-                .mark_as_one_line(start_col, end_col, sign, style, labels);
+        for mark in &self.marks {
+            let (sign, style) = mark.kind.sign_and_style();
+            let (start, end) = file.span_cursors(mark.span);
+            let labels = mark.labels.clone();
 
-            return self;
-        }
+            if start.line == end.line {
+                code_lines
+                    .entry(start.line)
+                    .or_insert(CodeLine::default())
+                    .mark_as_one_line(start.col, end.col, sign, style, labels);
+
+                continue;
+            }
 
-        let connection_margin = Rc::default(); // It will be updated later
+            let connection_margin = Rc::default(); // It will be updated later
 
-        self.code_lines
-            .entry(start_line)
-            .or_insert(CodeLine::default())
-            .mark_as_multi_line_start(start_col, sign, style, Rc::clone(&connection_margin));
+            code_lines
+                .entry(start.line)
+                .or_insert(CodeLine::default())
+                .mark_as_multi_line_start(start.col, sign, style, Rc::clone(&connection_margin));
 
-        self.code_lines
-            .entry(end_line)
-            .or_insert(CodeLine::default())
-            .mark_as_multi_line_end(end_col, sign, style, labels, connection_margin);
+            code_lines
+                .entry(end.line)
+                .or_insert(CodeLine::default())
+                .mark_as_multi_line_end(end.col, sign, style, labels, connection_margin);
 
-        for line in start_line + 1..end_line {
-            // Add lines in between to display them or to modify them later if markers were added to them
-            self.code_lines.entry(line).or_insert(CodeLine::default());
+            for line in start.line + 1..end.line {
+                // Add lines in between to display them or to modify them later if markers were added to them
+                code_lines.entry(line).or_insert(CodeLine::default());
+            }
         }
 
-        return self;
+        code_lines
     }
 }
 
-impl<'a> Display for CodeWindow<'a> {
+impl<'a> Display for WithSourceMap<'a, CodeWindow> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let WithSourceMap(code_window, source_map, numerals) = *self;
+        code_window.fmt_file(f, &source_map[code_window.file_id], numerals)
+    }
+}
+
+impl CodeWindow {
+    pub fn file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub fn marks(&self) -> &[Mark] {
+        &self.marks
+    }
+
+    /// The location of the code window in the file
+    pub fn cursor(&self, file: &SourceFile) -> SpanCursor {
+        file.cursor(self.lo)
+    }
+
+    /// Renders the marked lines of the file, the suggestions render the file after editing it
+    pub(crate) fn fmt_file(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        file: &SourceFile,
+        numerals: Numerals,
+    ) -> std::fmt::Result {
+        let code_lines = self.code_lines(file);
+        let file_lines = file.lines().collect::<Vec<_>>();
+        let cursor = self.cursor(file);
         let mut free_connection_margins = vec![];
         let mut connections_painter = Painter::new(
             Marker {
@@ -107,7 +189,7 @@ impl<'a> Display for CodeWindow<'a> {
             }, // Default is space
         );
         let mut big_sheet = vec![];
-        let mut lines_indecies = self.code_lines.keys().sorted();
+        let mut lines_indecies = code_lines.keys().sorted();
 
         let mut num_of_displayed_lines = 0;
 
@@ -116,12 +198,12 @@ impl<'a> Display for CodeWindow<'a> {
         for line_index in lines_indecies.clone() {
             max_line_num = line_index + 1; // Add one
 
-            let code_line = &self.code_lines[line_index];
+            let code_line = &code_lines[line_index];
 
-            let file_line = &self.file_lines[*line_index];
+            let file_line = file_lines[*line_index];
 
             big_sheet.push(vec![vec![Marker {
-                sign: MarkerSign::CodeLine(&file_line),
+                sign: MarkerSign::CodeLine(file_line),
                 style: Style::new(),
             }]]);
 
@@ -135,7 +217,7 @@ impl<'a> Display for CodeWindow<'a> {
             let painter_opt = code_line.draw(
                 &mut free_connection_margins,
                 &mut connections_painter,
-                &file_line,
+                file_line,
             );
 
             match painter_opt {
@@ -161,9 +243,9 @@ impl<'a> Display for CodeWindow<'a> {
             "{}{} {}:{}:{}",
             " ".repeat(max_line_num_indent).style(line_nums_style),
             "-->".style(line_nums_style),
-            self.file_path,
-            self.cursor.line + 1,
-            self.cursor.col + 1
+            file.path(),
+            numerals.fmt(cursor.line + 1),
+            numerals.fmt(cursor.col + 1)
         );
 
         let _ = write!(
@@ -187,27 +269,27 @@ impl<'a> Display for CodeWindow<'a> {
                 let current_line_num = lines_indecies.next().unwrap() + 1;
                 if prev_line_num > 0 && prev_line_num + 1 < current_line_num {
                     if prev_line_num + 2 == current_line_num {
-                        let line_num_str = (prev_line_num + 1).to_string();
+                        let line_num_str = numerals.fmt(prev_line_num + 1);
                         let _ = writeln!(
                             f,
                             "{}{} {} {}{}",
                             line_num_str.style(line_nums_style),
-                            " ".repeat(max_line_num_indent - line_num_str.len()),
+                            " ".repeat(max_line_num_indent - line_num_str.chars().count()),
                             '|'.style(line_nums_style),
                             " ".repeat(max_margin),
-                            self.file_lines[prev_line_num],
+                            file_lines[prev_line_num],
                         );
                     } else {
                         let _ = writeln!(f, "{}", "...".style(line_nums_style));
                     }
                 }
                 prev_line_num = current_line_num;
-                let line_num_str = prev_line_num.to_string();
+                let line_num_str = numerals.fmt(prev_line_num);
                 let _ = write!(
                     f,
                     "{}{} {} ",
                     line_num_str.style(line_nums_style),
-                    " ".repeat(max_line_num_indent - line_num_str.len()),
+                    " ".repeat(max_line_num_indent - line_num_str.chars().count()),
                     '|'.style(line_nums_style)
                 );
             } else {
@@ -666,17 +748,13 @@ enum MarkerType {
 mod tests {
 
     use std::{
-        collections::HashMap,
-        fmt::Debug,
         io::{self, Write},
         process::Command,
     };
 
-    use owo_colors::{Style, XtermColors};
-
     use crate::{
         span::{Span, SpanCursor},
-        DiagnosticPrint,
+        Numerals, SourceMap, WithSourceMap,
     };
 
     use super::CodeWindow;
@@ -689,99 +767,107 @@ mod tests {
             .unwrap();
     }
 
-    fn get_code_reporter() -> CodeWindow {
-        CodeWindow::new(
-            "اختبار.نظم",
-            &[
-                "حجز متغير أ = 555؛".to_string(),
-                "حجز متغير ب = 555؛".to_string(),
-                "حجز متغير ج = 555؛".to_string(),
-                "حجز متغير د = 555؛".to_string(),
-                "حجز متغير ه = 555؛".to_string(),
-                "حجز متغير و = 555؛".to_string(),
-                "حجز متغير ز = 555؛".to_string(),
-                "حجز متغير ح = 555؛".to_string(),
-                "حجز متغير ك = 555؛".to_string(),
-                "حجز متغير ل = 555؛".to_string(),
-                "حجز متغير م = 555؛".to_string(),
-                "حجز متغير ن = 555؛".to_string(),
-                "حجز متغير ز = 555؛".to_string(),
-            ],
-            SpanCursor { line: 0, col: 0 },
-        )
+    fn get_source_map() -> SourceMap {
+        let lines = [
+            "حجز متغير أ = 555؛".to_string(),
+            "حجز متغير ب = 555؛".to_string(),
+            "حجز متغير ج = 555؛".to_string(),
+            "حجز متغير د = 555؛".to_string(),
+            "حجز متغير ه = 555؛".to_string(),
+            "حجز متغير و = 555؛".to_string(),
+            "حجز متغير ز = 555؛".to_string(),
+            "حجز متغير ح = 555؛".to_string(),
+            "حجز متغير ك = 555؛".to_string(),
+            "حجز متغير ل = 555؛".to_string(),
+            "حجز متغير م = 555؛".to_string(),
+            "حجز متغير ن = 555؛".to_string(),
+            "حجز متغير ز = 555؛".to_string(),
+        ];
+        let mut source_map = SourceMap::new();
+        source_map
+            .add("اختبار.نظم".to_string(), lines.join("\n"))
+            .ok()
+            .unwrap();
+        source_map
+    }
+
+    /// The span from the start line and column to the end line and column in the test file
+    fn span(source_map: &SourceMap, start: (usize, usize), end: (usize, usize)) -> Span {
+        let (file_id, file) = source_map.files().next().unwrap();
+        let offset = |(line, col)| file.offset(SpanCursor { line, col });
+        Span::new(file_id, offset(start), offset(end))
+    }
+
+    fn get_code_reporter(source_map: &SourceMap) -> CodeWindow {
+        CodeWindow::new(span(source_map, (0, 0), (0, 0)))
     }
 
     #[test]
     fn test_one_line() {
         rtl();
-        let mut reporter = get_code_reporter();
+        let source_map = get_source_map();
+        let mut reporter = get_code_reporter(&source_map);
 
-        reporter.mark(
-            Span::new((0, 0), (0, 4)),
-            '?',
-            Style::new().blue().cyan(),
+        reporter.mark_help(
+            span(&source_map, (0, 0), (0, 4)),
             vec!["القيمة ليست متغيرة".to_string()],
         );
 
-        println!("{:?}", reporter)
+        let rendered = WithSourceMap(&reporter, &source_map, Numerals::Latin).to_string();
+        println!("{rendered}");
+        assert!(rendered.contains("اختبار.نظم:1:1"));
+        assert!(rendered.contains("القيمة ليست متغيرة"));
     }
 
     #[test]
     fn test_multi_line() {
         rtl();
-        let mut reporter = get_code_reporter();
+        let source_map = get_source_map();
+        let mut reporter = get_code_reporter(&source_map);
 
-        reporter.mark(
-            Span::new((0, 4), (1, 5)),
-            '^',
-            Style::new().red().bold(),
+        reporter.mark_error(
+            span(&source_map, (0, 4), (1, 5)),
             vec!["القيمة ليست متغيرة".to_string()],
         );
 
-        println!("{:?}", reporter)
+        let rendered = WithSourceMap(&reporter, &source_map, Numerals::Latin).to_string();
+        println!("{rendered}");
+        assert!(rendered.contains("اختبار.نظم:1:1"));
+        assert!(rendered.contains("القيمة ليست متغيرة"));
     }
 
     #[test]
     fn test_reporting_complex() {
         rtl();
-        let mut reporter = get_code_reporter();
+        let source_map = get_source_map();
+        let mut reporter = get_code_reporter(&source_map);
 
         reporter
-            .mark(
-                Span::new((0, 0), (0, 4)),
-                '?',
-                Style::new().blue().cyan(),
+            .mark_help(
+                span(&source_map, (0, 0), (0, 4)),
                 vec!["القيمة ليست متغيرة".to_string()],
             )
-            .mark(
-                Span::new((0, 15), (0, 18)),
-                '~',
-                Style::new().blue().green(),
+            .mark_note(
+                span(&source_map, (0, 15), (0, 18)),
                 vec![
                     "القيمة ليست متغيرة".to_string(),
                     "القيمة ليست متغيرة".to_string(),
                     "القيمة ليست متغيرة".to_string(),
                 ],
             )
-            .mark(
-                Span::new((0, 5), (0, 10)),
-                '-',
-                Style::new().blue().bold(),
+            .mark_secondary(
+                span(&source_map, (0, 5), (0, 10)),
                 vec!["القيمة ليست متغيرة".to_string()],
             )
-            .mark(
-                Span::new((2, 5), (2, 10)),
-                '^',
-                Style::new().yellow().bold(),
+            .mark_warning(
+                span(&source_map, (2, 5), (2, 10)),
                 vec![
                     "القيمة ليست متغيرة".to_string(),
                     "القيمة ليست متغيرة".to_string(),
                 ],
             )
-            .mark(
-                Span::new((1, 5), (2, 4)),
-                '^',
-                Style::new().red().bold(),
+            .mark_error(
+                span(&source_map, (1, 5), (2, 4)),
                 vec![
                     "علامة طويلة".to_string(),
                     "علامة طويلة".to_string(),
@@ -790,93 +876,70 @@ mod tests {
                     "ما قولتلك يا بني علامة طويلة".to_string(),
                 ],
             )
-            .mark(
-                Span::new((1, 15), (2, 19)),
-                '^',
-                Style::new().color(XtermColors::FlushOrange).bold(),
+            .mark_error(
+                span(&source_map, (1, 15), (2, 19)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((0, 13), (2, 13)),
-                '^',
-                Style::new().color(XtermColors::PinkFlamingo).bold(),
+            .mark_error(
+                span(&source_map, (0, 13), (2, 13)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((1, 0), (2, 0)),
-                '^',
-                Style::new().color(XtermColors::Brown).bold(),
+            .mark_tertiary(
+                span(&source_map, (1, 0), (2, 0)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((0, 11), (1, 4)),
-                '^',
-                Style::new().magenta().bold(),
+            .mark_error(
+                span(&source_map, (0, 11), (1, 4)),
                 vec![
                     "علامة طويلة".to_string(),
                     "علامة طويلة".to_string(),
                     "علامة طويلة".to_string(),
                 ],
             )
-            .mark(
-                Span::new((1, 8), (1, 10)),
-                '^',
-                Style::new().color(XtermColors::Bermuda).bold(),
+            .mark_error(
+                span(&source_map, (1, 8), (1, 10)),
                 vec![
                     "علامة طويلة".to_string(),
                     "علامة طويلة".to_string(),
                     "علامة طويلة".to_string(),
                 ],
             )
-            .mark(
-                Span::new((3, 5), (6, 10)),
-                '^',
-                Style::new().color(XtermColors::GreenYellow).bold(),
+            .mark_error(
+                span(&source_map, (3, 5), (6, 10)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((4, 11), (5, 5)),
-                '^',
-                Style::new().color(XtermColors::BayLeaf).bold(),
+            .mark_error(
+                span(&source_map, (4, 11), (5, 5)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((7, 15), (7, 19)),
-                '^',
-                Style::new().color(XtermColors::Dandelion).bold(),
+            .mark_tertiary(
+                span(&source_map, (7, 15), (7, 19)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((7, 0), (9, 4)),
-                '^',
-                Style::new().color(XtermColors::Caramel).bold(),
+            .mark_tertiary(
+                span(&source_map, (7, 0), (9, 4)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((7, 5), (9, 9)),
-                '^',
-                Style::new().color(XtermColors::CanCanPink).bold(),
+            .mark_error(
+                span(&source_map, (7, 5), (9, 9)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((7, 10), (9, 15)),
-                '^',
-                Style::new().color(XtermColors::DarkRose).bold(),
+            .mark_error(
+                span(&source_map, (7, 10), (9, 15)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((7, 12), (9, 19)),
-                '^',
-                Style::new().color(XtermColors::Dandelion).bold(),
+            .mark_tertiary(
+                span(&source_map, (7, 12), (9, 19)),
                 vec!["علامة طويلة".to_string()],
             )
-            .mark(
-                Span::new((11, 5), (12, 5)),
-                '^',
-                Style::new().color(XtermColors::Dandelion).bold(),
+            .mark_tertiary(
+                span(&source_map, (11, 5), (12, 5)),
                 vec!["علامة طويلة".to_string()],
             );
 
-        println!("{:?}", reporter);
+        let rendered = WithSourceMap(&reporter, &source_map, Numerals::Latin).to_string();
+        println!("{rendered}");
+        assert!(rendered.contains("حجز متغير ز = 555؛"));
+        assert!(rendered.contains("ما قولتلك يا بني علامة طويلة"));
     }
 }
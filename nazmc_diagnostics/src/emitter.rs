@@ -0,0 +1,267 @@
+use crate::{
+    code_window::{Mark, MarkKind},
+    span::Span,
+    Applicability, CodeWindow, Diagnostic, DiagnosticLevel, Numerals, SourceFile, SourceMap,
+    Suggestion, WithSourceMap,
+};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// How the diagnostics are rendered before printing them
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ErrorFormat {
+    /// The colored code windows for the terminal
+    #[default]
+    Human,
+    /// A JSON object for each diagnostic on a single line
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("`{s}` is not one of `human` or `json`")),
+        }
+    }
+}
+
+impl ErrorFormat {
+    /// The emitter which renders the diagnostics in this format, the numerals are used
+    /// for the numbers of the code windows
+    pub fn emitter(self, numerals: Numerals) -> Box<dyn Emitter> {
+        match self {
+            ErrorFormat::Human => Box::new(HumanEmitter { numerals }),
+            ErrorFormat::Json => Box::new(JsonEmitter),
+        }
+    }
+}
+
+/// Renders the diagnostics to the text that should be printed
+pub trait Emitter {
+    fn format(&self) -> ErrorFormat;
+
+    fn emit(&self, source_map: &SourceMap, diagnostics: &[Diagnostic]) -> String;
+}
+
+#[derive(Default)]
+pub struct HumanEmitter {
+    pub numerals: Numerals,
+}
+
+pub struct JsonEmitter;
+
+impl Emitter for HumanEmitter {
+    fn format(&self) -> ErrorFormat {
+        ErrorFormat::Human
+    }
+
+    fn emit(&self, source_map: &SourceMap, diagnostics: &[Diagnostic]) -> String {
+        let mut dd = String::new();
+        for (i, d) in diagnostics.iter().enumerate() {
+            if i != 0 {
+                dd.push('\n');
+            }
+            dd += &format!("{}\n", WithSourceMap(d, source_map, self.numerals));
+        }
+
+        let mut codes = diagnostics
+            .iter()
+            .filter_map(|d| match d.level {
+                DiagnosticLevel::ErrorWithCode(code) => Some(code),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+
+        match codes.as_slice() {
+            [] => {}
+            [code] => {
+                dd += &format!("\nلمعرفة المزيد عن هذا الخطأ نفّذ `nazmc explain {code}`\n");
+            }
+            codes => {
+                let codes = codes
+                    .iter()
+                    .map(|code| code.to_string())
+                    .collect::<Vec<_>>()
+                    .join("، ");
+                dd += &format!(
+                    "\nبعض الأخطاء لها شرح مفصل: {codes}\nلمعرفة المزيد عن أي منها نفّذ `nazmc explain <رقم الخطأ>`\n"
+                );
+            }
+        }
+
+        dd
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn format(&self) -> ErrorFormat {
+        ErrorFormat::Json
+    }
+
+    fn emit(&self, source_map: &SourceMap, diagnostics: &[Diagnostic]) -> String {
+        let mut dd = String::new();
+        for d in diagnostics {
+            // The positions are JSON numbers, so they are not affected by the numerals
+            dd += &serde_json::to_string(&JsonDiagnostic::from(WithSourceMap(
+                d,
+                source_map,
+                Numerals::Latin,
+            )))
+            .unwrap();
+            dd.push('\n');
+        }
+        dd
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    level: &'static str,
+    code: Option<usize>,
+    message: &'a str,
+    code_windows: Vec<JsonCodeWindow<'a>>,
+    children: Vec<JsonDiagnostic<'a>>,
+    suggestions: Vec<JsonSuggestion<'a>>,
+    notes: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion<'a> {
+    message: &'a str,
+    file: &'a str,
+    span: JsonSpan,
+    replacement: &'a str,
+    applicability: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonCodeWindow<'a> {
+    file: &'a str,
+    line: usize,
+    col: usize,
+    marks: Vec<JsonMark<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonMark<'a> {
+    kind: &'static str,
+    span: JsonSpan,
+    labels: &'a [String],
+}
+
+/// The lines and the columns start from 1 like the rendered code windows
+#[derive(Serialize)]
+struct JsonSpan {
+    start: [usize; 2],
+    end: [usize; 2],
+    /// The start and the end bytes of the span in the file
+    bytes: [u32; 2],
+}
+
+impl<'a> From<WithSourceMap<'a, Diagnostic>> for JsonDiagnostic<'a> {
+    fn from(WithSourceMap(d, source_map, numerals): WithSourceMap<'a, Diagnostic>) -> Self {
+        let (level, code) = match d.level {
+            DiagnosticLevel::Error => ("error", None),
+            DiagnosticLevel::ErrorWithCode(code) => ("error", Some(code)),
+            DiagnosticLevel::Warning => ("warning", None),
+            DiagnosticLevel::Help => ("help", None),
+            DiagnosticLevel::Note => ("note", None),
+        };
+
+        Self {
+            level,
+            code,
+            message: &d.msg,
+            code_windows: d
+                .code_windows
+                .iter()
+                .map(|c| WithSourceMap(c, source_map, numerals).into())
+                .collect(),
+            children: d
+                .chained_diagnostics
+                .iter()
+                .map(|d| WithSourceMap(d, source_map, numerals).into())
+                .collect(),
+            suggestions: d
+                .suggestions
+                .iter()
+                .map(|s| WithSourceMap(s, source_map, numerals).into())
+                .collect(),
+            notes: &d.free_texts,
+        }
+    }
+}
+
+impl<'a> From<WithSourceMap<'a, Suggestion>> for JsonSuggestion<'a> {
+    fn from(WithSourceMap(suggestion, source_map, _): WithSourceMap<'a, Suggestion>) -> Self {
+        let applicability = match suggestion.applicability {
+            Applicability::MachineApplicable => "machine-applicable",
+            Applicability::MaybeIncorrect => "maybe-incorrect",
+        };
+
+        let file = &source_map[suggestion.span.file_id];
+
+        Self {
+            message: &suggestion.msg,
+            file: file.path(),
+            span: JsonSpan::new(file, suggestion.span),
+            replacement: &suggestion.replacement,
+            applicability,
+        }
+    }
+}
+
+impl<'a> From<WithSourceMap<'a, CodeWindow>> for JsonCodeWindow<'a> {
+    fn from(WithSourceMap(code_window, source_map, _): WithSourceMap<'a, CodeWindow>) -> Self {
+        let file = &source_map[code_window.file_id];
+        let cursor = code_window.cursor(file);
+
+        Self {
+            file: file.path(),
+            line: cursor.line + 1,
+            col: cursor.col + 1,
+            marks: code_window
+                .marks
+                .iter()
+                .map(|mark| JsonMark::new(file, mark))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> JsonMark<'a> {
+    fn new(file: &SourceFile, mark: &'a Mark) -> Self {
+        let kind = match mark.kind {
+            MarkKind::Error => "error",
+            MarkKind::Warning => "warning",
+            MarkKind::Help => "help",
+            MarkKind::Note => "note",
+            MarkKind::Secondary => "secondary",
+            MarkKind::Tertiary => "tertiary",
+        };
+
+        Self {
+            kind,
+            span: JsonSpan::new(file, mark.span),
+            labels: &mark.labels,
+        }
+    }
+}
+
+impl JsonSpan {
+    fn new(file: &SourceFile, span: Span) -> Self {
+        let (start, end) = file.span_cursors(span);
+
+        Self {
+            start: [start.line + 1, start.col + 1],
+            end: [end.line + 1, end.col + 1],
+            bytes: [span.lo, span.hi],
+        }
+    }
+}
@@ -0,0 +1,21 @@
+macro_rules! explanations {
+    ($($code:literal),* $(,)?) => {
+        /// The long-form explanation of an error code, which is printed by `nazmc explain`
+        pub fn explanation(code: usize) -> Option<&'static str> {
+            match code {
+                $($code => Some(include_str!(concat!("explanations/", $code, ".md"))),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+explanations! {
+    // Lexer errors
+    100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118,
+    119, 120,
+    // Parser errors
+    200, 201, 202, 203, 204, 205, 206, 207, 208, 209, 210, 211,
+    // Name resolution errors
+    300, 301, 302, 303, 304, 305, 306, 307,
+}
@@ -1,64 +1,103 @@
 use owo_colors::OwoColorize;
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
 mod code_window;
+mod emitter;
+mod explanations;
+mod lints;
+mod numerals;
+mod source_map;
 pub mod span;
-pub use code_window::CodeWindow;
-
-pub fn eprint_diagnostics(diagnostics: Vec<Diagnostic>) {
-    let last_idx = diagnostics.len() - 1;
-    for (i, d) in diagnostics.iter().enumerate() {
-        eprintln!("{}", d);
-        if i != last_idx {
-            eprintln!();
-        }
+mod suggestion;
+pub use code_window::{CodeWindow, Mark, MarkKind};
+pub use emitter::*;
+pub use explanations::explanation;
+pub use lints::*;
+pub use numerals::*;
+pub use source_map::*;
+pub use suggestion::*;
+
+/// Whether any diagnostics are printed before, as the warnings of a phase are printed before the next phases
+static PRINTED: AtomicBool = AtomicBool::new(false);
+
+/// Prints the diagnostics to the standard error with the emitter, the code windows are
+/// read from the files of the source map
+pub fn eprint_diagnostics(
+    emitter: &dyn Emitter,
+    source_map: &SourceMap,
+    diagnostics: Vec<Diagnostic>,
+) {
+    if PRINTED.swap(true, Ordering::Relaxed) && emitter.format() == ErrorFormat::Human {
+        eprintln!();
     }
+    eprint!("{}", emitter.emit(source_map, &diagnostics));
 }
 
-pub fn fmt_diagnostics(diagnostics: Vec<Diagnostic>) -> String {
-    let last_idx = diagnostics.len() - 1;
-    let mut dd = String::new();
-    for (i, d) in diagnostics.iter().enumerate() {
-        dd += &format!("{d}\n");
-        if i != last_idx {
-            dd.push('\n');
-        }
-    }
-    dd
+/// Renders the diagnostics with the emitter
+pub fn fmt_diagnostics(
+    emitter: &dyn Emitter,
+    source_map: &SourceMap,
+    diagnostics: Vec<Diagnostic>,
+) -> String {
+    emitter.emit(source_map, &diagnostics)
 }
 
-pub struct Diagnostic<'a> {
+/// A diagnostic refers to its files only by the ids in the spans, so it could be built without
+/// the source map and rendered later with it
+pub struct Diagnostic {
     level: DiagnosticLevel,
     msg: String,
-    code_windows: Vec<CodeWindow<'a>>,
-    chained_diagnostics: Vec<Diagnostic<'a>>,
+    code_windows: Vec<CodeWindow>,
+    chained_diagnostics: Vec<Diagnostic>,
+    suggestions: Vec<Suggestion>,
     free_texts: Vec<String>,
 }
 
-impl<'a> Diagnostic<'a> {
-    pub fn error(msg: String, code_windows: Vec<CodeWindow<'a>>) -> Self {
+impl Diagnostic {
+    pub fn error(msg: String, code_windows: Vec<CodeWindow>) -> Self {
         Self::new(DiagnosticLevel::Error, msg, code_windows)
     }
 
-    pub fn help(msg: String, code_windows: Vec<CodeWindow<'a>>) -> Self {
+    /// An error with a stable code which could be explained by `nazmc explain`
+    pub fn error_with_code(code: usize, msg: String, code_windows: Vec<CodeWindow>) -> Self {
+        Self::new(DiagnosticLevel::ErrorWithCode(code), msg, code_windows)
+    }
+
+    pub fn warning(msg: String, code_windows: Vec<CodeWindow>) -> Self {
+        Self::new(DiagnosticLevel::Warning, msg, code_windows)
+    }
+
+    pub fn help(msg: String, code_windows: Vec<CodeWindow>) -> Self {
         Self::new(DiagnosticLevel::Help, msg, code_windows)
     }
 
-    pub fn note(msg: String, code_windows: Vec<CodeWindow<'a>>) -> Self {
+    pub fn note(msg: String, code_windows: Vec<CodeWindow>) -> Self {
         Self::new(DiagnosticLevel::Note, msg, code_windows)
     }
 
     #[inline]
-    fn new(level: DiagnosticLevel, msg: String, code_windows: Vec<CodeWindow<'a>>) -> Self {
+    fn new(level: DiagnosticLevel, msg: String, code_windows: Vec<CodeWindow>) -> Self {
         Diagnostic {
             level,
             msg,
             code_windows,
             chained_diagnostics: vec![],
+            suggestions: vec![],
             free_texts: vec![],
         }
     }
 
-    pub fn chain(&mut self, with: Diagnostic<'a>) -> &mut Self {
+    /// Whether the diagnostic should fail the build, warnings don't
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.level,
+            DiagnosticLevel::Error | DiagnosticLevel::ErrorWithCode(_)
+        )
+    }
+
+    pub fn chain(&mut self, with: Diagnostic) -> &mut Self {
         self.chained_diagnostics.push(with);
         self
     }
@@ -68,13 +107,52 @@ impl<'a> Diagnostic<'a> {
         self
     }
 
-    pub fn push_code_window(&mut self, code_window: CodeWindow<'a>) -> &mut Self {
+    pub fn push_code_window(&mut self, code_window: CodeWindow) -> &mut Self {
         self.code_windows.push(code_window);
         self
     }
+
+    /// Adds a suggestion which is shown as a help previewing the code after applying it
+    pub fn suggest(&mut self, suggestion: Suggestion) -> &mut Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    pub fn level(&self) -> DiagnosticLevel {
+        self.level
+    }
+
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn code_windows(&self) -> &[CodeWindow] {
+        &self.code_windows
+    }
+
+    pub fn chained_diagnostics(&self) -> &[Diagnostic] {
+        &self.chained_diagnostics
+    }
+
+    pub fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
+    pub fn free_texts(&self) -> &[String] {
+        &self.free_texts
+    }
+
+    /// Visits the suggestions of the diagnostic and its chained diagnostics
+    pub(crate) fn for_each_suggestion(&self, f: &mut impl FnMut(&Suggestion)) {
+        self.suggestions.iter().for_each(&mut *f);
+        for chained_diagnostic in &self.chained_diagnostics {
+            chained_diagnostic.for_each_suggestion(f);
+        }
+    }
 }
 
-enum DiagnosticLevel {
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiagnosticLevel {
     Error,
     ErrorWithCode(usize),
     Warning,
@@ -82,9 +160,19 @@ enum DiagnosticLevel {
     Note,
 }
 
-impl<'a> Display for Diagnostic<'a> {
+/// Displays the diagnostic or one of its parts with the code lines read from the source map
+/// and the numbers written with the numerals
+pub(crate) struct WithSourceMap<'a, T>(
+    pub(crate) &'a T,
+    pub(crate) &'a SourceMap,
+    pub(crate) Numerals,
+);
+
+impl<'a> Display for WithSourceMap<'a, Diagnostic> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let _ = match self.level {
+        let WithSourceMap(d, source_map, numerals) = *self;
+
+        let _ = match d.level {
             DiagnosticLevel::Error => write!(f, "{}", "خطأ".bold().red()),
             DiagnosticLevel::ErrorWithCode(error_code) => write!(
                 f,
@@ -94,22 +182,30 @@ impl<'a> Display for Diagnostic<'a> {
                 error_code.bold().red(),
                 "]".bold()
             ),
-            DiagnosticLevel::Warning => write!(f, "{}", "خطأ".bold().red()),
+            DiagnosticLevel::Warning => write!(f, "{}", "تحذير".bold().yellow()),
             DiagnosticLevel::Note => write!(f, "{}", "ملحوظة".bold().green()),
             DiagnosticLevel::Help => write!(f, "{}", "مساعدة".bold().cyan()),
         };
 
-        let _ = write!(f, "{} {}", ":".bold(), self.msg.bold());
+        let _ = write!(f, "{} {}", ":".bold(), d.msg.bold());
 
-        for code_window in &self.code_windows {
-            let _ = write!(f, "\n{}", code_window);
+        for code_window in &d.code_windows {
+            let _ = write!(f, "\n{}", WithSourceMap(code_window, source_map, numerals));
         }
 
-        for chained_diagnostic in &self.chained_diagnostics {
-            let _ = write!(f, "\n{}", chained_diagnostic);
+        for chained_diagnostic in &d.chained_diagnostics {
+            let _ = write!(
+                f,
+                "\n{}",
+                WithSourceMap(chained_diagnostic, source_map, numerals)
+            );
+        }
+
+        for suggestion in &d.suggestions {
+            let _ = write!(f, "\n{}", WithSourceMap(suggestion, source_map, numerals));
         }
 
-        for t in &self.free_texts {
+        for t in &d.free_texts {
             let _ = write!(f, "\n{}", t);
         }
 
@@ -0,0 +1,139 @@
+use crate::{CodeWindow, Diagnostic, DiagnosticLevel};
+use std::str::FromStr;
+
+/// The checks that report warnings about code which compiles but is likely a mistake
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Lint {
+    /// An import which is never used in its file
+    UnusedImports,
+    /// A local binding of `احجز` which is never used
+    UnusedLets,
+    /// A function which is not public and never called
+    UnusedPrivateFns,
+    /// A local binding declared with `متغير` which is never mutated
+    UnusedMut,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnusedImports,
+        Lint::UnusedLets,
+        Lint::UnusedPrivateFns,
+        Lint::UnusedMut,
+    ];
+
+    /// The name of the lint in `nazm.yaml` and in the command line options
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedImports => "استيراد_غير_مستخدم",
+            Lint::UnusedLets => "ربط_غير_مستخدم",
+            Lint::UnusedPrivateFns => "دالة_غير_مستخدمة",
+            Lint::UnusedMut => "متغير_غير_ضروري",
+        }
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .into_iter()
+            .find(|lint| lint.name() == s)
+            .ok_or_else(|| {
+                let names = Lint::ALL.map(|lint| format!("`{}`", lint.name()));
+                format!("`{s}` is not one of {}", names.join(", "))
+            })
+    }
+}
+
+/// What to do when a lint finds something
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LintLevel {
+    /// Report nothing
+    Allow,
+    /// Report a warning which doesn't fail the build
+    #[default]
+    Warn,
+    /// Report an error
+    Deny,
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "تجاهل" => Ok(Self::Allow),
+            "تحذير" => Ok(Self::Warn),
+            "منع" => Ok(Self::Deny),
+            _ => Err(format!("`{s}` is not one of `تجاهل`, `تحذير` or `منع`")),
+        }
+    }
+}
+
+/// The level of each lint, all of them warn by default
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LintLevels {
+    levels: [LintLevel; Lint::ALL.len()],
+    deny_warnings: bool,
+}
+
+impl LintLevels {
+    pub fn set(&mut self, lint: Lint, level: LintLevel) -> &mut Self {
+        self.levels[lint as usize] = level;
+        self
+    }
+
+    /// Turns every lint that warns to an error
+    pub fn deny_warnings(&mut self) -> &mut Self {
+        self.deny_warnings = true;
+        self
+    }
+
+    pub fn get(&self, lint: Lint) -> LintLevel {
+        match self.levels[lint as usize] {
+            LintLevel::Warn if self.deny_warnings => LintLevel::Deny,
+            level => level,
+        }
+    }
+}
+
+impl CodeWindow {
+    /// Marks the span as an error if the lint is denied or as a warning otherwise
+    pub fn mark_lint(
+        &mut self,
+        level: LintLevel,
+        span: crate::span::Span,
+        labels: Vec<String>,
+    ) -> &mut Self {
+        match level {
+            LintLevel::Deny => self.mark_error(span, labels),
+            _ => self.mark_warning(span, labels),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// A warning or an error reported by the lint with the given level,
+    /// the level should be checked first to not build the code windows of allowed lints
+    pub fn lint(lint: Lint, level: LintLevel, msg: String, code_windows: Vec<CodeWindow>) -> Self {
+        let (level, note) = match level {
+            LintLevel::Deny => (
+                DiagnosticLevel::Error,
+                format!("هذا الخطأ بسبب منع التحذير `{}`", lint.name()),
+            ),
+            _ => (
+                DiagnosticLevel::Warning,
+                format!(
+                    "يمكن تجاهل هذا التحذير بإضافة `{}: تجاهل` إلى `التحذيرات` في ملف nazm.yaml",
+                    lint.name()
+                ),
+            ),
+        };
+
+        let mut diagnostic = Self::new(level, msg, code_windows);
+        diagnostic.chain(Diagnostic::note(note, vec![]));
+        diagnostic
+    }
+}
@@ -0,0 +1,42 @@
+use std::{fmt::Display, str::FromStr};
+
+/// The digits used to print the numbers in the diagnostics and in the output of the programs
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Numerals {
+    /// The digits `0-9`
+    #[default]
+    Latin,
+    /// The Eastern Arabic-Indic digits `٠-٩`
+    ArabicIndic,
+}
+
+impl FromStr for Numerals {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "لاتينية" => Ok(Self::Latin),
+            "مشرقية" => Ok(Self::ArabicIndic),
+            _ => Err(format!("`{s}` is not one of `لاتينية` or `مشرقية`")),
+        }
+    }
+}
+
+impl Numerals {
+    /// Formats the number with these digits, the decimal point is written as `٫` with the Eastern Arabic-Indic digits
+    pub fn fmt(self, num: impl Display) -> String {
+        let num = num.to_string();
+
+        if self == Numerals::Latin {
+            return num;
+        }
+
+        num.chars()
+            .map(|ch| match ch {
+                '0'..='9' => char::from_u32('٠' as u32 + (ch as u32 - '0' as u32)).unwrap(),
+                '.' => '٫',
+                _ => ch,
+            })
+            .collect()
+    }
+}
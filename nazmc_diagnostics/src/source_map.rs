@@ -0,0 +1,143 @@
+use crate::{
+    span::{Span, SpanCursor},
+    Diagnostic,
+};
+use std::ops::Index;
+
+/// The index of a file in the source map, it is carried by the spans of the file
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct FileId(u32);
+
+/// A file with its content, the diagnostics of its spans read their code lines from it
+pub struct SourceFile {
+    path: String,
+    content: String,
+    /// The byte index of the start of each line
+    lines_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub(crate) fn new(path: String, content: String) -> Self {
+        let mut lines_starts = vec![0];
+        lines_starts.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+
+        Self {
+            path,
+            content,
+            lines_starts,
+        }
+    }
+
+    /// The path of the file as it is displayed in the diagnostics
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Returns the line at the index without its line break
+    pub fn line(&self, idx: usize) -> &str {
+        let start = self.lines_starts[idx];
+        let end = self
+            .lines_starts
+            .get(idx + 1)
+            .map_or(self.content.len(), |next_start| next_start - 1);
+        &self.content[start..end]
+    }
+
+    /// The lines of the file like the ones collected by the lexer, an empty file has one empty line
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        (0..self.lines_starts.len()).map(|idx| self.line(idx))
+    }
+
+    pub fn lines_count(&self) -> usize {
+        self.lines_starts.len()
+    }
+
+    /// Returns the line and the column of the char at the byte offset, an offset inside a char is
+    /// in its column and an offset after the end of the line is counted as columns after it
+    pub fn cursor(&self, offset: u32) -> SpanCursor {
+        let offset = offset as usize;
+        let line = self.lines_starts.partition_point(|start| *start <= offset) - 1;
+        let line_offset = offset - self.lines_starts[line];
+        let line_text = self.line(line);
+        let col = line_text
+            .char_indices()
+            .take_while(|(i, ch)| i + ch.len_utf8() <= line_offset)
+            .count()
+            + line_offset.saturating_sub(line_text.len());
+
+        SpanCursor { line, col }
+    }
+
+    /// Returns the start and the end cursors of the span, the end is after the char of its last byte
+    pub fn span_cursors(&self, span: Span) -> (SpanCursor, SpanCursor) {
+        let start = self.cursor(span.lo);
+
+        if span.hi <= span.lo {
+            return (start, start);
+        }
+
+        let mut end = self.cursor(span.hi - 1);
+        end.col += 1;
+
+        (start, end)
+    }
+
+    /// Returns the byte offset of the line and the column, it is the end of the line if the column is after it
+    pub fn offset(&self, cursor: SpanCursor) -> u32 {
+        let line = cursor.line.min(self.lines_starts.len() - 1);
+        let line_text = self.line(line);
+        let line_offset = line_text
+            .char_indices()
+            .nth(cursor.col)
+            .map_or(line_text.len(), |(i, _)| i);
+
+        (self.lines_starts[line] + line_offset) as u32
+    }
+}
+
+/// The files of the compiled package, the spans refer to them by their ids
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the file and returns its id, a file larger than 4 GiB is rejected as its byte offsets
+    /// don't fit in the spans
+    pub fn add(&mut self, path: String, content: String) -> Result<FileId, Diagnostic> {
+        if u32::try_from(content.len()).is_err() {
+            return Err(Diagnostic::error(
+                format!("الملف `{path}` أكبر من الحجم المسموح به وهو 4 جيجابايت"),
+                vec![],
+            ));
+        }
+
+        self.files.push(SourceFile::new(path, content));
+
+        Ok(FileId(self.files.len() as u32 - 1))
+    }
+
+    /// The files with their ids in the order they were added
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(idx, file)| (FileId(idx as u32), file))
+    }
+}
+
+impl Index<FileId> for SourceMap {
+    type Output = SourceFile;
+
+    fn index(&self, file_id: FileId) -> &Self::Output {
+        &self.files[file_id.0 as usize]
+    }
+}
@@ -1,11 +1,19 @@
-use std::{fmt::Debug, usize};
+use crate::FileId;
+use std::{fmt::Debug, ops::Range};
 
+/// The byte offsets of a code in a file, the lines and the columns are computed from them only
+/// when the diagnostics are rendered
 #[derive(Clone, Copy, PartialEq, Default)]
 pub struct Span {
-    pub start: SpanCursor,
-    pub end: SpanCursor,
+    /// The file of the span in the source map
+    pub file_id: FileId,
+    /// The byte offset of the start
+    pub lo: u32,
+    /// The byte offset after the end
+    pub hi: u32,
 }
 
+/// A location in a file resolved from a byte offset by the source map
 #[derive(Clone, Copy, PartialEq, Default)]
 pub struct SpanCursor {
     /// The line index
@@ -16,7 +24,7 @@ pub struct SpanCursor {
 
 impl Debug for Span {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "From {:?} to {:?}", self.start, self.end)
+        write!(f, "From {} to {}", self.lo, self.hi)
     }
 }
 
@@ -28,24 +36,16 @@ impl Debug for SpanCursor {
 
 impl Span {
     #[inline]
-    pub fn new(start: (usize, usize), end: (usize, usize)) -> Self {
-        Self {
-            start: SpanCursor {
-                line: start.0,
-                col: start.1,
-            },
-            end: SpanCursor {
-                line: end.0,
-                col: end.1,
-            },
-        }
+    pub fn new(file_id: FileId, lo: u32, hi: u32) -> Self {
+        Self { file_id, lo, hi }
     }
 
     #[inline]
     pub fn merged_with(&self, with: &Span) -> Self {
         Self {
-            start: self.start,
-            end: with.end,
+            file_id: self.file_id,
+            lo: self.lo,
+            hi: with.hi,
         }
     }
 
@@ -53,31 +53,37 @@ impl Span {
     #[inline]
     pub fn after(given: &Span) -> Self {
         Self {
-            start: given.end,
-            end: given.end,
+            file_id: given.file_id,
+            lo: given.hi,
+            hi: given.hi,
         }
     }
 
-    /// Returns a zero-column span located after given the span
+    /// Whether the byte offset is inside the span or right at its end
+    #[inline]
+    pub fn contains(&self, offset: u32) -> bool {
+        self.lo <= offset && offset <= self.hi
+    }
+
+    /// Returns a span of `len` bytes located after given the span, a span ending inside a char
+    /// is rendered to the end of the char
     #[inline]
-    pub fn len_after(given: &Span, len: usize) -> Self {
+    pub fn len_after(given: &Span, len: u32) -> Self {
         Self {
-            start: given.end,
-            end: SpanCursor {
-                line: given.end.line,
-                col: given.end.col + len,
-            },
+            file_id: given.file_id,
+            lo: given.hi,
+            hi: given.hi + len,
         }
     }
+
+    /// The bytes of the span to slice the content of its file
+    #[inline]
+    pub fn byte_range(&self) -> Range<usize> {
+        self.lo as usize..self.hi as usize
+    }
 }
 
 #[inline]
 pub fn sort_spans(spans: &mut [Span]) {
-    spans.sort_by_key(|a| a.start.line);
-
-    spans
-        .chunk_by_mut(|a, b| a.start.line == b.start.line)
-        .for_each(|f| {
-            f.sort_by_key(|a| a.start.col);
-        });
+    spans.sort_by_key(|a| a.lo);
 }
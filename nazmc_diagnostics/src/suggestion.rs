@@ -0,0 +1,140 @@
+use crate::{span::Span, CodeWindow, Diagnostic, SourceFile, SourceMap, WithSourceMap};
+use owo_colors::OwoColorize;
+use std::fmt::Display;
+
+/// How sure the compiler is that applying the suggestion fixes the code as the user intended
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Applicability {
+    /// The suggestion is certainly what the user intended, so `nazmc fix` applies it
+    MachineApplicable,
+    /// The suggestion may not be what the user intended, so it is only shown
+    MaybeIncorrect,
+}
+
+/// A replacement of the code in a span of a file with a new text
+pub struct Suggestion {
+    pub(crate) msg: String,
+    pub(crate) span: Span,
+    pub(crate) replacement: String,
+    pub(crate) applicability: Applicability,
+}
+
+impl Suggestion {
+    pub fn new(msg: String, span: Span, replacement: String, applicability: Applicability) -> Self {
+        Self {
+            msg,
+            span,
+            replacement,
+            applicability,
+        }
+    }
+
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    /// Returns the file after applying the suggestion and the span of the replacement in it
+    fn preview(&self, file: &SourceFile) -> (SourceFile, Span) {
+        let content = file.content();
+        let edited = format!(
+            "{}{}{}",
+            &content[..self.span.lo as usize],
+            self.replacement,
+            &content[self.span.hi as usize..]
+        );
+
+        (
+            SourceFile::new(file.path().to_string(), edited),
+            Span {
+                hi: self.span.lo + self.replacement.len() as u32,
+                ..self.span
+            },
+        )
+    }
+}
+
+impl<'a> Display for WithSourceMap<'a, Suggestion> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let WithSourceMap(suggestion, source_map, numerals) = *self;
+        let (edited_file, span) = suggestion.preview(&source_map[suggestion.span.file_id]);
+        let mut code_window = CodeWindow::new(span);
+
+        if span.lo == span.hi {
+            // Nothing is inserted, so the first remaining char is marked
+            code_window.mark_help(Span::len_after(&span, 1), vec![]);
+        } else {
+            code_window.mark_help(span, vec![]);
+        }
+
+        writeln!(
+            f,
+            "{}{} {}",
+            "مساعدة".bold().cyan(),
+            ":".bold(),
+            suggestion.msg.bold(),
+        )?;
+
+        code_window.fmt_file(f, &edited_file, numerals)
+    }
+}
+
+/// A machine-applicable suggestion of a diagnostic with the path of its file
+#[derive(Clone, Debug)]
+pub struct Fix {
+    pub file_path: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// Collects the machine-applicable suggestions of the diagnostics, they are applied by `nazmc fix`
+pub fn collect_fixes(source_map: &SourceMap, diagnostics: &[Diagnostic]) -> Vec<Fix> {
+    let mut fixes = vec![];
+    for d in diagnostics {
+        d.for_each_suggestion(&mut |s| {
+            if s.applicability == Applicability::MachineApplicable {
+                fixes.push(Fix {
+                    file_path: source_map[s.span.file_id].path().to_string(),
+                    span: s.span,
+                    replacement: s.replacement.clone(),
+                });
+            }
+        });
+    }
+    fixes
+}
+
+/// Applies the fixes to the content of a file and returns the new content with the number of the applied fixes,
+/// a fix which overlaps with a previous one is skipped
+pub fn apply_fixes(content: &str, fixes: &[Fix]) -> (String, usize) {
+    let mut edits = fixes
+        .iter()
+        .map(|fix| {
+            (
+                fix.span.lo as usize,
+                fix.span.hi as usize,
+                fix.replacement.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    edits.sort_by_key(|(start, end, _)| (*start, *end));
+    edits.dedup();
+
+    let mut fixed = String::with_capacity(content.len());
+    let mut applied = 0;
+    let mut last_end = 0;
+
+    for (start, end, replacement) in edits {
+        if start < last_end {
+            continue;
+        }
+        fixed += &content[last_end..start];
+        fixed += replacement;
+        last_end = end;
+        applied += 1;
+    }
+
+    fixed += &content[last_end..];
+
+    (fixed, applied)
+}
@@ -0,0 +1,135 @@
+//! Measures the memory and the time of lexing, parsing and lowering a large generated file to the NIR,
+//! it is run by `cargo bench -p nazmc_driver --bench spans`
+
+use nazmc_data_pool::DataPool;
+use nazmc_diagnostics::{
+    span::{Span, SpanCursor},
+    FileId,
+};
+use nazmc_driver::{Config, Session, SourceMap};
+use nazmc_lexer::{LexerIter, Token, TokenKind};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    mem::size_of,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+/// Counts the allocated bytes and their peak
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// The span before it was byte offsets, it is kept to compare the sizes
+#[allow(dead_code)]
+struct LineColSpan {
+    file_id: FileId,
+    start: SpanCursor,
+    end: SpanCursor,
+}
+
+/// The token before its bytes were taken from its span
+#[allow(dead_code)]
+struct LineColToken {
+    span: LineColSpan,
+    kind: TokenKind,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+const FNS_COUNT: usize = 20_000;
+
+fn generated_source() -> String {
+    let mut source = String::new();
+
+    for i in 0..FNS_COUNT {
+        source += &format!(
+            "دالة د{i}(س: ص4، ص: ص4): ص4 {{\n    احجز متغير ع = س * 2 + ص؛\n    لو ع > 100 {{\n        ع = ع - 100؛\n    }}\n    أرجع ع؛\n}}\n\n"
+        );
+    }
+
+    source + "دالة البداية() {\n    اظهر_(د0(1، 2))؛\n}\n"
+}
+
+/// Returns the peak of the allocated bytes while running the function over the bytes allocated before it
+fn peak_of<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let output = f();
+    (output, PEAK.load(Ordering::Relaxed) - before)
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn main() {
+    let mut source_map = SourceMap::new();
+    let file_id = source_map
+        .add("رئيسي.نظم".to_string(), generated_source())
+        .ok()
+        .unwrap();
+    let content = source_map[file_id].content();
+
+    println!(
+        "{FNS_COUNT} functions, {:.2} MiB of source",
+        mib(content.len())
+    );
+    println!(
+        "span: {} bytes (was {} bytes), token: {} bytes (was {} bytes)",
+        size_of::<Span>(),
+        size_of::<LineColSpan>(),
+        size_of::<Token>(),
+        size_of::<LineColToken>(),
+    );
+
+    let start = Instant::now();
+    let mut id_pool = DataPool::new();
+    let mut str_pool = DataPool::new();
+    let (tokens, _) = LexerIter::new(content, file_id, &mut id_pool, &mut str_pool).collect_all();
+    let lexing_time = start.elapsed();
+
+    println!(
+        "lexing: {} tokens in {:.2?}, {:.2} MiB of tokens (was {:.2} MiB)",
+        tokens.len(),
+        lexing_time,
+        mib(tokens.len() * size_of::<Token>()),
+        mib(tokens.len() * size_of::<LineColToken>()),
+    );
+
+    drop(tokens);
+
+    let start = Instant::now();
+    let (_, peak) = peak_of(|| {
+        let session = Session::parse(&source_map, Config::default()).ok().unwrap();
+        let (nrt, _) = session.resolve().ok().unwrap();
+        let (nir, _) = session.build_nir(&nrt).ok().unwrap();
+        (session.parsed_files.len(), nir.fns.len())
+    });
+
+    println!(
+        "lexing, parsing and lowering to the NIR: {:.2?}, peak memory {:.2} MiB",
+        start.elapsed(),
+        mib(peak)
+    );
+}
@@ -0,0 +1,238 @@
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{Diagnostic, LintLevels, Numerals};
+use nazmc_lexer::LexerIter;
+use nazmc_nir::NIR;
+use nazmc_parser::parse;
+use nazmc_resolve::{NIRBuilder, NameResolutionTree, NameResolver, ParsedFile, ResolverConfig};
+use nazmc_typeck::TypesTable;
+use std::collections::HashMap;
+use thin_vec::ThinVec;
+
+mod manifest;
+mod source_map;
+
+pub use manifest::Manifest;
+pub use nazmc_diagnostics::{FileId, SourceFile, SourceMap};
+pub use nazmc_interpreter::RunError;
+pub use source_map::read_sources;
+
+/// The output of a phase with its warnings, or all of its diagnostics if any of them is an error
+pub type PhaseResult<T> = Result<(T, Vec<Diagnostic>), Vec<Diagnostic>>;
+
+fn phase_result<T>(output: T, diagnostics: Vec<Diagnostic>) -> PhaseResult<T> {
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        Err(diagnostics)
+    } else {
+        Ok((output, diagnostics))
+    }
+}
+
+/// The settings of the compilation which are selected by the manifest and the command line options
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Config {
+    pub lint_levels: LintLevels,
+    /// The digits of the numbers in the diagnostics and in the output of the programs
+    pub numerals: Numerals,
+}
+
+/// The parsed files of a package with the pools of their ids and strings, the phases after parsing
+/// run on it and return their diagnostics instead of printing them
+pub struct Session<'s> {
+    pub source_map: &'s SourceMap,
+    pub config: Config,
+    /// The pool used to preserve ids string values
+    pub id_pool: DataPool<Built>,
+    /// The pool used to preserve the string literals values
+    pub str_pool: DataPool<Built>,
+    /// A map from pkgs ids segments to the pkgs indexes
+    pub packages: HashMap<ThinVec<PoolIdx>, usize>,
+    /// The ids segments of each package by its index
+    pub packages_names: Vec<ThinVec<PoolIdx>>,
+    /// A map from the pkgs indexes to the inner files indexes
+    pub packages_to_parsed_files: Vec<Vec<usize>>,
+    /// The files parsed without errors
+    pub parsed_files: Vec<ParsedFile>,
+}
+
+impl<'s> Session<'s> {
+    /// Lexes and parses the files of the source map, the files with syntax errors are left out of
+    /// the session and their diagnostics are returned with it
+    pub fn new(source_map: &'s SourceMap, config: Config) -> (Self, Vec<Diagnostic>) {
+        let mut id_pool = DataPool::new();
+        let mut str_pool = DataPool::new();
+        let mut packages = HashMap::new();
+        let mut packages_to_parsed_files: Vec<Vec<usize>> = vec![];
+        let mut parsed_files = vec![];
+        let mut diagnostics = vec![];
+
+        // Register the main fn id to index 0, the implicit lambda param name to index 1
+        // the built-in output fns to indices 2 and 3 and the wildcard pattern to index 4
+        id_pool.get("البداية");
+        id_pool.get("س");
+        id_pool.get("اظهر");
+        id_pool.get("اظهر_");
+        id_pool.get("_");
+
+        for (file_id, file) in source_map.files() {
+            let mut package_path = file
+                .path()
+                .split_terminator('/')
+                .map(|s| id_pool.get(s))
+                .collect::<ThinVec<_>>();
+
+            package_path.pop(); // remove the actual file
+
+            let package_idx = packages.len();
+            let package_idx = *packages.entry(package_path).or_insert(package_idx);
+
+            if package_idx >= packages_to_parsed_files.len() {
+                packages_to_parsed_files.resize(package_idx + 1, vec![]);
+            }
+
+            let (tokens, lexer_errors) =
+                LexerIter::new(file.content(), file_id, &mut id_pool, &mut str_pool).collect_all();
+
+            match parse(tokens, file.content(), lexer_errors) {
+                Ok(ast) => {
+                    packages_to_parsed_files[package_idx].push(parsed_files.len());
+                    parsed_files.push(ParsedFile {
+                        path: file.path().to_string(),
+                        ast,
+                    });
+                }
+                Err(d) => diagnostics.extend(d),
+            }
+        }
+
+        let mut packages_names = vec![ThinVec::new(); packages.len()];
+        for (pkg, idx) in &packages {
+            packages_names[*idx] = pkg.clone();
+        }
+
+        let session = Self {
+            source_map,
+            config,
+            id_pool: id_pool.build(),
+            str_pool: str_pool.build(),
+            packages,
+            packages_names,
+            packages_to_parsed_files,
+            parsed_files,
+        };
+
+        (session, diagnostics)
+    }
+
+    /// Like `Session::new` but fails if any file has syntax errors
+    pub fn parse(source_map: &'s SourceMap, config: Config) -> Result<Self, Vec<Diagnostic>> {
+        let (session, diagnostics) = Self::new(source_map, config);
+
+        if diagnostics.is_empty() {
+            Ok(session)
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// The package index of the parsed file
+    pub fn package_of(&self, file_idx: usize) -> usize {
+        self.packages_to_parsed_files
+            .iter()
+            .position(|files| files.contains(&file_idx))
+            .unwrap()
+    }
+
+    pub fn resolve(&self) -> PhaseResult<NameResolutionTree> {
+        let config = ResolverConfig {
+            source_map: self.source_map,
+            lint_levels: self.config.lint_levels,
+            numerals: self.config.numerals,
+        };
+
+        let (nrt, diagnostics) = NameResolver::new(
+            &self.id_pool,
+            &self.packages,
+            &self.packages_names,
+            &self.packages_to_parsed_files,
+            &self.parsed_files,
+            config,
+        )
+        .resolve();
+
+        phase_result(nrt, diagnostics)
+    }
+
+    /// Lowers the resolved files, it should be called only if the names are resolved without errors
+    pub fn build_nir(&self, nrt: &NameResolutionTree) -> PhaseResult<NIR> {
+        let (nir, diagnostics) = NIRBuilder::new(
+            &self.id_pool,
+            &self.packages,
+            &self.packages_to_parsed_files,
+            &self.parsed_files,
+            nrt,
+        )
+        .build();
+
+        phase_result(nir, diagnostics)
+    }
+
+    pub fn typeck(&self, nir: &NIR) -> PhaseResult<TypesTable> {
+        let (types, diagnostics) =
+            nazmc_typeck::TypeChecker::new(&self.id_pool, nir, self.config.numerals).check();
+
+        phase_result(types, diagnostics)
+    }
+
+    pub fn borrowck(&self, nir: &NIR, types: &TypesTable) -> PhaseResult<()> {
+        let diagnostics =
+            nazmc_borrowck::BorrowChecker::new(&self.id_pool, nir, types, self.config.lint_levels)
+                .check();
+
+        phase_result((), diagnostics)
+    }
+
+    pub fn lint(&self, nir: &NIR) -> PhaseResult<()> {
+        let diagnostics =
+            nazmc_lints::UnusedChecker::new(&self.id_pool, nir, self.config.lint_levels).check();
+
+        phase_result((), diagnostics)
+    }
+
+    /// Interprets the checked program, its output is written to the standard output
+    ///
+    /// It runs in its own thread to have a stack big enough for the nested calls of the program
+    pub fn run(&self, nir: &NIR, types: &TypesTable) -> Result<(), RunError> {
+        std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(nazmc_interpreter::STACK_SIZE)
+                .spawn_scoped(scope, || {
+                    nazmc_interpreter::Interpreter::new(
+                        &self.id_pool,
+                        &self.str_pool,
+                        nir,
+                        types,
+                        self.config.numerals,
+                    )
+                    .run()
+                })
+                .unwrap()
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+
+    /// Returns the C source of the checked program
+    pub fn generate_c(&self, nir: &NIR, types: &TypesTable) -> Result<String, Vec<Diagnostic>> {
+        let program = nazmc_codegen_c::Program {
+            id_pool: &self.id_pool,
+            str_pool: &self.str_pool,
+            source_map: self.source_map,
+            packages_names: &self.packages_names,
+            packages_to_parsed_files: &self.packages_to_parsed_files,
+            nir,
+            types,
+        };
+
+        nazmc_codegen_c::CodeGen::new(program, self.config.numerals).generate()
+    }
+}
@@ -0,0 +1,150 @@
+use nazmc_diagnostics::{Diagnostic, LintLevels, Numerals};
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::{collections::HashMap, fs, path::Path};
+
+#[derive(Deserialize)]
+struct NazmYaml {
+    الاسم: Option<String>,
+    المسارات: Vec<Value>,
+    /// The level of each lint by its name
+    التحذيرات: Option<HashMap<String, String>>,
+    /// The digits of the printed numbers, `لاتينية` or `مشرقية`
+    الأرقام: Option<String>,
+}
+
+/// The package options read from its `nazm.yaml`
+pub struct Manifest {
+    pub name: Option<String>,
+    /// The paths of the package files relative to its directory and without the extension
+    pub files_paths: Vec<String>,
+    pub lint_levels: LintLevels,
+    pub numerals: Option<Numerals>,
+}
+
+impl Manifest {
+    pub fn read(path: &Path) -> Result<Self, Diagnostic> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(_) => Err(Diagnostic::error(
+                format!("لم يتم العثور على ملف `{}`", path.display()),
+                vec![],
+            )),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Diagnostic> {
+        let no_paths_err = || {
+            Diagnostic::error(
+                "ملف nazm.yaml يجب أن يحتوي على خاصية `المسارات` مع مسار ملف واحد على الأقل"
+                    .to_string(),
+                vec![],
+            )
+        };
+
+        let Ok(mut val) = serde_yaml::from_str::<Value>(content) else {
+            return Err(no_paths_err());
+        };
+
+        if val.apply_merge().is_err() {
+            return Err(no_paths_err());
+        }
+
+        let Ok(NazmYaml {
+            الاسم,
+            المسارات,
+            التحذيرات,
+            الأرقام,
+        }) = serde_yaml::from_value(val)
+        else {
+            return Err(no_paths_err());
+        };
+
+        let mut files_paths = vec![];
+
+        collect_paths(المسارات, "", &mut files_paths)?;
+
+        if files_paths.is_empty() {
+            return Err(no_paths_err());
+        }
+
+        let mut lint_levels = LintLevels::default();
+
+        for (lint, level) in التحذيرات.unwrap_or_default() {
+            let Ok(lint) = lint.parse() else {
+                return Err(Diagnostic::error(
+                    format!("لا يوجد تحذير باسم `{lint}`"),
+                    vec![],
+                ));
+            };
+
+            let Ok(level) = level.parse() else {
+                return Err(Diagnostic::error(
+                    format!(
+                        "مستوى التحذير `{level}` غير صحيح، يجب أن يكون `تجاهل` أو `تحذير` أو `منع`"
+                    ),
+                    vec![],
+                ));
+            };
+
+            lint_levels.set(lint, level);
+        }
+
+        let numerals = match الأرقام {
+            Some(numerals) => match numerals.parse() {
+                Ok(numerals) => Some(numerals),
+                Err(_) => {
+                    return Err(Diagnostic::error(
+                        format!(
+                            "نوع الأرقام `{numerals}` غير صحيح، يجب أن يكون `لاتينية` أو `مشرقية`"
+                        ),
+                        vec![],
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            name: الاسم,
+            files_paths,
+            lint_levels,
+            numerals,
+        })
+    }
+}
+
+fn collect_paths(
+    paths: Vec<Value>,
+    prefix: &str,
+    collected_paths: &mut Vec<String>,
+) -> Result<(), Diagnostic> {
+    let join = |s: &str| {
+        if prefix.is_empty() {
+            s.to_string()
+        } else {
+            format!("{}/{}", prefix, s)
+        }
+    };
+
+    for path in paths {
+        match path {
+            Value::String(s) => collected_paths.push(join(&s)),
+            Value::Mapping(mapping) => {
+                for (key, value) in mapping {
+                    if let (Value::String(key), Value::Sequence(nested_paths)) = (key, value) {
+                        collect_paths(nested_paths, &join(&key), collected_paths)?;
+                    }
+                }
+            }
+            path => {
+                return Err(Diagnostic::error(
+                    format!("المسار `{path:?}` في ملف nazm.yaml غير صحيح"),
+                    vec![],
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
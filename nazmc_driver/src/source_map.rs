@@ -0,0 +1,31 @@
+use nazmc_diagnostics::{Diagnostic, SourceMap};
+use std::{fs, path::Path};
+
+/// Reads the files of the package from its directory to a source map in the order of the manifest,
+/// the paths are without the extension
+pub fn read_sources(root: &Path, files_paths: &[String]) -> Result<SourceMap, Vec<Diagnostic>> {
+    let mut source_map = SourceMap::new();
+    let mut diagnostics = vec![];
+
+    for file_path in files_paths {
+        let path = format!("{file_path}.نظم");
+
+        match fs::read_to_string(root.join(&path)) {
+            Ok(content) => {
+                if let Err(d) = source_map.add(path, content) {
+                    diagnostics.push(d);
+                }
+            }
+            Err(_) => diagnostics.push(Diagnostic::error(
+                format!("لا يمكن قراءة الملف `{path}` أو أنه غير موجود"),
+                vec![],
+            )),
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(source_map)
+    } else {
+        Err(diagnostics)
+    }
+}
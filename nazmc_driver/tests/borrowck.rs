@@ -0,0 +1,56 @@
+use common::{expect, parse, render, sources};
+use nazmc_driver::Config;
+
+mod common;
+
+/// Borrow checks the given content as the only file of the package and returns its rendered errors
+fn borrowck(content: &str) -> Option<String> {
+    let source_map = sources(&[("رئيسي.نظم", content)]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    let types = expect(&source_map, session.typeck(&nir));
+    session
+        .borrowck(&nir, &types)
+        .err()
+        .map(|diagnostics| render(&source_map, diagnostics))
+}
+
+#[test]
+fn test_use_after_move_is_an_error() {
+    let errors = borrowck(
+        "هيكل نقطة(ص4، ص4)\n\nدالة استهلك(ن: نقطة) {}\n\n\
+         دالة البداية() {\n    احجز ن = .نقطة(1، 2)؛\n    استهلك(ن)؛\n    استهلك(ن)؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("لا يمكن استخدام `ن` بعد نقله"));
+    assert!(errors.contains("تم نقله هنا"));
+    assert!(errors.contains("تم استخدامه هنا بعد نقله"));
+    assert!(errors.contains("رئيسي.نظم:8:12"));
+}
+
+#[test]
+fn test_overlapping_mutable_borrows_are_an_error() {
+    let errors = borrowck(
+        "دالة البداية() {\n    احجز متغير م = 1؛\n    احجز أ = #متغير م؛\n    احجز ب = #متغير م؛\n    \
+         *أ = 2؛\n    *ب = 3؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("لا يمكن استعارة `م` كمتغير أكثر من مرة في نفس الوقت"));
+}
+
+#[test]
+fn test_assigning_to_immutable_bindings_is_an_error() {
+    let errors = borrowck("دالة البداية() {\n    احجز م = 1؛\n    م = 2؛\n}\n").unwrap();
+    assert!(errors.contains("لا يمكن الإسناد إلى `م` لأنه غير متغير"));
+    assert!(errors.contains("تم تعريفه هنا بدون `متغير`"));
+}
+
+#[test]
+fn test_valid_borrows_are_accepted() {
+    assert!(borrowck(
+        "دالة البداية() {\n    احجز متغير م = 1؛\n    احجز أ = #متغير م؛\n    *أ = 2؛\n    \
+         احجز ب = #متغير م؛\n    *ب = 3؛\n    اظهر_(م)؛\n}\n",
+    )
+    .is_none());
+}
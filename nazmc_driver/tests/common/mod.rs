@@ -0,0 +1,37 @@
+//! The fixture shared by the integration tests of the driver, the phases expected to succeed panic
+//! with their rendered diagnostics instead of hiding them
+
+#![allow(dead_code)]
+
+use nazmc_diagnostics::{fmt_diagnostics, Diagnostic, HumanEmitter};
+use nazmc_driver::{Config, PhaseResult, Session, SourceMap};
+
+/// Adds the files with their paths and contents to a new source map
+pub fn sources(files: &[(&str, &str)]) -> SourceMap {
+    let mut source_map = SourceMap::new();
+    for (path, content) in files {
+        source_map
+            .add(path.to_string(), content.to_string())
+            .unwrap_or_else(|d| panic!("{}", render(&SourceMap::new(), vec![d])));
+    }
+    source_map
+}
+
+/// Renders the diagnostics like the compiler prints them
+pub fn render(source_map: &SourceMap, diagnostics: Vec<Diagnostic>) -> String {
+    fmt_diagnostics(&HumanEmitter::default(), source_map, diagnostics)
+}
+
+/// Parses the files of the source map
+pub fn parse(source_map: &SourceMap, config: Config) -> Session<'_> {
+    Session::parse(source_map, config)
+        .unwrap_or_else(|diagnostics| panic!("{}", render(source_map, diagnostics)))
+}
+
+/// Returns the output of the phase, its warnings are ignored
+pub fn expect<T>(source_map: &SourceMap, result: PhaseResult<T>) -> T {
+    match result {
+        Ok((output, _)) => output,
+        Err(diagnostics) => panic!("{}", render(source_map, diagnostics)),
+    }
+}
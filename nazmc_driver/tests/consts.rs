@@ -0,0 +1,66 @@
+use common::{expect, parse, render, sources};
+use nazmc_driver::Config;
+use nazmc_nir::PrimitiveType;
+use nazmc_typeck::ConstValue;
+
+mod common;
+
+/// Type checks the given content as the only file of the package, which evaluates its constants
+/// and statics, and returns its rendered errors
+fn typeck(content: &str) -> Option<String> {
+    let source_map = sources(&[("رئيسي.نظم", content)]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    session
+        .typeck(&nir)
+        .err()
+        .map(|diagnostics| render(&source_map, diagnostics))
+}
+
+#[test]
+fn test_consts_and_statics_are_evaluated() {
+    let source_map = sources(&[(
+        "رئيسي.نظم",
+        "ثابت الطول: ط = ب * 2؛\nثابت ب: ط = 3 + 1؛\nثابت ج: ص4 = -(5 * 2)؛\n\
+         مشترك العداد: ص4 = ج + 1؛\n\n\
+         دالة البداية() {\n    احجز أ: [ص4؛ الطول] = [0؛ الطول]؛\n    اظهر_(العداد)؛\n}\n",
+    )]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    let types = expect(&source_map, session.typeck(&nir));
+
+    let consts: Vec<_> = nir
+        .consts
+        .iter()
+        .map(|c| &session.id_pool[c.name.id])
+        .zip(&types.consts_values)
+        .collect();
+    assert!(consts.contains(&("الطول", &ConstValue::Int(8, PrimitiveType::U))));
+    assert!(consts.contains(&("ب", &ConstValue::Int(4, PrimitiveType::U))));
+    assert!(consts.contains(&("ج", &ConstValue::Int(-10, PrimitiveType::I4))));
+    assert_eq!(
+        types.statics_values[0],
+        ConstValue::Int(-9, PrimitiveType::I4)
+    );
+}
+
+#[test]
+fn test_invalid_consts_are_errors() {
+    let errors = typeck("ثابت أ: ص4 = ب + 1؛\nثابت ب: ص4 = أ؛\n\nدالة البداية() {}\n").unwrap();
+    assert!(errors.contains("يعتمد على نفسه"));
+
+    let errors = typeck("مشترك م: ص4 = 1؛\nثابت ث: ص4 = م؛\n\nدالة البداية() {}\n").unwrap();
+    assert!(errors.contains("لا يمكن استخدام المتغير المشترك `م` في تعريف ثابت"));
+
+    let errors =
+        typeck("دالة ف(): ص4 { أرجع 1؛ }\n\nثابت ث: ص4 = ف()؛\n\nدالة البداية() {}\n").unwrap();
+    assert!(errors.contains("لا يمكن حساب هذا التعبير أثناء الترجمة"));
+
+    let errors = typeck("ثابت ث: ص1 = 100 + 100؛\n\nدالة البداية() {}\n").unwrap();
+    assert!(errors.contains("حدث فائض في العملية الحسابية للنوع `ص1`"));
+
+    let errors = typeck("ثابت ث: ص4 = 1 / 0؛\n\nدالة البداية() {}\n").unwrap();
+    assert!(errors.contains("لا يمكن القسمة على صفر"));
+}
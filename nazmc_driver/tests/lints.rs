@@ -0,0 +1,71 @@
+use common::{expect, parse, render, sources};
+use nazmc_diagnostics::{Lint, LintLevel, LintLevels};
+use nazmc_driver::Config;
+
+mod common;
+
+const UNUSED: &str =
+    "دالة مساعدة() {}\n\nدالة البداية() {\n    احجز متغير أ = 1؛\n    احجز _ب = 2؛\n}\n";
+
+/// Runs the borrow checker and the lints on the content with the lint levels, and returns
+/// their rendered warnings, or their rendered errors if any of them has errors
+fn lints(content: &str, lint_levels: LintLevels) -> Result<String, String> {
+    let source_map = sources(&[("رئيسي.نظم", content)]);
+    let config = Config {
+        lint_levels,
+        ..Config::default()
+    };
+    let session = parse(&source_map, config);
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    let types = expect(&source_map, session.typeck(&nir));
+
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    for result in [session.borrowck(&nir, &types), session.lint(&nir)] {
+        match result {
+            Ok((_, diagnostics)) => warnings.extend(diagnostics),
+            Err(diagnostics) => errors.extend(diagnostics),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(render(&source_map, warnings))
+    } else {
+        Err(render(&source_map, errors))
+    }
+}
+
+#[test]
+fn test_lints_warn_by_default() {
+    let warnings = lints(UNUSED, LintLevels::default()).unwrap();
+    assert!(warnings.contains("لم يتم استخدام `أ`"));
+    assert!(warnings.contains("لم يتم استخدام الدالة `مساعدة`"));
+    assert!(warnings.contains("متغير_غير_ضروري"));
+    assert!(!warnings.contains("`_ب`"));
+}
+
+#[test]
+fn test_lint_levels_are_applied() {
+    let mut lint_levels = LintLevels::default();
+    lint_levels
+        .set(Lint::UnusedLets, LintLevel::Allow)
+        .set(Lint::UnusedMut, LintLevel::Allow)
+        .set(Lint::UnusedPrivateFns, LintLevel::Deny);
+    let errors = lints(UNUSED, lint_levels).unwrap_err();
+    assert!(errors.contains("لم يتم استخدام الدالة `مساعدة`"));
+    assert!(errors.contains("هذا الخطأ بسبب منع التحذير `دالة_غير_مستخدمة`"));
+    assert!(!errors.contains("`أ`"));
+
+    let mut lint_levels = LintLevels::default();
+    lint_levels.deny_warnings();
+    let errors = lints(UNUSED, lint_levels).unwrap_err();
+    assert!(errors.contains("لم يتم استخدام `أ`"));
+    assert!(errors.contains("هذا الخطأ بسبب منع التحذير `متغير_غير_ضروري`"));
+
+    let mut lint_levels = LintLevels::default();
+    for lint in Lint::ALL {
+        lint_levels.set(lint, LintLevel::Allow);
+    }
+    assert_eq!(lints(UNUSED, lint_levels), Ok(String::new()));
+}
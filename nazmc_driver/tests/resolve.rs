@@ -0,0 +1,58 @@
+use common::{parse, render, sources};
+use nazmc_driver::Config;
+
+mod common;
+
+/// Resolves the names of the package with the given files and returns its rendered errors
+fn resolve(files: &[(&str, &str)]) -> Option<String> {
+    let source_map = sources(files);
+    let session = parse(&source_map, Config::default());
+    session
+        .resolve()
+        .err()
+        .map(|diagnostics| render(&source_map, diagnostics))
+}
+
+#[test]
+fn test_similar_item_names_are_suggested() {
+    let errors = resolve(&[
+        ("رئيسي.نظم", "استيراد حسابات::أجمع؛\n\nدالة البداية() {}\n"),
+        ("حسابات/جمع.نظم", "تصدير دالة اجمع() {}\n"),
+    ])
+    .unwrap();
+    assert!(errors.contains("لم يتم العثور على الاسم `أجمع` في المسار"));
+    assert!(errors.contains("يوجد اسم مشابه `اجمع`"));
+    assert!(errors.contains("استيراد حسابات::اجمع؛"));
+
+    let errors = resolve(&[
+        (
+            "رئيسي.نظم",
+            "استيراد حسابات::مساعده؛\n\nدالة البداية() {}\n",
+        ),
+        ("حسابات/جمع.نظم", "تصدير دالة مساعدة() {}\n"),
+    ])
+    .unwrap();
+    assert!(errors.contains("يوجد اسم مشابه `مساعدة`"));
+}
+
+#[test]
+fn test_similar_package_segments_are_suggested() {
+    let errors = resolve(&[
+        ("رئيسي.نظم", "استيراد حسبات::اجمع؛\n\nدالة البداية() {}\n"),
+        ("حسابات/جمع.نظم", "تصدير دالة اجمع() {}\n"),
+    ])
+    .unwrap();
+    assert!(errors.contains("لم يتم العثور على الاسم `حسبات` في المسار"));
+    assert!(errors.contains("يوجد اسم مشابه `حسابات`"));
+}
+
+#[test]
+fn test_different_names_are_not_suggested() {
+    let errors = resolve(&[
+        ("رئيسي.نظم", "استيراد حسابات::اطرح؛\n\nدالة البداية() {}\n"),
+        ("حسابات/جمع.نظم", "تصدير دالة اجمع() {}\n"),
+    ])
+    .unwrap();
+    assert!(errors.contains("لم يتم العثور على الاسم `اطرح` في المسار"));
+    assert!(!errors.contains("يوجد اسم مشابه"));
+}
@@ -0,0 +1,112 @@
+use common::{expect, parse, render, sources};
+use nazmc_diagnostics::{fmt_diagnostics, span::Span, HumanEmitter, Numerals};
+use nazmc_driver::{Config, Manifest, RunError, Session};
+
+mod common;
+
+#[test]
+fn test_syntax_errors_are_returned() {
+    let source_map = sources(&[
+        ("رئيسي.نظم", "دالة البداية() {}\n"),
+        ("أخرى.نظم", "دالة أ( {}\n"),
+    ]);
+
+    let (session, diagnostics) = Session::new(&source_map, Config::default());
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics.iter().all(|d| d.is_error()));
+    assert_eq!(session.parsed_files.len(), 1);
+
+    assert!(Session::parse(&source_map, Config::default()).is_err());
+}
+
+#[test]
+fn test_phases_errors_are_returned() {
+    let source_map = sources(&[("رئيسي.نظم", "استيراد حسابات::اجمع؛\n\nدالة البداية() {}\n")]);
+    let session = parse(&source_map, Config::default());
+    assert!(session.resolve().is_err());
+
+    let source_map = sources(&[("رئيسي.نظم", "دالة البداية() {\n    احجز أ: ص4 = 1.5؛\n}\n")]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    assert!(session.typeck(&nir).is_err());
+}
+
+#[test]
+fn test_diagnostics_are_rendered_from_the_files_of_their_spans() {
+    let source_map = sources(&[
+        ("رئيسي.نظم", "استيراد حسابات::اجمع؛\n\nدالة البداية() {}\n"),
+        ("حسابات/جمع.نظم", "دالة اجمع() {}\n"),
+    ]);
+    let session = parse(&source_map, Config::default());
+    let diagnostics = session.resolve().err().unwrap();
+    let rendered = render(&source_map, diagnostics);
+
+    assert!(rendered.contains("رئيسي.نظم:1:17"));
+    assert!(rendered.contains("حسابات/جمع.نظم:1:6"));
+    assert!(rendered.contains("دالة اجمع() {}"));
+}
+
+#[test]
+fn test_span_cursors_are_computed_from_byte_offsets() {
+    let source_map = sources(&[("رئيسي.نظم", "دالة البداية() {\n    احجز أ = 1؛\n}\n")]);
+    let (file_id, file) = source_map.files().next().unwrap();
+
+    let lo = file.content().find('أ').unwrap() as u32;
+    let span = Span::new(file_id, lo, lo + 'أ'.len_utf8() as u32);
+    let (start, end) = file.span_cursors(span);
+
+    assert_eq!((start.line, start.col), (1, 9));
+    assert_eq!((end.line, end.col), (1, 10));
+    assert_eq!(file.offset(start), lo);
+}
+
+#[test]
+fn test_runtime_error_is_returned() {
+    let source_map = sources(&[(
+        "رئيسي.نظم",
+        "دالة البداية() {\n    احجز أ: ص4 = 0؛\n    اظهر_(1 / أ)؛\n}\n",
+    )]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    let types = expect(&source_map, session.typeck(&nir));
+    expect(&source_map, session.borrowck(&nir, &types));
+
+    let err = session.run(&nir, &types).unwrap_err();
+    assert!(matches!(err, RunError::Runtime(_)));
+    assert_eq!(err.exit_code(), 101);
+}
+
+#[test]
+fn test_manifest() {
+    let manifest = Manifest::parse("الاسم: حزمة\nالمسارات:\n  - رئيسي\n  - حسابات:\n    - جمع\n")
+        .ok()
+        .unwrap();
+    assert_eq!(manifest.name.as_deref(), Some("حزمة"));
+    assert_eq!(manifest.files_paths, ["رئيسي", "حسابات/جمع"]);
+
+    assert!(Manifest::parse("المسارات: []\n").is_err());
+    assert!(Manifest::parse("المسارات:\n  - رئيسي\nالتحذيرات:\n  غير_موجود: منع\n").is_err());
+}
+
+#[test]
+fn test_numerals_of_the_config_are_used() {
+    let source_map = sources(&[("رئيسي.نظم", "دالة البداية() {\n    احجز أ: ط1 = 300؛\n}\n")]);
+    let config = Config {
+        numerals: Numerals::ArabicIndic,
+        ..Config::default()
+    };
+    let session = parse(&source_map, config);
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    let diagnostics = session.typeck(&nir).err().unwrap();
+    let emitter = HumanEmitter {
+        numerals: Numerals::ArabicIndic,
+    };
+    let rendered = fmt_diagnostics(&emitter, &source_map, diagnostics);
+
+    assert!(rendered.contains("العدد ٣٠٠ أكبر من أن يتسع في النوع `ط1`"));
+    assert!(rendered.contains("أكبر قيمة للنوع `ط1` هى ٢٥٥"));
+    assert!(rendered.contains("رئيسي.نظم:٢:١٨"));
+}
@@ -0,0 +1,193 @@
+use common::{expect, parse, render, sources};
+use nazmc_driver::Config;
+
+mod common;
+
+/// Type checks the given content as the only file of the package and returns its rendered errors
+fn typeck(content: &str) -> Option<String> {
+    let source_map = sources(&[("رئيسي.نظم", content)]);
+    let session = parse(&source_map, Config::default());
+    let nrt = expect(&source_map, session.resolve());
+    let nir = expect(&source_map, session.build_nir(&nrt));
+    session
+        .typeck(&nir)
+        .err()
+        .map(|diagnostics| render(&source_map, diagnostics))
+}
+
+#[test]
+fn test_negating_unsigned_ints_is_an_error() {
+    let errors = typeck("دالة البداية() {\n    احجز م: ط4 = -5؛\n}\n").unwrap();
+    assert!(errors.contains("لا يمكن استخدام هذه العملية مع النوع `ط4`"));
+    assert!(errors.contains("رئيسي.نظم:2:19"));
+
+    let errors = typeck("دالة ف(أ: ط1) {}\n\nدالة البداية() {\n    ف(-1)؛\n}\n").unwrap();
+    assert!(errors.contains("لا يمكن استخدام هذه العملية مع النوع `ط1`"));
+
+    let errors =
+        typeck("دالة البداية() {\n    احجز متغير م = 1؛\n    م = -م؛\n    احجز ن: ط8 = م؛\n}\n")
+            .unwrap();
+    assert!(errors.contains("لا يمكن استخدام هذه العملية مع النوع `ط8`"));
+}
+
+#[test]
+fn test_negating_signed_numbers_is_accepted() {
+    assert_eq!(
+        typeck(
+            "دالة البداية() {\n    احجز م: ص4 = -5؛\n    احجز ن = -1.5؛\n    احجز ل: ع4 = -ن؛\n}\n"
+        ),
+        None
+    );
+    assert_eq!(
+        typeck("دالة ف(أ: ص1) {}\n\nدالة البداية() {\n    ف(-1)؛\n}\n"),
+        None
+    );
+}
+
+#[test]
+fn test_literals_widths_are_inferred_from_context() {
+    assert_eq!(
+        typeck("دالة ف(أ: ط8) {}\n\nدالة البداية() {\n    احجز م = 300؛\n    ف(م)؛\n}\n"),
+        None
+    );
+
+    let errors = typeck("دالة البداية() {\n    احجز م: ط1 = 300؛\n}\n").unwrap();
+    assert!(errors.contains("العدد 300 أكبر من أن يتسع في النوع `ط1`"));
+
+    let errors =
+        typeck("دالة ف(أ: ط1) {}\n\nدالة البداية() {\n    احجز م = 300؛\n    ف(م)؛\n}\n").unwrap();
+    assert!(errors.contains("العدد 300 أكبر من أن يتسع في النوع `ط1`"));
+
+    let errors = typeck("دالة البداية() {\n    احجز م: ص4 = 1.5؛\n}\n").unwrap();
+    assert!(errors.contains("يُتوقع النوع `ص4`، ولكن تم العثور على النوع `{عدد عشري}`"));
+}
+
+#[test]
+fn test_signed_literals_are_checked_against_the_bounds_of_their_types() {
+    let bounds = [
+        ("ص1", "128"),
+        ("ص2", "32768"),
+        ("ص4", "2147483648"),
+        ("ص8", "9223372036854775808"),
+        ("ص", "9223372036854775808"),
+    ];
+
+    for (ty, min) in bounds {
+        let max = (min.parse::<u64>().unwrap() - 1).to_string();
+        let after_min = (min.parse::<u64>().unwrap() + 1).to_string();
+
+        assert_eq!(
+            typeck(&format!(
+                "دالة البداية() {{\n    احجز م: {ty} = -{min}؛\n    احجز ن: {ty} = {max}؛\n}}\n"
+            )),
+            None
+        );
+
+        let errors = typeck(&format!(
+            "دالة البداية() {{\n    احجز م: {ty} = -{after_min}؛\n}}\n"
+        ))
+        .unwrap();
+        assert!(errors.contains(&format!(
+            "العدد -{after_min} أصغر من أن يتسع في النوع `{ty}`"
+        )));
+        assert!(errors.contains(&format!("أصغر قيمة للنوع `{ty}` هى -{min}")));
+
+        let errors = typeck(&format!(
+            "دالة البداية() {{\n    احجز م: {ty} = {min}؛\n}}\n"
+        ))
+        .unwrap();
+        assert!(errors.contains(&format!("العدد {min} أكبر من أن يتسع في النوع `{ty}`")));
+    }
+
+    assert_eq!(
+        typeck("دالة البداية() {\n    احجز م: ص1 = 0؛\n    احجز ن = عندما م { -128..<0 -> 1، _ -> 0، }؛\n}\n"),
+        None
+    );
+
+    let errors = typeck(
+        "دالة البداية() {\n    احجز م: ص1 = 0؛\n    احجز ن = عندما م { -129..<0 -> 1، _ -> 0، }؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("العدد -129 أصغر من أن يتسع في النوع `ص1`"));
+}
+
+#[test]
+fn test_call_args_are_checked_against_params() {
+    let errors = typeck("دالة ف(أ: ص4، ب: شرط) {}\n\nدالة البداية() {\n    ف(1، 2)؛\n}\n").unwrap();
+    assert!(errors.contains("يُتوقع النوع `شرط`، ولكن تم العثور على النوع `{عدد صحيح}`"));
+
+    let errors = typeck("دالة ف(أ: ص4، ب: شرط) {}\n\nدالة البداية() {\n    ف(1)؛\n}\n").unwrap();
+    assert!(errors.contains("يُتوقع 2 من المُعاملات، ولكن تم العثور على 1"));
+}
+
+#[test]
+fn test_struct_fields_are_checked_against_their_types() {
+    let errors = typeck(
+        "هيكل شخص { الاسم: #متن، العمر: ط4، }\n\n\
+         دالة البداية() {\n    احجز ش = .شخص { الاسم = \"علي\"، العمر = صحيح }؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("يُتوقع النوع `ط4`، ولكن تم العثور على النوع `شرط`"));
+
+    let errors =
+        typeck("هيكل نقطة(ص4، ص4)\n\nدالة البداية() {\n    احجز ن = .نقطة(5، 1.5)؛\n}\n").unwrap();
+    assert!(errors.contains("يُتوقع النوع `ص4`، ولكن تم العثور على النوع `{عدد عشري}`"));
+}
+
+#[test]
+fn test_when_arms_must_cover_all_values() {
+    let errors = typeck(
+        "تصنيف شكل { نقطة، دائرة(ص4)، }\n\n\
+         دالة البداية() {\n    احجز ش = .شكل::دائرة(1)؛\n    \
+         احجز ن = عندما ش { .شكل::نقطة -> 0، .شكل::دائرة(ن) لو ن > 0 -> ن، }؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("أنماط `عندما` لا تغطي كل القيم الممكنة"));
+    assert!(errors.contains("النمط `.شكل::دائرة(_)` غير مغطى"));
+
+    assert_eq!(
+        typeck(
+            "تصنيف شكل { نقطة، دائرة(ص4)، }\n\n\
+             دالة البداية() {\n    احجز ش = .شكل::دائرة(1)؛\n    \
+             احجز ن = عندما ش { .شكل::دائرة(ن) لو ن > 0 -> ن، _ -> 0، }؛\n}\n",
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_int_ranges_patterns_cover_the_values_of_their_types() {
+    let when = |ty: &str, arms: &str| {
+        typeck(&format!(
+            "دالة البداية() {{\n    احجز م: {ty} = 0؛\n    احجز ن = عندما م {{ {arms} }}؛\n}}\n"
+        ))
+    };
+
+    assert_eq!(when("ط1", "0..<128 -> 1، 128..255 -> 2،"), None);
+    assert_eq!(when("ط1", "0..<100 -> 1، 100 -> 2، 101..255 -> 3،"), None);
+    assert_eq!(when("ص1", "-128..-1 -> 1، 0 -> 2، 1..127 -> 3،"), None);
+
+    let errors = when("ط1", "0..<100 -> 1، 101..255 -> 2،").unwrap();
+    assert!(errors.contains("أنماط `عندما` لا تغطي كل القيم الممكنة"));
+    assert!(errors.contains("النمط `100` غير مغطى"));
+
+    let errors = when("ص1", "-128..-1 -> 1، 10..127 -> 2،").unwrap();
+    assert!(errors.contains("النمط `0..9` غير مغطى"));
+
+    let errors = when("ص4", "0 -> 1، ن لو ن > 0 -> 2،").unwrap();
+    assert!(errors.contains("النمط `-2147483648..-1` غير مغطى"));
+
+    assert_eq!(
+        typeck(
+            "دالة البداية() {\n    احجز م = (صحيح، 5ط1)؛\n    \
+             احجز ن = عندما م { (صحيح، 0..255) -> 1، (فاسد، _) -> 2، }؛\n}\n"
+        ),
+        None
+    );
+    let errors = typeck(
+        "دالة البداية() {\n    احجز م = (صحيح، 5ط1)؛\n    \
+         احجز ن = عندما م { (صحيح، 1..255) -> 1، (فاسد، _) -> 2، }؛\n}\n",
+    )
+    .unwrap();
+    assert!(errors.contains("النمط `(صحيح، 0)` غير مغطى"));
+}
@@ -0,0 +1,434 @@
+use crate::{
+    const_value,
+    value::{fit_float, int_bits, int_bounds, wrap_int, Closure, Place, Value},
+    Eval, Interpreter, Unwind,
+};
+use nazmc_diagnostics::span::Span;
+use nazmc_nir::*;
+use nazmc_typeck::Ty;
+use std::{cmp::Ordering, io::Write, rc::Rc};
+
+impl<'a> Interpreter<'a> {
+    pub(crate) fn eval_expr(&mut self, expr: &Expr) -> Eval<'a, Value<'a>> {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        let value = match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT => Value::Unit,
+            ExprKindAndIndex::LITERAL => self.eval_literal(&exprs.literals[index], expr),
+            ExprKindAndIndex::PARENS => self.eval_expr(&exprs.parens[index].expr)?,
+            ExprKindAndIndex::PATH => {
+                let path = &exprs.paths[index];
+                match path.resolved {
+                    ResolvedPath::Local(local_idx) => {
+                        self.frame().locals[&local_idx].borrow().clone()
+                    }
+                    ResolvedPath::Fn(fn_idx) => Value::Fn(fn_idx),
+                    ResolvedPath::UnitStruct(idx) => Value::UnitStruct(idx),
+                    ResolvedPath::Const(idx) => {
+                        const_value(self.str_pool, &self.types.consts_values[idx])
+                    }
+                    ResolvedPath::Static(idx) => self.statics[idx].borrow().clone(),
+                    ResolvedPath::TupleStruct(_)
+                    | ResolvedPath::FieldsStruct(_)
+                    | ResolvedPath::Enum(_)
+                    | ResolvedPath::Variant(..)
+                    | ResolvedPath::Builtin(_) => {
+                        unreachable!("The type checker rejects using them as values")
+                    }
+                }
+            }
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                let args = self.eval_exprs(&call.args)?;
+                match call.path.resolved {
+                    ResolvedPath::Fn(fn_idx) => self.call_fn(fn_idx, args, expr.span)?,
+                    ResolvedPath::Builtin(builtin) => self.call_builtin(builtin, args),
+                    ResolvedPath::Local(local_idx) => {
+                        let on = self.frame().locals[&local_idx].borrow().clone();
+                        self.call_value(on, args, expr.span)?
+                    }
+                    _ => unreachable!("The type checker only accepts calling fns and lambdas"),
+                }
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                let on = self.eval_expr(&call.on)?;
+                let args = self.eval_exprs(&call.args)?;
+                self.call_value(on, args, expr.span)?
+            }
+            ExprKindAndIndex::UNIT_STRUCT => match exprs.unit_structs[index].resolved {
+                ResolvedPath::UnitStruct(idx) => Value::UnitStruct(idx),
+                ResolvedPath::Variant(enum_idx, variant_idx) => {
+                    Value::Variant(enum_idx, variant_idx, vec![])
+                }
+                _ => unreachable!(),
+            },
+            ExprKindAndIndex::TUPLE_STRUCT => {
+                let tuple_struct = &exprs.tuple_structs[index];
+                let args = self.eval_exprs(&tuple_struct.args)?;
+                match tuple_struct.path.resolved {
+                    ResolvedPath::TupleStruct(idx) => Value::TupleStruct(idx, args),
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        Value::Variant(enum_idx, variant_idx, args)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                let fields_struct = &exprs.fields_structs[index];
+                let resolved = fields_struct.path.resolved;
+                let mut values = vec![Value::Unit; self.fields_count(resolved)];
+
+                // The fields are evaluated in the order they are written
+                for field in &fields_struct.fields {
+                    let field_idx = self.field_position(resolved, field.name.id);
+                    values[field_idx] = self.eval_expr(&field.expr)?;
+                }
+
+                match resolved {
+                    ResolvedPath::FieldsStruct(idx) => Value::FieldsStruct(idx, values),
+                    ResolvedPath::Variant(enum_idx, variant_idx) => {
+                        Value::Variant(enum_idx, variant_idx, values)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            ExprKindAndIndex::FIELD | ExprKindAndIndex::INDEX => self.eval_place(expr)?.read(),
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                Value::Array(self.eval_exprs(&exprs.array_elements[index].elements)?)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let array = &exprs.array_elements_sized[index];
+                let repeat = self.eval_expr(&array.repeat)?;
+                let Ty::Array(_, size) = self.types.expr_ty(expr) else {
+                    unreachable!()
+                };
+                Value::Array(vec![repeat; *size as usize])
+            }
+            ExprKindAndIndex::TUPLE_EXPR => {
+                Value::Tuple(self.eval_exprs(&exprs.tuples[index].elements)?)
+            }
+            ExprKindAndIndex::RETURN => return Err(Unwind::Return(Value::Unit)),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                let value = self.eval_expr(&exprs.returns[index].expr_to_return)?;
+                return Err(Unwind::Return(value));
+            }
+            ExprKindAndIndex::BREAK => return Err(Unwind::Break),
+            ExprKindAndIndex::CONTINUE => return Err(Unwind::Continue),
+            ExprKindAndIndex::IF_EXPR => self.eval_if(&exprs.ifs[index])?,
+            ExprKindAndIndex::WHEN_EXPR => self.eval_when(&exprs.whens[index])?,
+            ExprKindAndIndex::LAMBDA_EXPR => {
+                let frame = self.frames.last().unwrap();
+                Value::Lambda(Rc::new(Closure {
+                    lambda_idx: index,
+                    captured: frame.locals.clone(),
+                }))
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                self.eval_unary_expr(unary, expr)?
+            }
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                self.eval_bin_expr(bin, expr.span)?
+            }
+            _ => unreachable!("The type checker rejects the other expressions"),
+        };
+
+        Ok(value)
+    }
+
+    fn eval_exprs(&mut self, exprs: &[Expr]) -> Eval<'a, Vec<Value<'a>>> {
+        exprs.iter().map(|expr| self.eval_expr(expr)).collect()
+    }
+
+    fn eval_literal(&self, literal: &LiteralExpr, expr: &Expr) -> Value<'a> {
+        match literal {
+            LiteralExpr::Str(idx) => Value::Str(&self.str_pool[*idx]),
+            LiteralExpr::Char(ch) => Value::Char(*ch),
+            LiteralExpr::Bool(b) => Value::Bool(*b),
+            LiteralExpr::Num(num) => match *num {
+                NumKind::F4(f) => Value::Float(f as f64, PrimitiveType::F4),
+                NumKind::F8(f) => Value::Float(f, PrimitiveType::F8),
+                NumKind::I(i) => Value::Int(i as i128, PrimitiveType::I),
+                NumKind::I1(i) => Value::Int(i as i128, PrimitiveType::I1),
+                NumKind::I2(i) => Value::Int(i as i128, PrimitiveType::I2),
+                NumKind::I4(i) => Value::Int(i as i128, PrimitiveType::I4),
+                NumKind::I8(i) => Value::Int(i as i128, PrimitiveType::I8),
+                NumKind::U(u) => Value::Int(u as i128, PrimitiveType::U),
+                NumKind::U1(u) => Value::Int(u as i128, PrimitiveType::U1),
+                NumKind::U2(u) => Value::Int(u as i128, PrimitiveType::U2),
+                NumKind::U4(u) => Value::Int(u as i128, PrimitiveType::U4),
+                NumKind::U8(u) => Value::Int(u as i128, PrimitiveType::U8),
+                NumKind::UnspecifiedInt(u) => match self.types.expr_ty(expr) {
+                    Ty::Primitive(p) if p.is_float() => Value::Float(fit_float(u as f64, *p), *p),
+                    Ty::Primitive(p) => Value::Int(u as i128, *p),
+                    _ => unreachable!(),
+                },
+                NumKind::UnspecifiedFloat(f) => match self.types.expr_ty(expr) {
+                    Ty::Primitive(p) => Value::Float(fit_float(f, *p), *p),
+                    _ => unreachable!(),
+                },
+            },
+        }
+    }
+
+    /// Evaluates the expression to the location of its value,
+    /// the expressions which are not places are evaluated to temporary places
+    fn eval_place(&mut self, expr: &Expr) -> Eval<'a, Place<'a>> {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        let place = match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                ResolvedPath::Local(local_idx) => Place {
+                    cell: self.frame().locals[&local_idx].clone(),
+                    projections: vec![],
+                },
+                ResolvedPath::Static(idx) => Place {
+                    cell: self.statics[idx].clone(),
+                    projections: vec![],
+                },
+                _ => Place::temp(self.eval_expr(expr)?),
+            },
+            ExprKindAndIndex::PARENS => self.eval_place(&exprs.parens[index].expr)?,
+            ExprKindAndIndex::FIELD => {
+                let field = &exprs.fields[index];
+                let mut place = auto_deref(self.eval_place(&field.on)?);
+                let struct_idx = place.with(|value| match value {
+                    Value::FieldsStruct(struct_idx, _) => *struct_idx,
+                    _ => unreachable!("The type checker only accepts fields of structs"),
+                });
+                let field_idx =
+                    self.field_position(ResolvedPath::FieldsStruct(struct_idx), field.name.id);
+                place.projections.push(field_idx);
+                place
+            }
+            ExprKindAndIndex::INDEX => {
+                let index_expr = &exprs.indexes[index];
+                let mut place = auto_deref(self.eval_place(&index_expr.on)?);
+                let idx = match self.eval_expr(&index_expr.idx)? {
+                    Value::Int(idx, _) => idx,
+                    _ => unreachable!("The type checker only accepts int indices"),
+                };
+                let len = place.with(|value| value.elements().len());
+                if idx < 0 || idx >= len as i128 {
+                    let msg = format!(
+                        "الفهرس {} خارج حدود المصفوفة ذات الطول {}",
+                        self.numerals.fmt(idx),
+                        self.numerals.fmt(len)
+                    );
+                    return Err(self.runtime_err(msg, index_expr.idx.span));
+                }
+                place.projections.push(idx as usize);
+                place
+            }
+            ExprKindAndIndex::UNARY_EXPR if exprs.unary_exprs[index].op == UnaryOp::Deref => {
+                match self.eval_expr(&exprs.unary_exprs[index].expr)? {
+                    Value::Ref(place) => place,
+                    _ => unreachable!("The type checker only accepts dereferencing pointers"),
+                }
+            }
+            _ => Place::temp(self.eval_expr(expr)?),
+        };
+
+        Ok(place)
+    }
+
+    fn call_value(
+        &mut self,
+        on: Value<'a>,
+        args: Vec<Value<'a>>,
+        span: Span,
+    ) -> Eval<'a, Value<'a>> {
+        match on {
+            Value::Fn(fn_idx) => self.call_fn(fn_idx, args, span),
+            Value::Lambda(closure) => self.call_lambda(&closure, args, span),
+            _ => unreachable!("The type checker only accepts calling fns and lambdas"),
+        }
+    }
+
+    fn call_builtin(&mut self, builtin: BuiltinFn, args: Vec<Value<'a>>) -> Value<'a> {
+        let mut out = String::new();
+        self.fmt_value(&args[0], &mut out);
+
+        if builtin == BuiltinFn::PrintLn {
+            out.push('\n');
+        }
+
+        self.stdout.write_all(out.as_bytes()).unwrap();
+
+        Value::Unit
+    }
+
+    fn eval_unary_expr(&mut self, unary: &UnaryExpr, expr: &Expr) -> Eval<'a, Value<'a>> {
+        let value = match unary.op {
+            UnaryOp::Minus => match self.eval_expr(&unary.expr)? {
+                Value::Int(i, p) => self.check_int(-i, p, expr.span)?,
+                Value::Float(f, p) => Value::Float(-f, p),
+                _ => unreachable!(),
+            },
+            UnaryOp::LNot => match self.eval_expr(&unary.expr)? {
+                Value::Bool(b) => Value::Bool(!b),
+                _ => unreachable!(),
+            },
+            UnaryOp::BNot => match self.eval_expr(&unary.expr)? {
+                Value::Int(i, p) => Value::Int(wrap_int(!i, p), p),
+                _ => unreachable!(),
+            },
+            UnaryOp::Deref => self.eval_place(expr)?.read(),
+            UnaryOp::Borrow | UnaryOp::BorrowMut => Value::Ref(self.eval_place(&unary.expr)?),
+        };
+
+        Ok(value)
+    }
+
+    fn eval_bin_expr(&mut self, bin: &BinExpr, span: Span) -> Eval<'a, Value<'a>> {
+        let value = match bin.op {
+            BinOp::LOr | BinOp::LAnd => {
+                let left = self.eval_condition(&bin.left)?;
+                // Short circuit
+                if left == (bin.op == BinOp::LOr) {
+                    Value::Bool(left)
+                } else {
+                    Value::Bool(self.eval_condition(&bin.right)?)
+                }
+            }
+            BinOp::EqualEqual | BinOp::NotEqual | BinOp::GE | BinOp::GT | BinOp::LE | BinOp::LT => {
+                let left = self.eval_expr(&bin.left)?;
+                let right = self.eval_expr(&bin.right)?;
+                let ordering = left.compare(&right);
+                Value::Bool(match bin.op {
+                    BinOp::EqualEqual => ordering == Some(Ordering::Equal),
+                    BinOp::NotEqual => ordering != Some(Ordering::Equal),
+                    BinOp::GE => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    BinOp::GT => ordering == Some(Ordering::Greater),
+                    BinOp::LE => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    _ => ordering == Some(Ordering::Less),
+                })
+            }
+            BinOp::OpenOpenRange
+            | BinOp::CloseOpenRange
+            | BinOp::OpenCloseRange
+            | BinOp::CloseCloseRange => {
+                unreachable!("The type checker rejects ranges as values")
+            }
+            BinOp::Assign => {
+                let value = self.eval_expr(&bin.right)?;
+                self.eval_place(&bin.left)?.write(value);
+                Value::Unit
+            }
+            BinOp::PlusAssign
+            | BinOp::MinusAssign
+            | BinOp::TimesAssign
+            | BinOp::DivAssign
+            | BinOp::ModAssign
+            | BinOp::BAndAssign
+            | BinOp::BOrAssign
+            | BinOp::XorAssign
+            | BinOp::ShlAssign
+            | BinOp::ShrAssign => {
+                let right = self.eval_expr(&bin.right)?;
+                let place = self.eval_place(&bin.left)?;
+                let op = match bin.op {
+                    BinOp::PlusAssign => BinOp::Plus,
+                    BinOp::MinusAssign => BinOp::Minus,
+                    BinOp::TimesAssign => BinOp::Times,
+                    BinOp::DivAssign => BinOp::Div,
+                    BinOp::ModAssign => BinOp::Mod,
+                    BinOp::BAndAssign => BinOp::BAnd,
+                    BinOp::BOrAssign => BinOp::BOr,
+                    BinOp::XorAssign => BinOp::Xor,
+                    BinOp::ShlAssign => BinOp::Shl,
+                    _ => BinOp::Shr,
+                };
+                let value = self.eval_arithmetic(op, place.read(), right, span)?;
+                place.write(value);
+                Value::Unit
+            }
+            op => {
+                let left = self.eval_expr(&bin.left)?;
+                let right = self.eval_expr(&bin.right)?;
+                self.eval_arithmetic(op, left, right, span)?
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn eval_arithmetic(
+        &mut self,
+        op: BinOp,
+        left: Value<'a>,
+        right: Value<'a>,
+        span: Span,
+    ) -> Eval<'a, Value<'a>> {
+        let value = match (left, right) {
+            (Value::Int(l, p), Value::Int(r, _)) => match op {
+                BinOp::Plus => self.check_int(l + r, p, span)?,
+                BinOp::Minus => self.check_int(l - r, p, span)?,
+                BinOp::Times => self.check_int(l * r, p, span)?,
+                BinOp::Div | BinOp::Mod if r == 0 => {
+                    return Err(self.runtime_err("لا يمكن القسمة على صفر".to_string(), span))
+                }
+                BinOp::Div => self.check_int(l / r, p, span)?,
+                BinOp::Mod => self.check_int(l % r, p, span)?,
+                BinOp::BAnd => Value::Int(l & r, p),
+                BinOp::BOr => Value::Int(l | r, p),
+                BinOp::Xor => Value::Int(l ^ r, p),
+                BinOp::Shl | BinOp::Shr => {
+                    if r < 0 || r >= int_bits(p) as i128 {
+                        let msg = format!(
+                            "لا يمكن إزاحة النوع `{}` بمقدار {} من البتات",
+                            p.name(),
+                            self.numerals.fmt(r)
+                        );
+                        return Err(self.runtime_err(msg, span));
+                    }
+                    if op == BinOp::Shl {
+                        Value::Int(wrap_int(l << r, p), p)
+                    } else {
+                        Value::Int(l >> r, p)
+                    }
+                }
+                _ => unreachable!(),
+            },
+            (Value::Float(l, p), Value::Float(r, _)) => {
+                let value = match op {
+                    BinOp::Plus => l + r,
+                    BinOp::Minus => l - r,
+                    BinOp::Times => l * r,
+                    BinOp::Div => l / r,
+                    BinOp::Mod => l % r,
+                    _ => unreachable!(),
+                };
+                Value::Float(fit_float(value, p), p)
+            }
+            _ => unreachable!("The type checker only accepts numbers in arithmetic operations"),
+        };
+
+        Ok(value)
+    }
+
+    /// Reports the overflow if the value does not fit in the int type
+    fn check_int(&mut self, value: i128, typ: PrimitiveType, span: Span) -> Eval<'a, Value<'a>> {
+        let (min, max) = int_bounds(typ);
+        if value < min || value > max {
+            let msg = format!("حدث فائض في العملية الحسابية للنوع `{}`", typ.name());
+            return Err(self.runtime_err(msg, span));
+        }
+        Ok(Value::Int(value, typ))
+    }
+}
+
+/// Follows the references to the place of the underlying value, like the type checker does
+fn auto_deref(mut place: Place<'_>) -> Place<'_> {
+    while let Some(inner) = place.with(|value| match value {
+        Value::Ref(inner) => Some(inner.clone()),
+        _ => None,
+    }) {
+        place = inner;
+    }
+    place
+}
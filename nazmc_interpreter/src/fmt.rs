@@ -0,0 +1,82 @@
+use crate::{value::Value, Interpreter};
+use nazmc_nir::{PrimitiveType, VariantKind};
+
+impl<'a> Interpreter<'a> {
+    /// Writes the value as `اظهر` shows it
+    pub(crate) fn fmt_value(&self, value: &Value<'a>, out: &mut String) {
+        match value {
+            Value::Unit => out.push_str("()"),
+            Value::Int(i, _) => out.push_str(&self.numerals.fmt(i)),
+            Value::Float(f, PrimitiveType::F4) => {
+                out.push_str(&self.numerals.fmt(format!("{:?}", *f as f32)))
+            }
+            Value::Float(f, _) => out.push_str(&self.numerals.fmt(format!("{:?}", f))),
+            Value::Bool(true) => out.push_str("صحيح"),
+            Value::Bool(false) => out.push_str("فاسد"),
+            Value::Char(ch) => out.push(*ch),
+            Value::Str(s) => out.push_str(s),
+            Value::UnitStruct(idx) => {
+                out.push_str(&self.id_pool[self.nir.unit_structs[*idx].name.id]);
+            }
+            Value::TupleStruct(idx, elements) => {
+                out.push_str(&self.id_pool[self.nir.tuple_structs[*idx].name.id]);
+                self.fmt_values(elements, "(", ")", out);
+            }
+            Value::FieldsStruct(idx, fields) => {
+                let fields_struct = &self.nir.fields_structs[*idx];
+                out.push_str(&self.id_pool[fields_struct.name.id]);
+                out.push_str(" { ");
+                for (i, ((_, name, _), value)) in
+                    fields_struct.fields.iter().zip(fields).enumerate()
+                {
+                    if i != 0 {
+                        out.push_str("، ");
+                    }
+                    out.push_str(&self.id_pool[name.id]);
+                    out.push_str(" = ");
+                    self.fmt_value(value, out);
+                }
+                out.push_str(" }");
+            }
+            Value::Variant(enum_idx, variant_idx, fields) => {
+                let e = &self.nir.enums[*enum_idx];
+                let variant = &e.variants[*variant_idx];
+                out.push_str(&self.id_pool[e.name.id]);
+                out.push_str("::");
+                out.push_str(&self.id_pool[variant.name.id]);
+                match &variant.kind {
+                    VariantKind::Unit => {}
+                    VariantKind::Tuple(_) => self.fmt_values(fields, "(", ")", out),
+                    VariantKind::Fields(names) => {
+                        out.push_str(" { ");
+                        for (i, ((name, _), value)) in names.iter().zip(fields).enumerate() {
+                            if i != 0 {
+                                out.push_str("، ");
+                            }
+                            out.push_str(&self.id_pool[name.id]);
+                            out.push_str(" = ");
+                            self.fmt_value(value, out);
+                        }
+                        out.push_str(" }");
+                    }
+                }
+            }
+            Value::Tuple(elements) => self.fmt_values(elements, "(", ")", out),
+            Value::Array(elements) => self.fmt_values(elements, "[", "]", out),
+            Value::Ref(place) => place.with(|value| self.fmt_value(value, out)),
+            // The fns used as values have the same types of the lambdas
+            Value::Fn(_) | Value::Lambda(_) => out.push_str("لامدا"),
+        }
+    }
+
+    fn fmt_values(&self, values: &[Value<'a>], open: &str, close: &str, out: &mut String) {
+        out.push_str(open);
+        for (i, value) in values.iter().enumerate() {
+            if i != 0 {
+                out.push_str("، ");
+            }
+            self.fmt_value(value, out);
+        }
+        out.push_str(close);
+    }
+}
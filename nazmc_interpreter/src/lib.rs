@@ -0,0 +1,474 @@
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic, Numerals};
+use nazmc_nir::*;
+use nazmc_typeck::{ConstValue, TypesTable};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    io::{self, Write},
+    rc::Rc,
+};
+use value::{Cell, Closure, Locals, Value};
+
+mod exprs;
+mod fmt;
+mod value;
+
+/// The reason of leaving a body before reaching its end
+enum Unwind<'a> {
+    Break,
+    Continue,
+    Return(Value<'a>),
+    /// A runtime error which stops the whole program
+    Panic(Box<Diagnostic>),
+}
+
+type Eval<'a, T> = Result<T, Unwind<'a>>;
+
+/// The max number of the nested calls before reporting a stack overflow instead of overflowing the
+/// stack of the interpreter itself
+const MAX_FRAMES: usize = 1000;
+
+/// The stack size of the thread running the interpreter, it fits `MAX_FRAMES` nested calls even in
+/// the debug builds
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// The locals of a function or a lambda being executed
+struct Frame<'a> {
+    locals: Locals<'a>,
+}
+
+/// The reason of not running the program to its end
+pub enum RunError {
+    /// The program cannot start as `البداية` is missing or has params
+    Start(Box<Diagnostic>),
+    /// An error happened while executing the program, the output before it is already written
+    Runtime(Box<Diagnostic>),
+}
+
+impl RunError {
+    /// The exit code of the process, it is 101 for the runtime errors like the panics of Rust
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RunError::Start(_) => 1,
+            RunError::Runtime(_) => 101,
+        }
+    }
+
+    pub fn into_diagnostic(self) -> Diagnostic {
+        match self {
+            RunError::Start(d) | RunError::Runtime(d) => *d,
+        }
+    }
+}
+
+/// Executes the NIR directly starting from the `البداية` function
+pub struct Interpreter<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    /// The pool used to preserve the string literals values
+    str_pool: &'a DataPool<Built>,
+    nir: &'a NIR,
+    types: &'a TypesTable,
+    /// The digits of the numbers in the output and in the runtime errors
+    numerals: Numerals,
+    frames: Vec<Frame<'a>>,
+    /// The cells of the statics in `NIR.statics`, they live until the end of the program
+    statics: Vec<Cell<'a>>,
+    /// The output of `اظهر` and `اظهر_`
+    stdout: io::StdoutLock<'static>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(
+        id_pool: &'a DataPool<Built>,
+        str_pool: &'a DataPool<Built>,
+        nir: &'a NIR,
+        types: &'a TypesTable,
+        numerals: Numerals,
+    ) -> Self {
+        let statics = types
+            .statics_values
+            .iter()
+            .map(|value| Rc::new(RefCell::new(const_value(str_pool, value))))
+            .collect();
+
+        Self {
+            id_pool,
+            str_pool,
+            nir,
+            types,
+            numerals,
+            frames: vec![],
+            statics,
+            stdout: io::stdout().lock(),
+        }
+    }
+
+    pub fn run(mut self) -> Result<(), RunError> {
+        let Some(main_idx) = self.nir.fns.iter().position(|f| f.name.id == PoolIdx::MAIN) else {
+            return Err(RunError::Start(Box::new(Diagnostic::error(
+                "لم يتم العثور على الدالة `البداية` لبدء التنفيذ منها".to_string(),
+                vec![],
+            ))));
+        };
+
+        let main_fn = &self.nir.fns[main_idx];
+
+        if !main_fn.params.is_empty() {
+            let mut code_window = CodeWindow::new(main_fn.name.span);
+            code_window.mark_error(main_fn.name.span, vec![]);
+            return Err(RunError::Start(Box::new(Diagnostic::error(
+                "يجب ألا تستقبل الدالة `البداية` أي مُعاملات".to_string(),
+                vec![code_window],
+            ))));
+        }
+
+        let result = self.call_fn(main_idx, vec![], main_fn.name.span);
+
+        self.stdout.flush().unwrap();
+
+        match result {
+            Err(Unwind::Panic(d)) => Err(RunError::Runtime(d)),
+            _ => Ok(()),
+        }
+    }
+
+    fn call_fn(&mut self, fn_idx: usize, args: Vec<Value<'a>>, span: Span) -> Eval<'a, Value<'a>> {
+        let f = &self.nir.fns[fn_idx];
+
+        let locals = f
+            .params
+            .iter()
+            .zip(args)
+            .map(|((local_idx, _), arg)| (*local_idx, Rc::new(RefCell::new(arg))))
+            .collect();
+
+        self.call_body(Frame { locals }, f.body, span)
+    }
+
+    fn call_lambda(
+        &mut self,
+        closure: &Closure<'a>,
+        args: Vec<Value<'a>>,
+        span: Span,
+    ) -> Eval<'a, Value<'a>> {
+        let lambda = &self.nir.exprs.lambdas[closure.lambda_idx];
+
+        let mut frame = Frame {
+            locals: closure.captured.clone(),
+        };
+
+        for (param, arg) in lambda.params.iter().zip(args) {
+            bind(&mut frame.locals, &param.kind, arg);
+        }
+
+        self.call_body(frame, lambda.body, span)
+    }
+
+    /// Executes the body in a new frame, the span is of the call to report the stack overflow on it
+    fn call_body(&mut self, frame: Frame<'a>, body: Scope, span: Span) -> Eval<'a, Value<'a>> {
+        if self.frames.len() == MAX_FRAMES {
+            let msg = format!(
+                "طفح المكدس، تجاوز عدد الاستدعاءات المتداخلة {} استدعاء",
+                self.numerals.fmt(MAX_FRAMES)
+            );
+            return Err(self.runtime_err(msg, span));
+        }
+
+        self.frames.push(frame);
+
+        let value = match self.eval_scope(body) {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Break | Unwind::Continue) => {
+                unreachable!("The type checker only accepts `قطع` and `وصل` inside loops")
+            }
+            Err(unwind) => Err(unwind),
+        };
+
+        self.frames.pop();
+
+        value
+    }
+
+    fn eval_scope(&mut self, scope: Scope) -> Eval<'a, Value<'a>> {
+        let scope = &self.nir.scopes[scope.index];
+
+        for stm in &scope.stms {
+            self.exec_stm(stm)?;
+        }
+
+        match &scope.return_expr {
+            Some(expr) => self.eval_expr(expr),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn exec_stm(&mut self, stm: &Stm) -> Eval<'a, ()> {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET => self.exec_let_stm(&stms.lets[index])?,
+            StmKindAndIndex::LET_MUT => self.exec_let_stm(&stms.let_muts[index])?,
+            StmKindAndIndex::IF => {
+                self.eval_if(&stms.ifs[index])?;
+            }
+            StmKindAndIndex::WHILE => self.exec_while(&stms.whiles[index])?,
+            StmKindAndIndex::DO_WHILE => self.exec_do_while(&stms.do_whiles[index])?,
+            StmKindAndIndex::WHEN => {
+                self.eval_when(&stms.whens[index])?;
+            }
+            StmKindAndIndex::EXPR => {
+                self.eval_expr(&stms.exprs[index])?;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn exec_let_stm(&mut self, let_stm: &LetStm) -> Eval<'a, ()> {
+        let value = match &let_stm.assign {
+            Some(expr) => self.eval_expr(expr)?,
+            // The value will be assigned later
+            None => Value::Unit,
+        };
+
+        bind(&mut self.frame().locals, &let_stm.binding.kind, value);
+
+        Ok(())
+    }
+
+    fn exec_while(&mut self, while_stm: &ConditionalScope) -> Eval<'a, ()> {
+        while self.eval_condition(&while_stm.condition)? {
+            match self.eval_scope(while_stm.scope) {
+                Ok(_) | Err(Unwind::Continue) => {}
+                Err(Unwind::Break) => break,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn exec_do_while(&mut self, do_while_stm: &ConditionalScope) -> Eval<'a, ()> {
+        loop {
+            match self.eval_scope(do_while_stm.scope) {
+                Ok(_) | Err(Unwind::Continue) => {}
+                Err(Unwind::Break) => break,
+                Err(unwind) => return Err(unwind),
+            }
+
+            if !self.eval_condition(&do_while_stm.condition)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn eval_if(&mut self, if_expr: &IfExpr) -> Eval<'a, Value<'a>> {
+        if self.eval_condition(&if_expr.if_.condition)? {
+            return self.eval_scope(if_expr.if_.scope);
+        }
+
+        for else_if in &if_expr.else_ifs {
+            if self.eval_condition(&else_if.condition)? {
+                return self.eval_scope(else_if.scope);
+            }
+        }
+
+        match if_expr.else_ {
+            Some(scope) => self.eval_scope(scope),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn eval_when(&mut self, when_expr: &WhenExpr) -> Eval<'a, Value<'a>> {
+        let value = self.eval_expr(&when_expr.expr)?;
+
+        for arm in &when_expr.arms {
+            if !self.match_pattern(&arm.pattern, &value)? {
+                continue;
+            }
+
+            if let Some(guard) = &arm.guard {
+                if !self.eval_condition(guard)? {
+                    continue;
+                }
+            }
+
+            return self.eval_scope(arm.body);
+        }
+
+        unreachable!("The type checker only accepts `عندما` with patterns matching all the values")
+    }
+
+    /// Checks whether the value is matched by the pattern and binds the locals of the pattern
+    fn match_pattern(&mut self, pattern: &Pattern, value: &Value<'a>) -> Eval<'a, bool> {
+        let is_matched = match &pattern.kind {
+            PatternKind::Wildcard => true,
+            PatternKind::Binding(local_idx) => {
+                let cell: Cell = Rc::new(RefCell::new(value.clone()));
+                self.frame().locals.insert(*local_idx, cell);
+                true
+            }
+            PatternKind::Literal(expr) => {
+                self.eval_expr(expr)?.compare(value) == Some(Ordering::Equal)
+            }
+            PatternKind::Range(range) => {
+                let start = self.eval_expr(&range.start)?.compare(value);
+                let end = value.compare(&self.eval_expr(&range.end)?);
+                let after_start = match range.op {
+                    BinOp::OpenOpenRange | BinOp::OpenCloseRange => start == Some(Ordering::Less),
+                    _ => matches!(start, Some(Ordering::Less | Ordering::Equal)),
+                };
+                let before_end = match range.op {
+                    BinOp::OpenOpenRange | BinOp::CloseOpenRange => end == Some(Ordering::Less),
+                    _ => matches!(end, Some(Ordering::Less | Ordering::Equal)),
+                };
+                after_start && before_end
+            }
+            PatternKind::UnitStruct(path) => is_variant_of(path.resolved, value),
+            // The value of `()` has no elements
+            PatternKind::Tuple(patterns) if patterns.is_empty() => true,
+            PatternKind::Tuple(patterns) => self.match_patterns(patterns, value)?,
+            PatternKind::TupleStruct(path, patterns) => {
+                is_variant_of(path.resolved, value) && self.match_patterns(patterns, value)?
+            }
+            PatternKind::FieldsStruct(path, fields) => {
+                if !is_variant_of(path.resolved, value) {
+                    return Ok(false);
+                }
+                for (name, pattern) in fields {
+                    let field_idx = self.field_position(path.resolved, name.id);
+                    if !self.match_pattern(pattern, &value.elements()[field_idx])? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+        };
+
+        Ok(is_matched)
+    }
+
+    fn match_patterns(&mut self, patterns: &[Pattern], value: &Value<'a>) -> Eval<'a, bool> {
+        for (pattern, element) in patterns.iter().zip(value.elements()) {
+            if !self.match_pattern(pattern, element)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The count of the fields of the fields struct or the fields variant
+    fn fields_count(&self, resolved: ResolvedPath) -> usize {
+        match resolved {
+            ResolvedPath::FieldsStruct(idx) => self.nir.fields_structs[idx].fields.len(),
+            ResolvedPath::Variant(enum_idx, variant_idx) => {
+                match &self.nir.enums[enum_idx].variants[variant_idx].kind {
+                    VariantKind::Fields(fields) => fields.len(),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!("The type checker only accepts fields structs and fields variants"),
+        }
+    }
+
+    /// The index of the field in the fields struct or the fields variant by the order of declaration
+    fn field_position(&self, resolved: ResolvedPath, id: PoolIdx) -> usize {
+        match resolved {
+            ResolvedPath::FieldsStruct(idx) => self.nir.fields_structs[idx]
+                .fields
+                .iter()
+                .position(|(_, name, _)| name.id == id),
+            ResolvedPath::Variant(enum_idx, variant_idx) => {
+                match &self.nir.enums[enum_idx].variants[variant_idx].kind {
+                    VariantKind::Fields(fields) => {
+                        fields.iter().position(|(name, _)| name.id == id)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!("The type checker only accepts fields structs and fields variants"),
+        }
+        .unwrap()
+    }
+
+    fn eval_condition(&mut self, condition: &Expr) -> Eval<'a, bool> {
+        match self.eval_expr(condition)? {
+            Value::Bool(b) => Ok(b),
+            _ => unreachable!("The type checker only accepts `شرط` conditions"),
+        }
+    }
+
+    #[inline]
+    fn frame(&mut self) -> &mut Frame<'a> {
+        self.frames.last_mut().unwrap()
+    }
+
+    /// Reports an error happened while executing the program, it unwinds to `run` to stop it
+    fn runtime_err(&mut self, msg: String, span: Span) -> Unwind<'a> {
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_error(span, vec![]);
+
+        Unwind::Panic(Box::new(Diagnostic::error(msg, vec![code_window])))
+    }
+}
+
+/// Converts the value computed by the type checker to a runtime value
+fn const_value<'a>(str_pool: &'a DataPool<Built>, value: &ConstValue) -> Value<'a> {
+    match value {
+        ConstValue::Unit => Value::Unit,
+        ConstValue::Int(i, p) => Value::Int(*i, *p),
+        ConstValue::Float(f, p) => Value::Float(*f, *p),
+        ConstValue::Bool(b) => Value::Bool(*b),
+        ConstValue::Char(ch) => Value::Char(*ch),
+        ConstValue::Str(idx) => Value::Str(&str_pool[*idx]),
+        ConstValue::Tuple(values) => Value::Tuple(
+            values
+                .iter()
+                .map(|value| const_value(str_pool, value))
+                .collect(),
+        ),
+        ConstValue::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| const_value(str_pool, value))
+                .collect(),
+        ),
+        ConstValue::Error => unreachable!("The type checker rejects the invalid constants"),
+    }
+}
+
+/// Returns whether the value is of the variant when the path is resolved to a variant,
+/// the values of the structs are always matched by their paths
+fn is_variant_of(resolved: ResolvedPath, value: &Value) -> bool {
+    match (resolved, value) {
+        (ResolvedPath::Variant(_, variant_idx), Value::Variant(_, idx, _)) => variant_idx == *idx,
+        _ => true,
+    }
+}
+
+/// Binds the value to the locals of the binding, destructuring the tuples
+fn bind<'a>(locals: &mut Locals<'a>, kind: &BindingKind, value: Value<'a>) {
+    match kind {
+        BindingKind::Local(local_idx) => {
+            let cell: Cell = Rc::new(RefCell::new(value));
+            locals.insert(*local_idx, cell);
+        }
+        BindingKind::TupleDestruction(kinds, _) => {
+            let mut elements = match value {
+                Value::Tuple(elements) => elements.into_iter(),
+                // Declared without a value
+                _ => vec![Value::Unit; kinds.len()].into_iter(),
+            };
+
+            for kind in kinds {
+                bind(locals, kind, elements.next().unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use nazmc_nir::PrimitiveType;
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap, rc::Rc};
+
+/// A memory location which holds a value, all the locals are stored in cells
+/// so the references and the lambdas could share them
+pub(crate) type Cell<'a> = Rc<RefCell<Value<'a>>>;
+
+/// The locals visible in a function or a lambda body by their indices in `NIR.locals`
+pub(crate) type Locals<'a> = HashMap<usize, Cell<'a>>;
+
+/// The runtime value of an expression
+#[derive(Clone)]
+pub(crate) enum Value<'a> {
+    Unit,
+    /// The value is stored in a wider int, and it is always kept in the range of its type
+    Int(i128, PrimitiveType),
+    /// The value of `ع4` is stored in a wider float, and it is rounded after each operation
+    Float(f64, PrimitiveType),
+    Bool(bool),
+    Char(char),
+    Str(&'a str),
+    UnitStruct(usize),
+    TupleStruct(usize, Vec<Value<'a>>),
+    /// The fields are ordered as they are declared in the struct
+    FieldsStruct(usize, Vec<Value<'a>>),
+    /// The enum index, the variant index and the fields ordered as they are declared in the variant
+    Variant(usize, usize, Vec<Value<'a>>),
+    Tuple(Vec<Value<'a>>),
+    Array(Vec<Value<'a>>),
+    /// The value of all kinds of pointers and references
+    Ref(Place<'a>),
+    Fn(usize),
+    Lambda(Rc<Closure<'a>>),
+}
+
+/// A lambda expression with the locals it could see when it was created
+pub(crate) struct Closure<'a> {
+    /// The index in `NIR.exprs.lambdas`
+    pub(crate) lambda_idx: usize,
+    pub(crate) captured: Locals<'a>,
+}
+
+/// A location which could be read from or written to, it is a cell
+/// with the indices of the fields or the elements to reach in its value
+#[derive(Clone)]
+pub(crate) struct Place<'a> {
+    pub(crate) cell: Cell<'a>,
+    pub(crate) projections: Vec<usize>,
+}
+
+impl<'a> Place<'a> {
+    /// A place which is not bound to any local (e.g. borrowing a temporary value)
+    pub(crate) fn temp(value: Value<'a>) -> Self {
+        Self {
+            cell: Rc::new(RefCell::new(value)),
+            projections: vec![],
+        }
+    }
+
+    /// Calls `f` with the value in this place without cloning it
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&Value<'a>) -> R) -> R {
+        let mut value = &*self.cell.borrow();
+        for i in &self.projections {
+            value = &value.elements()[*i];
+        }
+        f(value)
+    }
+
+    #[inline]
+    pub(crate) fn read(&self) -> Value<'a> {
+        self.with(Value::clone)
+    }
+
+    pub(crate) fn write(&self, new_value: Value<'a>) {
+        let mut cell = self.cell.borrow_mut();
+        let mut value = &mut *cell;
+        for i in &self.projections {
+            value = &mut value.elements_mut()[*i];
+        }
+        *value = new_value;
+    }
+}
+
+impl<'a> Value<'a> {
+    /// The fields of the structs and the variants or the elements of the tuples and arrays
+    pub(crate) fn elements(&self) -> &[Value<'a>] {
+        match self {
+            Self::TupleStruct(_, elements)
+            | Self::FieldsStruct(_, elements)
+            | Self::Variant(_, _, elements)
+            | Self::Tuple(elements)
+            | Self::Array(elements) => elements,
+            _ => unreachable!("The type checker only accepts projections on compound values"),
+        }
+    }
+
+    fn elements_mut(&mut self) -> &mut [Value<'a>] {
+        match self {
+            Self::TupleStruct(_, elements)
+            | Self::FieldsStruct(_, elements)
+            | Self::Variant(_, _, elements)
+            | Self::Tuple(elements)
+            | Self::Array(elements) => elements,
+            _ => unreachable!("The type checker only accepts projections on compound values"),
+        }
+    }
+
+    /// Compares two values of the same type, references are compared by their underlying values
+    pub(crate) fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Unit, Self::Unit) => Some(Ordering::Equal),
+            (Self::Int(a, _), Self::Int(b, _)) => a.partial_cmp(b),
+            (Self::Float(a, _), Self::Float(b, _)) => a.partial_cmp(b),
+            (Self::Bool(a), Self::Bool(b)) => a.partial_cmp(b),
+            (Self::Char(a), Self::Char(b)) => a.partial_cmp(b),
+            (Self::Str(a), Self::Str(b)) => a.partial_cmp(b),
+            (Self::UnitStruct(_), Self::UnitStruct(_)) => Some(Ordering::Equal),
+            // The variants are ordered as they are declared in the enum
+            (Self::Variant(_, a_idx, _), Self::Variant(_, b_idx, _)) if a_idx != b_idx => {
+                a_idx.partial_cmp(b_idx)
+            }
+            (Self::Variant(_, _, a), Self::Variant(_, _, b))
+            | (Self::TupleStruct(_, a), Self::TupleStruct(_, b))
+            | (Self::FieldsStruct(_, a), Self::FieldsStruct(_, b))
+            | (Self::Tuple(a), Self::Tuple(b))
+            | (Self::Array(a), Self::Array(b)) => {
+                for (a, b) in a.iter().zip(b) {
+                    match a.compare(b)? {
+                        Ordering::Equal => {}
+                        ordering => return Some(ordering),
+                    }
+                }
+                a.len().partial_cmp(&b.len())
+            }
+            (Self::Ref(a), Self::Ref(b)) => a.read().compare(&b.read()),
+            (Self::Fn(a), Self::Fn(b)) if a == b => Some(Ordering::Equal),
+            (Self::Lambda(a), Self::Lambda(b)) if Rc::ptr_eq(a, b) => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+/// The bounds of the int types, `ص` and `م` are 64-bit wide
+pub(crate) fn int_bounds(typ: PrimitiveType) -> (i128, i128) {
+    match typ {
+        PrimitiveType::I1 => (i8::MIN as i128, i8::MAX as i128),
+        PrimitiveType::I2 => (i16::MIN as i128, i16::MAX as i128),
+        PrimitiveType::I4 => (i32::MIN as i128, i32::MAX as i128),
+        PrimitiveType::I | PrimitiveType::I8 => (i64::MIN as i128, i64::MAX as i128),
+        PrimitiveType::U1 => (0, u8::MAX as i128),
+        PrimitiveType::U2 => (0, u16::MAX as i128),
+        PrimitiveType::U4 => (0, u32::MAX as i128),
+        PrimitiveType::U | PrimitiveType::U8 => (0, u64::MAX as i128),
+        _ => unreachable!("Not an int type"),
+    }
+}
+
+/// The count of bits in the int type
+pub(crate) fn int_bits(typ: PrimitiveType) -> u32 {
+    match typ {
+        PrimitiveType::I1 | PrimitiveType::U1 => 8,
+        PrimitiveType::I2 | PrimitiveType::U2 => 16,
+        PrimitiveType::I4 | PrimitiveType::U4 => 32,
+        _ => 64,
+    }
+}
+
+/// Truncates the value to the bits of the int type, like casting in C
+pub(crate) fn wrap_int(value: i128, typ: PrimitiveType) -> i128 {
+    let bits = int_bits(typ);
+    let truncated = value & ((1i128 << bits) - 1);
+    if typ.is_signed_int() && truncated >> (bits - 1) == 1 {
+        truncated - (1i128 << bits)
+    } else {
+        truncated
+    }
+}
+
+/// Rounds the value of `ع4` to the nearest 32-bit float
+#[inline]
+pub(crate) fn fit_float(value: f64, typ: PrimitiveType) -> f64 {
+    if typ == PrimitiveType::F4 {
+        value as f32 as f64
+    } else {
+        value
+    }
+}
@@ -33,4 +33,37 @@ pub enum LexerErrorKind {
     InvalidDigitForBase(Base),
     NumIsOutOfRange(NumKind),
     DigitsEndWithCommma,
+    /// A Latin `,`, `;` or `?` which is lexed as its Arabic equivalent
+    LatinPunctuation(char),
+    /// Both Latin and Eastern Arabic-Indic digits are used in the same number
+    MixedDigitScripts,
+}
+
+impl LexerErrorKind {
+    /// The stable code of the error, it is shown as `خطأ[code]` and explained by `nazmc explain`
+    pub fn code(&self) -> usize {
+        match self {
+            LexerErrorKind::UnknownToken => 100,
+            LexerErrorKind::UnclosedStr => 101,
+            LexerErrorKind::UnclosedChar => 102,
+            LexerErrorKind::UnclosedDelimitedComment => 103,
+            LexerErrorKind::ZeroChars => 104,
+            LexerErrorKind::ManyChars => 105,
+            LexerErrorKind::KufrOrInvalidChar => 106,
+            LexerErrorKind::UnicodeCodePointHexDigitOnly => 107,
+            LexerErrorKind::InvalidUnicodeCodePoint => 108,
+            LexerErrorKind::UnknownEscapeSequence => 109,
+            LexerErrorKind::MissingDigitsAfterBasePrefix => 110,
+            LexerErrorKind::MissingDigitsAfterExponent => 111,
+            LexerErrorKind::InvalidIntBasePrefix => 112,
+            LexerErrorKind::InvalidNumSuffix => 113,
+            LexerErrorKind::InvalidFloatSuffix => 114,
+            LexerErrorKind::InvalidIntSuffix => 115,
+            LexerErrorKind::InvalidDigitForBase(_) => 116,
+            LexerErrorKind::NumIsOutOfRange(_) => 117,
+            LexerErrorKind::DigitsEndWithCommma => 118,
+            LexerErrorKind::LatinPunctuation(_) => 119,
+            LexerErrorKind::MixedDigitScripts => 120,
+        }
+    }
 }
@@ -1,2 +1,4 @@
 mod numbers;
 mod string_or_char;
+
+pub(crate) use numbers::DigitsScript;
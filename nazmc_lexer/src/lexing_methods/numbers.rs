@@ -1,7 +1,43 @@
 use crate::*;
 
+/// The script of the digits in a number literal, it is decided by the first digit and the other digits must match it
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum DigitsScript {
+    /// `0` to `9`
+    Latin,
+    /// `٠` to `٩`
+    ArabicIndic,
+    /// The digits are mixed and it is already reported
+    Mixed,
+}
+
+/// The valid type suffixes of the numbers
+const NUM_SUFFIXES: [&str; 12] = [
+    "ص1", "ص2", "ص4", "ص8", "ص", "ط1", "ط2", "ط4", "ط8", "ط", "ع4", "ع8",
+];
+
+/// Converts an Eastern Arabic-Indic digit to its Latin digit
+#[inline]
+fn arabic_indic_to_latin_digit(ch: char) -> Option<char> {
+    match ch {
+        '٠'..='٩' => char::from_digit(ch as u32 - '٠' as u32, 10),
+        _ => None,
+    }
+}
+
+#[inline]
+fn is_digit(ch: char) -> bool {
+    ch.is_ascii_digit() || arabic_indic_to_latin_digit(ch).is_some()
+}
+
 impl<'a> LexerIter<'a> {
     pub(crate) fn next_num_token(&mut self) -> TokenKind {
+        self.num_digits_script = if self.cursor.stopped_at.1.is_ascii_digit() {
+            DigitsScript::Latin
+        } else {
+            DigitsScript::ArabicIndic
+        };
+
         let result = self.next_num_kind();
         match result {
             Ok(num_kind) => TokenKind::Literal(LiteralKind::Num(num_kind)),
@@ -24,22 +60,19 @@ impl<'a> LexerIter<'a> {
     fn next_num_kind(&mut self) -> Result<NumKind, LexerError> {
         let prefix = &self.content[self.stopped_at_bidx..];
 
-        if prefix.starts_with("2#") {
-            self.next_cursor();
+        if prefix.starts_with("2#") || prefix.starts_with("٢#") {
             self.next_cursor();
             self.next_cursor(); // Skip "2#" and stop on next digit
             return self.next_num_sys_token(Base::Bin, |d| matches!(d, b'0' | b'1'));
         }
 
-        if prefix.starts_with("8#") {
-            self.next_cursor();
+        if prefix.starts_with("8#") || prefix.starts_with("٨#") {
             self.next_cursor();
             self.next_cursor(); // Skip "8#" and stop on next digit
             return self.next_num_sys_token(Base::Oct, |d| matches!(d, b'0'..=b'7'));
         }
 
-        if prefix.starts_with("16#") {
-            self.next_cursor();
+        if prefix.starts_with("16#") || prefix.starts_with("١٦#") {
             self.next_cursor();
             self.next_cursor();
             self.next_cursor(); // Skip "16#" and stop on next digit
@@ -64,7 +97,7 @@ impl<'a> LexerIter<'a> {
 
         let dot_or_exp = &self.content[self.stopped_at_bidx..];
 
-        if !dot_or_exp.starts_with("^^") && !dot_or_exp.starts_with(".") {
+        if !dot_or_exp.starts_with("^^") && !dot_or_exp.starts_with(['.', '٫']) {
             let digits_len = self.cursor.stopped_at.0.col - start_col;
 
             let suffix_str = self.next_valid_num_suffix()?;
@@ -98,11 +131,12 @@ impl<'a> LexerIter<'a> {
             return float_token;
         }
 
-        if dot_or_exp.starts_with(".") {
-            let after_dot = &self.content[self.stopped_at_bidx + 1..];
+        if dot_or_exp.starts_with(['.', '٫']) {
+            let after_dot =
+                &self.content[self.stopped_at_bidx + self.cursor.stopped_at.1.len_utf8()..];
 
             // Number before dot may be treated as an int object, so check if after the dot is a digit to build the float
-            if !after_dot.starts_with(|ch: char| ch.is_ascii_digit()) {
+            if !after_dot.starts_with(is_digit) {
                 let digits_len = self.cursor.stopped_at.0.col - start_col;
 
                 // After the dot is not a digit, so treat it as an int with no suffix
@@ -128,14 +162,14 @@ impl<'a> LexerIter<'a> {
                 self.next_cursor(); // Skip the sign
             }
 
-            match self.cursor.stopped_at.1 {
-                '0'..='9' => {
+            match self.to_latin_digit(self.cursor.stopped_at.1) {
+                Some(digit) => {
                     // Append the first digit
-                    digits.push(self.cursor.stopped_at.1);
+                    digits.push(digit);
                     // Append digits after the exponent
                     self.skip_digits_and_commas(&mut digits);
                 }
-                _ => {
+                None => {
                     return Err(LexerError {
                         token_idx: self.current_token_idx,
                         col: start_col,
@@ -154,66 +188,75 @@ impl<'a> LexerIter<'a> {
     }
 
     fn skip_hexdigits_and_commas(&mut self, digits: &mut String) {
-        let mut last_commas_len = 0;
-
         while let Some((_, ch)) = self.next_cursor_non_eol() {
-            if ch.is_ascii_hexdigit() {
-                digits.push(ch);
-                last_commas_len = 0;
+            if let Some(digit) = self.to_latin_hexdigit(ch) {
+                digits.push(digit);
             }
-            // Skip commas
-            else if ch == ',' {
-                last_commas_len += 1;
-            } else {
+            // Skip the commas between the digits
+            else if ch != ','
+                || !self.is_comma_before_digit(|ch| ch.is_ascii_hexdigit() || is_digit(ch))
+            {
                 break;
             }
         }
-
-        if last_commas_len > 0 {
-            self.errs.push(LexerError {
-                token_idx: self.current_token_idx,
-                col: self.cursor.stopped_at.0.col - last_commas_len,
-                len: last_commas_len,
-                kind: LexerErrorKind::DigitsEndWithCommma,
-            });
-        }
     }
 
     fn skip_digits_and_commas(&mut self, digits: &mut String) {
-        let mut last_commas_len = 0;
-
         while let Some((_, ch)) = self.next_cursor_non_eol() {
-            if ch.is_ascii_digit() {
-                digits.push(ch);
-                last_commas_len = 0;
+            if let Some(digit) = self.to_latin_digit(ch) {
+                digits.push(digit);
             }
-            // Skip commas
-            else if ch == ',' {
-                last_commas_len += 1;
-            } else {
+            // Skip the commas between the digits
+            else if ch != ',' || !self.is_comma_before_digit(is_digit) {
                 break;
             }
         }
+    }
 
-        if last_commas_len > 0 {
+    /// Whether the comma at the cursor is followed by a digit, otherwise it is not a separator of the
+    /// digits and the number ends before it, like the Latin comma between the arguments in `ف(1, 2)`
+    fn is_comma_before_digit(&self, is_digit: impl Fn(char) -> bool) -> bool {
+        self.content[self.stopped_at_bidx + ','.len_utf8()..].starts_with(is_digit)
+    }
+
+    /// Converts the digit to a Latin digit and reports it if its script is different from the number script
+    fn to_latin_digit(&mut self, ch: char) -> Option<char> {
+        let (digit, script) = match arabic_indic_to_latin_digit(ch) {
+            Some(digit) => (digit, DigitsScript::ArabicIndic),
+            None if ch.is_ascii_digit() => (ch, DigitsScript::Latin),
+            None => return None,
+        };
+
+        if self.num_digits_script != script && self.num_digits_script != DigitsScript::Mixed {
+            self.num_digits_script = DigitsScript::Mixed;
             self.errs.push(LexerError {
                 token_idx: self.current_token_idx,
-                col: self.cursor.stopped_at.0.col - last_commas_len,
-                len: last_commas_len,
-                kind: LexerErrorKind::DigitsEndWithCommma,
+                col: self.cursor.stopped_at.0.col,
+                len: 1,
+                kind: LexerErrorKind::MixedDigitScripts,
             });
         }
+
+        Some(digit)
+    }
+
+    /// Like `to_latin_digit` but the hex letters are accepted with any script
+    fn to_latin_hexdigit(&mut self, ch: char) -> Option<char> {
+        if ch.is_ascii_hexdigit() && !ch.is_ascii_digit() {
+            Some(ch)
+        } else {
+            self.to_latin_digit(ch)
+        }
     }
 
     fn next_digits_array(&mut self) -> String {
-        let stopped_char = self.cursor.stopped_at.1;
         let mut digits = String::new();
 
-        if !stopped_char.is_ascii_digit() {
+        let Some(digit) = self.to_latin_digit(self.cursor.stopped_at.1) else {
             return digits;
-        }
+        };
 
-        digits.push(stopped_char);
+        digits.push(digit);
 
         self.skip_digits_and_commas(&mut digits);
 
@@ -254,15 +297,14 @@ impl<'a> LexerIter<'a> {
 
     fn next_hex_num_token(&mut self) -> Result<NumKind, LexerError> {
         let prefix_end_col = self.cursor.stopped_at.0.col;
-        let stopped_char = self.cursor.stopped_at.1;
         let mut digits = String::new();
 
-        if !stopped_char.is_ascii_hexdigit() {
+        let Some(digit) = self.to_latin_hexdigit(self.cursor.stopped_at.1) else {
             let _ = self.next_valid_num_suffix(); // Skip any suffixes
             return missing_digits_after_base_prefix_bad_token(prefix_end_col);
-        }
+        };
 
-        digits.push(stopped_char);
+        digits.push(digit);
 
         self.skip_hexdigits_and_commas(&mut digits);
 
@@ -280,7 +322,7 @@ impl<'a> LexerIter<'a> {
         }
     }
 
-    fn next_valid_num_suffix(&mut self) -> Result<&str, LexerError> {
+    fn next_valid_num_suffix(&mut self) -> Result<&'static str, LexerError> {
         if !self.cursor.stopped_at.1.is_alphabetic() {
             return Ok("");
         }
@@ -296,19 +338,21 @@ impl<'a> LexerIter<'a> {
         let end_col = self.cursor.stopped_at.0.col;
         let end = self.stopped_at_bidx;
 
-        let id = &self.content[start..end];
+        // The size of the suffix could be written in any digits script, e.g. `ص4` or `ص٤`
+        let id = self.content[start..end]
+            .chars()
+            .map(|ch| arabic_indic_to_latin_digit(ch).unwrap_or(ch))
+            .collect::<String>();
 
-        match id {
-            "ص1" | "ص2" | "ص4" | "ص8" | "ص" | "ط1" | "ط2" | "ط4" | "ط8" | "ط" | "ع4" | "ع8" => {
-                Ok(id)
-            }
-            _ => Err(LexerError {
+        NUM_SUFFIXES
+            .into_iter()
+            .find(|suffix| *suffix == id)
+            .ok_or(LexerError {
                 token_idx: self.current_token_idx,
                 col: start_col,
                 len: end_col - start_col,
                 kind: LexerErrorKind::InvalidNumSuffix,
-            }),
-        }
+            })
     }
 }
 
@@ -5,24 +5,28 @@ mod token;
 use documented::DocumentedVariants;
 use error::{LexerError, LexerErrorKind};
 use itertools::Itertools;
+use lexing_methods::DigitsScript;
 use nazmc_data_pool::{DataPool, Init};
-use nazmc_diagnostics::span::{Span, SpanCursor};
+use nazmc_diagnostics::{
+    span::{Span, SpanCursor},
+    FileId,
+};
 use std::str::Chars;
 use strum::IntoEnumIterator;
 pub use token::*;
 
 pub struct LexerIter<'a> {
     content: &'a str,
+    /// The id of the lexed file in the source map, it is carried by the spans of the tokens
+    file_id: FileId,
     cursor: CharsCursor<'a>,
     /// The byte index the cursor stopped at
     stopped_at_bidx: usize,
-    /// The file lines to fill from lexing
-    file_lines: Vec<String>,
-    /// The start byte index of current line
-    current_line_start_bidx: usize,
     /// Errors
     errs: Vec<LexerError>,
     current_token_idx: usize,
+    /// The script of the digits in the number literal being lexed
+    num_digits_script: DigitsScript,
     id_pool: &'a mut DataPool<Init>,
     str_pool: &'a mut DataPool<Init>,
 }
@@ -31,40 +35,33 @@ impl<'a> Iterator for LexerIter<'a> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let start = self.cursor.stopped_at.0;
         let start_byte = self.stopped_at_bidx;
         let kind = self.next_token_type();
         if let TokenKind::Eof = kind {
             return None;
         }
-        let end = self.cursor.stopped_at.0;
         let end_byte = self.stopped_at_bidx;
-        // let val = &self.content[start_byte..end_byte];
-        let span = Span { start, end };
+        let span = Span::new(self.file_id, start_byte as u32, end_byte as u32);
         self.current_token_idx += 1;
-        Some(Token {
-            span,
-            kind,
-            start_byte,
-            end_byte,
-        })
+        Some(Token { span, kind })
     }
 }
 
 impl<'a> LexerIter<'a> {
     pub fn new(
         content: &'a str,
+        file_id: FileId,
         id_pool: &'a mut DataPool<Init>,
         str_pool: &'a mut DataPool<Init>,
     ) -> Self {
         let mut _self = Self {
             content,
+            file_id,
             cursor: CharsCursor::new(content),
             stopped_at_bidx: 0,
-            file_lines: vec![],
-            current_line_start_bidx: 0,
             errs: vec![],
             current_token_idx: 0,
+            num_digits_script: DigitsScript::Latin,
             id_pool,
             str_pool,
         };
@@ -72,14 +69,9 @@ impl<'a> LexerIter<'a> {
         _self
     }
 
-    pub fn collect_all(mut self) -> (Vec<Token>, Vec<String>, Vec<LexerError>) {
+    pub fn collect_all(mut self) -> (Vec<Token>, Vec<LexerError>) {
         let tokens = self.by_ref().collect_vec();
-
-        if self.file_lines.is_empty() {
-            self.file_lines.push("".to_string());
-        }
-
-        (tokens, self.file_lines, self.errs)
+        (tokens, self.errs)
     }
 
     fn next_token_type(&mut self) -> TokenKind {
@@ -97,6 +89,7 @@ impl<'a> LexerIter<'a> {
                 self.next_cursor();
                 TokenKind::Symbol(SymbolKind::QuestionMark)
             }
+            ',' | ';' | '?' => self.next_latin_punctuation(),
             '(' => {
                 self.next_cursor();
                 TokenKind::Symbol(SymbolKind::OpenParenthesis)
@@ -185,7 +178,7 @@ impl<'a> LexerIter<'a> {
                 self.next_cursor();
                 TokenKind::Eol
             }
-            '0'..='9' => self.next_num_token(),
+            '0'..='9' | '٠'..='٩' => self.next_num_token(),
             '\'' => {
                 let start = self.cursor.stopped_at.0;
                 let start_byte = self.stopped_at_bidx;
@@ -298,33 +291,8 @@ impl<'a> LexerIter<'a> {
     }
 
     fn next_cursor(&mut self) -> Option<(SpanCursor, char)> {
-        let current_line = &self.content[self.current_line_start_bidx..self.stopped_at_bidx];
-
-        let size = self.cursor.stopped_at.1.len_utf8();
-
-        self.stopped_at_bidx += size;
-
-        let stopped_at_eol = self.cursor.stopped_at.1 == '\n';
-
-        if stopped_at_eol {
-            self.file_lines.push(current_line.to_string());
-            self.current_line_start_bidx = self.stopped_at_bidx;
-        }
-
-        let next = self.cursor.next();
-
-        if next.is_none() {
-            if stopped_at_eol {
-                self.file_lines.push("".to_string());
-            } else if self.current_line_start_bidx < self.content.len() {
-                let current_line =
-                    &self.content[self.current_line_start_bidx..self.stopped_at_bidx];
-                self.file_lines.push(current_line.to_string());
-                self.current_line_start_bidx = self.stopped_at_bidx;
-            }
-        }
-
-        next
+        self.stopped_at_bidx += self.cursor.stopped_at.1.len_utf8();
+        self.cursor.next()
     }
 
     fn next_cursor_non_eol(&mut self) -> Option<(SpanCursor, char)> {
@@ -380,8 +348,29 @@ impl<'a> LexerIter<'a> {
         }
     }
 
+    /// The Latin punctuation is reported but lexed as its Arabic equivalent to continue parsing
+    fn next_latin_punctuation(&mut self) -> TokenKind {
+        let ch = self.cursor.stopped_at.1;
+
+        self.errs.push(LexerError {
+            token_idx: self.current_token_idx,
+            col: self.cursor.stopped_at.0.col,
+            len: 1,
+            kind: LexerErrorKind::LatinPunctuation(ch),
+        });
+
+        self.next_cursor();
+
+        match ch {
+            ',' => TokenKind::Symbol(SymbolKind::Comma),
+            ';' => TokenKind::Symbol(SymbolKind::Semicolon),
+            _ => TokenKind::Symbol(SymbolKind::QuestionMark),
+        }
+    }
+
     fn next_id_or_keyword(&mut self) -> TokenKind {
-        if !self.cursor.stopped_at.1.is_alphabetic() {
+        // The ids could start with `_`, and `_` alone is used in the patterns to match anything
+        if !self.cursor.stopped_at.1.is_alphabetic() && self.cursor.stopped_at.1 != '_' {
             let c = self.cursor.stopped_at.1;
             let pool_idx = self.id_pool.get(&c.to_string());
             self.next_cursor();
@@ -529,90 +518,31 @@ mod tests {
     use std::vec;
 
     use super::{KeywordKind, LexerIter, SymbolKind};
-    use crate::{lexer::TokenKind, Token};
+    use crate::{
+        error::{LexerError, LexerErrorKind},
+        LiteralKind, NumKind, Token, TokenKind,
+    };
     use documented::DocumentedVariants;
-    use itertools::Itertools;
     use nazmc_data_pool::DataPool;
-    use nazmc_diagnostics::span::{Span, SpanCursor};
+    use nazmc_diagnostics::{span::Span, FileId};
     use strum::IntoEnumIterator;
 
-    #[test]
-    fn test_lines() {
-        let mut id_pool = DataPool::new();
-        let mut str_pool = DataPool::new();
-        assert_eq!(
-            vec![""],
-            LexerIter::new("", &mut id_pool, &mut str_pool)
-                .collect_all()
-                .1
-        );
-
-        let content = concat!(
-            "\n",
-            "/*\n",
-            "multiline comment\n",
-            "*/\n",
-            "123456789\n",
-            "\n",
-            "احجز\n",
-            "\n",
-            "a\n",
-        );
-
-        let lexer: LexerIter = LexerIter::new(content, &mut id_pool, &mut str_pool);
-
-        let (_, lines, _) = lexer.collect_all();
-        let expected_lines = content.split('\n').collect_vec();
-
-        assert_eq!(expected_lines, lines);
-
-        // No new line at the end
-        let content = concat!(
-            "\n",
-            "/*\n",
-            "multiline comment\n",
-            "*/\n",
-            "123456789\n",
-            "\n",
-            "احجز\n",
-            "\n",
-            "a",
-        );
-
-        let lexer: LexerIter = LexerIter::new(content, &mut id_pool, &mut str_pool);
-
-        let (_, lines, _) = lexer.collect_all();
-        let expected_lines = content.split('\n').collect_vec();
-
-        assert_eq!(expected_lines, lines);
-    }
-
     #[test]
     fn test_symbols_lexing() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
         for symbol in SymbolKind::iter() {
             let symbol_val = symbol.get_variant_docs().unwrap();
-            let Token {
-                span,
-                kind,
-                start_byte,
-                end_byte,
-            } = LexerIter::new(symbol_val, &mut id_pool, &mut str_pool)
-                .next()
-                .unwrap();
+            let Token { span, kind } =
+                LexerIter::new(symbol_val, FileId::default(), &mut id_pool, &mut str_pool)
+                    .next()
+                    .unwrap();
 
-            let val = &symbol_val[start_byte..end_byte];
+            let val = &symbol_val[span.byte_range()];
 
             assert_eq!(
                 span,
-                Span {
-                    start: SpanCursor { line: 0, col: 0 },
-                    end: SpanCursor {
-                        line: 0,
-                        col: symbol_val.chars().count()
-                    },
-                }
+                Span::new(FileId::default(), 0, symbol_val.len() as u32)
             );
             assert_eq!(val, symbol_val);
             assert_eq!(kind, TokenKind::Symbol(symbol));
@@ -624,39 +554,29 @@ mod tests {
             symbols_line.push_str(symbol_val);
         }
 
-        let tokens = LexerIter::new(&symbols_line, &mut id_pool, &mut str_pool);
+        let tokens = LexerIter::new(
+            &symbols_line,
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        );
         let mut symbols_iter = SymbolKind::iter();
-        let mut columns = 0;
-
-        for Token {
-            span,
-            kind,
-            start_byte,
-            end_byte,
-        } in tokens
-        {
-            let val = &symbols_line[start_byte..end_byte];
+        let mut offset = 0;
+
+        for Token { span, kind } in tokens {
+            let val = &symbols_line[span.byte_range()];
             let symbol = symbols_iter.next().unwrap();
             let symbol_val = symbol.get_variant_docs().unwrap();
 
             assert_eq!(
                 span,
-                Span {
-                    start: SpanCursor {
-                        line: 0,
-                        col: columns
-                    },
-                    end: SpanCursor {
-                        line: 0,
-                        col: columns + symbol_val.chars().count()
-                    },
-                },
+                Span::new(FileId::default(), offset, offset + symbol_val.len() as u32),
                 "Maybe the tokens are overlapping for left: `{}`, right: `{}`",
                 val,
                 symbol_val
             );
 
-            columns += symbol_val.chars().count();
+            offset += symbol_val.len() as u32;
 
             assert_eq!(val, symbol_val);
             assert_eq!(kind, TokenKind::Symbol(symbol));
@@ -669,33 +589,24 @@ mod tests {
             symbols_line.push('\n');
         }
 
-        let mut tokens = LexerIter::new(&symbols_line, &mut id_pool, &mut str_pool);
+        let mut tokens = LexerIter::new(
+            &symbols_line,
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        );
         let mut symbols_iter = SymbolKind::iter();
-        let mut lines = 0;
-
-        while let Some(Token {
-            span,
-            kind,
-            start_byte,
-            end_byte,
-        }) = tokens.next()
-        {
-            let val = &symbols_line[start_byte..end_byte];
+        let mut line_start = 0;
+
+        while let Some(Token { span, kind }) = tokens.next() {
+            let val = &symbols_line[span.byte_range()];
             let symbol = symbols_iter.next().unwrap();
             let symbol_val = symbol.get_variant_docs().unwrap();
+            let line_end = line_start + symbol_val.len() as u32;
 
             assert_eq!(
                 span,
-                Span {
-                    start: SpanCursor {
-                        line: lines,
-                        col: 0
-                    },
-                    end: SpanCursor {
-                        line: lines,
-                        col: symbol_val.chars().count()
-                    },
-                },
+                Span::new(FileId::default(), line_start, line_end),
                 "Maybe the tokens are overlapping for left: `{}`, right: `{}`",
                 val,
                 symbol_val
@@ -704,32 +615,19 @@ mod tests {
             assert_eq!(val, symbol_val);
             assert_eq!(kind, TokenKind::Symbol(symbol));
 
-            let Token {
-                span,
-                kind,
-                start_byte,
-                end_byte,
-            } = tokens.next().unwrap();
+            let Token { span, kind } = tokens.next().unwrap();
+            let val = &symbols_line[span.byte_range()];
 
             assert_eq!(
                 span,
-                Span {
-                    start: SpanCursor {
-                        line: lines,
-                        col: symbol_val.chars().count()
-                    },
-                    end: SpanCursor {
-                        line: lines + 1,
-                        col: 0
-                    },
-                },
+                Span::new(FileId::default(), line_end, line_end + 1),
                 "On symbol `{}`",
                 symbol_val
             );
 
             assert_eq!(val, "\n");
             assert_eq!(kind, TokenKind::Eol);
-            lines += 1;
+            line_start = line_end + 1;
         }
     }
 
@@ -739,29 +637,167 @@ mod tests {
         let mut str_pool = DataPool::new();
         for keyword in KeywordKind::iter() {
             let keyword_val = keyword.get_variant_docs().unwrap();
-            let Token {
-                span,
-                kind,
-                start_byte,
-                end_byte,
-            } = LexerIter::new(keyword_val, &mut id_pool, &mut str_pool)
-                .next()
-                .unwrap();
+            let Token { span, kind } =
+                LexerIter::new(keyword_val, FileId::default(), &mut id_pool, &mut str_pool)
+                    .next()
+                    .unwrap();
 
-            let val = &keyword_val[start_byte..end_byte];
+            let val = &keyword_val[span.byte_range()];
 
             assert_eq!(
                 span,
-                Span {
-                    start: SpanCursor { line: 0, col: 0 },
-                    end: SpanCursor {
-                        line: 0,
-                        col: keyword_val.chars().count()
-                    },
-                }
+                Span::new(FileId::default(), 0, keyword_val.len() as u32)
             );
             assert_eq!(val, keyword_val);
             assert_eq!(kind, TokenKind::Keyword(keyword));
         }
     }
+
+    /// Lexes the content and returns the kinds of its tokens without the spaces with the errors
+    fn lex(content: &str) -> (Vec<TokenKind>, Vec<LexerError>) {
+        let mut id_pool = DataPool::new();
+        let mut str_pool = DataPool::new();
+        let (tokens, errs) =
+            LexerIter::new(content, FileId::default(), &mut id_pool, &mut str_pool).collect_all();
+        let kinds = tokens
+            .into_iter()
+            .map(|token| token.kind)
+            .filter(|kind| *kind != TokenKind::Space)
+            .collect();
+        (kinds, errs)
+    }
+
+    #[test]
+    fn test_latin_punctuation_lexing() {
+        let (kinds, errs) = lex("أ, ب; ج?");
+
+        let symbols = kinds
+            .into_iter()
+            .filter_map(|kind| match kind {
+                TokenKind::Symbol(symbol) => Some(symbol),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            symbols,
+            [
+                SymbolKind::Comma,
+                SymbolKind::Semicolon,
+                SymbolKind::QuestionMark
+            ]
+        );
+
+        assert_eq!(
+            errs,
+            [
+                LexerError {
+                    token_idx: 1,
+                    col: 1,
+                    len: 1,
+                    kind: LexerErrorKind::LatinPunctuation(','),
+                },
+                LexerError {
+                    token_idx: 4,
+                    col: 4,
+                    len: 1,
+                    kind: LexerErrorKind::LatinPunctuation(';'),
+                },
+                LexerError {
+                    token_idx: 7,
+                    col: 7,
+                    len: 1,
+                    kind: LexerErrorKind::LatinPunctuation('?'),
+                },
+            ]
+        );
+
+        let (arabic_kinds, errs) = lex("أ، ب؛ ج؟");
+        assert!(errs.is_empty());
+        assert_eq!(arabic_kinds, lex("أ, ب; ج?").0);
+    }
+
+    #[test]
+    fn test_arabic_indic_digits_lexing() {
+        let num = |content| match lex(content) {
+            (kinds, errs) if errs.is_empty() => kinds,
+            (_, errs) => panic!("Unexpected errors in `{content}`: {errs:?}"),
+        };
+        let lit = |num_kind| vec![TokenKind::Literal(LiteralKind::Num(num_kind))];
+
+        assert_eq!(num("١٢٣"), lit(NumKind::UnspecifiedInt(123)));
+        assert_eq!(num("١,٠٠٠"), lit(NumKind::UnspecifiedInt(1000)));
+        assert_eq!(num("٢٫٥"), lit(NumKind::UnspecifiedFloat(2.5)));
+        assert_eq!(num("٢.٥"), lit(NumKind::UnspecifiedFloat(2.5)));
+        assert_eq!(num("١^^٣"), lit(NumKind::UnspecifiedFloat(1000.0)));
+        assert_eq!(num("٢#١٠١"), lit(NumKind::UnspecifiedInt(5)));
+        assert_eq!(num("٨#١٧"), lit(NumKind::UnspecifiedInt(15)));
+        assert_eq!(num("١٦#ff"), lit(NumKind::UnspecifiedInt(255)));
+        assert_eq!(num("١٦#١٠"), lit(NumKind::UnspecifiedInt(16)));
+        assert_eq!(num("١٠ص٤"), lit(NumKind::I4(10)));
+        assert_eq!(num("١٠ص4"), lit(NumKind::I4(10)));
+        assert_eq!(num("10ط٨"), lit(NumKind::U8(10)));
+        assert_eq!(num("٢٫٥ع٨"), lit(NumKind::F8(2.5)));
+    }
+
+    #[test]
+    fn test_mixed_digit_scripts() {
+        let (kinds, errs) = lex("1٢3");
+
+        assert_eq!(
+            kinds,
+            [TokenKind::Literal(LiteralKind::Num(
+                NumKind::UnspecifiedInt(123)
+            ))]
+        );
+        assert_eq!(
+            errs,
+            [LexerError {
+                token_idx: 0,
+                col: 1,
+                len: 1,
+                kind: LexerErrorKind::MixedDigitScripts,
+            }]
+        );
+
+        // Only the first mixed digit is reported
+        let (_, errs) = lex("٣.1٤2");
+        assert_eq!(
+            errs,
+            [LexerError {
+                token_idx: 0,
+                col: 2,
+                len: 1,
+                kind: LexerErrorKind::MixedDigitScripts,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_commas_in_numbers() {
+        let lit = |num| TokenKind::Literal(LiteralKind::Num(NumKind::UnspecifiedInt(num)));
+
+        assert_eq!(lex("1,000"), (vec![lit(1000)], vec![]));
+        assert_eq!(lex("1,2"), (vec![lit(12)], vec![]));
+
+        // The comma which is not followed by a digit ends the number
+        let (kinds, errs) = lex("1, 2");
+        assert_eq!(
+            kinds,
+            [lit(1), TokenKind::Symbol(SymbolKind::Comma), lit(2)]
+        );
+        assert_eq!(
+            errs,
+            [LexerError {
+                token_idx: 1,
+                col: 1,
+                len: 1,
+                kind: LexerErrorKind::LatinPunctuation(','),
+            }]
+        );
+
+        let (kinds, errs) = lex("16#ff,");
+        assert_eq!(kinds, [lit(255), TokenKind::Symbol(SymbolKind::Comma)]);
+        assert_eq!(errs.len(), 1);
+    }
 }
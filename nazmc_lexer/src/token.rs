@@ -8,8 +8,6 @@ pub struct Token {
     // pub val: &'a str,
     pub span: Span,
     pub kind: TokenKind,
-    pub start_byte: usize,
-    pub end_byte: usize,
 }
 
 #[derive(Clone, PartialEq, Debug, Default)]
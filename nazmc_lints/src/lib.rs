@@ -0,0 +1,275 @@
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic, Lint, LintLevel, LintLevels};
+use nazmc_nir::*;
+
+/// Finds the `احجز` bindings and the functions which are never used
+pub struct UnusedChecker<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    nir: &'a NIR,
+    lint_levels: LintLevels,
+    diagnostics: Vec<Diagnostic>,
+    /// Whether each local is used in any expression
+    used_locals: Vec<bool>,
+    /// The locals bound by `احجز` statements
+    let_locals: Vec<usize>,
+    /// Whether each function is used outside its body
+    used_fns: Vec<bool>,
+    /// The index of the function whose body is being walked
+    current_fn_idx: Option<usize>,
+}
+
+impl<'a> UnusedChecker<'a> {
+    pub fn new(id_pool: &'a DataPool<Built>, nir: &'a NIR, lint_levels: LintLevels) -> Self {
+        Self {
+            id_pool,
+            nir,
+            lint_levels,
+            diagnostics: vec![],
+            used_locals: vec![false; nir.locals.len()],
+            let_locals: vec![],
+            used_fns: vec![false; nir.fns.len()],
+            current_fn_idx: None,
+        }
+    }
+
+    /// Returns the diagnostics of the unused code, they are errors only if their lints are denied
+    pub fn check(mut self) -> Vec<Diagnostic> {
+        let nir = self.nir;
+
+        for c in nir.consts.iter().chain(&nir.statics) {
+            self.walk_expr(&c.expr);
+        }
+
+        for (fn_idx, f) in nir.fns.iter().enumerate() {
+            self.current_fn_idx = Some(fn_idx);
+            self.walk_scope(f.body);
+        }
+
+        self.check_unused_lets();
+        self.check_unused_fns();
+
+        self.diagnostics
+    }
+
+    fn check_unused_lets(&mut self) {
+        let level = self.lint_levels.get(Lint::UnusedLets);
+
+        if level == LintLevel::Allow {
+            return;
+        }
+
+        for &local_idx in &self.let_locals {
+            let local = &self.nir.locals[local_idx];
+            let name = &self.id_pool[local.name.id];
+
+            // The names starting with `_` are unused on purpose
+            if self.used_locals[local_idx] || name.starts_with('_') {
+                continue;
+            }
+
+            let msg = format!("لم يتم استخدام `{}`", name);
+            let mut diagnostic =
+                self.unused_diagnostic(Lint::UnusedLets, level, msg, local.name.span);
+            diagnostic.chain(Diagnostic::help(
+                format!(
+                    "إذا كان عدم استخدامه مقصودا فيمكن تسميته `_{}` بدلا منه",
+                    name
+                ),
+                vec![],
+            ));
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn check_unused_fns(&mut self) {
+        let level = self.lint_levels.get(Lint::UnusedPrivateFns);
+
+        if level == LintLevel::Allow {
+            return;
+        }
+
+        for (fn_idx, f) in self.nir.fns.iter().enumerate() {
+            if self.used_fns[fn_idx]
+                || matches!(f.vis, VisModifier::Public)
+                || f.name.id == PoolIdx::MAIN
+            {
+                continue;
+            }
+
+            let msg = format!("لم يتم استخدام الدالة `{}`", &self.id_pool[f.name.id]);
+            let diagnostic =
+                self.unused_diagnostic(Lint::UnusedPrivateFns, level, msg, f.name.span);
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn unused_diagnostic(
+        &self,
+        lint: Lint,
+        level: LintLevel,
+        msg: String,
+        span: Span,
+    ) -> Diagnostic {
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_lint(level, span, vec!["غير مستخدم".to_string()]);
+        Diagnostic::lint(lint, level, msg, vec![code_window])
+    }
+
+    fn use_path(&mut self, path: &ModPathWithItem) {
+        match path.resolved {
+            ResolvedPath::Local(local_idx) => self.used_locals[local_idx] = true,
+            ResolvedPath::Fn(fn_idx) if self.current_fn_idx != Some(fn_idx) => {
+                self.used_fns[fn_idx] = true
+            }
+            _ => {}
+        }
+    }
+
+    fn bind(&mut self, kind: &BindingKind) {
+        match kind {
+            BindingKind::Local(local_idx) => self.let_locals.push(*local_idx),
+            BindingKind::TupleDestruction(kinds, _) => {
+                for kind in kinds {
+                    self.bind(kind);
+                }
+            }
+        }
+    }
+
+    fn walk_scope(&mut self, scope: Scope) {
+        let scope = &self.nir.scopes[scope.index];
+
+        for stm in &scope.stms {
+            self.walk_stm(stm);
+        }
+
+        if let Some(expr) = &scope.return_expr {
+            self.walk_expr(expr);
+        }
+    }
+
+    fn walk_stm(&mut self, stm: &Stm) {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET | StmKindAndIndex::LET_MUT => {
+                let let_stm = if stm.kind_and_index.kind() == StmKindAndIndex::LET {
+                    &stms.lets[index]
+                } else {
+                    &stms.let_muts[index]
+                };
+                if let Some(expr) = &let_stm.assign {
+                    self.walk_expr(expr);
+                }
+                self.bind(&let_stm.binding.kind);
+            }
+            StmKindAndIndex::IF => self.walk_if(&stms.ifs[index]),
+            StmKindAndIndex::WHILE | StmKindAndIndex::DO_WHILE => {
+                let while_stm = if stm.kind_and_index.kind() == StmKindAndIndex::WHILE {
+                    &stms.whiles[index]
+                } else {
+                    &stms.do_whiles[index]
+                };
+                self.walk_expr(&while_stm.condition);
+                self.walk_scope(while_stm.scope);
+            }
+            StmKindAndIndex::WHEN => self.walk_when(&stms.whens[index]),
+            StmKindAndIndex::EXPR => self.walk_expr(&stms.exprs[index]),
+            _ => unreachable!(),
+        }
+    }
+
+    fn walk_if(&mut self, if_expr: &IfExpr) {
+        for ConditionalScope { condition, scope } in
+            std::iter::once(&if_expr.if_).chain(&if_expr.else_ifs)
+        {
+            self.walk_expr(condition);
+            self.walk_scope(*scope);
+        }
+
+        if let Some(scope) = if_expr.else_ {
+            self.walk_scope(scope);
+        }
+    }
+
+    fn walk_when(&mut self, when_expr: &WhenExpr) {
+        self.walk_expr(&when_expr.expr);
+
+        for arm in &when_expr.arms {
+            if let Some(guard) = &arm.guard {
+                self.walk_expr(guard);
+            }
+            self.walk_scope(arm.body);
+        }
+    }
+
+    fn walk_exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.walk_expr(expr);
+        }
+    }
+
+    fn walk_expr(&mut self, expr: &Expr) {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::PARENS => self.walk_expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => self.use_path(&exprs.paths[index]),
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                self.use_path(&call.path);
+                self.walk_exprs(&call.args);
+            }
+            ExprKindAndIndex::METHOD_CALL => {
+                let call = &exprs.method_calls[index];
+                self.walk_expr(&call.on);
+                self.walk_exprs(&call.args);
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                self.walk_expr(&call.on);
+                self.walk_exprs(&call.args);
+            }
+            ExprKindAndIndex::TUPLE_STRUCT => self.walk_exprs(&exprs.tuple_structs[index].args),
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                for field in &exprs.fields_structs[index].fields {
+                    self.walk_expr(&field.expr);
+                }
+            }
+            ExprKindAndIndex::FIELD => self.walk_expr(&exprs.fields[index].on),
+            ExprKindAndIndex::INDEX => {
+                let index = &exprs.indexes[index];
+                self.walk_expr(&index.on);
+                self.walk_expr(&index.idx);
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                self.walk_exprs(&exprs.array_elements[index].elements)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let array = &exprs.array_elements_sized[index];
+                self.walk_expr(&array.repeat);
+                self.walk_expr(&array.size);
+            }
+            ExprKindAndIndex::TUPLE_EXPR => self.walk_exprs(&exprs.tuples[index].elements),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                self.walk_expr(&exprs.returns[index].expr_to_return)
+            }
+            ExprKindAndIndex::BREAK_WITH_VALUE => {
+                self.walk_expr(&exprs.breaks[index].expr_to_break)
+            }
+            ExprKindAndIndex::IF_EXPR => self.walk_if(&exprs.ifs[index]),
+            ExprKindAndIndex::WHEN_EXPR => self.walk_when(&exprs.whens[index]),
+            ExprKindAndIndex::LAMBDA_EXPR => self.walk_scope(exprs.lambdas[index].body),
+            ExprKindAndIndex::UNARY_EXPR => self.walk_expr(&exprs.unary_exprs[index].expr),
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                self.walk_expr(&bin.left);
+                self.walk_expr(&bin.right);
+            }
+            _ => {}
+        }
+    }
+}
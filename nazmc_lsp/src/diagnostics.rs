@@ -0,0 +1,112 @@
+use crate::package::to_lsp_range;
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url,
+};
+use nazmc_diagnostics::{Diagnostic as NazmDiagnostic, DiagnosticLevel, Mark, MarkKind, SourceMap};
+use std::path::Path;
+
+/// Converts the diagnostic to the LSP one in the file of its first code window, the other marks and
+/// the code windows of the chained diagnostics become its related information
+pub fn to_lsp_diagnostics(
+    d: &NazmDiagnostic,
+    root: &Path,
+    source_map: &SourceMap,
+) -> Option<(String, Diagnostic)> {
+    let code_window = d.code_windows().first()?;
+    let file = &source_map[code_window.file_id()];
+
+    let primary_mark = code_window
+        .marks()
+        .iter()
+        .find(|mark| matches!(mark.kind(), MarkKind::Error | MarkKind::Warning))
+        .or(code_window.marks().first());
+
+    let cursors = match primary_mark {
+        Some(mark) => file.span_cursors(mark.span()),
+        None => {
+            let cursor = code_window.cursor(file);
+            (cursor, cursor)
+        }
+    };
+
+    let (severity, code) = match d.level() {
+        DiagnosticLevel::Error => (DiagnosticSeverity::ERROR, None),
+        DiagnosticLevel::ErrorWithCode(code) => (
+            DiagnosticSeverity::ERROR,
+            Some(NumberOrString::Number(code as i32)),
+        ),
+        DiagnosticLevel::Warning => (DiagnosticSeverity::WARNING, None),
+        DiagnosticLevel::Note => (DiagnosticSeverity::INFORMATION, None),
+        DiagnosticLevel::Help => (DiagnosticSeverity::HINT, None),
+    };
+
+    let mut message = d.msg().to_string();
+    if let Some(labels) = primary_mark.map(Mark::labels) {
+        for label in labels {
+            message += &format!("\n{label}");
+        }
+    }
+    for chained_diagnostic in d.chained_diagnostics() {
+        message += &format!("\n{}", chained_diagnostic.msg());
+    }
+    for suggestion in d.suggestions() {
+        message += &format!("\nمساعدة: {}", suggestion.msg());
+    }
+    for free_text in d.free_texts() {
+        message += &format!("\n{free_text}");
+    }
+
+    let mut related_information = vec![];
+    let windows = d
+        .code_windows()
+        .iter()
+        .map(|code_window| (code_window, d.msg()))
+        .chain(
+            d.chained_diagnostics()
+                .iter()
+                .flat_map(|chained_diagnostic| {
+                    chained_diagnostic
+                        .code_windows()
+                        .iter()
+                        .map(move |code_window| (code_window, chained_diagnostic.msg()))
+                }),
+        );
+
+    for (window, window_message) in windows {
+        let window_file = &source_map[window.file_id()];
+        let Ok(uri) = Url::from_file_path(root.join(window_file.path())) else {
+            continue;
+        };
+
+        for mark in window.marks() {
+            if primary_mark.is_some_and(|primary_mark| std::ptr::eq(primary_mark, mark)) {
+                continue;
+            }
+
+            let message = match mark.labels().is_empty() {
+                true => window_message.to_string(),
+                false => mark.labels().join("\n"),
+            };
+
+            related_information.push(DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.clone(),
+                    range: to_lsp_range(window_file, window_file.span_cursors(mark.span())),
+                },
+                message,
+            });
+        }
+    }
+
+    let diagnostic = Diagnostic {
+        range: to_lsp_range(file, cursors),
+        severity: Some(severity),
+        code,
+        source: Some("nazmc".to_string()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Default::default()
+    };
+
+    Some((file.path().to_string(), diagnostic))
+}
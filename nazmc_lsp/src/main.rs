@@ -0,0 +1,288 @@
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{GotoDefinition, HoverRequest, Request as _},
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use nazmc_driver::{Config, Manifest};
+use package::{find_manifest, Analysis, ItemAt};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+mod diagnostics;
+mod package;
+
+fn main() {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+
+    if connection
+        .initialize(serde_json::to_value(capabilities).unwrap())
+        .is_err()
+    {
+        return;
+    }
+
+    Server {
+        connection: &connection,
+        overlays: HashMap::new(),
+        analyses: HashMap::new(),
+    }
+    .run();
+
+    drop(connection);
+    io_threads.join().unwrap();
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    /// The content of the open documents which may not be saved yet
+    overlays: HashMap<PathBuf, String>,
+    /// The last analysis of each package by the path of its `nazm.yaml`
+    analyses: HashMap<PathBuf, Analysis>,
+}
+
+impl<'a> Server<'a> {
+    fn run(&mut self) {
+        for msg in &self.connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req).unwrap_or(true) {
+                        return;
+                    }
+                    self.handle_request(req);
+                }
+                Message::Notification(not) => self.handle_notification(not),
+                Message::Response(_) => {}
+            }
+        }
+    }
+
+    fn handle_request(&mut self, req: Request) {
+        let result = match req.method.as_str() {
+            GotoDefinition::METHOD => {
+                let params: GotoDefinitionParams = match serde_json::from_value(req.params) {
+                    Ok(params) => params,
+                    Err(err) => return self.reply_invalid_params(req.id, err),
+                };
+                let doc = params.text_document_position_params;
+                serde_json::to_value(self.definition(&doc.text_document.uri, doc.position))
+            }
+            HoverRequest::METHOD => {
+                let params: HoverParams = match serde_json::from_value(req.params) {
+                    Ok(params) => params,
+                    Err(err) => return self.reply_invalid_params(req.id, err),
+                };
+                let doc = params.text_document_position_params;
+                serde_json::to_value(self.hover(&doc.text_document.uri, doc.position))
+            }
+            _ => {
+                let resp = Response::new_err(
+                    req.id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request `{}`", req.method),
+                );
+                self.connection.sender.send(resp.into()).unwrap();
+                return;
+            }
+        };
+
+        let resp = Response::new_ok(req.id, result.unwrap());
+        self.connection.sender.send(resp.into()).unwrap();
+    }
+
+    /// Replies with an error to the request whose params couldn't be parsed
+    fn reply_invalid_params(&self, id: RequestId, err: serde_json::Error) {
+        let resp = Response::new_err(
+            id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            format!("invalid params: {err}"),
+        );
+        self.connection.sender.send(resp.into()).unwrap();
+    }
+
+    /// The notifications have no responses, so the ones with invalid params are ignored
+    fn handle_notification(&mut self, not: Notification) {
+        let (uri, text) = match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<lsp_types::DidOpenTextDocumentParams>(not.params)
+                else {
+                    return;
+                };
+                (params.text_document.uri, Some(params.text_document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(mut params) =
+                    serde_json::from_value::<lsp_types::DidChangeTextDocumentParams>(not.params)
+                else {
+                    return;
+                };
+                // The whole document is sent on each change
+                let text = params.content_changes.pop().map(|change| change.text);
+                (params.text_document.uri, text)
+            }
+            DidSaveTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<lsp_types::DidSaveTextDocumentParams>(not.params)
+                else {
+                    return;
+                };
+                (params.text_document.uri, None)
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<lsp_types::DidCloseTextDocumentParams>(not.params)
+                else {
+                    return;
+                };
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    self.overlays.remove(&path);
+                }
+                (params.text_document.uri, None)
+            }
+            _ => return,
+        };
+
+        let Ok(path) = uri.to_file_path() else {
+            return;
+        };
+
+        if let Some(text) = text {
+            self.overlays.insert(path.clone(), text);
+        }
+
+        if let Some(manifest) = find_manifest(&path) {
+            self.analyze(manifest);
+        }
+    }
+
+    /// Analyzes the package again and publishes the diagnostics of all of its files,
+    /// the files without diagnostics are published with an empty list to clear the old ones
+    fn analyze(&mut self, manifest: PathBuf) {
+        let Ok(Manifest {
+            files_paths,
+            lint_levels,
+            numerals,
+            ..
+        }) = Manifest::read(&manifest)
+        else {
+            return;
+        };
+
+        let root = manifest.parent().unwrap().to_path_buf();
+        let analysis = Analysis::new(
+            root,
+            files_paths,
+            Config {
+                lint_levels,
+                numerals: numerals.unwrap_or_default(),
+            },
+            &self.overlays,
+        );
+
+        for path in &analysis.files_paths {
+            let Ok(uri) = Url::from_file_path(analysis.root.join(path)) else {
+                continue;
+            };
+
+            let params = PublishDiagnosticsParams {
+                uri,
+                diagnostics: analysis.diagnostics.get(path).cloned().unwrap_or_default(),
+                version: None,
+            };
+
+            let not = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+            self.connection.sender.send(not.into()).unwrap();
+        }
+
+        self.analyses.insert(manifest, analysis);
+    }
+
+    /// Finds the analysis of the package of the file and its path relative to the package directory
+    fn analysis_of(&mut self, uri: &Url) -> Option<(&Analysis, String)> {
+        let path = uri.to_file_path().ok()?;
+        let manifest = find_manifest(&path)?;
+
+        if !self.analyses.contains_key(&manifest) {
+            self.analyze(manifest.clone());
+        }
+
+        let analysis = self.analyses.get(&manifest)?;
+        let relative_path = analysis.relative_path(&path)?;
+        Some((analysis, relative_path))
+    }
+
+    fn definition(
+        &mut self,
+        uri: &Url,
+        position: lsp_types::Position,
+    ) -> Option<GotoDefinitionResponse> {
+        let (analysis, path) = self.analysis_of(uri)?;
+        let ItemAt { file, item, .. } = analysis.item_at(&path, position)?;
+
+        let location = Location {
+            uri: Url::from_file_path(analysis.root.join(&file.path)).ok()?,
+            range: analysis.to_lsp_range(item.name.span),
+        };
+
+        Some(GotoDefinitionResponse::Scalar(location))
+    }
+
+    fn hover(&mut self, uri: &Url, position: lsp_types::Position) -> Option<Hover> {
+        let (analysis, path) = self.analysis_of(uri)?;
+        let item_at = analysis.item_at(&path, position)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: hover_text(&item_at),
+            }),
+            range: Some(analysis.to_lsp_range(item_at.span)),
+        })
+    }
+}
+
+/// The declaration of the item with its kind, package and visibility
+fn hover_text(item_at: &ItemAt) -> String {
+    let (keyword, kind) = match item_at.item.kind {
+        nazmc_ast::ItemKind::UnitStruct
+        | nazmc_ast::ItemKind::TupleStruct(_)
+        | nazmc_ast::ItemKind::FieldsStruct(_) => ("هيكل", "الهيكل"),
+        nazmc_ast::ItemKind::Enum(_) => ("تصنيف", "التصنيف"),
+        nazmc_ast::ItemKind::Const(_) => ("ثابت", "الثابت"),
+        nazmc_ast::ItemKind::Static(_) => ("مشترك", "المتغير المشترك"),
+        nazmc_ast::ItemKind::Fn(_) => ("دالة", "الدالة"),
+    };
+
+    let (modifier, visibility) = match item_at.item.vis {
+        nazmc_ast::VisModifier::Default => ("", "لا يمكن استيراده إلا من داخل حزمته"),
+        nazmc_ast::VisModifier::Public => ("تصدير ", "يمكن استيراده من الحزم الأخرى"),
+        nazmc_ast::VisModifier::Private => ("تخصيص ", "يمكن استيراده من الحزم الأخرى"),
+    };
+
+    let path = if item_at.pkg_name.is_empty() {
+        item_at.name.to_string()
+    } else {
+        format!("{}::{}", item_at.pkg_name, item_at.name)
+    };
+
+    format!(
+        "```نظم\n{modifier}{keyword} {}\n```\n{kind} `{path}` في الملف `{}`، {visibility}",
+        item_at.name,
+        Path::new(&item_at.file.path).display(),
+    )
+}
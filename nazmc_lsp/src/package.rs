@@ -0,0 +1,261 @@
+use crate::diagnostics::to_lsp_diagnostics;
+use lsp_types::{Diagnostic, Position, Range};
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{span::Span, span::SpanCursor};
+use nazmc_driver::{Config, Session, SourceFile, SourceMap};
+use nazmc_resolve::{NameResolutionTree, NameResolver, ParsedFile, ResolverConfig};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use thin_vec::ThinVec;
+
+/// Finds the nearest `nazm.yaml` in the directory of the file or in its ancestors
+pub fn find_manifest(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .map(|dir| dir.join("nazm.yaml"))
+        .find(|manifest| manifest.is_file())
+}
+
+/// The lexed, parsed and resolved files of a package, it is built again after each change
+pub struct Analysis {
+    /// The directory of the package, the paths of the files are relative to it
+    pub root: PathBuf,
+    /// The paths of all the files of the package
+    pub files_paths: Vec<String>,
+    /// The read files, the spans are converted to the ranges of the LSP in them
+    source_map: SourceMap,
+    /// The diagnostics of each file by its path
+    pub diagnostics: HashMap<String, Vec<Diagnostic>>,
+    id_pool: DataPool<Built>,
+    packages_names: Vec<ThinVec<PoolIdx>>,
+    /// The package index of each parsed file
+    files_packages: Vec<usize>,
+    parsed_files: Vec<ParsedFile>,
+    /// The names are resolved only if all the files are parsed without errors like the compiler
+    nrt: Option<NameResolutionTree>,
+}
+
+/// An item found under the cursor, the span is the one under the cursor
+pub struct ItemAt<'a> {
+    pub span: Span,
+    pub file: &'a ParsedFile,
+    pub item: &'a nazmc_ast::Item,
+    pub pkg_name: String,
+    pub name: &'a str,
+}
+
+impl Analysis {
+    /// Analyzes the package files, the open documents are read from `overlays` instead of the disk
+    pub fn new(
+        root: PathBuf,
+        files_paths: Vec<String>,
+        config: Config,
+        overlays: &HashMap<PathBuf, String>,
+    ) -> Self {
+        let mut source_map = SourceMap::new();
+        let mut compiler_diagnostics = vec![];
+        let mut fail_after_parsing = false;
+
+        let files_paths = files_paths
+            .into_iter()
+            .map(|file_path| format!("{file_path}.نظم"))
+            .collect::<Vec<_>>();
+
+        for path in &files_paths {
+            let full_path = root.join(path);
+            let content = overlays
+                .get(&full_path)
+                .cloned()
+                .or_else(|| fs::read_to_string(&full_path).ok());
+
+            match content {
+                Some(content) => {
+                    fail_after_parsing |= source_map.add(path.clone(), content).is_err()
+                }
+                None => fail_after_parsing = true,
+            }
+        }
+
+        let (session, diagnostics) = Session::new(&source_map, config);
+        fail_after_parsing |= !diagnostics.is_empty();
+        compiler_diagnostics.extend(diagnostics);
+
+        let nrt = (!fail_after_parsing).then(|| {
+            // The tree is kept even if it has errors, so the resolved imports could be used
+            let resolver = NameResolver::new(
+                &session.id_pool,
+                &session.packages,
+                &session.packages_names,
+                &session.packages_to_parsed_files,
+                &session.parsed_files,
+                ResolverConfig {
+                    source_map: &source_map,
+                    lint_levels: config.lint_levels,
+                    numerals: config.numerals,
+                },
+            );
+
+            let (nrt, diagnostics) = resolver.resolve();
+            compiler_diagnostics.extend(diagnostics);
+            nrt
+        });
+
+        let mut diagnostics = HashMap::<String, Vec<Diagnostic>>::new();
+        for d in &compiler_diagnostics {
+            if let Some((path, d)) = to_lsp_diagnostics(d, &root, &source_map) {
+                diagnostics.entry(path).or_default().push(d);
+            }
+        }
+
+        let files_packages = (0..session.parsed_files.len())
+            .map(|file_idx| session.package_of(file_idx))
+            .collect();
+
+        let Session {
+            id_pool,
+            packages_names,
+            parsed_files,
+            ..
+        } = session;
+
+        Self {
+            root,
+            files_paths,
+            source_map,
+            diagnostics,
+            id_pool,
+            packages_names,
+            files_packages,
+            parsed_files,
+            nrt,
+        }
+    }
+
+    /// The path of the file relative to the package directory if it is one of its files
+    pub fn relative_path(&self, file: &Path) -> Option<String> {
+        let path = file.strip_prefix(&self.root).ok()?.to_str()?.to_string();
+        self.files_paths.contains(&path).then_some(path)
+    }
+
+    /// Finds the item whose name is under the cursor, either in its definition or in an import of it
+    pub fn item_at(&self, path: &str, position: Position) -> Option<ItemAt<'_>> {
+        let file_idx = self.parsed_files.iter().position(|f| f.path == path)?;
+        let file = &self.parsed_files[file_idx];
+        let cursor = from_lsp_position(find_file(&self.source_map, path)?, position)?;
+
+        for item in &file.ast.items {
+            if item.name.span.contains(cursor) {
+                return Some(self.item_info(item.name.span, file_idx, item));
+            }
+        }
+
+        let nrt = self.nrt.as_ref()?;
+        let resolved_imports =
+            nrt.resolved_imports[self.files_packages[file_idx]].get(&file_idx)?;
+
+        for (import, alias) in &file.ast.imports {
+            let span = if import.item.span.contains(cursor) {
+                import.item.span
+            } else if alias.span.contains(cursor) {
+                alias.span
+            } else {
+                continue;
+            };
+
+            let resolved = resolved_imports
+                .iter()
+                .find(|resolved| resolved.alias.span == alias.span)?;
+
+            let item_file = &self.parsed_files[resolved.item.file_idx];
+            let item = &item_file.ast.items[resolved.item.item_idx];
+
+            return Some(self.item_info(span, resolved.item.file_idx, item));
+        }
+
+        None
+    }
+
+    fn item_info<'a>(
+        &'a self,
+        span: Span,
+        file_idx: usize,
+        item: &'a nazmc_ast::Item,
+    ) -> ItemAt<'a> {
+        let pkg_name = self.packages_names[self.files_packages[file_idx]]
+            .iter()
+            .map(|id| &self.id_pool[*id])
+            .collect::<Vec<_>>()
+            .join("::");
+
+        ItemAt {
+            span,
+            file: &self.parsed_files[file_idx],
+            item,
+            pkg_name,
+            name: &self.id_pool[item.name.id],
+        }
+    }
+
+    /// Converts the span to the range of the LSP in its file
+    pub fn to_lsp_range(&self, span: Span) -> Range {
+        let file = &self.source_map[span.file_id];
+        to_lsp_range(file, file.span_cursors(span))
+    }
+}
+
+/// The file of the package with the path
+fn find_file<'a>(source_map: &'a SourceMap, path: &str) -> Option<&'a SourceFile> {
+    source_map
+        .files()
+        .find(|(_, file)| file.path() == path)
+        .map(|(_, file)| file)
+}
+
+/// Converts the start and the end in the file to the range of the LSP which counts the columns in UTF-16
+pub fn to_lsp_range(file: &SourceFile, (start, end): (SpanCursor, SpanCursor)) -> Range {
+    Range {
+        start: to_lsp_position(file, start),
+        end: to_lsp_position(file, end),
+    }
+}
+
+fn to_lsp_position(file: &SourceFile, cursor: SpanCursor) -> Position {
+    let character = match cursor.line < file.lines_count() {
+        true => file
+            .line(cursor.line)
+            .chars()
+            .take(cursor.col)
+            .map(char::len_utf16)
+            .sum(),
+        false => cursor.col,
+    };
+
+    Position {
+        line: cursor.line as u32,
+        character: character as u32,
+    }
+}
+
+/// Converts the position of the LSP to the byte offset in the file
+fn from_lsp_position(file: &SourceFile, position: Position) -> Option<u32> {
+    let line = position.line as usize;
+    let mut col = 0;
+    let mut utf16_col = 0;
+
+    if line >= file.lines_count() {
+        return None;
+    }
+
+    for ch in file.line(line).chars() {
+        if utf16_col >= position.character as usize {
+            break;
+        }
+        utf16_col += ch.len_utf16();
+        col += 1;
+    }
+
+    Some(file.offset(SpanCursor { line, col }))
+}
@@ -0,0 +1,241 @@
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// A scripted client which talks to `nazmc-lsp` over its standard input and output
+struct Client {
+    server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_nazmc-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = server.stdin.take().unwrap();
+        let stdout = BufReader::new(server.stdout.take().unwrap());
+        Self {
+            server,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    fn send(&mut self, msg: Value) {
+        let content = msg.to_string();
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn recv(&mut self) -> Value {
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(len) = header.strip_prefix("Content-Length: ") {
+                content_length = len.parse().unwrap();
+            }
+        }
+        let mut content = vec![0; content_length];
+        self.stdout.read_exact(&mut content).unwrap();
+        serde_json::from_slice(&content).unwrap()
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let msg = self.recv();
+            if msg["id"] == id {
+                return msg["result"].clone();
+            }
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Waits for the diagnostics published for the document
+    fn diagnostics(&mut self, uri: &str) -> Vec<Value> {
+        loop {
+            let msg = self.recv();
+            if msg["method"] == "textDocument/publishDiagnostics" && msg["params"]["uri"] == uri {
+                return msg["params"]["diagnostics"].as_array().unwrap().clone();
+            }
+        }
+    }
+
+    fn shutdown(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.server.wait().unwrap().success());
+    }
+}
+
+fn create_package(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nazmc_lsp_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("حسابات")).unwrap();
+    fs::write(
+        root.join("nazm.yaml"),
+        "المسارات:\n  - رئيسي\n  - حسابات:\n    - جمع\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("حسابات/جمع.نظم"),
+        "تصدير دالة اجمع(أ: ص4، ب: ص4): ص4 { أ + ب }\n",
+    )
+    .unwrap();
+    root
+}
+
+fn uri(path: &Path) -> String {
+    lsp_types::Url::from_file_path(path).unwrap().to_string()
+}
+
+fn open(client: &mut Client, uri: &str, text: &str) {
+    client.notify(
+        "textDocument/didOpen",
+        json!({ "textDocument": { "uri": uri, "languageId": "nazm", "version": 1, "text": text } }),
+    );
+}
+
+fn initialize(root: &Path) -> Client {
+    let mut client = Client::start();
+    let result = client.request(
+        "initialize",
+        json!({ "processId": null, "rootUri": uri(root), "capabilities": {} }),
+    );
+    assert_eq!(result["capabilities"]["definitionProvider"], true);
+    assert_eq!(result["capabilities"]["hoverProvider"], true);
+    client.notify("initialized", json!({}));
+    client
+}
+
+#[test]
+fn test_publish_diagnostics_on_change() {
+    let root = create_package("diagnostics");
+    let main_uri = uri(&root.join("رئيسي.نظم"));
+    let mut client = initialize(&root);
+
+    open(
+        &mut client,
+        &main_uri,
+        "استيراد حسابات::اجمع؛\n\nدالة البداية() {\n    اظهر_(اجمع(1، 2))؛\n}\n",
+    );
+    assert!(client.diagnostics(&main_uri).is_empty());
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": main_uri, "version": 2 },
+            "contentChanges": [{ "text": "استيراد حسابات::اجمح؛\n\nدالة البداية() {}\n" }],
+        }),
+    );
+    let diagnostics = client.diagnostics(&main_uri);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["code"], 302);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 0, "character": 16 }, "end": { "line": 0, "character": 20 } })
+    );
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": main_uri, "version": 3 },
+            "contentChanges": [{ "text": "دالة البداية() {\n    احجز س = \"نص؛\n}\n" }],
+        }),
+    );
+    let diagnostics = client.diagnostics(&main_uri);
+    assert!(!diagnostics.is_empty());
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+    client.shutdown();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_definition_and_hover_of_import() {
+    let root = create_package("definition");
+    let main_uri = uri(&root.join("رئيسي.نظم"));
+    let mut client = initialize(&root);
+
+    open(
+        &mut client,
+        &main_uri,
+        "استيراد حسابات::اجمع؛\n\nدالة البداية() {\n    اظهر_(اجمع(1، 2))؛\n}\n",
+    );
+    client.diagnostics(&main_uri);
+
+    let position =
+        json!({ "textDocument": { "uri": main_uri }, "position": { "line": 0, "character": 18 } });
+
+    let definition = client.request("textDocument/definition", position.clone());
+    assert_eq!(definition["uri"], uri(&root.join("حسابات/جمع.نظم")));
+    assert_eq!(
+        definition["range"],
+        json!({ "start": { "line": 0, "character": 11 }, "end": { "line": 0, "character": 15 } })
+    );
+
+    let hover = client.request("textDocument/hover", position);
+    let hover_text = hover["contents"]["value"].as_str().unwrap();
+    assert!(hover_text.contains("تصدير دالة اجمع"));
+    assert!(hover_text.contains("الدالة `حسابات::اجمع`"));
+    assert!(hover_text.contains("يمكن استيراده من الحزم الأخرى"));
+
+    let outside =
+        json!({ "textDocument": { "uri": main_uri }, "position": { "line": 2, "character": 0 } });
+    assert_eq!(client.request("textDocument/hover", outside), Value::Null);
+
+    client.shutdown();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_invalid_params_are_rejected() {
+    let root = create_package("invalid_params");
+    let mut client = initialize(&root);
+
+    client.notify("textDocument/didOpen", json!({ "textDocument": 1 }));
+
+    client.send(json!({
+        "jsonrpc": "2.0",
+        "id": 100,
+        "method": "textDocument/hover",
+        "params": { "position": "بداية" },
+    }));
+    let resp = loop {
+        let msg = client.recv();
+        if msg["id"] == 100 {
+            break msg;
+        }
+    };
+    assert_eq!(resp["error"]["code"], -32602);
+
+    client.shutdown();
+    fs::remove_dir_all(root).unwrap();
+}
@@ -1,6 +1,6 @@
 use crate::{stms::Binding, ConditionalScope, ModPathWithItem, NIRId, Scope};
 use nazmc_data_pool::PoolIdx;
-use nazmc_diagnostics::span::{Span, SpanCursor};
+use nazmc_diagnostics::span::Span;
 use thin_vec::ThinVec;
 
 pub struct Expr {
@@ -8,6 +8,7 @@ pub struct Expr {
     pub span: Span,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ExprKindAndIndex(u64);
 
 impl ExprKindAndIndex {
@@ -35,6 +36,14 @@ impl ExprKindAndIndex {
     pub const LAMBDA_EXPR: u64 = 15 << Self::KIND_SHIFT;
     pub const UNARY_EXPR: u64 = 16 << Self::KIND_SHIFT;
     pub const BIN_EXPR: u64 = 17 << Self::KIND_SHIFT;
+    pub const PATH: u64 = 18 << Self::KIND_SHIFT;
+    pub const CALL: u64 = 19 << Self::KIND_SHIFT;
+    pub const RETURN: u64 = 20 << Self::KIND_SHIFT;
+    pub const BREAK: u64 = 21 << Self::KIND_SHIFT;
+    pub const BREAK_WITH_VALUE: u64 = 22 << Self::KIND_SHIFT;
+    pub const CONTINUE: u64 = 23 << Self::KIND_SHIFT;
+    pub const ON: u64 = 24 << Self::KIND_SHIFT;
+    pub const WHEN_EXPR: u64 = 25 << Self::KIND_SHIFT;
 
     // Create a new encoded value for a given kind and index
     pub fn new(kind: u64, index: usize) -> Self {
@@ -47,8 +56,8 @@ impl ExprKindAndIndex {
     }
 
     // Decode the index of the expression
-    pub fn index(self) -> u64 {
-        self.0 & Self::INDEX_MASK
+    pub fn index(self) -> usize {
+        (self.0 & Self::INDEX_MASK) as usize
     }
 }
 
@@ -59,6 +68,7 @@ pub struct Exprs {
     pub paths: ThinVec<ModPathWithItem>,
     pub path_calls: ThinVec<PathCallExpr>,
     pub method_calls: ThinVec<MethodCallExpr>,
+    pub calls: ThinVec<CallExpr>,
     pub unit_structs: ThinVec<ModPathWithItem>,
     pub tuple_structs: ThinVec<TupleStructExpr>,
     pub fields_structs: ThinVec<FieldsStructExpr>,
@@ -68,12 +78,15 @@ pub struct Exprs {
     pub array_elements_sized: ThinVec<ArrayElementsSizedExpr>,
     pub tuples: ThinVec<TupleExpr>,
     pub returns: ThinVec<ReturWithValueExpr>,
+    pub breaks: ThinVec<BreakWithValueExpr>,
     pub ifs: ThinVec<IfExpr>,
+    pub whens: ThinVec<WhenExpr>,
     pub lambdas: ThinVec<LambdaExpr>,
     pub unary_exprs: ThinVec<UnaryExpr>,
     pub bin_exprs: ThinVec<BinExpr>,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum LiteralExpr {
     Str(PoolIdx),
     Char(char),
@@ -81,6 +94,7 @@ pub enum LiteralExpr {
     Num(NumKind),
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum NumKind {
     F4(f32),
     F8(f64),
@@ -114,6 +128,13 @@ pub struct MethodCallExpr {
     pub parens_span: Span,
 }
 
+/// A call on any expression other than a path (e.g. a returned lambda)
+pub struct CallExpr {
+    pub on: Expr,
+    pub args: ThinVec<Expr>,
+    pub parens_span: Span,
+}
+
 pub struct TupleStructExpr {
     pub path: ModPathWithItem,
     pub args: ThinVec<Expr>,
@@ -157,14 +178,54 @@ pub struct ReturWithValueExpr {
     pub expr_to_return: Expr,
 }
 
+pub struct BreakWithValueExpr {
+    pub expr_to_break: Expr,
+}
+
 pub struct IfExpr {
     pub if_: ConditionalScope,
     pub else_ifs: ThinVec<ConditionalScope>,
     pub else_: Option<Scope>,
 }
 
+pub struct WhenExpr {
+    pub expr: Expr,
+    pub arms: ThinVec<WhenArm>,
+}
+
+pub struct WhenArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Scope,
+}
+
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Span,
+}
+
+pub enum PatternKind {
+    /// `_`
+    Wildcard,
+    /// The index of the bound name in the locals of the NIR
+    Binding(usize),
+    /// A literal or a negated number literal
+    Literal(Expr),
+    Range(Box<RangePattern>),
+    Tuple(ThinVec<Pattern>),
+    UnitStruct(ModPathWithItem),
+    TupleStruct(ModPathWithItem, ThinVec<Pattern>),
+    FieldsStruct(ModPathWithItem, ThinVec<(NIRId, Pattern)>),
+}
+
+pub struct RangePattern {
+    pub start: Expr,
+    pub op: BinOp,
+    pub end: Expr,
+}
+
 pub struct LambdaExpr {
-    pub param: ThinVec<Binding>,
+    pub params: ThinVec<Binding>,
     pub body: Scope,
 }
 
@@ -174,6 +235,7 @@ pub struct UnaryExpr {
     pub expr: Expr,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum UnaryOp {
     Minus,
     LNot,
@@ -185,11 +247,13 @@ pub enum UnaryOp {
 
 pub struct BinExpr {
     pub op: BinOp,
-    pub op_span_cursor: SpanCursor,
+    /// The byte offset of the operator
+    pub op_span_lo: u32,
     pub left: Expr,
     pub right: Expr,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum BinOp {
     LOr,
     LAnd,
@@ -1,9 +1,9 @@
-use exprs::{Expr, Exprs};
+pub use exprs::*;
 use nazmc_data_pool::PoolIdx;
 use nazmc_diagnostics::span::Span;
-use stms::{Stm, Stms};
+pub use stms::*;
 use thin_vec::ThinVec;
-use types::{Type, Types};
+pub use types::*;
 
 mod exprs;
 mod stms;
@@ -11,13 +11,17 @@ mod types;
 
 #[derive(Default)]
 pub struct NIR {
-    pub imports: ThinVec<ModPathWithItem>,
-    pub star_imports: ThinVec<ModPath>,
     pub types: Types,
     pub unit_structs: ThinVec<UnitStruct>,
     pub tuple_structs: ThinVec<TupleStruct>,
     pub fields_structs: ThinVec<FieldsStruct>,
+    pub enums: ThinVec<Enum>,
+    pub consts: ThinVec<Const>,
+    /// The statics have the same declarations of the constants
+    pub statics: ThinVec<Const>,
     pub fns: ThinVec<Fn>,
+    /// All the names bound by params, lambda params and `احجز` statements
+    pub locals: ThinVec<Local>,
     pub scopes: ThinVec<ScopeBody>,
     pub stms: Stms,
     pub exprs: Exprs,
@@ -31,13 +35,52 @@ pub struct ModPath {
 pub struct ModPathWithItem {
     pub mod_path: ModPath,
     pub item: NIRId,
+    /// What this path refers to after name resolution
+    pub resolved: ResolvedPath,
 }
 
+#[derive(Clone, Copy)]
 pub struct NIRId {
     pub span: Span,
     pub id: PoolIdx,
 }
 
+/// The target of a path with its index in the arenas of the NIR
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ResolvedPath {
+    Local(usize),
+    UnitStruct(usize),
+    TupleStruct(usize),
+    FieldsStruct(usize),
+    Enum(usize),
+    /// The enum index and the variant index in it
+    Variant(usize, usize),
+    Const(usize),
+    Static(usize),
+    Fn(usize),
+    Builtin(BuiltinFn),
+}
+
+/// The functions provided by the compiler which are not defined in any file
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BuiltinFn {
+    /// `اظهر`
+    Print,
+    /// `اظهر_`
+    PrintLn,
+}
+
+impl BuiltinFn {
+    pub fn from_id(id: PoolIdx) -> Option<Self> {
+        match id {
+            PoolIdx::PRINT => Some(Self::Print),
+            PoolIdx::PRINTLN => Some(Self::PrintLn),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub enum VisModifier {
     Default,
     Public,
@@ -47,33 +90,79 @@ pub enum VisModifier {
 pub struct UnitStruct {
     pub vis: VisModifier,
     pub name: NIRId,
+    /// The parsed file index where the struct is defined
+    pub file_idx: usize,
 }
 
 pub struct TupleStruct {
     pub vis: VisModifier,
     pub name: NIRId,
+    /// The parsed file index where the struct is defined
+    pub file_idx: usize,
     pub types: ThinVec<(VisModifier, Type)>,
 }
 
 pub struct FieldsStruct {
     pub vis: VisModifier,
     pub name: NIRId,
+    /// The parsed file index where the struct is defined
+    pub file_idx: usize,
     pub fields: ThinVec<(VisModifier, NIRId, Type)>,
 }
 
+pub struct Enum {
+    pub vis: VisModifier,
+    pub name: NIRId,
+    /// The parsed file index where the enum is defined
+    pub file_idx: usize,
+    pub variants: ThinVec<Variant>,
+}
+
+pub struct Variant {
+    pub name: NIRId,
+    pub kind: VariantKind,
+}
+
+pub enum VariantKind {
+    Unit,
+    Tuple(ThinVec<Type>),
+    Fields(ThinVec<(NIRId, Type)>),
+}
+
+pub struct Const {
+    pub vis: VisModifier,
+    pub name: NIRId,
+    /// The parsed file index where the constant is defined
+    pub file_idx: usize,
+    pub typ: Type,
+    /// The value which is evaluated before running the program
+    pub expr: Expr,
+}
+
 pub struct Fn {
     pub vis: VisModifier,
     pub name: NIRId,
-    pub params: ThinVec<(NIRId, Type)>,
+    /// The parsed file index where the function is defined
+    pub file_idx: usize,
+    /// The local index of each param with its type
+    pub params: ThinVec<(usize, Type)>,
     pub return_type: Type,
     pub body: Scope,
 }
 
+pub struct Local {
+    pub name: NIRId,
+    pub is_mut: bool,
+    /// The parsed file index where the local is bound
+    pub file_idx: usize,
+}
+
 pub struct ScopeBody {
     pub stms: ThinVec<Stm>,
     pub return_expr: Option<Expr>,
 }
 
+#[derive(Clone, Copy)]
 pub struct Scope {
     pub index: usize,
 }
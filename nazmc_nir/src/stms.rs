@@ -2,13 +2,47 @@ use nazmc_diagnostics::span::Span;
 use thin_vec::ThinVec;
 
 use crate::{
-    exprs::{Expr, IfExpr},
+    exprs::{Expr, IfExpr, WhenExpr},
     types::Type,
-    ConditionalScope, NIRId,
+    ConditionalScope,
 };
 
 pub struct Stm {
-    pub kind_and_index: u64,
+    pub kind_and_index: StmKindAndIndex,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct StmKindAndIndex(u64);
+
+impl StmKindAndIndex {
+    pub const KIND_BITS: u64 = 3;
+    pub const KIND_SHIFT: u64 = 64 - Self::KIND_BITS;
+    pub const KIND_MASK: u64 = 0b111 << Self::KIND_SHIFT;
+    pub const INDEX_MASK: u64 = !Self::KIND_MASK;
+
+    // Possible kinds
+    pub const LET: u64 = 0 << Self::KIND_SHIFT;
+    pub const LET_MUT: u64 = 1 << Self::KIND_SHIFT;
+    pub const IF: u64 = 2 << Self::KIND_SHIFT;
+    pub const WHILE: u64 = 3 << Self::KIND_SHIFT;
+    pub const EXPR: u64 = 4 << Self::KIND_SHIFT;
+    pub const WHEN: u64 = 5 << Self::KIND_SHIFT;
+    pub const DO_WHILE: u64 = 6 << Self::KIND_SHIFT;
+
+    // Create a new encoded value for a given kind and index
+    pub fn new(kind: u64, index: usize) -> Self {
+        Self(kind | index as u64)
+    }
+
+    // Decode the kind of the statement
+    pub fn kind(self) -> u64 {
+        self.0 & Self::KIND_MASK
+    }
+
+    // Decode the index of the statement
+    pub fn index(self) -> usize {
+        (self.0 & Self::INDEX_MASK) as usize
+    }
 }
 
 #[derive(Default)]
@@ -17,6 +51,9 @@ pub struct Stms {
     pub let_muts: ThinVec<LetStm>,
     pub ifs: ThinVec<IfExpr>,
     pub whiles: ThinVec<ConditionalScope>,
+    /// The `افعل ... طالما` loops, their bodies are executed once before checking the conditions
+    pub do_whiles: ThinVec<ConditionalScope>,
+    pub whens: ThinVec<WhenExpr>,
     pub exprs: ThinVec<Expr>,
 }
 
@@ -27,10 +64,11 @@ pub struct LetStm {
 
 pub struct Binding {
     pub kind: BindingKind,
-    pub typ: Type,
+    pub typ: Option<Type>,
 }
 
 pub enum BindingKind {
-    Name(NIRId),
-    TupleDestruction(ThinVec<Binding>, Span),
+    /// The index of the bound name in the locals of the NIR
+    Local(usize),
+    TupleDestruction(ThinVec<BindingKind>, Span),
 }
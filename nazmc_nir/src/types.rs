@@ -7,27 +7,146 @@ pub struct Types {
     pub paths: ThinVec<ModPathWithItem>,
     pub ptrs: ThinVec<Type>,
     pub refs: ThinVec<Type>,
-    pub ptrs_mut: ThinVec<PtrMutType>,
-    pub refs_mut: ThinVec<RefMutType>,
-    pub parens: ThinVec<Type>,
+    pub ptrs_mut: ThinVec<Type>,
+    pub refs_mut: ThinVec<Type>,
     pub slices: ThinVec<Type>,
     pub tuples: ThinVec<TupleType>,
     pub arrays: ThinVec<ArrayType>,
+    pub lambdas: ThinVec<LambdaType>,
 }
 
 pub struct Type {
-    pub kind_and_index: u64,
+    pub kind_and_index: TypeKindAndIndex,
     pub span: Span,
 }
 
-pub struct PtrMutType {
-    pub typ: Type,
-    pub star_mut_span: Span,
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TypeKindAndIndex(u64);
+
+impl TypeKindAndIndex {
+    pub const KIND_BITS: u64 = 4;
+    pub const KIND_SHIFT: u64 = 64 - Self::KIND_BITS;
+    pub const KIND_MASK: u64 = 0b1111 << Self::KIND_SHIFT;
+    pub const INDEX_MASK: u64 = !Self::KIND_MASK;
+
+    // Possible kinds
+    pub const UNIT: u64 = 0 << Self::KIND_SHIFT;
+    /// The index is the `PrimitiveType` value and not an index in an arena
+    pub const PRIMITIVE: u64 = 1 << Self::KIND_SHIFT;
+    pub const PATH: u64 = 2 << Self::KIND_SHIFT;
+    pub const PTR: u64 = 3 << Self::KIND_SHIFT;
+    pub const REF: u64 = 4 << Self::KIND_SHIFT;
+    pub const PTR_MUT: u64 = 5 << Self::KIND_SHIFT;
+    pub const REF_MUT: u64 = 6 << Self::KIND_SHIFT;
+    pub const SLICE: u64 = 7 << Self::KIND_SHIFT;
+    pub const TUPLE: u64 = 8 << Self::KIND_SHIFT;
+    pub const ARRAY: u64 = 9 << Self::KIND_SHIFT;
+    pub const LAMBDA: u64 = 10 << Self::KIND_SHIFT;
+
+    // Create a new encoded value for a given kind and index
+    pub fn new(kind: u64, index: usize) -> Self {
+        Self(kind | index as u64)
+    }
+
+    // Decode the kind of the type
+    pub fn kind(self) -> u64 {
+        self.0 & Self::KIND_MASK
+    }
+
+    // Decode the index of the type
+    pub fn index(self) -> usize {
+        (self.0 & Self::INDEX_MASK) as usize
+    }
 }
 
-pub struct RefMutType {
-    pub typ: Type,
-    pub hash_mut_span: Span,
+/// The built-in types that are not defined by any package
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PrimitiveType {
+    I,
+    I1,
+    I2,
+    I4,
+    I8,
+    U,
+    U1,
+    U2,
+    U4,
+    U8,
+    F4,
+    F8,
+    Bool,
+    Char,
+    Str,
+}
+
+impl PrimitiveType {
+    pub const ALL: [Self; 15] = [
+        Self::I,
+        Self::I1,
+        Self::I2,
+        Self::I4,
+        Self::I8,
+        Self::U,
+        Self::U1,
+        Self::U2,
+        Self::U4,
+        Self::U8,
+        Self::F4,
+        Self::F8,
+        Self::Bool,
+        Self::Char,
+        Self::Str,
+    ];
+
+    /// The name used in the source code for this type
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::I => "ص",
+            Self::I1 => "ص1",
+            Self::I2 => "ص2",
+            Self::I4 => "ص4",
+            Self::I8 => "ص8",
+            Self::U => "ط",
+            Self::U1 => "ط1",
+            Self::U2 => "ط2",
+            Self::U4 => "ط4",
+            Self::U8 => "ط8",
+            Self::F4 => "ع4",
+            Self::F8 => "ع8",
+            Self::Bool => "شرط",
+            Self::Char => "حرف",
+            Self::Str => "متن",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    #[inline]
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL[index]
+    }
+
+    #[inline]
+    pub fn is_signed_int(self) -> bool {
+        matches!(self, Self::I | Self::I1 | Self::I2 | Self::I4 | Self::I8)
+    }
+
+    #[inline]
+    pub fn is_unsigned_int(self) -> bool {
+        matches!(self, Self::U | Self::U1 | Self::U2 | Self::U4 | Self::U8)
+    }
+
+    #[inline]
+    pub fn is_int(self) -> bool {
+        self.is_signed_int() || self.is_unsigned_int()
+    }
+
+    #[inline]
+    pub fn is_float(self) -> bool {
+        matches!(self, Self::F4 | Self::F8)
+    }
 }
 
 pub struct TupleType {
@@ -39,3 +158,8 @@ pub struct ArrayType {
     pub typ: Type,
     pub size: Expr,
 }
+
+pub struct LambdaType {
+    pub params: ThinVec<Type>,
+    pub return_type: Type,
+}
@@ -177,6 +177,50 @@ fn lower_file_items(file_items: Vec<ParseResult<FileItem>>) -> ThinVec<nazmc_ast
                     }
                 }
             }
+            Item::Enum(e) => {
+                let name = e.name.unwrap();
+                let name = nazmc_ast::ASTId {
+                    span: name.span,
+                    id: name.data.val,
+                };
+
+                let mut variants = ThinVec::new();
+
+                if let Some(PunctuatedEnumVariant {
+                    first_item,
+                    rest_items,
+                    trailing_comma: _,
+                }) = e.variants.unwrap().items
+                {
+                    variants.push(lower_enum_variant(first_item.unwrap()));
+
+                    for r in rest_items {
+                        variants.push(lower_enum_variant(r.unwrap().item));
+                    }
+                }
+
+                items.push(nazmc_ast::Item {
+                    name,
+                    vis,
+                    kind: nazmc_ast::ItemKind::Enum(nazmc_ast::Enum { variants }),
+                });
+            }
+            Item::Const(c) => {
+                let (name, c) = lower_const(c.name, c.typ, c.assign);
+                items.push(nazmc_ast::Item {
+                    name,
+                    vis,
+                    kind: nazmc_ast::ItemKind::Const(c),
+                });
+            }
+            Item::Static(s) => {
+                let (name, s) = lower_const(s.name, s.typ, s.assign);
+                items.push(nazmc_ast::Item {
+                    name,
+                    vis,
+                    kind: nazmc_ast::ItemKind::Static(s),
+                });
+            }
             Item::Fn(f) => {
                 let name = f.name.unwrap();
                 let name = nazmc_ast::ASTId {
@@ -224,6 +268,21 @@ fn lower_file_items(file_items: Vec<ParseResult<FileItem>>) -> ThinVec<nazmc_ast
     items
 }
 
+fn lower_const(
+    name: ParseResult<Id>,
+    typ: ParseResult<ColonWithType>,
+    assign: ParseResult<LetAssign>,
+) -> (nazmc_ast::ASTId, nazmc_ast::Const) {
+    let name = name.unwrap();
+    let name = nazmc_ast::ASTId {
+        span: name.span,
+        id: name.data.val,
+    };
+    let typ = lower_type(typ.unwrap().typ.unwrap());
+    let expr = lower_expr(assign.unwrap().expr.unwrap());
+    (name, nazmc_ast::Const { typ, expr })
+}
+
 fn lower_tuple_struct_field(field: TupleStructField) -> (nazmc_ast::VisModifier, nazmc_ast::Type) {
     let vis = match field.visibility {
         Some(Terminal {
@@ -267,6 +326,57 @@ fn lower_struct_field(
     (vis, name, typ)
 }
 
+fn lower_enum_variant(variant: EnumVariant) -> nazmc_ast::Variant {
+    let name = nazmc_ast::ASTId {
+        span: variant.name.span,
+        id: variant.name.data.val,
+    };
+
+    let kind = match variant.kind {
+        None => nazmc_ast::VariantKind::Unit,
+        Some(EnumVariantKind::Tuple(tuple_struct_fields)) => {
+            let mut types = ThinVec::new();
+
+            if let Some(PunctuatedTupleStructField {
+                first_item,
+                rest_items,
+                trailing_comma: _,
+            }) = tuple_struct_fields.items
+            {
+                types.push(lower_tuple_struct_field(first_item.unwrap()).1);
+
+                for r in rest_items {
+                    types.push(lower_tuple_struct_field(r.unwrap().item).1);
+                }
+            }
+
+            nazmc_ast::VariantKind::Tuple(types)
+        }
+        Some(EnumVariantKind::Fields(struct_fields)) => {
+            let mut fields = ThinVec::new();
+
+            if let Some(PunctuatedStructField {
+                first_item,
+                rest_items,
+                trailing_comma: _,
+            }) = struct_fields.items
+            {
+                let (_, name, typ) = lower_struct_field(first_item.unwrap());
+                fields.push((name, typ));
+
+                for r in rest_items {
+                    let (_, name, typ) = lower_struct_field(r.unwrap().item);
+                    fields.push((name, typ));
+                }
+            }
+
+            nazmc_ast::VariantKind::Fields(fields)
+        }
+    };
+
+    nazmc_ast::Variant { name, kind }
+}
+
 fn lower_fn_param(param: FnParam) -> (nazmc_ast::ASTId, nazmc_ast::Type) {
     let name = nazmc_ast::ASTId {
         span: param.name.span,
@@ -379,6 +489,9 @@ fn lower_simple_path(mut simple_path: SimplePath) -> nazmc_ast::PkgPathWithItem
             id: item.data.val,
         };
 
+        mod_path.ids.push(simple_path.top.data.val);
+        mod_path.spans.push(simple_path.top.span);
+
         for inner in simple_path.inners {
             let inner = inner.inner.unwrap();
             mod_path.ids.push(inner.data.val);
@@ -425,8 +538,12 @@ fn lower_lambda_stms_and_return_expr(
                 lower_expr(while_stm.conditional_block.condition.unwrap()),
                 lower_lambda_as_body(while_stm.conditional_block.block.unwrap()),
             ))),
+            Stm::DoWhile(do_while_stm) => nazmc_ast::Stm::DoWhile(Box::new((
+                lower_lambda_as_body(do_while_stm.block.unwrap()),
+                lower_expr(do_while_stm.condition.unwrap()),
+            ))),
             Stm::If(if_expr) => nazmc_ast::Stm::If(Box::new(lower_if_expr(if_expr))),
-            Stm::When(_when_expr) => todo!(),
+            Stm::When(when_expr) => nazmc_ast::Stm::When(Box::new(lower_when_expr(when_expr))),
             Stm::Expr(stm) => nazmc_ast::Stm::Expr(Box::new(lower_expr(stm.expr))),
         };
         ast_stms.push(stm);
@@ -495,7 +612,7 @@ fn lower_expr(expr: Expr) -> nazmc_ast::Expr {
     for b in expr.bin {
         let right = lower_primary_expr(b.right.unwrap());
         let op = lower_bin_op(b.op.data);
-        let op_span_cursor = b.op.span.start;
+        let op_span_lo = b.op.span.lo;
 
         // Pop operators from the stack while they have higher or equal precedence
         while let Some((last_op, _)) = ops_stack.last() {
@@ -503,7 +620,7 @@ fn lower_expr(expr: Expr) -> nazmc_ast::Expr {
                 break;
             }
 
-            let (last_op, last_op_span_cursor) = ops_stack.pop().unwrap();
+            let (last_op, last_op_span_lo) = ops_stack.pop().unwrap();
             let right_expr = expr_stack.pop().unwrap();
             let left_expr = expr_stack.pop().unwrap();
 
@@ -512,7 +629,7 @@ fn lower_expr(expr: Expr) -> nazmc_ast::Expr {
                 span: left_expr.span.merged_with(&right_expr.span),
                 kind: nazmc_ast::ExprKind::BinaryOp(Box::new(nazmc_ast::BinaryOpExpr {
                     op: last_op,
-                    op_span_cursor: last_op_span_cursor,
+                    op_span_lo: last_op_span_lo,
                     left: left_expr,
                     right: right_expr,
                 })),
@@ -522,12 +639,12 @@ fn lower_expr(expr: Expr) -> nazmc_ast::Expr {
         }
 
         // Push the current operator and the right-hand expression onto the stacks
-        ops_stack.push((op, op_span_cursor));
+        ops_stack.push((op, op_span_lo));
         expr_stack.push(right);
     }
 
     // Apply remaining operators in the stack
-    while let Some((last_op, last_op_span_cursor)) = ops_stack.pop() {
+    while let Some((last_op, last_op_span_lo)) = ops_stack.pop() {
         let right_expr = expr_stack.pop().unwrap();
         let left_expr = expr_stack.pop().unwrap();
 
@@ -536,7 +653,7 @@ fn lower_expr(expr: Expr) -> nazmc_ast::Expr {
             span: left_expr.span.merged_with(&right_expr.span),
             kind: nazmc_ast::ExprKind::BinaryOp(Box::new(nazmc_ast::BinaryOpExpr {
                 op: last_op,
-                op_span_cursor: last_op_span_cursor,
+                op_span_lo: last_op_span_lo,
                 left: left_expr,
                 right: right_expr,
             })),
@@ -786,7 +903,23 @@ fn lower_atomic_expr(atomic_expr: AtomicExpr) -> nazmc_ast::Expr {
         AtomicExpr::Paren(paren_expr) => lower_paren_expr(paren_expr),
         AtomicExpr::Struct(struct_expr) => lower_struct_expr(struct_expr),
         AtomicExpr::Lambda(lambda_expr) => lower_lambda_expr(lambda_expr),
-        AtomicExpr::When(when_expr) => lower_when_expr(when_expr),
+        AtomicExpr::When(when_expr) => {
+            let span = when_expr.when_keyword.span.merged_with(
+                &when_expr
+                    .arms
+                    .as_ref()
+                    .unwrap()
+                    .close_delim
+                    .as_ref()
+                    .unwrap()
+                    .span,
+            );
+
+            nazmc_ast::Expr {
+                span,
+                kind: nazmc_ast::ExprKind::When(Box::new(lower_when_expr(when_expr))),
+            }
+        }
         AtomicExpr::If(if_expr) => {
             let span_end = if let Some(ref else_) = if_expr.else_cluase {
                 &else_
@@ -845,36 +978,7 @@ fn lower_atomic_expr(atomic_expr: AtomicExpr) -> nazmc_ast::Expr {
                 kind: nazmc_ast::ExprKind::Path(Box::new(path)),
             }
         }
-        AtomicExpr::Literal(lit) => {
-            let literal_expr = match lit.data {
-                LiteralKind::Str(pool_idx) => nazmc_ast::LiteralExpr::Str(pool_idx),
-                LiteralKind::Char(ch) => nazmc_ast::LiteralExpr::Char(ch),
-                LiteralKind::Bool(b) => nazmc_ast::LiteralExpr::Bool(b),
-                LiteralKind::Num(num_kind) => {
-                    let num_kind = match num_kind {
-                        NumKind::F4(f4) => nazmc_ast::NumKind::F4(f4),
-                        NumKind::F8(f8) => nazmc_ast::NumKind::F8(f8),
-                        NumKind::I(i) => nazmc_ast::NumKind::I(i),
-                        NumKind::I1(i1) => nazmc_ast::NumKind::I1(i1),
-                        NumKind::I2(i2) => nazmc_ast::NumKind::I2(i2),
-                        NumKind::I4(i4) => nazmc_ast::NumKind::I4(i4),
-                        NumKind::I8(i8) => nazmc_ast::NumKind::I8(i8),
-                        NumKind::U(u) => nazmc_ast::NumKind::U(u),
-                        NumKind::U1(u1) => nazmc_ast::NumKind::U1(u1),
-                        NumKind::U2(u2) => nazmc_ast::NumKind::U2(u2),
-                        NumKind::U4(u4) => nazmc_ast::NumKind::U4(u4),
-                        NumKind::U8(u8) => nazmc_ast::NumKind::U8(u8),
-                        NumKind::UnspecifiedInt(i) => nazmc_ast::NumKind::UnspecifiedInt(i),
-                        NumKind::UnspecifiedFloat(f) => nazmc_ast::NumKind::UnspecifiedFloat(f),
-                    };
-                    nazmc_ast::LiteralExpr::Num(num_kind)
-                }
-            };
-            nazmc_ast::Expr {
-                span: lit.span,
-                kind: nazmc_ast::ExprKind::Literal(literal_expr),
-            }
-        }
+        AtomicExpr::Literal(lit) => lower_literal_expr(lit),
         AtomicExpr::Return(return_expr) => {
             let expr = return_expr.expr.map(|e| Box::new(lower_expr(e)));
 
@@ -914,6 +1018,37 @@ fn lower_atomic_expr(atomic_expr: AtomicExpr) -> nazmc_ast::Expr {
     }
 }
 
+fn lower_literal_expr(lit: LiteralExpr) -> nazmc_ast::Expr {
+    let literal_expr = match lit.data {
+        LiteralKind::Str(pool_idx) => nazmc_ast::LiteralExpr::Str(pool_idx),
+        LiteralKind::Char(ch) => nazmc_ast::LiteralExpr::Char(ch),
+        LiteralKind::Bool(b) => nazmc_ast::LiteralExpr::Bool(b),
+        LiteralKind::Num(num_kind) => {
+            let num_kind = match num_kind {
+                NumKind::F4(f4) => nazmc_ast::NumKind::F4(f4),
+                NumKind::F8(f8) => nazmc_ast::NumKind::F8(f8),
+                NumKind::I(i) => nazmc_ast::NumKind::I(i),
+                NumKind::I1(i1) => nazmc_ast::NumKind::I1(i1),
+                NumKind::I2(i2) => nazmc_ast::NumKind::I2(i2),
+                NumKind::I4(i4) => nazmc_ast::NumKind::I4(i4),
+                NumKind::I8(i8) => nazmc_ast::NumKind::I8(i8),
+                NumKind::U(u) => nazmc_ast::NumKind::U(u),
+                NumKind::U1(u1) => nazmc_ast::NumKind::U1(u1),
+                NumKind::U2(u2) => nazmc_ast::NumKind::U2(u2),
+                NumKind::U4(u4) => nazmc_ast::NumKind::U4(u4),
+                NumKind::U8(u8) => nazmc_ast::NumKind::U8(u8),
+                NumKind::UnspecifiedInt(i) => nazmc_ast::NumKind::UnspecifiedInt(i),
+                NumKind::UnspecifiedFloat(f) => nazmc_ast::NumKind::UnspecifiedFloat(f),
+            };
+            nazmc_ast::LiteralExpr::Num(num_kind)
+        }
+    };
+    nazmc_ast::Expr {
+        span: lit.span,
+        kind: nazmc_ast::ExprKind::Literal(literal_expr),
+    }
+}
+
 #[inline]
 fn lower_array_expr(array_expr: ArrayExpr) -> nazmc_ast::Expr {
     let span = array_expr
@@ -1165,6 +1300,219 @@ fn lower_if_expr(if_expr: IfExpr) -> nazmc_ast::IfExpr {
     }
 }
 
-fn lower_when_expr(_when_expr: WhenExpr) -> nazmc_ast::Expr {
-    todo!()
+fn lower_when_expr(when_expr: WhenExpr) -> nazmc_ast::WhenExpr {
+    let expr = lower_expr(when_expr.expr.unwrap());
+
+    let mut arms = ThinVec::new();
+
+    if let Some(PunctuatedWhenArm {
+        first_item,
+        rest_items,
+        trailing_comma: _,
+    }) = when_expr.arms.unwrap().items
+    {
+        arms.push(lower_when_arm(first_item.unwrap()));
+        for r in rest_items {
+            arms.push(lower_when_arm(r.unwrap().item));
+        }
+    }
+
+    nazmc_ast::WhenExpr { expr, arms }
+}
+
+fn lower_when_arm(arm: WhenArm) -> nazmc_ast::WhenArm {
+    let pattern = lower_pattern(arm.pattern);
+
+    let guard = arm.guard.map(|g| lower_expr(g.condition.unwrap()));
+
+    let expr = arm.expr.unwrap();
+
+    // A lambda without an arrow is the block of the arm
+    let is_block = expr.bin.is_empty()
+        && expr.left.post_ops.is_empty()
+        && expr.left.inner_access.is_empty()
+        && matches!(
+            &expr.left.kind,
+            PrimaryExprKind::Atomic(AtomicExpr::Lambda(LambdaExpr {
+                lambda_arrow: None,
+                ..
+            }))
+        );
+
+    let body = if is_block {
+        let PrimaryExprKind::Atomic(AtomicExpr::Lambda(lambda)) = expr.left.kind else {
+            unreachable!()
+        };
+        lower_lambda_as_body(lambda)
+    } else {
+        nazmc_ast::Scope {
+            stms: ThinVec::new(),
+            return_expr: Some(lower_expr(expr)),
+        }
+    };
+
+    nazmc_ast::WhenArm {
+        pattern,
+        guard,
+        body,
+    }
+}
+
+fn lower_pattern(pattern: Pattern) -> nazmc_ast::Pattern {
+    match pattern {
+        Pattern::Literal(LiteralPattern {
+            minus,
+            literal,
+            range_end,
+        }) => {
+            let start = lower_pattern_literal(minus, literal);
+
+            let Some(RangePatternEnd { op, minus, literal }) = range_end else {
+                return nazmc_ast::Pattern {
+                    span: start.span,
+                    kind: nazmc_ast::PatternKind::Literal(Box::new(start)),
+                };
+            };
+
+            let end = lower_pattern_literal(minus, literal.unwrap());
+
+            nazmc_ast::Pattern {
+                span: start.span.merged_with(&end.span),
+                kind: nazmc_ast::PatternKind::Range(Box::new(nazmc_ast::RangePattern {
+                    start,
+                    op: lower_bin_op(op.data),
+                    end,
+                })),
+            }
+        }
+        Pattern::Tuple(tuple_pattern) => {
+            let span = tuple_pattern
+                .open_delim
+                .span
+                .merged_with(&tuple_pattern.close_delim.as_ref().unwrap().span);
+
+            nazmc_ast::Pattern {
+                span,
+                kind: nazmc_ast::PatternKind::Tuple(lower_tuple_pattern(*tuple_pattern)),
+            }
+        }
+        Pattern::Struct(struct_pattern) => {
+            let path = lower_simple_path(struct_pattern.path.unwrap());
+
+            match struct_pattern.fields {
+                Some(StructPatternFields::Tuple(tuple_pattern)) => {
+                    let span = struct_pattern
+                        .dot
+                        .span
+                        .merged_with(&tuple_pattern.close_delim.as_ref().unwrap().span);
+
+                    let patterns = lower_tuple_pattern(tuple_pattern);
+
+                    nazmc_ast::Pattern {
+                        span,
+                        kind: nazmc_ast::PatternKind::TupleStruct(Box::new(
+                            nazmc_ast::TupleStructPattern { path, patterns },
+                        )),
+                    }
+                }
+                Some(StructPatternFields::Fields(fields_pattern)) => {
+                    let span = struct_pattern
+                        .dot
+                        .span
+                        .merged_with(&fields_pattern.close_delim.unwrap().span);
+
+                    let mut fields = ThinVec::new();
+
+                    if let Some(PunctuatedFieldPattern {
+                        first_item,
+                        rest_items,
+                        trailing_comma: _,
+                    }) = fields_pattern.items
+                    {
+                        fields.push(lower_field_pattern(first_item.unwrap()));
+                        for r in rest_items {
+                            fields.push(lower_field_pattern(r.unwrap().item));
+                        }
+                    }
+
+                    nazmc_ast::Pattern {
+                        span,
+                        kind: nazmc_ast::PatternKind::FieldsStruct(Box::new(
+                            nazmc_ast::FieldsStructPattern { path, fields },
+                        )),
+                    }
+                }
+                None => nazmc_ast::Pattern {
+                    span: struct_pattern.dot.span.merged_with(&path.item.span),
+                    kind: nazmc_ast::PatternKind::UnitStruct(Box::new(path)),
+                },
+            }
+        }
+        Pattern::Id(id) => lower_id_pattern(id),
+    }
+}
+
+fn lower_id_pattern(id: Id) -> nazmc_ast::Pattern {
+    let kind = if id.data.val == PoolIdx::UNDERSCORE {
+        nazmc_ast::PatternKind::Wildcard
+    } else {
+        nazmc_ast::PatternKind::Binding(nazmc_ast::ASTId {
+            span: id.span,
+            id: id.data.val,
+        })
+    };
+
+    nazmc_ast::Pattern {
+        span: id.span,
+        kind,
+    }
+}
+
+fn lower_pattern_literal(minus: Option<MinusSymbol>, literal: LiteralExpr) -> nazmc_ast::Expr {
+    let expr = lower_literal_expr(literal);
+
+    match minus {
+        Some(minus) => nazmc_ast::Expr {
+            span: minus.span.merged_with(&expr.span),
+            kind: nazmc_ast::ExprKind::UnaryOp(Box::new(nazmc_ast::UnaryOpExpr {
+                op: nazmc_ast::UnaryOp::Minus,
+                op_span: minus.span,
+                expr,
+            })),
+        },
+        None => expr,
+    }
+}
+
+fn lower_tuple_pattern(tuple_pattern: TuplePattern) -> ThinVec<nazmc_ast::Pattern> {
+    let mut patterns = ThinVec::new();
+
+    if let Some(PunctuatedPattern {
+        first_item,
+        rest_items,
+        trailing_comma: _,
+    }) = tuple_pattern.items
+    {
+        patterns.push(lower_pattern(first_item.unwrap()));
+        for r in rest_items {
+            patterns.push(lower_pattern(r.unwrap().item));
+        }
+    }
+
+    patterns
+}
+
+fn lower_field_pattern(field: FieldPattern) -> (nazmc_ast::ASTId, nazmc_ast::Pattern) {
+    let name = nazmc_ast::ASTId {
+        span: field.name.span,
+        id: field.name.data.val,
+    };
+
+    let pattern = match field.pattern {
+        Some(explicit) => lower_pattern(explicit.pattern.unwrap()),
+        // The shorthand binds the field value to a local with the same name
+        None => lower_id_pattern(field.name),
+    };
+
+    (name, pattern)
 }
@@ -0,0 +1,709 @@
+use crate::*;
+use nazmc_data_pool::DataPool;
+use nazmc_diagnostics::FileId;
+use std::collections::HashMap;
+
+/// The indentation of one level
+const INDENT: &str = "    ";
+
+/// How the spaces are written around a token, it is decided by the node of the token in the syntax tree
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum Role {
+    /// The spaces are decided by the kind of the token and the tokens around it
+    #[default]
+    Plain,
+    /// A binary operator or `=`, it is surrounded by spaces
+    BinOp,
+    /// The arrow `->` of the lambdas and the arms of `عندما`, it is surrounded by spaces
+    Arrow,
+    /// A unary operator, the symbol of a pointer or a reference type or the dot before a struct path,
+    /// nothing is written between it and the next token
+    Prefix,
+    /// The double colons of a path, nothing is written around them
+    DoubleColons,
+}
+
+/// A list generated by `generateDelimitedPunctuated!` or the elements of an array, it has one item at least
+struct DelimitedList {
+    close_delim: Span,
+    trailing_comma: Option<Span>,
+    /// The trailing comma of a single item in parentheses makes it a tuple so it is never removed
+    keep_trailing_comma: bool,
+}
+
+/// The roles of the terminals and the delimited lists collected from the syntax tree
+#[derive(Default)]
+struct Layout {
+    /// The role of each terminal by the byte offset of its start with the byte offset of its end,
+    /// a terminal may be more than one token like `<<=` or `::`
+    roles: HashMap<u32, (Role, u32)>,
+    lists: Vec<DelimitedList>,
+}
+
+/// Collects the items of a list generated by `generateDelimitedPunctuated!` then the list itself
+macro_rules! collect_delimited {
+    ($self:ident, $list:expr, $tuple_like:literal, |$item:ident| $collect_item:expr) => {{
+        let list = $list;
+        if let Some(items) = &list.items {
+            let $item = items.first_item.as_ref().unwrap();
+            $collect_item;
+
+            for comma_with_item in &items.rest_items {
+                let $item = &comma_with_item.as_ref().unwrap().item;
+                $collect_item;
+            }
+
+            $self.lists.push(DelimitedList {
+                close_delim: list.close_delim.as_ref().unwrap().span,
+                trailing_comma: items.trailing_comma.as_ref().map(|comma| comma.span),
+                keep_trailing_comma: $tuple_like && items.rest_items.is_empty(),
+            });
+        }
+    }};
+}
+
+/// The syntax tree is walked only after checking that it has no errors, so all of its nodes are unwrapped
+impl Layout {
+    fn role(&mut self, span: Span, role: Role) {
+        self.roles.insert(span.lo, (role, span.hi));
+    }
+
+    fn collect_file(&mut self, file: &File) {
+        for import in &file.imports {
+            self.role(
+                import.sec.as_ref().unwrap().double_colons.span,
+                Role::DoubleColons,
+            );
+            for seg in &import.segs {
+                self.role(seg.double_colons.span, Role::DoubleColons);
+            }
+        }
+
+        for item in &file.content.items {
+            match item.as_ref().unwrap() {
+                FileItem::WithVisModifier(item) => self.collect_item(item.item.as_ref().unwrap()),
+                FileItem::WithoutModifier(item) => self.collect_item(item),
+            }
+        }
+    }
+
+    fn collect_item(&mut self, item: &Item) {
+        match item {
+            Item::Struct(s) => match s.kind.as_ref().unwrap() {
+                StructKind::Unit(_) => {}
+                StructKind::Tuple(fields) => self.collect_tuple_struct_fields(fields),
+                StructKind::Fields(fields) => self.collect_struct_fields(fields),
+            },
+            Item::Enum(e) => {
+                collect_delimited!(self, e.variants.as_ref().unwrap(), false, |variant| {
+                    match &variant.kind {
+                        Some(EnumVariantKind::Tuple(fields)) => {
+                            self.collect_tuple_struct_fields(fields)
+                        }
+                        Some(EnumVariantKind::Fields(fields)) => self.collect_struct_fields(fields),
+                        None => {}
+                    }
+                })
+            }
+            Item::Const(Const { typ, assign, .. }) | Item::Static(Static { typ, assign, .. }) => {
+                self.collect_type(typ.as_ref().unwrap().typ.as_ref().unwrap());
+                self.collect_let_assign(assign.as_ref().unwrap());
+            }
+            Item::Fn(f) => {
+                collect_delimited!(self, f.params_decl.as_ref().unwrap(), false, |param| {
+                    self.collect_type(param.typ.as_ref().unwrap().typ.as_ref().unwrap())
+                });
+
+                if let Some(return_type) = &f.return_type {
+                    self.collect_type(return_type.typ.as_ref().unwrap());
+                }
+
+                self.collect_lambda(f.body.as_ref().unwrap());
+            }
+        }
+    }
+
+    fn collect_tuple_struct_fields(&mut self, fields: &TupleStructFields) {
+        collect_delimited!(self, fields, false, |field| {
+            self.collect_type(field.typ.as_ref().unwrap())
+        });
+    }
+
+    fn collect_struct_fields(&mut self, fields: &StructFields) {
+        collect_delimited!(self, fields, false, |field| {
+            self.collect_type(field.typ.as_ref().unwrap().typ.as_ref().unwrap())
+        });
+    }
+
+    fn collect_type(&mut self, typ: &Type) {
+        match typ {
+            Type::Path(simple_path) => self.collect_simple_path(simple_path),
+            Type::Ptr(ptr_type) => {
+                self.role(ptr_type.star.span, Role::Prefix);
+                self.collect_type(ptr_type.typ.as_ref().unwrap());
+            }
+            Type::Ref(ref_type) => {
+                self.role(ref_type.hash.span, Role::Prefix);
+                self.collect_type(ref_type.typ.as_ref().unwrap());
+            }
+            Type::Slice(slice_type) => {
+                self.collect_type(slice_type.typ.as_ref().unwrap());
+                if let Some(array_size) = &slice_type.array_size {
+                    self.collect_expr(array_size.expr.as_ref().unwrap());
+                }
+            }
+            Type::Paren(paren_type) => {
+                collect_delimited!(self, &paren_type.tuple, true, |typ| self.collect_type(typ));
+
+                if let Some(lambda_type) = &paren_type.lambda {
+                    self.role(lambda_type.r_arrow.span, Role::Arrow);
+                    self.collect_type(lambda_type.typ.as_ref().unwrap());
+                }
+            }
+        }
+    }
+
+    fn collect_simple_path(&mut self, simple_path: &SimplePath) {
+        for inner in &simple_path.inners {
+            self.role(inner.double_colons.span, Role::DoubleColons);
+        }
+    }
+
+    fn collect_let_assign(&mut self, let_assign: &LetAssign) {
+        self.role(let_assign.equal.span, Role::BinOp);
+        self.collect_expr(let_assign.expr.as_ref().unwrap());
+    }
+
+    fn collect_binding(&mut self, binding: &Binding) {
+        self.collect_binding_kind(&binding.kind);
+
+        if let Some(typ) = &binding.typ {
+            self.collect_type(typ.typ.as_ref().unwrap());
+        }
+    }
+
+    fn collect_binding_kind(&mut self, binding_kind: &BindingKind) {
+        if let BindingKind::Destructed(destructed_tuple) = binding_kind {
+            collect_delimited!(self, destructed_tuple.as_ref(), true, |kind| {
+                self.collect_binding_kind(kind)
+            });
+        }
+    }
+
+    fn collect_lambda(&mut self, lambda: &LambdaExpr) {
+        match &lambda.lambda_arrow {
+            Some(LambdaArrow::NoParams(r_arrow)) => self.role(r_arrow.span, Role::Arrow),
+            Some(LambdaArrow::WithParams(params)) => {
+                self.collect_binding(&params.first);
+                for param in &params.rest {
+                    self.collect_binding(&param.item);
+                }
+                self.role(params.r_arrow.as_ref().unwrap().span, Role::Arrow);
+            }
+            None => {}
+        }
+
+        for stm in &lambda.stms {
+            self.collect_stm(stm.as_ref().unwrap());
+        }
+
+        if let Some(expr) = &lambda.last_expr {
+            self.collect_expr(expr);
+        }
+    }
+
+    fn collect_stm(&mut self, stm: &Stm) {
+        match stm {
+            Stm::Semicolon(_) => {}
+            Stm::Let(let_stm) => {
+                self.collect_binding(let_stm.binding.as_ref().unwrap());
+                if let Some(let_assign) = &let_stm.let_assign {
+                    self.collect_let_assign(let_assign);
+                }
+            }
+            Stm::While(while_stm) => self.collect_conditional_block(&while_stm.conditional_block),
+            Stm::DoWhile(do_while_stm) => {
+                self.collect_lambda(do_while_stm.block.as_ref().unwrap());
+                self.collect_expr(do_while_stm.condition.as_ref().unwrap());
+            }
+            Stm::If(if_expr) => self.collect_if_expr(if_expr),
+            Stm::When(when_expr) => self.collect_when_expr(when_expr),
+            Stm::Expr(expr_stm) => self.collect_expr(&expr_stm.expr),
+        }
+    }
+
+    fn collect_conditional_block(&mut self, conditional_block: &ConditionalBlock) {
+        self.collect_expr(conditional_block.condition.as_ref().unwrap());
+        self.collect_lambda(conditional_block.block.as_ref().unwrap());
+    }
+
+    fn collect_expr(&mut self, expr: &Expr) {
+        self.collect_primary_expr(&expr.left);
+
+        for bin_expr in &expr.bin {
+            self.role(bin_expr.op.span, Role::BinOp);
+            self.collect_primary_expr(bin_expr.right.as_ref().unwrap());
+        }
+    }
+
+    fn collect_primary_expr(&mut self, expr: &PrimaryExpr) {
+        match &expr.kind {
+            PrimaryExprKind::Unary(unary_expr) => {
+                self.role(unary_expr.first_op.span, Role::Prefix);
+                for op in &unary_expr.rest_ops {
+                    self.role(op.span, Role::Prefix);
+                }
+                self.collect_atomic_expr(unary_expr.expr.as_ref().unwrap());
+            }
+            PrimaryExprKind::Atomic(atomic_expr) => self.collect_atomic_expr(atomic_expr),
+        }
+
+        self.collect_post_ops(&expr.post_ops);
+
+        for inner_access in &expr.inner_access {
+            self.collect_post_ops(&inner_access.post_ops);
+        }
+    }
+
+    fn collect_post_ops(&mut self, post_ops: &[PostOpExpr]) {
+        for post_op in post_ops {
+            match post_op {
+                PostOpExpr::Invoke(paren_expr) => self.collect_paren_expr(paren_expr),
+                PostOpExpr::Lambda(lambda) => self.collect_lambda(lambda),
+                PostOpExpr::Index(idx_expr) => self.collect_expr(idx_expr.expr.as_ref().unwrap()),
+            }
+        }
+    }
+
+    fn collect_paren_expr(&mut self, paren_expr: &ParenExpr) {
+        collect_delimited!(self, paren_expr, true, |expr| self.collect_expr(expr));
+    }
+
+    fn collect_atomic_expr(&mut self, expr: &AtomicExpr) {
+        match expr {
+            AtomicExpr::Literal(_) | AtomicExpr::On(_) | AtomicExpr::Continue(_) => {}
+            AtomicExpr::Array(array_expr) => match &array_expr.expr_kind {
+                Some(ArrayExprKind::ExplicitSize(explicit_size)) => {
+                    self.collect_expr(explicit_size.repeated_expr.as_ref().unwrap());
+                    self.collect_expr(explicit_size.size_expr.as_ref().unwrap());
+                }
+                Some(ArrayExprKind::Elements(elements)) => {
+                    self.collect_expr(elements.first.as_ref().unwrap());
+                    for comma_with_expr in &elements.rest {
+                        self.collect_expr(&comma_with_expr.as_ref().unwrap().item);
+                    }
+
+                    self.lists.push(DelimitedList {
+                        close_delim: array_expr.close_bracket.as_ref().unwrap().span,
+                        trailing_comma: elements.trailing_comma.as_ref().map(|comma| comma.span),
+                        keep_trailing_comma: false,
+                    });
+                }
+                None => {}
+            },
+            AtomicExpr::Paren(paren_expr) => self.collect_paren_expr(paren_expr),
+            AtomicExpr::Struct(struct_expr) => {
+                self.role(struct_expr.dot.span, Role::Prefix);
+                self.collect_simple_path(struct_expr.path.as_ref().unwrap());
+
+                match &struct_expr.init {
+                    Some(StructInit::Tuple(paren_expr)) => self.collect_paren_expr(paren_expr),
+                    Some(StructInit::Fields(fields)) => {
+                        collect_delimited!(self, fields, false, |field| {
+                            if let Some(explicit) = &field.expr {
+                                self.role(explicit.equal.span, Role::BinOp);
+                                self.collect_expr(explicit.expr.as_ref().unwrap());
+                            }
+                        })
+                    }
+                    None => {}
+                }
+            }
+            AtomicExpr::Path(simple_path) => self.collect_simple_path(simple_path),
+            AtomicExpr::Lambda(lambda) => self.collect_lambda(lambda),
+            AtomicExpr::Break(BreakExpr { expr, .. })
+            | AtomicExpr::Return(ReturnExpr { expr, .. }) => {
+                if let Some(expr) = expr {
+                    self.collect_expr(expr);
+                }
+            }
+            AtomicExpr::If(if_expr) => self.collect_if_expr(if_expr),
+            AtomicExpr::When(when_expr) => self.collect_when_expr(when_expr),
+        }
+    }
+
+    fn collect_if_expr(&mut self, if_expr: &IfExpr) {
+        self.collect_conditional_block(&if_expr.conditional_block);
+
+        for else_if in &if_expr.else_ifs {
+            self.collect_conditional_block(&else_if.conditional_block);
+        }
+
+        if let Some(else_clause) = &if_expr.else_cluase {
+            self.collect_lambda(else_clause.block.as_ref().unwrap());
+        }
+    }
+
+    fn collect_when_expr(&mut self, when_expr: &WhenExpr) {
+        self.collect_expr(when_expr.expr.as_ref().unwrap());
+
+        collect_delimited!(self, when_expr.arms.as_ref().unwrap(), false, |arm| {
+            self.collect_pattern(&arm.pattern);
+            if let Some(guard) = &arm.guard {
+                self.collect_expr(guard.condition.as_ref().unwrap());
+            }
+            self.role(arm.r_arrow.as_ref().unwrap().span, Role::Arrow);
+            self.collect_expr(arm.expr.as_ref().unwrap());
+        });
+    }
+
+    fn collect_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Literal(literal_pattern) => {
+                if let Some(minus) = &literal_pattern.minus {
+                    self.role(minus.span, Role::Prefix);
+                }
+
+                if let Some(range_end) = &literal_pattern.range_end {
+                    self.role(range_end.op.span, Role::BinOp);
+                    if let Some(minus) = &range_end.minus {
+                        self.role(minus.span, Role::Prefix);
+                    }
+                }
+            }
+            Pattern::Tuple(tuple_pattern) => self.collect_tuple_pattern(tuple_pattern),
+            Pattern::Struct(struct_pattern) => {
+                self.role(struct_pattern.dot.span, Role::Prefix);
+                self.collect_simple_path(struct_pattern.path.as_ref().unwrap());
+
+                match &struct_pattern.fields {
+                    Some(StructPatternFields::Tuple(tuple_pattern)) => {
+                        self.collect_tuple_pattern(tuple_pattern)
+                    }
+                    Some(StructPatternFields::Fields(fields)) => {
+                        collect_delimited!(self, fields, false, |field| {
+                            if let Some(explicit) = &field.pattern {
+                                self.role(explicit.equal.span, Role::BinOp);
+                                self.collect_pattern(explicit.pattern.as_ref().unwrap());
+                            }
+                        })
+                    }
+                    None => {}
+                }
+            }
+            Pattern::Id(_) => {}
+        }
+    }
+
+    fn collect_tuple_pattern(&mut self, tuple_pattern: &TuplePattern) {
+        collect_delimited!(self, tuple_pattern, true, |pattern| {
+            self.collect_pattern(pattern)
+        });
+    }
+}
+
+/// A token which is written in the formatted file, the spaces and the line breaks are not written as tokens
+struct Element<'a> {
+    kind: &'a TokenKind,
+    /// The byte offset of the token in the file
+    start: u32,
+    text: &'a str,
+    role: Role,
+    /// It is not the first token of a terminal of more tokens like `<<=`, so nothing is written before it
+    glued: bool,
+    /// The count of the line breaks between it and the previous token in the file
+    line_breaks: usize,
+    /// Whether it is separated from the previous token by spaces or line breaks in the file
+    spaced: bool,
+    /// A comma is written after it as it is the last item of a list broken over lines without a trailing comma
+    comma_after: bool,
+    /// It is a trailing comma of a list written on one line
+    removed: bool,
+}
+
+impl<'a> Element<'a> {
+    fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::LineComment | TokenKind::DelimitedComment
+        )
+    }
+
+    fn is_symbol(&self, symbol: SymbolKind) -> bool {
+        matches!(self.kind, TokenKind::Symbol(kind) if *kind == symbol)
+    }
+
+    fn is_open_delim(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Symbol(
+                SymbolKind::OpenParenthesis
+                    | SymbolKind::OpenCurlyBrace
+                    | SymbolKind::OpenSquareBracket
+            )
+        )
+    }
+
+    fn is_close_delim(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Symbol(
+                SymbolKind::CloseParenthesis
+                    | SymbolKind::CloseCurlyBrace
+                    | SymbolKind::CloseSquareBracket
+            )
+        )
+    }
+
+    /// An identifier, a keyword or a literal which cannot be written right after another one
+    fn is_word(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Id(_) | TokenKind::Keyword(_) | TokenKind::Literal(_)
+        )
+    }
+
+    /// Whether a postfix `(` or `[` of a call or an index could be written right after it
+    fn ends_operand(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Id(_)
+                | TokenKind::Symbol(SymbolKind::CloseParenthesis | SymbolKind::CloseSquareBracket)
+        )
+    }
+}
+
+/// Whether a space is written between two tokens on the same line
+fn space_between(prev: &Element, next: &Element) -> bool {
+    if next.glued {
+        return false;
+    }
+
+    if prev.is_comment() || next.is_comment() {
+        return next.kind == &TokenKind::LineComment || next.spaced;
+    }
+
+    if prev.is_word() && next.is_word() {
+        return true;
+    }
+
+    match (prev.role, next.role) {
+        (Role::DoubleColons, _) | (_, Role::DoubleColons) => return false,
+        (Role::BinOp | Role::Arrow, _) | (_, Role::BinOp | Role::Arrow) => return true,
+        (Role::Prefix, _) => return false,
+        _ => {}
+    }
+
+    if prev.is_symbol(SymbolKind::OpenParenthesis)
+        || prev.is_symbol(SymbolKind::OpenSquareBracket)
+        || prev.is_symbol(SymbolKind::Dot) && prev.role == Role::Plain
+    {
+        return false;
+    }
+
+    if next.role == Role::Prefix {
+        return true;
+    }
+
+    match next.kind {
+        TokenKind::Symbol(
+            SymbolKind::Comma
+            | SymbolKind::Semicolon
+            | SymbolKind::Colon
+            | SymbolKind::Dot
+            | SymbolKind::CloseParenthesis
+            | SymbolKind::CloseSquareBracket,
+        ) => false,
+        TokenKind::Symbol(SymbolKind::OpenParenthesis | SymbolKind::OpenSquareBracket) => {
+            !prev.ends_operand()
+        }
+        TokenKind::Symbol(SymbolKind::CloseCurlyBrace) => {
+            !prev.is_symbol(SymbolKind::OpenCurlyBrace)
+        }
+        _ => true,
+    }
+}
+
+/// Whether the line continues the expression of the previous line, so it is indented one more level
+fn is_continuation(prev: &Element, next: &Element) -> bool {
+    prev.role == Role::BinOp
+        || next.role == Role::BinOp
+        || next.is_symbol(SymbolKind::Dot) && next.role == Role::Plain
+}
+
+/// Formats the file which is checked that it has no syntax errors, it returns `None` if the formatted file
+/// doesn't have the same tokens of the file (except the trailing commas) so it is not written
+pub(crate) fn format(file: &File, tokens: &[Token], file_content: &str) -> Option<String> {
+    let mut layout = Layout::default();
+    layout.collect_file(file);
+
+    let mut elements = vec![];
+    let mut line_breaks = 0;
+    let mut spaced = false;
+    let mut multi_token_end = None;
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Eof => break,
+            TokenKind::Space => spaced = true,
+            TokenKind::Eol => {
+                line_breaks += 1;
+                spaced = true;
+            }
+            _ => {
+                let start = token.span.lo;
+                let (role, glued) = match layout.roles.get(&start) {
+                    Some((role, end)) => {
+                        multi_token_end = Some((*role, *end));
+                        (*role, false)
+                    }
+                    None => match multi_token_end {
+                        Some((role, end)) if start < end => (role, true),
+                        _ => (Role::Plain, false),
+                    },
+                };
+
+                let text = &file_content[token.span.byte_range()];
+
+                elements.push(Element {
+                    kind: &token.kind,
+                    start,
+                    text: if token.kind == TokenKind::LineComment {
+                        text.trim_end()
+                    } else {
+                        text
+                    },
+                    role,
+                    glued,
+                    line_breaks,
+                    spaced,
+                    comma_after: false,
+                    removed: false,
+                });
+
+                line_breaks = 0;
+                spaced = false;
+            }
+        }
+    }
+
+    apply_trailing_commas_rules(&mut elements, &layout.lists);
+
+    let mut formatted = String::with_capacity(file_content.len());
+    let mut expected_tokens = vec![];
+    // The indentation of the lines of the open delimiters
+    let mut indents: Vec<usize> = vec![];
+    let mut line_indent = 0;
+    let mut prev: Option<&Element> = None;
+
+    for element in elements.iter().filter(|element| !element.removed) {
+        if let Some(prev) = prev {
+            if element.line_breaks > 0 {
+                let blank_line =
+                    element.line_breaks > 1 && !prev.is_open_delim() && !element.is_close_delim();
+
+                formatted.push_str(if blank_line { "\n\n" } else { "\n" });
+
+                line_indent = if element.is_close_delim() {
+                    indents.last().copied().unwrap_or(0)
+                } else {
+                    indents.last().map_or(0, |indent| indent + 1)
+                        + is_continuation(prev, element) as usize
+                };
+
+                formatted.push_str(&INDENT.repeat(line_indent));
+            } else if space_between(prev, element) {
+                formatted.push(' ');
+            }
+        }
+
+        formatted.push_str(element.text);
+
+        if !element.is_comment() {
+            expected_tokens.push(element.text);
+        }
+
+        if element.comma_after {
+            formatted.push('،');
+            expected_tokens.push("،");
+        }
+
+        if element.is_open_delim() {
+            indents.push(line_indent);
+        } else if element.is_close_delim() {
+            indents.pop();
+        }
+
+        prev = Some(element);
+    }
+
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+
+    has_tokens(&formatted, &expected_tokens).then_some(formatted)
+}
+
+/// Adds a trailing comma to each list whose close delimiter is on a line after its last item
+/// and removes it from the lists written on one line
+fn apply_trailing_commas_rules(elements: &mut [Element], lists: &[DelimitedList]) {
+    let indices = elements
+        .iter()
+        .enumerate()
+        .map(|(i, element)| (element.start, i))
+        .collect::<HashMap<_, _>>();
+
+    for list in lists {
+        let close_idx = indices[&list.close_delim.lo];
+        let trailing_comma_idx = list
+            .trailing_comma
+            .map(|trailing_comma| indices[&trailing_comma.lo]);
+
+        // The comments after the last item are skipped
+        let last_item_idx = (0..close_idx)
+            .rev()
+            .find(|i| !elements[*i].is_comment() && Some(*i) != trailing_comma_idx)
+            .unwrap();
+
+        let broken_over_lines = elements[last_item_idx + 1..=close_idx]
+            .iter()
+            .any(|element| element.line_breaks > 0);
+
+        match trailing_comma_idx {
+            None if broken_over_lines => elements[last_item_idx].comma_after = true,
+            Some(idx) if !broken_over_lines && !list.keep_trailing_comma => {
+                elements[idx].removed = true
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the formatted file has the expected tokens, it guards against writing a file changed by mistake
+fn has_tokens(formatted: &str, expected_tokens: &[&str]) -> bool {
+    let mut id_pool = DataPool::new();
+    let mut str_pool = DataPool::new();
+    let (tokens, lexer_errors) =
+        LexerIter::new(formatted, FileId::default(), &mut id_pool, &mut str_pool).collect_all();
+
+    if !lexer_errors.is_empty() {
+        return false;
+    }
+
+    let mut tokens = tokens.iter().filter(|token| {
+        !matches!(
+            token.kind,
+            TokenKind::Eof
+                | TokenKind::Eol
+                | TokenKind::Space
+                | TokenKind::LineComment
+                | TokenKind::DelimitedComment
+        )
+    });
+
+    expected_tokens.iter().all(|expected| {
+        tokens
+            .next()
+            .is_some_and(|token| &formatted[token.span.byte_range()] == *expected)
+    }) && tokens.next().is_none()
+}
@@ -1,13 +1,11 @@
 use ast_generator::lower_file;
 use error::*;
-use nazmc_diagnostics::{
-    eprint_diagnostics, fmt_diagnostics, span::SpanCursor, CodeWindow, Diagnostic,
-};
+use nazmc_diagnostics::{Applicability, CodeWindow, Diagnostic, Suggestion};
 use nazmc_lexer::*;
-use std::{io::Write, panic};
 use syntax::File;
 
 mod ast_generator;
+mod formatter;
 pub(crate) mod parse_methods;
 pub(crate) mod syntax;
 pub(crate) mod tokens_iter;
@@ -18,22 +16,20 @@ pub(crate) use parse_methods::*;
 pub(crate) use syntax::*;
 pub(crate) use tokens_iter::TokensIter;
 
+/// Parses the file and lowers it to the AST, the diagnostics refer to the file by the id in the spans of its tokens
 pub fn parse(
     tokens: Vec<Token>,
-    file_path: &str,
     file_content: &str,
-    file_lines: &[String],
     lexer_errors: Vec<LexerError>,
-) -> Result<nazmc_ast::File, String> {
+) -> Result<nazmc_ast::File, Vec<Diagnostic>> {
     let mut reporter = ParseErrorsReporter {
-        file_path,
-        file_lines: &file_lines,
         file_content,
         tokens: &tokens,
+        lexer_errors: &lexer_errors,
         diagnostics: vec![],
     };
 
-    reporter.report_lexer_errors(&lexer_errors);
+    reporter.report_lexer_errors();
 
     let mut tokens_iter = TokensIter::new(&tokens);
 
@@ -46,27 +42,85 @@ pub fn parse(
     if reporter.diagnostics.is_empty() {
         Ok(lower_file(file))
     } else {
-        Err(fmt_diagnostics(reporter.diagnostics))
+        Err(reporter.diagnostics)
+    }
+}
+
+/// Formats the file canonically, the diagnostics are returned like `parse` if it has syntax errors
+pub fn format_file(
+    tokens: &[Token],
+    file_path: &str,
+    file_content: &str,
+    lexer_errors: Vec<LexerError>,
+) -> Result<String, Vec<Diagnostic>> {
+    let mut reporter = ParseErrorsReporter {
+        file_content,
+        tokens,
+        lexer_errors: &lexer_errors,
+        diagnostics: vec![],
+    };
+
+    reporter.report_lexer_errors();
+
+    let mut tokens_iter = TokensIter::new(tokens);
+
+    tokens_iter.next_non_space_or_comment(); // To init recent()
+
+    let file = ParseResult::<File>::parse(&mut tokens_iter).unwrap();
+
+    reporter.check_file(&file);
+
+    if !reporter.diagnostics.is_empty() {
+        return Err(reporter.diagnostics);
+    }
+
+    formatter::format(&file, tokens, file_content).ok_or_else(|| {
+        vec![Diagnostic::error(
+            format!("تعذر تنسيق الملف `{file_path}` دون تغيير رموزه"),
+            vec![],
+        )]
+    })
+}
+
+/// Reports the lexer errors only, it is used when the tokens are needed without parsing them
+pub fn check_lexer_errors(
+    tokens: &[Token],
+    file_content: &str,
+    lexer_errors: Vec<LexerError>,
+) -> Result<(), Vec<Diagnostic>> {
+    let mut reporter = ParseErrorsReporter {
+        file_content,
+        tokens,
+        lexer_errors: &lexer_errors,
+        diagnostics: vec![],
+    };
+
+    reporter.report_lexer_errors();
+
+    if reporter.diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(reporter.diagnostics)
     }
 }
 
 struct ParseErrorsReporter<'a> {
     tokens: &'a [Token],
-    file_path: &'a str,
-    file_lines: &'a [String],
     file_content: &'a str,
-    diagnostics: Vec<Diagnostic<'a>>,
+    lexer_errors: &'a [LexerError],
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> ParseErrorsReporter<'a> {
     fn report(
         &mut self,
+        code: usize,
         msg: String,
         span: Span,
         primary_label: String,
         secondary_labels: Vec<(Span, Vec<String>)>,
     ) {
-        let mut code_window = CodeWindow::new(self.file_path, &self.file_lines, span.start);
+        let mut code_window = CodeWindow::new(span);
 
         code_window.mark_error(span, vec![primary_label]);
 
@@ -74,25 +128,48 @@ impl<'a> ParseErrorsReporter<'a> {
             code_window.mark_secondary(span, multiline_label);
         }
 
-        let diagnostic = Diagnostic::error(msg, vec![code_window]);
+        let diagnostic = Diagnostic::error_with_code(code, msg, vec![code_window]);
 
         self.diagnostics.push(diagnostic);
     }
 
-    fn report_lexer_errors(&mut self, lexer_errors: &[LexerError]) {
-        for err in lexer_errors {
+    /// Adds a suggestion to replace the span with the replacement to the last reported diagnostic
+    fn suggest(
+        &mut self,
+        msg: String,
+        span: Span,
+        replacement: String,
+        applicability: Applicability,
+    ) {
+        let suggestion = Suggestion::new(msg, span, replacement, applicability);
+
+        self.diagnostics.last_mut().unwrap().suggest(suggestion);
+    }
+
+    /// Returns the byte offset of the column in the line of the byte offset, the lexer errors are
+    /// located by the columns in the lines of their tokens
+    fn offset_of_col(&self, offset: u32, col: usize) -> u32 {
+        let line_start = self.file_content[..offset as usize]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let line = self.file_content[line_start..].split('\n').next().unwrap();
+        let line_offset = match line.char_indices().nth(col) {
+            Some((i, _)) => i,
+            None => line.len() + col - line.chars().count(),
+        };
+
+        (line_start + line_offset) as u32
+    }
+
+    fn report_lexer_errors(&mut self) {
+        for err in self.lexer_errors {
             let token_span = self.tokens[err.token_idx].span;
 
             let err_span = if err.len > 0 {
                 Span {
-                    start: SpanCursor {
-                        line: token_span.start.line,
-                        col: err.col,
-                    },
-                    end: SpanCursor {
-                        line: token_span.end.line,
-                        col: err.col + err.len,
-                    },
+                    file_id: token_span.file_id,
+                    lo: self.offset_of_col(token_span.lo, err.col),
+                    hi: self.offset_of_col(token_span.hi, err.col + err.len),
                 }
             } else {
                 token_span
@@ -101,6 +178,7 @@ impl<'a> ParseErrorsReporter<'a> {
             match &err.kind {
                 LexerErrorKind::UnknownToken => {
                     self.report(
+                        err.kind.code(),
                         "رمز غير مدعوم".to_string(),
                         err_span,
                         "".to_string(),
@@ -109,63 +187,70 @@ impl<'a> ParseErrorsReporter<'a> {
                 }
                 LexerErrorKind::UnclosedStr => {
                     self.report(
+                        err.kind.code(),
                         "علامة تنصيص مفقودة".to_string(),
                         err_span,
                         "قٌم بإضافة `\"`".to_string(),
                         vec![(
-                            Span {
-                                start: token_span.start,
-                                end: SpanCursor {
-                                    line: token_span.start.line,
-                                    col: token_span.start.col + 1,
-                                },
-                            },
+                            Span::new(token_span.file_id, token_span.lo, token_span.lo + 1),
                             vec!["لم يتم إغلاق علامة التنصيص هذه".to_string()],
                         )],
                     );
+                    self.suggest(
+                        "أضف علامة التنصيص في نهاية النص".to_string(),
+                        Span::new(err_span.file_id, err_span.lo, err_span.lo),
+                        "\"".to_string(),
+                        Applicability::MachineApplicable,
+                    );
                 }
                 LexerErrorKind::UnclosedChar => {
                     self.report(
+                        err.kind.code(),
                         "علامة تنصيص مفقودة".to_string(),
                         err_span,
                         "قٌم بإضافة `\'`".to_string(),
                         vec![(
-                            Span {
-                                start: token_span.start,
-                                end: SpanCursor {
-                                    line: token_span.start.line,
-                                    col: token_span.start.col + 1,
-                                },
-                            },
+                            Span::new(token_span.file_id, token_span.lo, token_span.lo + 1),
                             vec!["لم يتم إغلاق علامة التنصيص هذه".to_string()],
                         )],
                     );
+                    self.suggest(
+                        "أضف علامة التنصيص في نهاية الحرف".to_string(),
+                        Span::new(err_span.file_id, err_span.lo, err_span.lo),
+                        "'".to_string(),
+                        Applicability::MaybeIncorrect,
+                    );
                 }
                 LexerErrorKind::UnclosedDelimitedComment => self.report(
+                    err.kind.code(),
                     "لم يتم إغلاق التعليق".to_string(),
                     err_span,
                     "تم بدء التعليق هنا".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::ZeroChars => self.report(
+                    err.kind.code(),
                     "لا يوجد حروف ولكن يُتوقع حرف واحد بين علامتي التنصيص".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::ManyChars => self.report(
+                    err.kind.code(),
                     "يوجد أكثر من حرف ولكن يُتوقع حرف واحد بين علامتي التنصيص".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::KufrOrInvalidChar => self.report(
+                    err.kind.code(),
                     "يحتوي على رمز كُفر أو رمز غير مدعوم".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::UnicodeCodePointHexDigitOnly => self.report(
+                    err.kind.code(),
                     "رمز اليونيكود يجب أن يحتوي فقط على أرقام بالنظام العددي السُداسي عشر"
                         .to_string(),
                     err_span,
@@ -173,30 +258,35 @@ impl<'a> ParseErrorsReporter<'a> {
                     vec![],
                 ),
                 LexerErrorKind::InvalidUnicodeCodePoint => self.report(
+                    err.kind.code(),
                     "رمز يونيكود غير صالح".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::UnknownEscapeSequence => self.report(
+                    err.kind.code(),
                     "حرف تسلسل غير صالح".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::MissingDigitsAfterBasePrefix => self.report(
+                    err.kind.code(),
                     "يُتوقع أرقام بعد رمز النظام العددي".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::MissingDigitsAfterExponent => self.report(
+                    err.kind.code(),
                     "يُتوقع أرقام الأس".to_string(),
                     err_span,
                     "".to_string(),
                     vec![],
                 ),
                 LexerErrorKind::InvalidIntBasePrefix => self.report(
+                    err.kind.code(),
                     "نظام عددي غير مدعوم".to_string(),
                     err_span,
                     "".to_string(),
@@ -204,6 +294,7 @@ impl<'a> ParseErrorsReporter<'a> {
                 ),
                 LexerErrorKind::InvalidNumSuffix => {
                     self.report(
+                        err.kind.code(),
                         "لاحقة غير صحيحة للعدد".to_string(),
                         err_span,
                         "".to_string(),
@@ -218,6 +309,7 @@ impl<'a> ParseErrorsReporter<'a> {
                 }
                 LexerErrorKind::InvalidFloatSuffix => {
                     self.report(
+                        err.kind.code(),
                         "لاحقة غير صحيحة للعدد العشري".to_string(),
                         err_span,
                         "".to_string(),
@@ -231,6 +323,7 @@ impl<'a> ParseErrorsReporter<'a> {
                 }
                 LexerErrorKind::InvalidIntSuffix => {
                     self.report(
+                        err.kind.code(),
                         "لاحقة غير صالحة للعدد الصحيح".to_string(),
                         err_span,
                         "".to_string(),
@@ -251,6 +344,7 @@ impl<'a> ParseErrorsReporter<'a> {
                         crate::Base::Hex => "السُداسي عشر",
                     };
                     self.report(
+                        err.kind.code(),
                         format!("رقم غير صالح في النظام العددي {}", base_str),
                         err_span,
                         "".to_string(),
@@ -259,6 +353,7 @@ impl<'a> ParseErrorsReporter<'a> {
                 }
                 LexerErrorKind::NumIsOutOfRange(num_kind) => {
                     self.report(
+                        err.kind.code(),
                         "قيمة العدد خارج النطاق المسموح به".to_string(),
                         err_span,
                         "".to_string(),
@@ -297,7 +392,46 @@ impl<'a> ParseErrorsReporter<'a> {
                             "قُم بإزالة هذه الفاصلة",
                         )
                     };
-                    self.report(msg.to_string(), err_span, label.to_string(), vec![]);
+                    self.report(
+                        err.kind.code(),
+                        msg.to_string(),
+                        err_span,
+                        label.to_string(),
+                        vec![],
+                    );
+                    self.suggest(
+                        label.to_string(),
+                        err_span,
+                        String::new(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                LexerErrorKind::MixedDigitScripts => self.report(
+                    err.kind.code(),
+                    "لا يمكن الخلط بين الأرقام `0-9` والأرقام `٠-٩` في نفس العدد".to_string(),
+                    err_span,
+                    "هذا الرقم مختلف عن أول رقم في العدد".to_string(),
+                    vec![],
+                ),
+                LexerErrorKind::LatinPunctuation(ch) => {
+                    let (arabic_ch, name) = match ch {
+                        ',' => ('،', "الفاصلة العربية"),
+                        ';' => ('؛', "الفاصلة المنقوطة العربية"),
+                        _ => ('؟', "علامة الاستفهام العربية"),
+                    };
+                    self.report(
+                        err.kind.code(),
+                        format!("استخدم {} `{}` بدلا من `{}`", name, arabic_ch, ch),
+                        err_span,
+                        "رمز لاتيني".to_string(),
+                        vec![],
+                    );
+                    self.suggest(
+                        format!("استبدل `{}` بـ`{}`", ch, arabic_ch),
+                        err_span,
+                        arabic_ch.to_string(),
+                        Applicability::MachineApplicable,
+                    );
                 }
             };
         }
@@ -308,13 +442,23 @@ impl<'a> ParseErrorsReporter<'a> {
         expected: &str,
         err: &ParseErr,
         secondary_labels: Vec<(Span, Vec<String>)>,
+    ) {
+        self.report_expected_with_code(200, expected, err, secondary_labels);
+    }
+
+    fn report_expected_with_code(
+        &mut self,
+        code: usize,
+        expected: &str,
+        err: &ParseErr,
+        secondary_labels: Vec<(Span, Vec<String>)>,
     ) {
         let (found_token_span, found_token_val, primary_label) =
             if err.found_token_index < self.tokens.len() {
                 let token = &self.tokens[err.found_token_index];
                 (
                     token.span,
-                    &self.file_content[token.start_byte..token.end_byte],
+                    &self.file_content[token.span.byte_range()],
                     "رمز غير متوقع".to_string(),
                 )
             } else {
@@ -331,7 +475,7 @@ impl<'a> ParseErrorsReporter<'a> {
             expected, found_token_val
         );
 
-        self.report(msg, found_token_span, primary_label, secondary_labels);
+        self.report(code, msg, found_token_span, primary_label, secondary_labels);
     }
 
     fn report_expected_comma_or_item(
@@ -368,17 +512,18 @@ impl<'a> ParseErrorsReporter<'a> {
             }
         };
 
-        let expected = if comma_err.found_token_index > err.found_token_index {
-            expected
+        let (code, expected) = if comma_err.found_token_index > err.found_token_index {
+            (200, expected)
         } else {
-            "فاصلة `،`"
+            (201, "فاصلة `،`")
         };
 
-        self.report_expected(expected, &comma_err, secondary_labels);
+        self.report_expected_with_code(code, expected, &comma_err, secondary_labels);
     }
 
     fn report_unclosed_delimiter(&mut self, open_delim_span: Span) {
         self.report(
+            203,
             "لم يتم إغلاق القوس".to_string(),
             open_delim_span,
             "يجب إغلاق هذا القوس".to_string(),
@@ -406,6 +551,7 @@ impl<'a> ParseErrorsReporter<'a> {
                 },
                 Err(_) => {
                     self.report(
+                        206,
                         "يُتوقع `::` ثم اسم عنصر أو `*` بعد الحزمة".to_string(),
                         if let Ok(t) = &import.top {
                             t.span
@@ -425,6 +571,7 @@ impl<'a> ParseErrorsReporter<'a> {
                         if let PathSegInImportStm::Star(s) = a {
                             if let Some(span) = last_star_symbol_span {
                                 self.report(
+                                    207,
                                     "الرمز `*` يجب أن يكون في آخر المسار".to_string(),
                                     span,
                                     "".to_string(),
@@ -437,6 +584,7 @@ impl<'a> ParseErrorsReporter<'a> {
                     }
                     Err(_) => {
                         self.report(
+                            206,
                             "يُتوقع `::` ثم اسم عنصر أو `*` بعد الحزمة".to_string(),
                             if let Ok(t) = &import.top {
                                 t.span
@@ -456,7 +604,7 @@ impl<'a> ParseErrorsReporter<'a> {
     }
 
     fn check_file_items(&mut self, items: &[ParseResult<FileItem>]) {
-        let expected = "عنصر ملف (دالة أو هيكل)";
+        let expected = "عنصر ملف (دالة أو هيكل أو تصنيف أو ثابت أو متغير مشترك)";
         for item in items {
             let node = match item {
                 Ok(node) => node,
@@ -471,7 +619,8 @@ impl<'a> ParseErrorsReporter<'a> {
                     Ok(item) => item,
                     Err(_) => {
                         self.report(
-                            "يُتوقع عنصر ملف (دالة أو هيكل) بعد مُعامِل الوصول".to_string(),
+                            208,
+                            "يُتوقع عنصر ملف (دالة أو هيكل أو تصنيف أو ثابت أو متغير مشترك) بعد مُعامِل الوصول".to_string(),
                             visibility.span,
                             "مُعامِل الوصول".to_string(),
                             vec![],
@@ -484,6 +633,35 @@ impl<'a> ParseErrorsReporter<'a> {
 
             match item {
                 Item::Struct(s) => self.check_struct(s),
+                Item::Enum(e) => self.check_enum(e),
+                Item::Const(Const {
+                    const_keyword,
+                    name,
+                    typ,
+                    assign,
+                    semicolon,
+                }) => self.check_const(
+                    ("الثابت", "للثابت"),
+                    const_keyword.span,
+                    name,
+                    typ,
+                    assign,
+                    semicolon,
+                ),
+                Item::Static(Static {
+                    static_keyword,
+                    name,
+                    typ,
+                    assign,
+                    semicolon,
+                }) => self.check_const(
+                    ("المتغير المشترك", "للمتغير المشترك"),
+                    static_keyword.span,
+                    name,
+                    typ,
+                    assign,
+                    semicolon,
+                ),
                 Item::Fn(f) => self.check_fn(f),
             }
         }
@@ -501,6 +679,7 @@ impl<'a> ParseErrorsReporter<'a> {
 
         if missing_name && missing_decl {
             self.report(
+                204,
                 "لم يتم تعريف الهيكل".to_string(),
                 struct_keyword.span,
                 "".to_string(),
@@ -511,6 +690,7 @@ impl<'a> ParseErrorsReporter<'a> {
 
         if missing_name {
             self.report(
+                205,
                 "يجب إعطاء اسم للهيكل".to_string(),
                 struct_keyword.span,
                 "".to_string(),
@@ -528,79 +708,239 @@ impl<'a> ParseErrorsReporter<'a> {
 
         match kind {
             StructKind::Unit(_) => {}
-            StructKind::Tuple(TupleStructFields {
-                open_delim,
-                items,
-                close_delim,
-            }) => {
-                if let Some(PunctuatedTupleStructField {
-                    first_item,
-                    rest_items,
-                    trailing_comma: _,
-                }) = items
-                {
-                    match first_item {
-                        Ok(TupleStructField { visibility: _, typ }) => self.check_type_result(typ),
-                        Err(err) => {
-                            self.report_expected("نوع", err, vec![]);
-                        }
+            StructKind::Tuple(fields) => self.check_tuple_struct_fields(fields, false),
+            StructKind::Fields(fields) => self.check_struct_fields(fields, false),
+        }
+    }
+
+    fn check_enum(&mut self, e: &Enum) {
+        let Enum {
+            enum_keyword,
+            name,
+            variants,
+        } = e;
+
+        if name.is_err() && variants.is_err() {
+            self.report(
+                204,
+                "لم يتم تعريف التصنيف".to_string(),
+                enum_keyword.span,
+                "".to_string(),
+                vec![],
+            );
+            return;
+        }
+
+        if name.is_err() {
+            self.report(
+                205,
+                "يجب إعطاء اسم للتصنيف".to_string(),
+                enum_keyword.span,
+                "".to_string(),
+                vec![],
+            );
+        }
+
+        let EnumVariants {
+            open_delim,
+            items,
+            close_delim,
+        } = match variants {
+            Ok(variants) => variants,
+            Err(err) => {
+                self.report_expected("بعد التصنيف `{`", err, vec![]);
+                return;
+            }
+        };
+
+        if let Some(PunctuatedEnumVariant {
+            first_item,
+            rest_items,
+            trailing_comma: _,
+        }) = items
+        {
+            match first_item {
+                Ok(variant) => self.check_enum_variant(variant),
+                Err(err) => self.report_expected("حالة من حالات التصنيف", err, vec![]),
+            }
+
+            for variant in rest_items {
+                match variant {
+                    Ok(CommaWithEnumVariant { comma: _, item }) => self.check_enum_variant(item),
+                    Err(err) => {
+                        self.report_expected_comma_or_item("حالة من حالات التصنيف", err, vec![])
                     }
+                }
+            }
+        }
 
-                    for field in rest_items {
-                        match field {
-                            Ok(CommaWithTupleStructField {
-                                comma: _,
-                                item: TupleStructField { visibility: _, typ },
-                            }) => self.check_type_result(typ),
-                            Err(err) => {
-                                self.report_expected_comma_or_item("نوع", err, vec![]);
-                            }
-                        }
+        if close_delim.is_err() {
+            self.report_unclosed_delimiter(open_delim.span);
+        }
+    }
+
+    fn check_enum_variant(&mut self, variant: &EnumVariant) {
+        match &variant.kind {
+            None => {}
+            Some(EnumVariantKind::Tuple(fields)) => self.check_tuple_struct_fields(fields, true),
+            Some(EnumVariantKind::Fields(fields)) => self.check_struct_fields(fields, true),
+        }
+    }
+
+    /// The fields of the enums variants are accessible wherever their enums are,
+    /// so they cannot have visibility modifiers
+    fn check_field_visibility(&mut self, visibility: &Option<VisModifier>, in_variant: bool) {
+        if let (true, Some(visibility)) = (in_variant, visibility) {
+            self.report(
+                209,
+                "لا يمكن استخدام مُعامِل الوصول مع حقول حالات التصنيف".to_string(),
+                visibility.span,
+                "مُعامِل الوصول".to_string(),
+                vec![],
+            );
+        }
+    }
+
+    fn check_tuple_struct_fields(&mut self, fields: &TupleStructFields, in_variant: bool) {
+        let TupleStructFields {
+            open_delim,
+            items,
+            close_delim,
+        } = fields;
+
+        if let Some(PunctuatedTupleStructField {
+            first_item,
+            rest_items,
+            trailing_comma: _,
+        }) = items
+        {
+            match first_item {
+                Ok(TupleStructField { visibility, typ }) => {
+                    self.check_field_visibility(visibility, in_variant);
+                    self.check_type_result(typ);
+                }
+                Err(err) => {
+                    self.report_expected("نوع", err, vec![]);
+                }
+            }
+
+            for field in rest_items {
+                match field {
+                    Ok(CommaWithTupleStructField {
+                        comma: _,
+                        item: TupleStructField { visibility, typ },
+                    }) => {
+                        self.check_field_visibility(visibility, in_variant);
+                        self.check_type_result(typ);
+                    }
+                    Err(err) => {
+                        self.report_expected_comma_or_item("نوع", err, vec![]);
                     }
                 }
-                if close_delim.is_err() {
-                    self.report_unclosed_delimiter(open_delim.span);
+            }
+        }
+        if close_delim.is_err() {
+            self.report_unclosed_delimiter(open_delim.span);
+        }
+    }
+
+    fn check_struct_fields(&mut self, fields: &StructFields, in_variant: bool) {
+        let StructFields {
+            open_delim,
+            items,
+            close_delim,
+        } = fields;
+
+        if let Some(PunctuatedStructField {
+            first_item,
+            rest_items,
+            trailing_comma: _,
+        }) = &items
+        {
+            match first_item {
+                Ok(StructField {
+                    visibility, typ, ..
+                }) => {
+                    self.check_field_visibility(visibility, in_variant);
+                    match typ {
+                        Ok(ColonWithType { colon: _, typ }) => self.check_type_result(typ),
+                        Err(err) => self.report_expected("`:` ثم نوع الحقل", err, vec![]),
+                    }
+                }
+                Err(err) => {
+                    self.report_expected("حقل", err, vec![]);
                 }
             }
-            StructKind::Fields(StructFields {
-                open_delim,
-                items,
-                close_delim,
-            }) => {
-                if let Some(PunctuatedStructField {
-                    first_item,
-                    rest_items,
-                    trailing_comma: _,
-                }) = &items
-                {
-                    match first_item {
-                        Ok(StructField { typ, .. }) => match typ {
+
+            for field in rest_items {
+                match field {
+                    Ok(CommaWithStructField {
+                        item: StructField {
+                            visibility, typ, ..
+                        },
+                        ..
+                    }) => {
+                        self.check_field_visibility(visibility, in_variant);
+                        match typ {
                             Ok(ColonWithType { colon: _, typ }) => self.check_type_result(typ),
                             Err(err) => self.report_expected("`:` ثم نوع الحقل", err, vec![]),
-                        },
-                        Err(err) => {
-                            self.report_expected("حقل", err, vec![]);
-                        }
-                    }
-
-                    for field in rest_items {
-                        match field {
-                            Ok(CommaWithStructField {
-                                item: StructField { typ, .. },
-                                ..
-                            }) => match typ {
-                                Ok(ColonWithType { colon: _, typ }) => self.check_type_result(typ),
-                                Err(err) => self.report_expected("`:` ثم نوع الحقل", err, vec![]),
-                            },
-                            Err(err) => self.report_expected_comma_or_item("حقل", err, vec![]),
                         }
                     }
+                    Err(err) => self.report_expected_comma_or_item("حقل", err, vec![]),
                 }
+            }
+        }
 
-                if close_delim.is_err() {
-                    self.report_unclosed_delimiter(open_delim.span);
+        if close_delim.is_err() {
+            self.report_unclosed_delimiter(open_delim.span);
+        }
+    }
+
+    /// Checks the declaration of a constant or a static, `item` is the name of its kind
+    /// and the same name prefixed by `ل`
+    fn check_const(
+        &mut self,
+        item: (&str, &str),
+        keyword_span: Span,
+        name: &ParseResult<Id>,
+        typ: &ParseResult<ColonWithType>,
+        assign: &ParseResult<LetAssign>,
+        semicolon: &ParseResult<SemicolonSymbol>,
+    ) {
+        if name.is_err() && typ.is_err() && assign.is_err() {
+            self.report(
+                204,
+                format!("لم يتم تعريف {}", item.0),
+                keyword_span,
+                "".to_string(),
+                vec![],
+            );
+            return;
+        }
+
+        if name.is_err() {
+            self.report(
+                205,
+                format!("يجب إعطاء اسم {}", item.1),
+                keyword_span,
+                "".to_string(),
+                vec![],
+            );
+        }
+
+        match typ {
+            Ok(ColonWithType { colon: _, typ }) => self.check_type_result(typ),
+            Err(err) => self.report_expected(&format!("نوع {} بعد `:`", item.0), err, vec![]),
+        }
+
+        match assign {
+            Ok(LetAssign { equal: _, expr }) => {
+                self.check_expr_result(expr);
+                if expr.is_ok() {
+                    self.check_semicolon_result(semicolon);
                 }
             }
+            Err(err) => self.report_expected(&format!("قيمة {} بعد `=`", item.0), err, vec![]),
         }
     }
 
@@ -620,6 +960,7 @@ impl<'a> ParseErrorsReporter<'a> {
 
         if missing_name && missing_params && no_return_type && missing_body {
             self.report(
+                204,
                 "لم يتم تعريف الدالة".to_string(),
                 fn_keyword.span,
                 "".to_string(),
@@ -630,6 +971,7 @@ impl<'a> ParseErrorsReporter<'a> {
 
         if missing_name {
             self.report(
+                205,
                 "يجب إعطاء اسم للدالة".to_string(),
                 fn_keyword.span,
                 "".to_string(),
@@ -702,43 +1044,10 @@ impl<'a> ParseErrorsReporter<'a> {
                     return;
                 };
 
-                let span = match lambda_arrow {
-                    LambdaArrow::NoParams(r_arrow) => r_arrow.span,
-                    LambdaArrow::WithParams(LambdaParams {
-                        first,
-                        rest,
-                        trailing_comma,
-                        r_arrow,
-                    }) => {
-                        let first_span = match &first.kind {
-                            BindingKind::Id(terminal) => terminal.span,
-                            BindingKind::Destructed(destructed_tuple) => {
-                                destructed_tuple.open_delim.span
-                            }
-                        };
-
-                        let start = first_span.start;
-
-                        let end = if let Ok(Terminal { span, .. }) = r_arrow {
-                            span.end
-                        } else if let Some(Terminal { span, .. }) = trailing_comma {
-                            span.end
-                        } else if !rest.is_empty() {
-                            match &rest[rest.len() - 1].item.kind {
-                                BindingKind::Id(terminal) => terminal.span.end,
-                                BindingKind::Destructed(destructed_tuple) => {
-                                    destructed_tuple.open_delim.span.end
-                                }
-                            }
-                        } else {
-                            first_span.end
-                        };
-
-                        Span { start, end }
-                    }
-                };
+                let span = lambda_arrow_span(lambda_arrow);
 
                 self.report(
+                    210,
                     "يُتوقع محتوى الدالة وليس مُعاملات لامدا".to_string(),
                     span,
                     "قٌم بإزالة هذا".to_string(),
@@ -899,6 +1208,47 @@ impl<'a> ParseErrorsReporter<'a> {
                             Err(err) => self.report_expected("محتوى `طالما`", err, vec![]),
                         }
                     }
+                    Stm::DoWhile(DoWhileStm {
+                        do_keyword: _,
+                        block,
+                        while_keyword,
+                        condition,
+                        semicolon,
+                    }) => {
+                        match block {
+                            Ok(block) => {
+                                if let Some(lambda_arrow) = &block.lambda_arrow {
+                                    self.report(
+                                        210,
+                                        "يُتوقع محتوى `افعل` وليس مُعاملات لامدا".to_string(),
+                                        lambda_arrow_span(lambda_arrow),
+                                        "قٌم بإزالة هذا".to_string(),
+                                        vec![],
+                                    );
+                                }
+                                self.check_block(block);
+                            }
+                            Err(err) => {
+                                self.report_expected("محتوى `افعل`", err, vec![]);
+                                continue;
+                            }
+                        }
+
+                        if let Err(err) = while_keyword {
+                            self.report_expected("`طالما` بعد محتوى `افعل`", err, vec![]);
+                            continue;
+                        }
+
+                        match condition {
+                            Ok(expr) => {
+                                self.check_expr(expr);
+                                self.check_semicolon_result(semicolon);
+                            }
+                            Err(err) => {
+                                self.report_expected("تعبير برمجي (شرط `طالما`)", err, vec![])
+                            }
+                        }
+                    }
                     Stm::If(if_expr) => self.check_if_expr(if_expr),
                     Stm::When(when_expr) => self.check_when_expr(when_expr),
                     Stm::Expr(ExprStm { expr, semicolon }) => {
@@ -935,7 +1285,7 @@ impl<'a> ParseErrorsReporter<'a> {
                     let token = &self.tokens[err.found_token_index];
                     (
                         token.span,
-                        &self.file_content[token.start_byte..token.end_byte],
+                        &self.file_content[token.span.byte_range()],
                         "رمز غير متوقع".to_string(),
                     )
                 } else {
@@ -953,11 +1303,25 @@ impl<'a> ParseErrorsReporter<'a> {
             );
 
             self.report(
+                202,
                 msg,
                 Span::len_after(&self.tokens[i].span, 1),
                 "قُم هنا بإضافة فاصلة منقوطة `؛`".to_string(),
                 vec![(found_token_span, vec![secondary_label.to_string()])],
             );
+            // The end of an invalid token may not be where the user intended it to be, e.g. an unclosed char
+            let applicability = if self.lexer_errors.iter().any(|err| err.token_idx == i) {
+                Applicability::MaybeIncorrect
+            } else {
+                Applicability::MachineApplicable
+            };
+
+            self.suggest(
+                "أضف فاصلة منقوطة `؛`".to_string(),
+                Span::after(&self.tokens[i].span),
+                "؛".to_string(),
+                applicability,
+            );
         }
     }
 
@@ -1169,9 +1533,182 @@ impl<'a> ParseErrorsReporter<'a> {
         }
     }
 
-    fn check_when_expr(&mut self, _when_expr: &WhenExpr) {
-        // TODO
-        todo!()
+    fn check_when_expr(&mut self, when_expr: &WhenExpr) {
+        match &when_expr.expr {
+            Ok(expr) => self.check_expr(expr),
+            Err(err) => self.report_expected("تعبير برمجي (قيمة `عندما`)", err, vec![]),
+        }
+
+        let arms = match &when_expr.arms {
+            Ok(arms) => arms,
+            Err(err) => {
+                self.report_expected("حالات `عندما`", err, vec![]);
+                return;
+            }
+        };
+
+        if let Some(PunctuatedWhenArm {
+            first_item,
+            rest_items,
+            trailing_comma: _,
+        }) = &arms.items
+        {
+            match first_item {
+                Ok(arm) => self.check_when_arm(arm),
+                Err(err) => self.report_expected("حالة", err, vec![]),
+            }
+
+            for result in rest_items {
+                match result {
+                    Ok(CommaWithWhenArm { comma: _, item }) => self.check_when_arm(item),
+                    Err(err) => self.report_expected_comma_or_item("حالة", err, vec![]),
+                }
+            }
+        }
+
+        if arms.close_delim.is_err() {
+            self.report_unclosed_delimiter(arms.open_delim.span);
+        }
+    }
+
+    fn check_when_arm(&mut self, arm: &WhenArm) {
+        self.check_pattern(&arm.pattern);
+
+        if let Some(WhenArmGuard {
+            if_keyword: _,
+            condition,
+        }) = &arm.guard
+        {
+            match condition {
+                Ok(expr) => self.check_expr(expr),
+                Err(err) => self.report_expected("تعبير برمجي (شرط الحالة)", err, vec![]),
+            }
+        }
+
+        if let Err(err) = &arm.r_arrow {
+            self.report_expected("`->`", err, vec![]);
+            return;
+        }
+
+        self.check_expr_result(&arm.expr);
+    }
+
+    fn check_pattern_result(&mut self, pattern: &ParseResult<Pattern>) {
+        match pattern {
+            Ok(pattern) => self.check_pattern(pattern),
+            Err(err) => self.report_expected("نمط", err, vec![]),
+        }
+    }
+
+    fn check_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Literal(LiteralPattern {
+                range_end: Some(RangePatternEnd { op, literal, .. }),
+                ..
+            }) => {
+                if !matches!(
+                    op.data,
+                    BinOpToken::OpenOpenRange
+                        | BinOpToken::CloseOpenRange
+                        | BinOpToken::OpenCloseRange
+                        | BinOpToken::CloseCloseRange
+                ) {
+                    self.report(
+                        211,
+                        "يُتوقع عامل نطاق بين قيمتي النمط".to_string(),
+                        op.span,
+                        "ليس عامل نطاق".to_string(),
+                        vec![],
+                    );
+                }
+
+                if let Err(err) = literal {
+                    self.report_expected("قيمة (نهاية النطاق)", err, vec![]);
+                }
+            }
+            Pattern::Literal(_) | Pattern::Id(_) => {}
+            Pattern::Tuple(tuple_pattern) => self.check_tuple_pattern(tuple_pattern),
+            Pattern::Struct(struct_pattern) => {
+                match &struct_pattern.path {
+                    Ok(simple_path) => self.check_simple_path(simple_path),
+                    Err(err) => self.report_expected("اسم هيكل أو مساره", err, vec![]),
+                }
+
+                match &struct_pattern.fields {
+                    Some(StructPatternFields::Tuple(tuple_pattern)) => {
+                        self.check_tuple_pattern(tuple_pattern)
+                    }
+                    Some(StructPatternFields::Fields(FieldsPattern {
+                        open_delim,
+                        items,
+                        close_delim,
+                    })) => {
+                        if let Some(PunctuatedFieldPattern {
+                            first_item,
+                            rest_items,
+                            trailing_comma: _,
+                        }) = items
+                        {
+                            match first_item {
+                                Ok(FieldPattern {
+                                    name: _,
+                                    pattern: Some(explicit),
+                                }) => self.check_pattern_result(&explicit.pattern),
+                                Ok(_) => {}
+                                Err(err) => self.report_expected("مُعرِّف", err, vec![]),
+                            }
+
+                            for result in rest_items {
+                                match result {
+                                    Ok(CommaWithFieldPattern { comma: _, item }) => {
+                                        if let Some(explicit) = &item.pattern {
+                                            self.check_pattern_result(&explicit.pattern);
+                                        }
+                                    }
+                                    Err(err) => {
+                                        self.report_expected_comma_or_item("مُعرِّف", err, vec![])
+                                    }
+                                }
+                            }
+                        }
+
+                        if close_delim.is_err() {
+                            self.report_unclosed_delimiter(open_delim.span);
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    fn check_tuple_pattern(
+        &mut self,
+        TuplePattern {
+            open_delim,
+            items,
+            close_delim,
+        }: &TuplePattern,
+    ) {
+        if let Some(PunctuatedPattern {
+            first_item,
+            rest_items,
+            trailing_comma: _,
+        }) = items
+        {
+            self.check_pattern_result(first_item);
+
+            for result in rest_items {
+                match result {
+                    Ok(CommaWithPattern { comma: _, item }) => self.check_pattern(item),
+                    Err(err) => self.report_expected_comma_or_item("نمط", err, vec![]),
+                }
+            }
+        }
+
+        if close_delim.is_err() {
+            self.report_unclosed_delimiter(open_delim.span);
+        }
     }
 
     fn check_lambda_expr(&mut self, lambda: &LambdaExpr) {
@@ -1225,3 +1762,40 @@ impl<'a> ParseErrorsReporter<'a> {
         }
     }
 }
+
+/// The span of the lambda params and the arrow, which are not allowed in the blocks of functions and statements
+fn lambda_arrow_span(lambda_arrow: &LambdaArrow) -> Span {
+    match lambda_arrow {
+        LambdaArrow::NoParams(r_arrow) => r_arrow.span,
+        LambdaArrow::WithParams(LambdaParams {
+            first,
+            rest,
+            trailing_comma,
+            r_arrow,
+        }) => {
+            let first_span = match &first.kind {
+                BindingKind::Id(terminal) => terminal.span,
+                BindingKind::Destructed(destructed_tuple) => destructed_tuple.open_delim.span,
+            };
+
+            let lo = first_span.lo;
+
+            let hi = if let Ok(Terminal { span, .. }) = r_arrow {
+                span.hi
+            } else if let Some(Terminal { span, .. }) = trailing_comma {
+                span.hi
+            } else if !rest.is_empty() {
+                match &rest[rest.len() - 1].item.kind {
+                    BindingKind::Id(terminal) => terminal.span.hi,
+                    BindingKind::Destructed(destructed_tuple) => {
+                        destructed_tuple.open_delim.span.hi
+                    }
+                }
+            } else {
+                first_span.hi
+            };
+
+            Span::new(first_span.file_id, lo, hi)
+        }
+    }
+}
@@ -196,6 +196,7 @@ where
 mod tests {
 
     use nazmc_data_pool::DataPool;
+    use nazmc_diagnostics::FileId;
     use nazmc_parse_derive::{NazmcParse, SpannedAndCheck};
 
     use super::super::*;
@@ -211,8 +212,13 @@ mod tests {
     fn test_enum() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("+-  /** */ - +", &mut id_pool, &mut str_pool).collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "+-  /** */ - +",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -351,6 +357,7 @@ mod tests {
         let mut str_pool = DataPool::new();
         let (tokens, ..) = LexerIter::new(
             "دالة البداية(123 دالة، ت: ح 444، س: ص، ع: ك،) {}",
+            FileId::default(),
             &mut id_pool,
             &mut str_pool,
         )
@@ -383,8 +390,13 @@ mod tests {
     fn test_zero_params() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("دالة البداية() {}", &mut id_pool, &mut str_pool).collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "دالة البداية() {}",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -403,8 +415,13 @@ mod tests {
     fn test_one_param_no_trailing_comma() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("دالة البداية(س: ص8) {}", &mut id_pool, &mut str_pool).collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "دالة البداية(س: ص8) {}",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -428,8 +445,13 @@ mod tests {
     fn test_one_param_with_trailing_comma() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("دالة البداية(س: ص8،) {}", &mut id_pool, &mut str_pool).collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "دالة البداية(س: ص8،) {}",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -453,9 +475,13 @@ mod tests {
     fn test_two_params_no_trailing_comma() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("دالة البداية(س: ص8، ك: م) {}", &mut id_pool, &mut str_pool)
-                .collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "دالة البداية(س: ص8، ك: م) {}",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -480,9 +506,13 @@ mod tests {
     fn test_two_params_with_trailing_comma() {
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let (tokens, ..) =
-            LexerIter::new("دالة البداية(س: ص8، ك: م،) {}", &mut id_pool, &mut str_pool)
-                .collect_all();
+        let (tokens, ..) = LexerIter::new(
+            "دالة البداية(س: ص8، ك: م،) {}",
+            FileId::default(),
+            &mut id_pool,
+            &mut str_pool,
+        )
+        .collect_all();
         let mut tokens_iter = TokensIter::new(&tokens);
         tokens_iter.next(); // Init recent
 
@@ -22,13 +22,71 @@ pub(crate) struct ElseClause {
     pub(crate) block: ParseResult<LambdaExpr>,
 }
 
-#[derive(NazmcParse, Debug)]
+#[derive(Debug)]
 pub(crate) struct WhenExpr {
     pub(crate) when_keyword: WhenKeyword,
     pub(crate) expr: ParseResult<Expr>,
-    // TODO
+    pub(crate) arms: ParseResult<WhenArms>,
+}
+
+/// The expression matched by `عندما`, it's parsed like `Expr` without the lambdas after the primary
+/// expressions, so the open curly brace after it starts the arms, and it's converted to `Expr` after parsing
+#[derive(NazmcParse, Debug)]
+pub(crate) struct WhenScrutinee {
+    pub(crate) left: Box<ScrutineePrimaryExpr>,
+    pub(crate) bin: Vec<ScrutineeBinExpr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct ScrutineeBinExpr {
+    pub(crate) op: BinOp,
+    pub(crate) right: ParseResult<ScrutineePrimaryExpr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct ScrutineePrimaryExpr {
+    pub(crate) kind: PrimaryExprKind,
+    pub(crate) post_ops: Vec<ScrutineePostOpExpr>,
+    pub(crate) inner_access: Vec<ScrutineeInnerAccessExpr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) enum ScrutineePostOpExpr {
+    Invoke(ParenExpr),
+    Index(IdxExpr),
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct ScrutineeInnerAccessExpr {
+    pub(crate) dot: DotSymbol,
+    pub(crate) inner: ParseResult<Id>,
+    pub(crate) post_ops: Vec<ScrutineePostOpExpr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct WhenArm {
+    pub(crate) pattern: Pattern,
+    pub(crate) guard: Option<WhenArmGuard>,
+    pub(crate) r_arrow: ParseResult<RArrowSymbol>,
+    /// If it's a lambda without an arrow, it's the block of the arm
+    pub(crate) expr: ParseResult<Expr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct WhenArmGuard {
+    pub(crate) if_keyword: IfKeyword,
+    pub(crate) condition: ParseResult<Expr>,
 }
 
+generatePunctuatedItem!(WhenArm);
+
+generateDelimitedPunctuated!(
+    WhenArms,
+    OpenCurlyBraceSymbol,
+    WhenArm,
+    CloseCurlyBraceSymbol
+);
+
 #[derive(NazmcParse, Debug)]
 pub(crate) struct BreakExpr {
     pub(crate) break_keyword: BreakKeyword,
@@ -116,3 +174,77 @@ impl NazmcParse for ParseResult<ConditionalBlock> {
         })
     }
 }
+
+impl NazmcParse for ParseResult<WhenExpr> {
+    fn parse(iter: &mut TokensIter) -> Self {
+        let when_keyword = ParseResult::<WhenKeyword>::parse(iter)?;
+
+        let expr = match ParseResult::<WhenScrutinee>::parse(iter) {
+            Ok(expr) => Expr::from(expr),
+            Err(err) => {
+                return Ok(WhenExpr {
+                    when_keyword,
+                    expr: Err(err.clone()),
+                    arms: Err(err),
+                })
+            }
+        };
+
+        let arms = match expr.bin.last() {
+            Some(BinExpr {
+                right: Err(err), ..
+            }) => Err(err.clone()), // No expressions found after the bin op so no arms are parsed
+            _ => ParseResult::<WhenArms>::parse(iter),
+        };
+
+        Ok(WhenExpr {
+            when_keyword,
+            expr: Ok(expr),
+            arms,
+        })
+    }
+}
+
+impl From<WhenScrutinee> for Expr {
+    fn from(scrutinee: WhenScrutinee) -> Self {
+        Expr {
+            left: Box::new(PrimaryExpr::from(*scrutinee.left)),
+            bin: scrutinee
+                .bin
+                .into_iter()
+                .map(|bin| BinExpr {
+                    op: bin.op,
+                    right: bin.right.map(PrimaryExpr::from),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<ScrutineePrimaryExpr> for PrimaryExpr {
+    fn from(primary_expr: ScrutineePrimaryExpr) -> Self {
+        let post_ops = |post_ops: Vec<ScrutineePostOpExpr>| {
+            post_ops
+                .into_iter()
+                .map(|post_op| match post_op {
+                    ScrutineePostOpExpr::Invoke(paren_expr) => PostOpExpr::Invoke(paren_expr),
+                    ScrutineePostOpExpr::Index(idx_expr) => PostOpExpr::Index(idx_expr),
+                })
+                .collect()
+        };
+
+        PrimaryExpr {
+            kind: primary_expr.kind,
+            post_ops: post_ops(primary_expr.post_ops),
+            inner_access: primary_expr
+                .inner_access
+                .into_iter()
+                .map(|inner_access| InnerAccessExpr {
+                    dot: inner_access.dot,
+                    inner: inner_access.inner,
+                    post_ops: post_ops(inner_access.post_ops),
+                })
+                .collect(),
+        }
+    }
+}
@@ -15,6 +15,9 @@ pub(crate) struct ItemWithVisibility {
 #[derive(NazmcParse, Debug)]
 pub(crate) enum Item {
     Struct(Struct),
+    Enum(Enum),
+    Const(Const),
+    Static(Static),
     Fn(Fn),
 }
 
@@ -63,6 +66,54 @@ generateDelimitedPunctuated!(
     CloseCurlyBraceSymbol
 );
 
+#[derive(NazmcParse, Debug)]
+pub(crate) struct Enum {
+    pub(crate) enum_keyword: EnumKeyword,
+    pub(crate) name: ParseResult<Id>,
+    pub(crate) variants: ParseResult<EnumVariants>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct EnumVariant {
+    pub(crate) name: Id,
+    pub(crate) kind: Option<EnumVariantKind>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) enum EnumVariantKind {
+    /// The visibility modifiers of the fields must be checked that they are not written
+    Tuple(TupleStructFields),
+    /// The visibility modifiers of the fields must be checked that they are not written
+    Fields(StructFields),
+}
+
+generatePunctuatedItem!(EnumVariant);
+
+generateDelimitedPunctuated!(
+    EnumVariants,
+    OpenCurlyBraceSymbol,
+    EnumVariant,
+    CloseCurlyBraceSymbol
+);
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct Const {
+    pub(crate) const_keyword: ConstKeyword,
+    pub(crate) name: ParseResult<Id>,
+    pub(crate) typ: ParseResult<ColonWithType>,
+    pub(crate) assign: ParseResult<LetAssign>,
+    pub(crate) semicolon: ParseResult<SemicolonSymbol>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct Static {
+    pub(crate) static_keyword: StaticKeyword,
+    pub(crate) name: ParseResult<Id>,
+    pub(crate) typ: ParseResult<ColonWithType>,
+    pub(crate) assign: ParseResult<LetAssign>,
+    pub(crate) semicolon: ParseResult<SemicolonSymbol>,
+}
+
 #[derive(NazmcParse, Debug)]
 pub(crate) struct Fn {
     pub(crate) fn_keyword: FnKeyword,
@@ -22,6 +22,9 @@ pub(crate) use stm::*;
 pub(crate) mod expr;
 pub(crate) use expr::*;
 
+pub(crate) mod pattern;
+pub(crate) use pattern::*;
+
 generateTrailingCommaWithCloseDelimiter!(CloseParenthesisSymbol);
 
 generateTrailingCommaWithCloseDelimiter!(CloseAngleBracketOrGreaterSymbol);
@@ -0,0 +1,68 @@
+use super::*;
+
+#[derive(NazmcParse, Debug)]
+pub(crate) enum Pattern {
+    Literal(LiteralPattern),
+    Tuple(Box<TuplePattern>), // Box for the large size
+    Struct(Box<StructPattern>),
+    /// It's a wildcard if it's `_`, otherwise it binds the matched value to the name
+    Id(Id),
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct LiteralPattern {
+    pub(crate) minus: Option<MinusSymbol>,
+    pub(crate) literal: LiteralExpr,
+    pub(crate) range_end: Option<RangePatternEnd>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct RangePatternEnd {
+    /// This must be checked that it's a range operator
+    pub(crate) op: BinOp,
+    pub(crate) minus: Option<MinusSymbol>,
+    pub(crate) literal: ParseResult<LiteralExpr>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct StructPattern {
+    pub(crate) dot: DotSymbol,
+    pub(crate) path: ParseResult<SimplePath>,
+    pub(crate) fields: Option<StructPatternFields>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) enum StructPatternFields {
+    Tuple(TuplePattern),
+    Fields(FieldsPattern),
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct FieldPattern {
+    pub(crate) name: Id,
+    pub(crate) pattern: Option<FieldPatternExplicit>,
+}
+
+#[derive(NazmcParse, Debug)]
+pub(crate) struct FieldPatternExplicit {
+    pub(crate) equal: EqualSymbol,
+    pub(crate) pattern: ParseResult<Pattern>,
+}
+
+generatePunctuatedItem!(Pattern);
+
+generateDelimitedPunctuated!(
+    TuplePattern,
+    OpenParenthesisSymbol,
+    Pattern,
+    CloseParenthesisSymbol
+);
+
+generatePunctuatedItem!(FieldPattern);
+
+generateDelimitedPunctuated!(
+    FieldsPattern,
+    OpenCurlyBraceSymbol,
+    FieldPattern,
+    CloseCurlyBraceSymbol
+);
@@ -5,6 +5,7 @@ pub(crate) enum Stm {
     Semicolon(SemicolonSymbol),
     Let(LetStm),
     While(WhileStm),
+    DoWhile(DoWhileStm),
     If(IfExpr),
     When(WhenExpr),
     Expr(ExprStm),
@@ -61,13 +62,13 @@ pub(crate) struct WhileStm {
 }
 
 #[derive(NazmcParse, Debug)]
-pub struct DoWhileStm {
-    // TODO
+pub(crate) struct DoWhileStm {
     pub(crate) do_keyword: DoKeyword,
     /// This must be checked that it doesn't have a lambda arrow
     pub(crate) block: ParseResult<LambdaExpr>,
     pub(crate) while_keyword: ParseResult<WhileKeyword>,
     pub(crate) condition: ParseResult<Expr>,
+    pub(crate) semicolon: ParseResult<SemicolonSymbol>,
 }
 
 #[derive(NazmcParse, Debug)]
@@ -293,7 +293,7 @@ impl NazmcParse for ParseResult<DoubleColonsSymbol> {
                 },
             ) if match_peek_symbols!(iter, Colon) => {
                 let mut span = token.span;
-                span.end.col += 1;
+                span.hi += 1;
                 iter.peek_idx += 1; // Eat next colon
                 iter.next_non_space_or_comment();
                 Ok(Terminal {
@@ -319,7 +319,7 @@ impl NazmcParse for ParseResult<RArrowSymbol> {
                 },
             ) if match_peek_symbols!(iter, CloseAngleBracketOrGreater) => {
                 let mut span = token.span;
-                span.end.col += 1;
+                span.hi += 1;
                 iter.peek_idx += 1; // Eat next '>'
                 iter.next_non_space_or_comment();
                 Ok(Terminal {
@@ -426,6 +426,12 @@ impl NazmcParse for ParseResult<BinOp> {
                     SymbolKind::Minus if match_peek_symbols!(iter, Equal) => {
                         (BinOpToken::MinusAssign, 1)
                     }
+                    // It's `->` which ends the guards and the patterns in `عندما`
+                    SymbolKind::Minus if match_peek_symbols!(iter, CloseAngleBracketOrGreater) => {
+                        return Err(ParseErr {
+                            found_token_index: iter.peek_idx - 1,
+                        });
+                    }
                     SymbolKind::Minus => (BinOpToken::Minus, 0),
 
                     SymbolKind::Star if match_peek_symbols!(iter, Equal) => {
@@ -451,7 +457,7 @@ impl NazmcParse for ParseResult<BinOp> {
                 };
 
                 iter.peek_idx += peek_inc;
-                span.end.col += peek_inc;
+                span.hi += peek_inc as u32;
                 iter.next_non_space_or_comment();
 
                 Ok(Terminal {
@@ -486,17 +492,18 @@ impl NazmcParse for ParseResult<UnaryOp> {
                     SymbolKind::Star if !match_peek_symbols!(iter, Equal) => UnaryOpToken::Deref,
                     SymbolKind::Hash => {
                         let peek_idx = iter.peek_idx;
+                        iter.next_non_space_or_comment();
                         if let Some(Token {
                             span: mut_keyword_span,
                             kind: TokenKind::Keyword(KeywordKind::Mut),
                             ..
-                        }) = iter.next_non_space_or_comment()
+                        }) = iter.recent()
                         {
                             span = span.merged_with(mut_keyword_span);
-                            UnaryOpToken::Borrow
+                            UnaryOpToken::BorrowMut
                         } else {
                             iter.peek_idx = peek_idx;
-                            UnaryOpToken::BorrowMut
+                            UnaryOpToken::Borrow
                         }
                     }
                     _ => {
@@ -595,6 +602,7 @@ impl NazmcParse for ParseResult<Eof> {
 mod tests {
 
     use crate::LexerIter;
+    use nazmc_diagnostics::FileId;
 
     use super::*;
 
@@ -603,7 +611,7 @@ mod tests {
         let content = "دالة البداية(/* تعليق */){}";
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let lexer = LexerIter::new(content, &mut id_pool, &mut str_pool);
+        let lexer = LexerIter::new(content, FileId::default(), &mut id_pool, &mut str_pool);
 
         let (tokens, ..) = lexer.collect_all();
         let mut iter = TokensIter::new(&tokens);
@@ -629,7 +637,7 @@ mod tests {
         let content = "دالة البداية(عدد: ص8){}";
         let mut id_pool = DataPool::new();
         let mut str_pool = DataPool::new();
-        let lexer = LexerIter::new(content, &mut id_pool, &mut str_pool);
+        let lexer = LexerIter::new(content, FileId::default(), &mut id_pool, &mut str_pool);
 
         let (tokens, ..) = lexer.collect_all();
         let mut iter = TokensIter::new(&tokens);
@@ -0,0 +1,537 @@
+use crate::{
+    find_variant, is_accessible, item_kind_to_str, FileItemKindAndIdx, ItemInFile, NameResolver,
+};
+use nazmc_data_pool::PoolIdx;
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic};
+use nazmc_nir::{BuiltinFn, PrimitiveType};
+
+impl<'a> NameResolver<'a> {
+    /// Checks all the paths used in the items types and the functions bodies
+    pub(crate) fn resolve_bodies(&mut self) {
+        let parsed_files = self.parsed_files;
+
+        for (pkg_idx, parsed_files_in_package) in self.packages_to_parsed_files.iter().enumerate() {
+            for parsed_file_idx in parsed_files_in_package {
+                self.current_pkg_idx = pkg_idx;
+                self.current_file_idx = *parsed_file_idx;
+
+                for item in &parsed_files[*parsed_file_idx].ast.items {
+                    self.resolve_item(item);
+                }
+            }
+        }
+    }
+
+    fn resolve_item(&mut self, item: &nazmc_ast::Item) {
+        match &item.kind {
+            nazmc_ast::ItemKind::UnitStruct => {}
+            nazmc_ast::ItemKind::TupleStruct(tuple_struct) => {
+                for (_, typ) in &tuple_struct.types {
+                    self.resolve_type(typ);
+                }
+            }
+            nazmc_ast::ItemKind::FieldsStruct(fields_struct) => {
+                for (_, _, typ) in &fields_struct.fields {
+                    self.resolve_type(typ);
+                }
+            }
+            nazmc_ast::ItemKind::Enum(e) => {
+                self.check_variants_conflicts(e);
+
+                for variant in &e.variants {
+                    match &variant.kind {
+                        nazmc_ast::VariantKind::Unit => {}
+                        nazmc_ast::VariantKind::Tuple(types) => {
+                            for typ in types {
+                                self.resolve_type(typ);
+                            }
+                        }
+                        nazmc_ast::VariantKind::Fields(fields) => {
+                            for (_, typ) in fields {
+                                self.resolve_type(typ);
+                            }
+                        }
+                    }
+                }
+            }
+            nazmc_ast::ItemKind::Const(c) | nazmc_ast::ItemKind::Static(c) => {
+                self.resolve_type(&c.typ);
+                self.resolve_expr(&c.expr);
+            }
+            nazmc_ast::ItemKind::Fn(f) => {
+                self.scopes_names.push(vec![]);
+
+                for (name, typ) in &f.params {
+                    self.resolve_type(typ);
+                    self.scopes_names.last_mut().unwrap().push(name.id);
+                }
+
+                self.resolve_type(&f.return_type);
+                self.resolve_scope(&f.body);
+
+                self.scopes_names.pop();
+            }
+        }
+    }
+
+    fn check_variants_conflicts(&mut self, e: &nazmc_ast::Enum) {
+        for (i, variant) in e.variants.iter().enumerate() {
+            let Some(first) = e.variants[..i]
+                .iter()
+                .find(|first| first.name.id == variant.name.id)
+            else {
+                continue;
+            };
+
+            let msg = format!(
+                "يوجد أكثر من حالة بنفس الاسم `{}` في نفس التصنيف",
+                &self.id_pool[variant.name.id]
+            );
+            let mut code_window = CodeWindow::new(first.name.span);
+            code_window.mark_secondary(
+                first.name.span,
+                vec!["هنا تم العثور على أول حالة بهذا الاسم".to_string()],
+            );
+            code_window.mark_error(
+                variant.name.span,
+                vec!["هنا تم العثور على نفس الاسم مرة أخرى".to_string()],
+            );
+            self.diagnostics
+                .push(Diagnostic::error_with_code(304, msg, vec![code_window]));
+        }
+    }
+
+    fn resolve_scope(&mut self, scope: &nazmc_ast::Scope) {
+        self.scopes_names.push(vec![]);
+
+        for stm in &scope.stms {
+            self.resolve_stm(stm);
+        }
+
+        if let Some(expr) = &scope.return_expr {
+            self.resolve_expr(expr);
+        }
+
+        self.scopes_names.pop();
+    }
+
+    fn resolve_stm(&mut self, stm: &nazmc_ast::Stm) {
+        match stm {
+            nazmc_ast::Stm::Let(let_stm) | nazmc_ast::Stm::LetMut(let_stm) => {
+                // The value cannot see the names it is bound to
+                if let Some(expr) = &let_stm.assign {
+                    self.resolve_expr(expr);
+                }
+                self.resolve_binding(&let_stm.binding);
+            }
+            nazmc_ast::Stm::While(while_stm) => {
+                let (condition, scope) = &**while_stm;
+                self.resolve_expr(condition);
+                self.resolve_scope(scope);
+            }
+            nazmc_ast::Stm::DoWhile(do_while_stm) => {
+                let (scope, condition) = &**do_while_stm;
+                self.resolve_scope(scope);
+                self.resolve_expr(condition);
+            }
+            nazmc_ast::Stm::If(if_expr) => self.resolve_if_expr(if_expr),
+            nazmc_ast::Stm::When(when_expr) => self.resolve_when_expr(when_expr),
+            nazmc_ast::Stm::Expr(expr) => self.resolve_expr(expr),
+        }
+    }
+
+    fn resolve_binding(&mut self, binding: &nazmc_ast::Binding) {
+        if let Some(typ) = &binding.typ {
+            self.resolve_type(typ);
+        }
+        self.bind(&binding.kind);
+    }
+
+    fn bind(&mut self, kind: &nazmc_ast::BindingKind) {
+        match kind {
+            nazmc_ast::BindingKind::Id(id) => self.scopes_names.last_mut().unwrap().push(id.id),
+            nazmc_ast::BindingKind::Tuple(kinds, _) => {
+                for kind in kinds {
+                    self.bind(kind);
+                }
+            }
+        }
+    }
+
+    fn resolve_if_expr(&mut self, if_expr: &nazmc_ast::IfExpr) {
+        self.resolve_expr(&if_expr.if_.0);
+        self.resolve_scope(&if_expr.if_.1);
+
+        for (condition, scope) in &if_expr.else_ifs {
+            self.resolve_expr(condition);
+            self.resolve_scope(scope);
+        }
+
+        if let Some(scope) = &if_expr.else_ {
+            self.resolve_scope(scope);
+        }
+    }
+
+    fn resolve_when_expr(&mut self, when_expr: &nazmc_ast::WhenExpr) {
+        self.resolve_expr(&when_expr.expr);
+
+        for arm in &when_expr.arms {
+            // The names bound by the pattern are seen by the guard and the body only
+            self.scopes_names.push(vec![]);
+
+            self.resolve_pattern(&arm.pattern);
+
+            if let Some(guard) = &arm.guard {
+                self.resolve_expr(guard);
+            }
+
+            self.resolve_scope(&arm.body);
+
+            self.scopes_names.pop();
+        }
+    }
+
+    fn resolve_pattern(&mut self, pattern: &nazmc_ast::Pattern) {
+        match &pattern.kind {
+            nazmc_ast::PatternKind::Wildcard => {}
+            nazmc_ast::PatternKind::Binding(id) => {
+                self.scopes_names.last_mut().unwrap().push(id.id)
+            }
+            nazmc_ast::PatternKind::Literal(expr) => self.resolve_expr(expr),
+            nazmc_ast::PatternKind::Range(range) => {
+                self.resolve_expr(&range.start);
+                self.resolve_expr(&range.end);
+            }
+            nazmc_ast::PatternKind::Tuple(patterns) => {
+                for pattern in patterns {
+                    self.resolve_pattern(pattern);
+                }
+            }
+            nazmc_ast::PatternKind::UnitStruct(path) => self.resolve_struct_path(path),
+            nazmc_ast::PatternKind::TupleStruct(tuple_struct) => {
+                self.resolve_struct_path(&tuple_struct.path);
+                for pattern in &tuple_struct.patterns {
+                    self.resolve_pattern(pattern);
+                }
+            }
+            nazmc_ast::PatternKind::FieldsStruct(fields_struct) => {
+                self.resolve_struct_path(&fields_struct.path);
+                for (_, pattern) in &fields_struct.fields {
+                    self.resolve_pattern(pattern);
+                }
+            }
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &nazmc_ast::Expr) {
+        match &expr.kind {
+            nazmc_ast::ExprKind::Literal(_)
+            | nazmc_ast::ExprKind::Return(None)
+            | nazmc_ast::ExprKind::Break(None)
+            | nazmc_ast::ExprKind::Continue
+            | nazmc_ast::ExprKind::On => {}
+            nazmc_ast::ExprKind::Parens(expr)
+            | nazmc_ast::ExprKind::Return(Some(expr))
+            | nazmc_ast::ExprKind::Break(Some(expr)) => self.resolve_expr(expr),
+            nazmc_ast::ExprKind::Path(path) => self.resolve_value_path(path),
+            nazmc_ast::ExprKind::Call(call) => {
+                self.resolve_expr(&call.on);
+                for arg in &call.args {
+                    self.resolve_expr(arg);
+                }
+            }
+            nazmc_ast::ExprKind::UnitStruct(path) => self.resolve_struct_path(path),
+            nazmc_ast::ExprKind::TupleStruct(tuple_struct) => {
+                self.resolve_struct_path(&tuple_struct.path);
+                for arg in &tuple_struct.args {
+                    self.resolve_expr(arg);
+                }
+            }
+            nazmc_ast::ExprKind::FieldsStruct(fields_struct) => {
+                self.resolve_struct_path(&fields_struct.path);
+                for (_, expr) in &fields_struct.fields {
+                    self.resolve_expr(expr);
+                }
+            }
+            nazmc_ast::ExprKind::Field(field) => self.resolve_expr(&field.on),
+            nazmc_ast::ExprKind::Index(index) => {
+                self.resolve_expr(&index.on);
+                self.resolve_expr(&index.index);
+            }
+            nazmc_ast::ExprKind::Tuple(elements) | nazmc_ast::ExprKind::ArrayElemnts(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
+            nazmc_ast::ExprKind::ArrayElemntsSized(array) => {
+                self.resolve_expr(&array.repeat);
+                self.resolve_expr(&array.size);
+            }
+            nazmc_ast::ExprKind::If(if_expr) => self.resolve_if_expr(if_expr),
+            nazmc_ast::ExprKind::When(when_expr) => self.resolve_when_expr(when_expr),
+            nazmc_ast::ExprKind::Lambda(lambda) => {
+                self.scopes_names.push(vec![]);
+
+                for param in &lambda.params {
+                    self.resolve_binding(param);
+                }
+
+                self.resolve_scope(&lambda.body);
+
+                self.scopes_names.pop();
+            }
+            nazmc_ast::ExprKind::UnaryOp(unary) => self.resolve_expr(&unary.expr),
+            nazmc_ast::ExprKind::BinaryOp(bin) => {
+                self.resolve_expr(&bin.left);
+                self.resolve_expr(&bin.right);
+            }
+        }
+    }
+
+    fn resolve_type(&mut self, typ: &nazmc_ast::Type) {
+        match typ {
+            nazmc_ast::Type::Path(path) => self.resolve_type_path(path),
+            nazmc_ast::Type::Unit(_) => {}
+            nazmc_ast::Type::Tuple(types, _) => {
+                for typ in types {
+                    self.resolve_type(typ);
+                }
+            }
+            nazmc_ast::Type::Paren(typ, _)
+            | nazmc_ast::Type::Slice(typ, _)
+            | nazmc_ast::Type::Ptr(typ, _)
+            | nazmc_ast::Type::Ref(typ, _)
+            | nazmc_ast::Type::PtrMut(typ, _)
+            | nazmc_ast::Type::RefMut(typ, _) => self.resolve_type(typ),
+            nazmc_ast::Type::Array(typ, size, _) => {
+                self.resolve_type(typ);
+                self.resolve_expr(size);
+            }
+            nazmc_ast::Type::Lambda(params, return_type) => {
+                for typ in params {
+                    self.resolve_type(typ);
+                }
+                self.resolve_type(return_type);
+            }
+        }
+    }
+
+    fn resolve_value_path(&mut self, path: &nazmc_ast::PkgPathWithItem) {
+        if path.pkg_path.ids.is_empty()
+            && (self.is_bound_in_scopes(path.item.id)
+                || self.find_item_in_file_scope(path.item.id).is_none()
+                    && BuiltinFn::from_id(path.item.id).is_some())
+        {
+            return;
+        }
+
+        self.resolve_item_path(path);
+    }
+
+    fn resolve_type_path(&mut self, path: &nazmc_ast::PkgPathWithItem) {
+        if path.pkg_path.ids.is_empty()
+            && self.find_item_in_file_scope(path.item.id).is_none()
+            && PrimitiveType::from_name(&self.id_pool[path.item.id]).is_some()
+        {
+            return;
+        }
+
+        let Some(item) = self.resolve_item_path(path) else {
+            return;
+        };
+
+        if matches!(
+            item.kind_and_idx.kind(),
+            FileItemKindAndIdx::FN | FileItemKindAndIdx::CONST | FileItemKindAndIdx::STATIC
+        ) {
+            self.add_expected_struct_err(path, item, "هيكل أو تصنيف");
+        }
+    }
+
+    /// Resolves the path of a struct or an enum variant in a struct expression or a pattern
+    fn resolve_struct_path(&mut self, path: &nazmc_ast::PkgPathWithItem) {
+        if let Some((enum_pkg_idx, enum_item)) = self.nrt.find_variant_enum(
+            self.parsed_files,
+            self.packages,
+            self.current_pkg_idx,
+            self.current_file_idx,
+            path,
+        ) {
+            if let [enum_id] = path.pkg_path.ids.as_slice() {
+                self.used_names.insert((self.current_file_idx, *enum_id));
+            }
+            self.resolve_variant_path(path, enum_pkg_idx, enum_item);
+            return;
+        }
+
+        let Some(item) = self.resolve_item_path(path) else {
+            return;
+        };
+
+        if matches!(
+            item.kind_and_idx.kind(),
+            FileItemKindAndIdx::FN
+                | FileItemKindAndIdx::ENUM
+                | FileItemKindAndIdx::CONST
+                | FileItemKindAndIdx::STATIC
+        ) {
+            self.add_expected_struct_err(path, item, "هيكل أو حالة تصنيف");
+        }
+    }
+
+    fn resolve_variant_path(
+        &mut self,
+        path: &nazmc_ast::PkgPathWithItem,
+        enum_pkg_idx: usize,
+        enum_item: ItemInFile,
+    ) {
+        let enum_file = &self.parsed_files[enum_item.file_idx];
+        let enum_ast = &enum_file.ast.items[enum_item.item_idx];
+
+        if !is_accessible(
+            self.parsed_files,
+            enum_item,
+            enum_pkg_idx,
+            self.current_pkg_idx,
+        ) {
+            let enum_path = nazmc_ast::PkgPathWithItem {
+                pkg_path: nazmc_ast::PkgPath {
+                    ids: path.pkg_path.ids[..path.pkg_path.ids.len() - 1].into(),
+                    spans: path.pkg_path.spans[..path.pkg_path.spans.len() - 1].into(),
+                },
+                item: nazmc_ast::ASTId {
+                    span: *path.pkg_path.spans.last().unwrap(),
+                    id: *path.pkg_path.ids.last().unwrap(),
+                },
+            };
+            self.add_encapsulation_err(&enum_path, enum_ast);
+            return;
+        }
+
+        if find_variant(self.parsed_files, enum_item, path.item.id).is_some() {
+            return;
+        }
+
+        let msg = format!(
+            "لا توجد حالة باسم `{}` في التصنيف `{}`",
+            &self.id_pool[path.item.id],
+            &self.id_pool[enum_ast.name.id]
+        );
+        let mut code_window = CodeWindow::new(path.item.span);
+        code_window.mark_error(path.item.span, vec!["حالة غير معرّفة".to_string()]);
+        let mut diagnostic = Diagnostic::error_with_code(305, msg, vec![code_window]);
+
+        let mut note_code_window = CodeWindow::new(enum_ast.name.span);
+        note_code_window.mark_note(enum_ast.name.span, vec![]);
+        let note = Diagnostic::note(
+            "تم العثور على التصنيف هنا".to_string(),
+            vec![note_code_window],
+        );
+        diagnostic.chain(note);
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Reports the unresolved paths and returns the item of the path if it is resolved
+    fn resolve_item_path(&mut self, path: &nazmc_ast::PkgPathWithItem) -> Option<ItemInFile> {
+        if path.pkg_path.ids.is_empty() {
+            let item = self.find_item_in_file_scope(path.item.id);
+
+            if item.is_none() {
+                self.add_unresolved_name_err(path.item.id, path.item.span);
+            }
+
+            return item;
+        }
+
+        let Some(resolved_package_idx) = self.packages.get(&path.pkg_path.ids) else {
+            self.add_pkg_path_err(path.pkg_path.ids.clone(), path.pkg_path.spans.clone());
+            return None;
+        };
+
+        let Some(resolved_item) = self.nrt.packages_to_items[*resolved_package_idx]
+            .get(&path.item.id)
+            .copied()
+        else {
+            let similar_id = self.find_similar_item_name(*resolved_package_idx, path.item.id);
+            self.add_unresolved_import_err(path.item.id, path.item.span, similar_id);
+            return None;
+        };
+
+        if !is_accessible(
+            self.parsed_files,
+            resolved_item,
+            *resolved_package_idx,
+            self.current_pkg_idx,
+        ) {
+            let item_resolved_file = &self.parsed_files[resolved_item.file_idx];
+            let resolved_item_ast = &item_resolved_file.ast.items[resolved_item.item_idx];
+            self.add_encapsulation_err(path, resolved_item_ast);
+            return None;
+        }
+
+        Some(resolved_item)
+    }
+
+    #[inline]
+    fn find_item_in_file_scope(&mut self, id: PoolIdx) -> Option<ItemInFile> {
+        self.used_names.insert((self.current_file_idx, id));
+        self.nrt.find_item_in_file_scope(
+            self.parsed_files,
+            self.current_pkg_idx,
+            self.current_file_idx,
+            id,
+        )
+    }
+
+    #[inline]
+    fn is_bound_in_scopes(&self, id: PoolIdx) -> bool {
+        self.scopes_names.iter().any(|names| names.contains(&id))
+    }
+
+    fn add_unresolved_name_err(&mut self, id: PoolIdx, span: Span) {
+        let name = &self.id_pool[id];
+        let msg = format!("لم يتم العثور على الاسم `{}` في هذا النطاق", name);
+
+        let mut code_window = CodeWindow::new(span);
+
+        code_window.mark_error(span, vec!["هذا الاسم غير معرّف في هذا النطاق".to_string()]);
+
+        let mut diagnostic = Diagnostic::error_with_code(306, msg, vec![code_window]);
+
+        self.chain_similar_items_help(&mut diagnostic, id);
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn add_expected_struct_err(
+        &mut self,
+        path: &nazmc_ast::PkgPathWithItem,
+        item: ItemInFile,
+        expected: &str,
+    ) {
+        let name = &self.id_pool[path.item.id];
+        let item_file = &self.parsed_files[item.file_idx];
+        let item_ast = &item_file.ast.items[item.item_idx];
+        let item_kind_str = item_kind_to_str(&item_ast.kind);
+        let msg = format!(
+            "يُتوقع {}، ولكن تم العثور على {} `{}`",
+            expected, item_kind_str, name
+        );
+
+        let mut code_window = CodeWindow::new(path.item.span);
+        code_window.mark_error(path.item.span, vec!["ليس هيكلًا".to_string()]);
+        let mut diagnostic = Diagnostic::error_with_code(307, msg, vec![code_window]);
+
+        let item_span = item_ast.name.span;
+        let mut note_code_window = CodeWindow::new(item_span);
+        note_code_window.mark_note(item_span, vec![]);
+        let note = Diagnostic::note(
+            format!("تم العثور على {} هنا", item_kind_str),
+            vec![note_code_window],
+        );
+        diagnostic.chain(note);
+
+        self.diagnostics.push(diagnostic);
+    }
+}
@@ -1,12 +1,21 @@
 use nazmc_data_pool::{Built, DataPool, PoolIdx};
-use nazmc_diagnostics::{eprint_diagnostics, span::Span, CodeWindow, Diagnostic};
-use std::{collections::HashMap, process::exit};
+use nazmc_diagnostics::{
+    span::Span, Applicability, CodeWindow, Diagnostic, Lint, LintLevel, LintLevels, Numerals,
+    SourceMap, Suggestion,
+};
+use std::collections::{HashMap, HashSet};
+use suggest::find_similar_name;
 use thin_vec::ThinVec;
 
+mod bodies;
+mod nir_builder;
+mod suggest;
+
+pub use nir_builder::NIRBuilder;
+
 #[derive(Clone)]
 pub struct ParsedFile {
     pub path: String,
-    pub lines: Vec<String>,
     pub ast: nazmc_ast::File,
 }
 
@@ -15,6 +24,9 @@ pub struct ASTItemsCounter {
     pub unit_structs: usize,
     pub tuple_structs: usize,
     pub fields_structs: usize,
+    pub enums: usize,
+    pub consts: usize,
+    pub statics: usize,
     pub fns: usize,
 }
 
@@ -24,7 +36,7 @@ pub struct FileItemKindAndIdx(u64);
 impl FileItemKindAndIdx {
     const KIND_BITS: u64 = 4;
     const KIND_SHIFT: u64 = 64 - Self::KIND_BITS;
-    const KIND_MASK: u64 = 0b11 << Self::KIND_SHIFT;
+    const KIND_MASK: u64 = 0b1111 << Self::KIND_SHIFT;
     const INDEX_MASK: u64 = !Self::KIND_MASK;
 
     // Possible kinds
@@ -32,6 +44,9 @@ impl FileItemKindAndIdx {
     pub const TUPLE_STRUCT: u64 = 1 << Self::KIND_SHIFT;
     pub const FIELDS_STRUCT: u64 = 2 << Self::KIND_SHIFT;
     pub const FN: u64 = 3 << Self::KIND_SHIFT;
+    pub const ENUM: u64 = 4 << Self::KIND_SHIFT;
+    pub const CONST: u64 = 5 << Self::KIND_SHIFT;
+    pub const STATIC: u64 = 6 << Self::KIND_SHIFT;
 
     // Create a new encoded value for a given kind and index
     pub fn new(kind: u64, index: usize) -> Self {
@@ -69,9 +84,20 @@ pub struct ResolvedImport {
     pub alias: nazmc_ast::ASTId,
 }
 
+/// The options of the resolver and the files used to locate the similar items in the diagnostics
+pub struct ResolverConfig<'a> {
+    /// The files of the spans, the locations of the similar items are computed from them
+    pub source_map: &'a SourceMap,
+    pub lint_levels: LintLevels,
+    /// The digits of the numbers in the diagnostics
+    pub numerals: Numerals,
+}
+
 pub struct NameResolver<'a> {
     /// The pool used to preserve ids string values
     id_pool: &'a DataPool<Built>,
+    /// The files of the spans, the locations of the similar items are computed from them
+    source_map: &'a SourceMap,
     /// A map from pkgs ids segments to the pkgs indexes
     packages: &'a HashMap<ThinVec<PoolIdx>, usize>,
     /// A map from the pkgs indexes to their segments
@@ -80,8 +106,18 @@ pub struct NameResolver<'a> {
     packages_to_parsed_files: &'a [Vec<usize>],
     /// The parsed filese array
     parsed_files: &'a [ParsedFile],
+    lint_levels: LintLevels,
+    numerals: Numerals,
     /// The diagnostics which will be filled in different phases
-    diagnostics: Vec<Diagnostic<'a>>,
+    diagnostics: Vec<Diagnostic>,
+    /// The package index of the item whose body is being resolved
+    current_pkg_idx: usize,
+    /// The file index of the item whose body is being resolved
+    current_file_idx: usize,
+    /// The names bound in each nested scope of the function body being resolved
+    scopes_names: Vec<Vec<PoolIdx>>,
+    /// The names used from the file scope in each file, to find the unused imports
+    used_names: HashSet<(usize, PoolIdx)>,
     nrt: NameResolutionTree,
 }
 
@@ -105,14 +141,28 @@ impl<'a> NameResolver<'a> {
         packages_names: &'a [ThinVec<PoolIdx>],
         packages_to_parsed_files: &'a [Vec<usize>],
         parsed_files: &'a [ParsedFile],
+        config: ResolverConfig<'a>,
     ) -> Self {
+        let ResolverConfig {
+            source_map,
+            lint_levels,
+            numerals,
+        } = config;
+
         Self {
             id_pool,
+            source_map,
             packages,
             packages_names,
             packages_to_parsed_files,
             parsed_files,
+            lint_levels,
+            numerals,
             diagnostics: vec![],
+            current_pkg_idx: 0,
+            current_file_idx: 0,
+            scopes_names: vec![],
+            used_names: HashSet::new(),
             nrt: NameResolutionTree {
                 packages_to_items: vec![HashMap::new(); packages.len()],
                 resolved_imports: vec![HashMap::new(); packages.len()],
@@ -122,22 +172,25 @@ impl<'a> NameResolver<'a> {
         }
     }
 
-    pub fn resolve(mut self) -> NameResolutionTree {
+    /// Resolves the names and returns the diagnostics with the tree, the phases after the conflicts
+    /// or the imports errors are skipped so the tree is partially resolved then
+    pub fn resolve(mut self) -> (NameResolutionTree, Vec<Diagnostic>) {
         self.check_pkg_items_conflicts();
 
         if !self.diagnostics.is_empty() {
-            eprint_diagnostics(self.diagnostics);
-            exit(1)
+            return (self.nrt, self.diagnostics);
         }
 
         self.resolve_imports();
 
         if !self.diagnostics.is_empty() {
-            eprint_diagnostics(self.diagnostics);
-            exit(1)
+            return (self.nrt, self.diagnostics);
         }
 
-        self.nrt
+        self.resolve_bodies();
+        self.check_unused_imports();
+
+        (self.nrt, self.diagnostics)
     }
 
     fn check_pkg_items_conflicts(&mut self) {
@@ -158,12 +211,11 @@ impl<'a> NameResolver<'a> {
         for ((_pkg_idx, conflicting_name), name_conflicts_in_single_package) in conflicts {
             let name = &self.id_pool[conflicting_name];
             let msg = format!("يوجد أكثر من عنصر بنفس الاسم `{}` في نفس الحزمة", name);
-            let mut diagnostic = Diagnostic::error(msg, vec![]);
+            let mut diagnostic = Diagnostic::error_with_code(300, msg, vec![]);
             let mut occurrences = 1;
 
-            for (file_idx, spans) in name_conflicts_in_single_package {
-                let parsed_file = &self.parsed_files[file_idx];
-                let code_window = occurrences_code_window(parsed_file, &mut occurrences, spans);
+            for spans in name_conflicts_in_single_package.into_values() {
+                let code_window = occurrences_code_window(&mut occurrences, spans, self.numerals);
                 diagnostic.push_code_window(code_window);
             }
 
@@ -215,6 +267,16 @@ impl<'a> NameResolver<'a> {
                             FileItemKindAndIdx::FIELDS_STRUCT,
                             &mut self.nrt.ast_counter.fields_structs,
                         ),
+                        nazmc_ast::ItemKind::Enum(_) => {
+                            (FileItemKindAndIdx::ENUM, &mut self.nrt.ast_counter.enums)
+                        }
+                        nazmc_ast::ItemKind::Const(_) => {
+                            (FileItemKindAndIdx::CONST, &mut self.nrt.ast_counter.consts)
+                        }
+                        nazmc_ast::ItemKind::Static(_) => (
+                            FileItemKindAndIdx::STATIC,
+                            &mut self.nrt.ast_counter.statics,
+                        ),
                         nazmc_ast::ItemKind::Fn(_) => {
                             (FileItemKindAndIdx::FN, &mut self.nrt.ast_counter.fns)
                         }
@@ -280,27 +342,66 @@ impl<'a> NameResolver<'a> {
             }
         }
 
-        for (file_idx, name_conflicts_in_single_file) in conflicts {
-            let parsed_file = &self.parsed_files[file_idx];
-
+        for name_conflicts_in_single_file in conflicts.into_values() {
             for (conflicting_name, spans) in name_conflicts_in_single_file {
                 let name = &self.id_pool[conflicting_name];
                 let msg = format!("يوجد أكثر من عنصر بنفس الاسم `{}` في نفس الملف", name);
-                let mut diagnostic = Diagnostic::error(msg, vec![]);
+                let mut diagnostic = Diagnostic::error_with_code(301, msg, vec![]);
                 let mut occurrences = 1;
-                let code_window = occurrences_code_window(parsed_file, &mut occurrences, spans);
+                let code_window = occurrences_code_window(&mut occurrences, spans, self.numerals);
                 diagnostic.push_code_window(code_window);
                 self.diagnostics.push(diagnostic);
             }
         }
     }
 
+    fn check_unused_imports(&mut self) {
+        let level = self.lint_levels.get(Lint::UnusedImports);
+
+        if level == LintLevel::Allow {
+            return;
+        }
+
+        for (pkg_idx, parsed_files_in_package) in self.packages_to_parsed_files.iter().enumerate() {
+            for parsed_file_idx in parsed_files_in_package {
+                let Some(resolved_imports) =
+                    self.nrt.resolved_imports[pkg_idx].get(parsed_file_idx)
+                else {
+                    continue;
+                };
+
+                for resolved_import in resolved_imports {
+                    let alias = resolved_import.alias;
+
+                    if self.used_names.contains(&(*parsed_file_idx, alias.id)) {
+                        continue;
+                    }
+
+                    let name = &self.id_pool[alias.id];
+                    let msg = format!("لم يتم استخدام `{}` المستورد", name);
+                    let mut code_window = CodeWindow::new(alias.span);
+                    code_window.mark_lint(
+                        level,
+                        alias.span,
+                        vec!["استيراد غير مستخدم".to_string()],
+                    );
+                    self.diagnostics.push(Diagnostic::lint(
+                        Lint::UnusedImports,
+                        level,
+                        msg,
+                        vec![code_window],
+                    ));
+                }
+            }
+        }
+    }
+
     #[inline]
     fn resolve_file_star_imports(&mut self, pkg_idx: usize, parsed_file_idx: usize) {
         let parsed_file = &self.parsed_files[parsed_file_idx];
         for import in &parsed_file.ast.star_imports {
             let Some(resolved_package_idx) = self.packages.get(&import.ids) else {
-                self.add_pkg_path_err(&parsed_file, import.ids.clone(), import.spans.clone());
+                self.add_pkg_path_err(import.ids.clone(), import.spans.clone());
                 continue;
             };
 
@@ -316,34 +417,27 @@ impl<'a> NameResolver<'a> {
         let parsed_file = &self.parsed_files[parsed_file_idx];
         for (import, item_alias) in &parsed_file.ast.imports {
             let Some(resolved_package_idx) = self.packages.get(&import.pkg_path.ids) else {
-                self.add_pkg_path_err(
-                    &parsed_file,
-                    import.pkg_path.ids.clone(),
-                    import.pkg_path.spans.clone(),
-                );
+                self.add_pkg_path_err(import.pkg_path.ids.clone(), import.pkg_path.spans.clone());
                 continue;
             };
 
             let Some(resolved_item) =
                 self.nrt.packages_to_items[*resolved_package_idx].get(&import.item.id)
             else {
-                self.add_unresolved_import_err(&parsed_file, import.item.id, import.item.span);
+                let similar_id = self.find_similar_item_name(*resolved_package_idx, import.item.id);
+                self.add_unresolved_import_err(import.item.id, import.item.span, similar_id);
                 continue;
             };
 
-            let item_resolved_file = &self.parsed_files[resolved_item.file_idx];
-
-            let resolved_item_ast = &item_resolved_file.ast.items[resolved_item.item_idx];
-
-            if pkg_idx != *resolved_package_idx
-                && matches!(resolved_item_ast.vis, nazmc_ast::VisModifier::Default)
-            {
-                self.add_encapsulation_err(
-                    parsed_file,
-                    item_resolved_file,
-                    import,
-                    resolved_item_ast,
-                );
+            if !is_accessible(
+                self.parsed_files,
+                *resolved_item,
+                *resolved_package_idx,
+                pkg_idx,
+            ) {
+                let item_resolved_file = &self.parsed_files[resolved_item.file_idx];
+                let resolved_item_ast = &item_resolved_file.ast.items[resolved_item.item_idx];
+                self.add_encapsulation_err(import, resolved_item_ast);
             } else {
                 self.nrt.resolved_imports[pkg_idx]
                     .entry(parsed_file_idx)
@@ -359,8 +453,6 @@ impl<'a> NameResolver<'a> {
 
     fn add_encapsulation_err(
         &mut self,
-        parsed_file: &'a ParsedFile,
-        item_resolved_file: &'a ParsedFile,
         import: &nazmc_ast::PkgPathWithItem,
         resolved_item_ast: &nazmc_ast::Item,
     ) {
@@ -375,26 +467,30 @@ impl<'a> NameResolver<'a> {
                     name
                 )
             }
+            nazmc_ast::ItemKind::Enum(_) => format!(
+                "لا يمكن الوصول إلى تصنيف `{}` لأنه خاص بالحزمة التابع لها",
+                name
+            ),
+            nazmc_ast::ItemKind::Const(_) => format!(
+                "لا يمكن الوصول إلى ثابت `{}` لأنه خاص بالحزمة التابع لها",
+                name
+            ),
+            nazmc_ast::ItemKind::Static(_) => format!(
+                "لا يمكن الوصول إلى متغير مشترك `{}` لأنه خاص بالحزمة التابع لها",
+                name
+            ),
             nazmc_ast::ItemKind::Fn(_) => format!(
                 "لا يمكن الوصول إلى دالة `{}` لأنها خاصة بالحزمة التابعة لها",
                 name
             ),
         };
 
-        let mut code_window = CodeWindow::new(
-            &parsed_file.path,
-            &parsed_file.lines,
-            import.item.span.start,
-        );
+        let mut code_window = CodeWindow::new(import.item.span);
         code_window.mark_error(import.item.span, vec![]);
-        let mut diagnostic = Diagnostic::error(msg, vec![code_window]);
+        let mut diagnostic = Diagnostic::error_with_code(303, msg, vec![code_window]);
 
         let help_msg = format!("تم العثور على {} هنا", item_kind_str);
-        let mut help_code_window = CodeWindow::new(
-            &item_resolved_file.path,
-            &item_resolved_file.lines,
-            resolved_item_ast.name.span.start,
-        );
+        let mut help_code_window = CodeWindow::new(resolved_item_ast.name.span);
         help_code_window.mark_note(resolved_item_ast.name.span, vec![]);
         let help = Diagnostic::note(help_msg, vec![help_code_window]);
         diagnostic.chain(help);
@@ -402,34 +498,52 @@ impl<'a> NameResolver<'a> {
         self.diagnostics.push(diagnostic);
     }
 
-    fn add_unresolved_import_err(&mut self, file: &'a ParsedFile, id: PoolIdx, span: Span) {
+    fn add_unresolved_import_err(&mut self, id: PoolIdx, span: Span, similar_id: Option<PoolIdx>) {
         let name = &self.id_pool[id];
         let msg = format!("لم يتم العثور على الاسم `{}` في المسار", name);
 
-        let mut code_window = CodeWindow::new(&file.path, &file.lines, span.start);
+        let mut code_window = CodeWindow::new(span);
 
         code_window.mark_error(
             span,
             vec!["هذا الاسم غير موجود داخل المسار المحدد".to_string()],
         );
 
-        let mut diagnostic = Diagnostic::error(msg, vec![code_window]);
+        let mut diagnostic = Diagnostic::error_with_code(302, msg, vec![code_window]);
 
+        if let Some(similar_id) = similar_id {
+            let similar_name = &self.id_pool[similar_id];
+            diagnostic.suggest(Suggestion::new(
+                format!("يوجد اسم مشابه `{}`", similar_name),
+                span,
+                similar_name.to_string(),
+                Applicability::MaybeIncorrect,
+            ));
+        }
+
+        self.chain_similar_items_help(&mut diagnostic, id);
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Suggests the paths of the items with the same name in all packages
+    fn chain_similar_items_help(&self, diagnostic: &mut Diagnostic, id: PoolIdx) {
         let mut possible_paths = vec![];
 
         for (pkg_idx, pkg_to_items) in self.nrt.packages_to_items.iter().enumerate() {
             if let Some(found_item) = pkg_to_items.get(&id) {
                 let item_file = &self.parsed_files[found_item.file_idx];
                 let item_ast = &item_file.ast.items[found_item.item_idx];
-                let item_span_cursor = item_ast.name.span.start;
+                let item_span = item_ast.name.span;
+                let item_span_cursor = self.source_map[item_span.file_id].cursor(item_span.lo);
                 let item_kind_str = item_kind_to_str(&item_ast.kind);
                 let pkg_name = self.fmt_pkg_name(pkg_idx);
                 let name = &self.id_pool[id];
                 let item_path = format!(
                     "{}:{}:{}",
                     &item_file.path,
-                    item_span_cursor.line + 1,
-                    item_span_cursor.col + 1
+                    self.numerals.fmt(item_span_cursor.line + 1),
+                    self.numerals.fmt(item_span_cursor.col + 1)
                 );
                 let path = format!(
                     "\t- {} {}::{} في {}",
@@ -442,7 +556,7 @@ impl<'a> NameResolver<'a> {
 
         if !possible_paths.is_empty() {
             let mut help = Diagnostic::help(
-                format!("تم العثور على عناصر مشابهة بنفس الاسم في المسارات التالية:"),
+                "تم العثور على عناصر مشابهة بنفس الاسم في المسارات التالية:".to_string(),
                 vec![],
             );
 
@@ -452,13 +566,10 @@ impl<'a> NameResolver<'a> {
 
             diagnostic.chain(help);
         }
-
-        self.diagnostics.push(diagnostic);
     }
 
     fn add_pkg_path_err(
         &mut self,
-        file: &'a ParsedFile,
         mut pkg_path: ThinVec<PoolIdx>,
         mut pkg_path_spans: ThinVec<Span>,
     ) {
@@ -466,11 +577,37 @@ impl<'a> NameResolver<'a> {
             let first_invalid_seg_span = pkg_path_spans.pop().unwrap();
 
             if self.packages.contains_key(&pkg_path) {
-                self.add_unresolved_import_err(&file, first_invalid_seg, first_invalid_seg_span);
+                let similar_id = self.find_similar_pkg_seg(&pkg_path, first_invalid_seg);
+                self.add_unresolved_import_err(
+                    first_invalid_seg,
+                    first_invalid_seg_span,
+                    similar_id,
+                );
             }
         }
     }
 
+    /// Finds a name of an item in the package which is similar to the unresolved name
+    fn find_similar_item_name(&self, pkg_idx: usize, id: PoolIdx) -> Option<PoolIdx> {
+        find_similar_name(
+            self.id_pool,
+            id,
+            self.nrt.packages_to_items[pkg_idx].keys().copied(),
+        )
+    }
+
+    /// Finds a segment which is similar to the unresolved one among the segments after the package path
+    fn find_similar_pkg_seg(&self, pkg_path: &[PoolIdx], id: PoolIdx) -> Option<PoolIdx> {
+        let segs = self
+            .packages
+            .keys()
+            .filter(|ids| ids.len() > pkg_path.len() && ids.starts_with(pkg_path))
+            .map(|ids| ids[pkg_path.len()])
+            .collect::<HashSet<_>>();
+
+        find_similar_name(self.id_pool, id, segs)
+    }
+
     fn fmt_pkg_name(&self, pkg_idx: usize) -> String {
         self.packages_names[pkg_idx]
             .iter()
@@ -480,22 +617,118 @@ impl<'a> NameResolver<'a> {
     }
 }
 
+impl NameResolutionTree {
+    /// Finds the item bound to the name in the scope of the file,
+    /// which is either an item in the same package, an import alias or an item from a star import
+    pub(crate) fn find_item_in_file_scope(
+        &self,
+        parsed_files: &[ParsedFile],
+        pkg_idx: usize,
+        file_idx: usize,
+        id: PoolIdx,
+    ) -> Option<ItemInFile> {
+        if let Some(item) = self.packages_to_items[pkg_idx].get(&id) {
+            return Some(*item);
+        }
+
+        if let Some(import) = self.resolved_imports[pkg_idx]
+            .get(&file_idx)
+            .into_iter()
+            .flatten()
+            .find(|import| import.alias.id == id)
+        {
+            return Some(import.item);
+        }
+
+        self.resolved_star_imports[pkg_idx]
+            .get(&file_idx)
+            .into_iter()
+            .flatten()
+            .find_map(|star_pkg_idx| {
+                self.packages_to_items[*star_pkg_idx]
+                    .get(&id)
+                    .filter(|item| is_accessible(parsed_files, **item, *star_pkg_idx, pkg_idx))
+                    .copied()
+            })
+    }
+
+    /// Finds the enum of a variant path, which is the path of the enum followed by the variant name,
+    /// and returns it with the package index where it is found
+    pub(crate) fn find_variant_enum(
+        &self,
+        parsed_files: &[ParsedFile],
+        packages: &HashMap<ThinVec<PoolIdx>, usize>,
+        pkg_idx: usize,
+        file_idx: usize,
+        path: &nazmc_ast::PkgPathWithItem,
+    ) -> Option<(usize, ItemInFile)> {
+        // The items of the packages take precedence over the variants of the enums with the same paths
+        if let Some(item_pkg_idx) = packages.get(&path.pkg_path.ids) {
+            if self.packages_to_items[*item_pkg_idx].contains_key(&path.item.id) {
+                return None;
+            }
+        }
+
+        let (enum_id, enum_pkg_path) = path.pkg_path.ids.split_last()?;
+
+        let (enum_pkg_idx, item) = if enum_pkg_path.is_empty() {
+            let item = self.find_item_in_file_scope(parsed_files, pkg_idx, file_idx, *enum_id)?;
+            (pkg_idx, item)
+        } else {
+            let enum_pkg_idx = *packages.get(enum_pkg_path)?;
+            let item = *self.packages_to_items[enum_pkg_idx].get(enum_id)?;
+            (enum_pkg_idx, item)
+        };
+
+        (item.kind_and_idx.kind() == FileItemKindAndIdx::ENUM).then_some((enum_pkg_idx, item))
+    }
+}
+
+/// Returns the index of the variant with the name in the enum item
+#[inline]
+pub(crate) fn find_variant(
+    parsed_files: &[ParsedFile],
+    item: ItemInFile,
+    id: PoolIdx,
+) -> Option<usize> {
+    let nazmc_ast::ItemKind::Enum(e) = &parsed_files[item.file_idx].ast.items[item.item_idx].kind
+    else {
+        unreachable!()
+    };
+    e.variants.iter().position(|variant| variant.name.id == id)
+}
+
+/// Checks if the item in `item_pkg_idx` could be used from `pkg_idx`
+#[inline]
+fn is_accessible(
+    parsed_files: &[ParsedFile],
+    item: ItemInFile,
+    item_pkg_idx: usize,
+    pkg_idx: usize,
+) -> bool {
+    let item_ast = &parsed_files[item.file_idx].ast.items[item.item_idx];
+    item_pkg_idx == pkg_idx || !matches!(item_ast.vis, nazmc_ast::VisModifier::Default)
+}
+
 #[inline]
 fn item_kind_to_str(kind: &nazmc_ast::ItemKind) -> &'static str {
     match kind {
         nazmc_ast::ItemKind::UnitStruct
         | nazmc_ast::ItemKind::TupleStruct(_)
         | nazmc_ast::ItemKind::FieldsStruct(_) => "الهيكل",
+        nazmc_ast::ItemKind::Enum(_) => "التصنيف",
+        nazmc_ast::ItemKind::Const(_) => "الثابت",
+        nazmc_ast::ItemKind::Static(_) => "المتغير المشترك",
         nazmc_ast::ItemKind::Fn(_) => "الدالة",
     }
 }
 
-fn occurrences_code_window<'a>(
-    parsed_file: &'a ParsedFile,
+fn occurrences_code_window(
     occurrences: &mut usize,
     mut spans: Vec<Span>,
-) -> CodeWindow<'a> {
-    let mut code_window = CodeWindow::new(&parsed_file.path, &parsed_file.lines, spans[0].start);
+    numerals: Numerals,
+) -> CodeWindow {
+    let mut code_window = CodeWindow::new(spans[0]);
 
     nazmc_diagnostics::span::sort_spans(&mut spans);
 
@@ -511,7 +744,7 @@ fn occurrences_code_window<'a>(
             8 => "هنا تم العثور على نفس الاسم للمرة الثامنة".to_string(),
             9 => "هنا تم العثور على نفس الاسم للمرة التاسعة".to_string(),
             10 => "هنا تم العثور على نفس الاسم للمرة العاشرة".to_string(),
-            o => format!("هنا تم العثور على نفس الاسم للمرة {}", o),
+            o => format!("هنا تم العثور على نفس الاسم للمرة {}", numerals.fmt(o)),
         };
 
         if *occurrences == 1 {
@@ -0,0 +1,1024 @@
+use crate::{find_variant, FileItemKindAndIdx, ItemInFile, NameResolutionTree, ParsedFile};
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic};
+use nazmc_nir::*;
+use std::collections::HashMap;
+use thin_vec::ThinVec;
+
+/// Lowers the parsed files into the NIR arenas after their names have been resolved
+///
+/// Every item is placed at the index given to it by the resolver in `FileItemKindAndIdx`,
+/// so the paths could be bound to their items before lowering them
+pub struct NIRBuilder<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    /// A map from pkgs ids segments to the pkgs indexes
+    packages: &'a HashMap<ThinVec<PoolIdx>, usize>,
+    /// A map from the pkgs indexes to the inner files indexes
+    packages_to_parsed_files: &'a [Vec<usize>],
+    /// The parsed filese array
+    parsed_files: &'a [ParsedFile],
+    /// The result of the resolver
+    nrt: &'a NameResolutionTree,
+    /// The package index of the item being lowered
+    current_pkg_idx: usize,
+    /// The file index of the item being lowered
+    current_file_idx: usize,
+    /// The names bound in each nested scope of the function being lowered to their local indexes
+    scopes_names: Vec<Vec<(PoolIdx, usize)>>,
+    /// The diagnostics of the paths which couldn't be bound, they should be reported by the resolver first
+    diagnostics: Vec<Diagnostic>,
+    nir: NIR,
+}
+
+impl<'a> NIRBuilder<'a> {
+    pub fn new(
+        id_pool: &'a DataPool<Built>,
+        packages: &'a HashMap<ThinVec<PoolIdx>, usize>,
+        packages_to_parsed_files: &'a [Vec<usize>],
+        parsed_files: &'a [ParsedFile],
+        nrt: &'a NameResolutionTree,
+    ) -> Self {
+        let counter = &nrt.ast_counter;
+        let nir = NIR {
+            unit_structs: ThinVec::with_capacity(counter.unit_structs),
+            tuple_structs: ThinVec::with_capacity(counter.tuple_structs),
+            fields_structs: ThinVec::with_capacity(counter.fields_structs),
+            enums: ThinVec::with_capacity(counter.enums),
+            consts: ThinVec::with_capacity(counter.consts),
+            statics: ThinVec::with_capacity(counter.statics),
+            fns: ThinVec::with_capacity(counter.fns),
+            ..Default::default()
+        };
+
+        Self {
+            id_pool,
+            packages,
+            packages_to_parsed_files,
+            parsed_files,
+            nrt,
+            current_pkg_idx: 0,
+            current_file_idx: 0,
+            scopes_names: vec![],
+            diagnostics: vec![],
+            nir,
+        }
+    }
+
+    pub fn build(mut self) -> (NIR, Vec<Diagnostic>) {
+        let parsed_files = self.parsed_files;
+
+        // The same order of `NameResolver::check_pkg_items_conflicts`, so each item will be pushed to its index
+        for (pkg_idx, parsed_files_in_package) in self.packages_to_parsed_files.iter().enumerate() {
+            for parsed_file_idx in parsed_files_in_package {
+                self.current_pkg_idx = pkg_idx;
+                self.current_file_idx = *parsed_file_idx;
+
+                for item in &parsed_files[*parsed_file_idx].ast.items {
+                    self.lower_item(item);
+                }
+            }
+        }
+
+        (self.nir, self.diagnostics)
+    }
+
+    fn lower_item(&mut self, item: &nazmc_ast::Item) {
+        let vis = lower_vis(item.vis);
+        let name = lower_id(item.name);
+        let file_idx = self.current_file_idx;
+
+        match &item.kind {
+            nazmc_ast::ItemKind::UnitStruct => {
+                self.nir.unit_structs.push(UnitStruct {
+                    vis,
+                    name,
+                    file_idx,
+                });
+            }
+            nazmc_ast::ItemKind::TupleStruct(tuple_struct) => {
+                let types = tuple_struct
+                    .types
+                    .iter()
+                    .map(|(vis, typ)| (lower_vis(*vis), self.lower_type(typ)))
+                    .collect();
+
+                self.nir.tuple_structs.push(TupleStruct {
+                    vis,
+                    name,
+                    file_idx,
+                    types,
+                });
+            }
+            nazmc_ast::ItemKind::FieldsStruct(fields_struct) => {
+                let fields = fields_struct
+                    .fields
+                    .iter()
+                    .map(|(vis, name, typ)| {
+                        (lower_vis(*vis), lower_id(*name), self.lower_type(typ))
+                    })
+                    .collect();
+
+                self.nir.fields_structs.push(FieldsStruct {
+                    vis,
+                    name,
+                    file_idx,
+                    fields,
+                });
+            }
+            nazmc_ast::ItemKind::Enum(e) => {
+                let variants = e
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let kind = match &variant.kind {
+                            nazmc_ast::VariantKind::Unit => VariantKind::Unit,
+                            nazmc_ast::VariantKind::Tuple(types) => VariantKind::Tuple(
+                                types.iter().map(|typ| self.lower_type(typ)).collect(),
+                            ),
+                            nazmc_ast::VariantKind::Fields(fields) => VariantKind::Fields(
+                                fields
+                                    .iter()
+                                    .map(|(name, typ)| (lower_id(*name), self.lower_type(typ)))
+                                    .collect(),
+                            ),
+                        };
+                        Variant {
+                            name: lower_id(variant.name),
+                            kind,
+                        }
+                    })
+                    .collect();
+
+                self.nir.enums.push(Enum {
+                    vis,
+                    name,
+                    file_idx,
+                    variants,
+                });
+            }
+            nazmc_ast::ItemKind::Const(c) => {
+                let c = self.lower_const(vis, name, c);
+                self.nir.consts.push(c);
+            }
+            nazmc_ast::ItemKind::Static(s) => {
+                let s = self.lower_const(vis, name, s);
+                self.nir.statics.push(s);
+            }
+            nazmc_ast::ItemKind::Fn(f) => {
+                self.scopes_names.push(vec![]);
+
+                let params = f
+                    .params
+                    .iter()
+                    .map(|(name, typ)| {
+                        let typ = self.lower_type(typ);
+                        (self.new_local(*name, false), typ)
+                    })
+                    .collect();
+
+                let return_type = match f.return_type {
+                    nazmc_ast::Type::Unit(None) => Type {
+                        kind_and_index: TypeKindAndIndex::new(TypeKindAndIndex::UNIT, 0),
+                        span: name.span,
+                    },
+                    ref typ => self.lower_type(typ),
+                };
+
+                let body = self.lower_scope(&f.body);
+
+                self.scopes_names.pop();
+
+                self.nir.fns.push(Fn {
+                    vis,
+                    name,
+                    file_idx,
+                    params,
+                    return_type,
+                    body,
+                });
+            }
+        }
+    }
+
+    fn lower_const(&mut self, vis: VisModifier, name: NIRId, c: &nazmc_ast::Const) -> Const {
+        Const {
+            vis,
+            name,
+            file_idx: self.current_file_idx,
+            typ: self.lower_type(&c.typ),
+            expr: self.lower_expr(&c.expr),
+        }
+    }
+
+    fn new_local(&mut self, name: nazmc_ast::ASTId, is_mut: bool) -> usize {
+        let local_idx = self.nir.locals.len();
+
+        self.nir.locals.push(Local {
+            name: lower_id(name),
+            is_mut,
+            file_idx: self.current_file_idx,
+        });
+
+        self.scopes_names
+            .last_mut()
+            .unwrap()
+            .push((name.id, local_idx));
+
+        local_idx
+    }
+
+    fn find_local(&self, id: PoolIdx) -> Option<usize> {
+        self.scopes_names
+            .iter()
+            .rev()
+            .flat_map(|names| names.iter().rev())
+            .find(|(name, _)| *name == id)
+            .map(|(_, local_idx)| *local_idx)
+    }
+
+    fn lower_scope(&mut self, scope: &nazmc_ast::Scope) -> Scope {
+        self.scopes_names.push(vec![]);
+
+        let stms = scope.stms.iter().map(|stm| self.lower_stm(stm)).collect();
+
+        let return_expr = scope.return_expr.as_ref().map(|expr| self.lower_expr(expr));
+
+        self.scopes_names.pop();
+
+        let index = self.nir.scopes.len();
+
+        self.nir.scopes.push(ScopeBody { stms, return_expr });
+
+        Scope { index }
+    }
+
+    fn lower_conditional_scope(
+        &mut self,
+        condition: &nazmc_ast::Expr,
+        scope: &nazmc_ast::Scope,
+    ) -> ConditionalScope {
+        let condition = self.lower_expr(condition);
+        let scope = self.lower_scope(scope);
+        ConditionalScope { condition, scope }
+    }
+
+    fn lower_stm(&mut self, stm: &nazmc_ast::Stm) -> Stm {
+        let kind_and_index = match stm {
+            nazmc_ast::Stm::Let(let_stm) => {
+                let let_stm = self.lower_let_stm(let_stm, false);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::LET, push(&mut stms.lets, let_stm))
+            }
+            nazmc_ast::Stm::LetMut(let_stm) => {
+                let let_stm = self.lower_let_stm(let_stm, true);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::LET_MUT, push(&mut stms.let_muts, let_stm))
+            }
+            nazmc_ast::Stm::While(while_stm) => {
+                let (condition, scope) = &**while_stm;
+                let while_stm = self.lower_conditional_scope(condition, scope);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::WHILE, push(&mut stms.whiles, while_stm))
+            }
+            nazmc_ast::Stm::DoWhile(do_while_stm) => {
+                let (scope, condition) = &**do_while_stm;
+                let do_while_stm = self.lower_conditional_scope(condition, scope);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(
+                    StmKindAndIndex::DO_WHILE,
+                    push(&mut stms.do_whiles, do_while_stm),
+                )
+            }
+            nazmc_ast::Stm::If(if_expr) => {
+                let if_expr = self.lower_if_expr(if_expr);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::IF, push(&mut stms.ifs, if_expr))
+            }
+            nazmc_ast::Stm::When(when_expr) => {
+                let when_expr = self.lower_when_expr(when_expr);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::WHEN, push(&mut stms.whens, when_expr))
+            }
+            nazmc_ast::Stm::Expr(expr) => {
+                let expr = self.lower_expr(expr);
+                let stms = &mut self.nir.stms;
+                StmKindAndIndex::new(StmKindAndIndex::EXPR, push(&mut stms.exprs, expr))
+            }
+        };
+
+        Stm { kind_and_index }
+    }
+
+    fn lower_let_stm(&mut self, let_stm: &nazmc_ast::LetStm, is_mut: bool) -> LetStm {
+        // The value is lowered first as it cannot see the names it is bound to
+        let assign = let_stm.assign.as_ref().map(|expr| self.lower_expr(expr));
+        let binding = self.lower_binding(&let_stm.binding, is_mut);
+        LetStm { binding, assign }
+    }
+
+    fn lower_binding(&mut self, binding: &nazmc_ast::Binding, is_mut: bool) -> Binding {
+        let typ = binding.typ.as_ref().map(|typ| self.lower_type(typ));
+        let kind = self.lower_binding_kind(&binding.kind, is_mut);
+        Binding { kind, typ }
+    }
+
+    fn lower_binding_kind(&mut self, kind: &nazmc_ast::BindingKind, is_mut: bool) -> BindingKind {
+        match kind {
+            nazmc_ast::BindingKind::Id(id) => BindingKind::Local(self.new_local(*id, is_mut)),
+            nazmc_ast::BindingKind::Tuple(kinds, span) => {
+                let kinds = kinds
+                    .iter()
+                    .map(|kind| self.lower_binding_kind(kind, is_mut))
+                    .collect();
+                BindingKind::TupleDestruction(kinds, *span)
+            }
+        }
+    }
+
+    fn lower_if_expr(&mut self, if_expr: &nazmc_ast::IfExpr) -> IfExpr {
+        let if_ = self.lower_conditional_scope(&if_expr.if_.0, &if_expr.if_.1);
+
+        let else_ifs = if_expr
+            .else_ifs
+            .iter()
+            .map(|(condition, scope)| self.lower_conditional_scope(condition, scope))
+            .collect();
+
+        let else_ = if_expr.else_.as_ref().map(|scope| self.lower_scope(scope));
+
+        IfExpr {
+            if_,
+            else_ifs,
+            else_,
+        }
+    }
+
+    fn lower_when_expr(&mut self, when_expr: &nazmc_ast::WhenExpr) -> WhenExpr {
+        let expr = self.lower_expr(&when_expr.expr);
+
+        let arms = when_expr
+            .arms
+            .iter()
+            .map(|arm| {
+                // The names bound by the pattern are seen by the guard and the body only
+                self.scopes_names.push(vec![]);
+
+                let pattern = self.lower_pattern(&arm.pattern);
+                let guard = arm.guard.as_ref().map(|guard| self.lower_expr(guard));
+                let body = self.lower_scope(&arm.body);
+
+                self.scopes_names.pop();
+
+                WhenArm {
+                    pattern,
+                    guard,
+                    body,
+                }
+            })
+            .collect();
+
+        WhenExpr { expr, arms }
+    }
+
+    fn lower_pattern(&mut self, pattern: &nazmc_ast::Pattern) -> Pattern {
+        let kind = match &pattern.kind {
+            nazmc_ast::PatternKind::Wildcard => PatternKind::Wildcard,
+            nazmc_ast::PatternKind::Binding(id) => PatternKind::Binding(self.new_local(*id, false)),
+            nazmc_ast::PatternKind::Literal(expr) => PatternKind::Literal(self.lower_expr(expr)),
+            nazmc_ast::PatternKind::Range(range) => PatternKind::Range(Box::new(RangePattern {
+                start: self.lower_expr(&range.start),
+                op: lower_bin_op(&range.op),
+                end: self.lower_expr(&range.end),
+            })),
+            nazmc_ast::PatternKind::Tuple(patterns) => PatternKind::Tuple(
+                patterns
+                    .iter()
+                    .map(|pattern| self.lower_pattern(pattern))
+                    .collect(),
+            ),
+            nazmc_ast::PatternKind::UnitStruct(path) => {
+                PatternKind::UnitStruct(self.lower_struct_path(path))
+            }
+            nazmc_ast::PatternKind::TupleStruct(tuple_struct) => {
+                let path = self.lower_struct_path(&tuple_struct.path);
+                let patterns = tuple_struct
+                    .patterns
+                    .iter()
+                    .map(|pattern| self.lower_pattern(pattern))
+                    .collect();
+                PatternKind::TupleStruct(path, patterns)
+            }
+            nazmc_ast::PatternKind::FieldsStruct(fields_struct) => {
+                let path = self.lower_struct_path(&fields_struct.path);
+                let fields = fields_struct
+                    .fields
+                    .iter()
+                    .map(|(name, pattern)| (lower_id(*name), self.lower_pattern(pattern)))
+                    .collect();
+                PatternKind::FieldsStruct(path, fields)
+            }
+        };
+
+        Pattern {
+            kind,
+            span: pattern.span,
+        }
+    }
+
+    fn lower_exprs(&mut self, exprs: &[nazmc_ast::Expr]) -> ThinVec<Expr> {
+        exprs.iter().map(|expr| self.lower_expr(expr)).collect()
+    }
+
+    fn lower_expr(&mut self, expr: &nazmc_ast::Expr) -> Expr {
+        let kind_and_index = match &expr.kind {
+            nazmc_ast::ExprKind::Literal(literal) => {
+                let literal = lower_literal(literal);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::LITERAL,
+                    push(&mut exprs.literals, literal),
+                )
+            }
+            nazmc_ast::ExprKind::Parens(expr) => {
+                let expr = self.lower_expr(expr);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::PARENS,
+                    push(&mut exprs.parens, ParensExpr { expr }),
+                )
+            }
+            nazmc_ast::ExprKind::Path(path) => {
+                let path = self.lower_value_path(path);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(ExprKindAndIndex::PATH, push(&mut exprs.paths, path))
+            }
+            nazmc_ast::ExprKind::Call(call) => {
+                let nazmc_ast::CallExpr {
+                    on,
+                    args,
+                    parens_span,
+                } = &**call;
+
+                if let nazmc_ast::ExprKind::Path(path) = &on.kind {
+                    let path = self.lower_value_path(path);
+                    let args = self.lower_exprs(args);
+                    let path_call = PathCallExpr {
+                        path,
+                        args,
+                        parens_span: *parens_span,
+                    };
+                    let exprs = &mut self.nir.exprs;
+                    ExprKindAndIndex::new(
+                        ExprKindAndIndex::PATH_CALL,
+                        push(&mut exprs.path_calls, path_call),
+                    )
+                } else {
+                    let on = self.lower_expr(on);
+                    let args = self.lower_exprs(args);
+                    let call = CallExpr {
+                        on,
+                        args,
+                        parens_span: *parens_span,
+                    };
+                    let exprs = &mut self.nir.exprs;
+                    ExprKindAndIndex::new(ExprKindAndIndex::CALL, push(&mut exprs.calls, call))
+                }
+            }
+            nazmc_ast::ExprKind::UnitStruct(path) => {
+                let path = self.lower_struct_path(path);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::UNIT_STRUCT,
+                    push(&mut exprs.unit_structs, path),
+                )
+            }
+            nazmc_ast::ExprKind::TupleStruct(tuple_struct) => {
+                let path = self.lower_struct_path(&tuple_struct.path);
+                let args = self.lower_exprs(&tuple_struct.args);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::TUPLE_STRUCT,
+                    push(&mut exprs.tuple_structs, TupleStructExpr { path, args }),
+                )
+            }
+            nazmc_ast::ExprKind::FieldsStruct(fields_struct) => {
+                let path = self.lower_struct_path(&fields_struct.path);
+                let fields = fields_struct
+                    .fields
+                    .iter()
+                    .map(|(name, expr)| FieldInStructExpr {
+                        name: lower_id(*name),
+                        expr: self.lower_expr(expr),
+                    })
+                    .collect();
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::FIELDS_STRUCT,
+                    push(&mut exprs.fields_structs, FieldsStructExpr { path, fields }),
+                )
+            }
+            nazmc_ast::ExprKind::Field(field) => {
+                let on = self.lower_expr(&field.on);
+                let name = lower_id(field.name);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::FIELD,
+                    push(&mut exprs.fields, FieldExpr { on, name }),
+                )
+            }
+            nazmc_ast::ExprKind::Index(index) => {
+                let on = self.lower_expr(&index.on);
+                let idx = self.lower_expr(&index.index);
+                let index = IndexExpr {
+                    on,
+                    idx,
+                    brackets_span: index.brackets_span,
+                };
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(ExprKindAndIndex::INDEX, push(&mut exprs.indexes, index))
+            }
+            nazmc_ast::ExprKind::Tuple(elements) if elements.is_empty() => {
+                ExprKindAndIndex::new(ExprKindAndIndex::UNIT, 0)
+            }
+            nazmc_ast::ExprKind::Tuple(elements) => {
+                let elements = self.lower_exprs(elements);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::TUPLE_EXPR,
+                    push(&mut exprs.tuples, TupleExpr { elements }),
+                )
+            }
+            nazmc_ast::ExprKind::ArrayElemnts(elements) => {
+                let elements = self.lower_exprs(elements);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::ARRAY_ELEMENTS,
+                    push(&mut exprs.array_elements, ArrayElementsExpr { elements }),
+                )
+            }
+            nazmc_ast::ExprKind::ArrayElemntsSized(array) => {
+                let repeat = self.lower_expr(&array.repeat);
+                let size = self.lower_expr(&array.size);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::ARRAY_ELEMENTS_SIZED,
+                    push(
+                        &mut exprs.array_elements_sized,
+                        ArrayElementsSizedExpr { repeat, size },
+                    ),
+                )
+            }
+            nazmc_ast::ExprKind::If(if_expr) => {
+                let if_expr = self.lower_if_expr(if_expr);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(ExprKindAndIndex::IF_EXPR, push(&mut exprs.ifs, if_expr))
+            }
+            nazmc_ast::ExprKind::When(when_expr) => {
+                let when_expr = self.lower_when_expr(when_expr);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::WHEN_EXPR,
+                    push(&mut exprs.whens, when_expr),
+                )
+            }
+            nazmc_ast::ExprKind::Lambda(lambda) => {
+                self.scopes_names.push(vec![]);
+
+                let mut params: ThinVec<_> = lambda
+                    .params
+                    .iter()
+                    .map(|binding| self.lower_binding(binding, false))
+                    .collect();
+
+                let paths_len = self.nir.exprs.paths.len();
+                let path_calls_len = self.nir.exprs.path_calls.len();
+
+                let body = self.lower_scope(&lambda.body);
+
+                self.scopes_names.pop();
+
+                // The implicit `س` is a param only if the body uses it,
+                // so lambdas like `{ اظهر_(1) }` take no params
+                if is_implicit_param(lambda, expr.span) {
+                    let BindingKind::Local(local_idx) = params[0].kind else {
+                        unreachable!()
+                    };
+                    let exprs = &self.nir.exprs;
+                    let is_used = exprs.paths[paths_len..]
+                        .iter()
+                        .chain(exprs.path_calls[path_calls_len..].iter().map(|c| &c.path))
+                        .any(|path| path.resolved == ResolvedPath::Local(local_idx));
+                    if !is_used {
+                        params.clear();
+                    }
+                }
+
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::LAMBDA_EXPR,
+                    push(&mut exprs.lambdas, LambdaExpr { params, body }),
+                )
+            }
+            nazmc_ast::ExprKind::UnaryOp(unary) => {
+                let expr = self.lower_expr(&unary.expr);
+                let unary = UnaryExpr {
+                    op: lower_unary_op(&unary.op),
+                    op_span: unary.op_span,
+                    expr,
+                };
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::UNARY_EXPR,
+                    push(&mut exprs.unary_exprs, unary),
+                )
+            }
+            nazmc_ast::ExprKind::BinaryOp(bin) => {
+                let left = self.lower_expr(&bin.left);
+                let right = self.lower_expr(&bin.right);
+                let bin = BinExpr {
+                    op: lower_bin_op(&bin.op),
+                    op_span_lo: bin.op_span_lo,
+                    left,
+                    right,
+                };
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(ExprKindAndIndex::BIN_EXPR, push(&mut exprs.bin_exprs, bin))
+            }
+            nazmc_ast::ExprKind::Return(None) => ExprKindAndIndex::new(ExprKindAndIndex::RETURN, 0),
+            nazmc_ast::ExprKind::Return(Some(expr)) => {
+                let expr_to_return = self.lower_expr(expr);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::RETURN_WITH_VALUE,
+                    push(&mut exprs.returns, ReturWithValueExpr { expr_to_return }),
+                )
+            }
+            nazmc_ast::ExprKind::Break(None) => ExprKindAndIndex::new(ExprKindAndIndex::BREAK, 0),
+            nazmc_ast::ExprKind::Break(Some(expr)) => {
+                let expr_to_break = self.lower_expr(expr);
+                let exprs = &mut self.nir.exprs;
+                ExprKindAndIndex::new(
+                    ExprKindAndIndex::BREAK_WITH_VALUE,
+                    push(&mut exprs.breaks, BreakWithValueExpr { expr_to_break }),
+                )
+            }
+            nazmc_ast::ExprKind::Continue => ExprKindAndIndex::new(ExprKindAndIndex::CONTINUE, 0),
+            nazmc_ast::ExprKind::On => ExprKindAndIndex::new(ExprKindAndIndex::ON, 0),
+        };
+
+        Expr {
+            kind_and_index,
+            span: expr.span,
+        }
+    }
+
+    fn lower_type(&mut self, typ: &nazmc_ast::Type) -> Type {
+        let (kind, index, span) = match typ {
+            nazmc_ast::Type::Path(path) => {
+                let span = path_span(path);
+
+                if path.pkg_path.ids.is_empty() && self.find_item(path).is_none() {
+                    if let Some(primitive) = PrimitiveType::from_name(&self.id_pool[path.item.id]) {
+                        let kind_and_index =
+                            TypeKindAndIndex::new(TypeKindAndIndex::PRIMITIVE, primitive as usize);
+                        return Type {
+                            kind_and_index,
+                            span,
+                        };
+                    }
+                }
+
+                let path = self.lower_item_path(path);
+                let types = &mut self.nir.types;
+                (TypeKindAndIndex::PATH, push(&mut types.paths, path), span)
+            }
+            nazmc_ast::Type::Unit(span) => (TypeKindAndIndex::UNIT, 0, span.unwrap_or_default()),
+            nazmc_ast::Type::Tuple(types, parens_span) => {
+                let types = types.iter().map(|typ| self.lower_type(typ)).collect();
+                let tuple = TupleType {
+                    types,
+                    parens_span: *parens_span,
+                };
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::TUPLE,
+                    push(&mut types.tuples, tuple),
+                    *parens_span,
+                )
+            }
+            nazmc_ast::Type::Paren(typ, parens_span) => {
+                let typ = self.lower_type(typ);
+                return Type {
+                    kind_and_index: typ.kind_and_index,
+                    span: *parens_span,
+                };
+            }
+            nazmc_ast::Type::Slice(typ, span) => {
+                let typ = self.lower_type(typ);
+                let types = &mut self.nir.types;
+                (TypeKindAndIndex::SLICE, push(&mut types.slices, typ), *span)
+            }
+            nazmc_ast::Type::Array(typ, size, span) => {
+                let typ = self.lower_type(typ);
+                let size = self.lower_expr(size);
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::ARRAY,
+                    push(&mut types.arrays, ArrayType { typ, size }),
+                    *span,
+                )
+            }
+            nazmc_ast::Type::Ptr(typ, span) => {
+                let typ = self.lower_type(typ);
+                let underlying_span = typ.span;
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::PTR,
+                    push(&mut types.ptrs, typ),
+                    span.merged_with(&underlying_span),
+                )
+            }
+            nazmc_ast::Type::Ref(typ, span) => {
+                let typ = self.lower_type(typ);
+                let underlying_span = typ.span;
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::REF,
+                    push(&mut types.refs, typ),
+                    span.merged_with(&underlying_span),
+                )
+            }
+            nazmc_ast::Type::PtrMut(typ, span) => {
+                let typ = self.lower_type(typ);
+                let underlying_span = typ.span;
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::PTR_MUT,
+                    push(&mut types.ptrs_mut, typ),
+                    span.merged_with(&underlying_span),
+                )
+            }
+            nazmc_ast::Type::RefMut(typ, span) => {
+                let typ = self.lower_type(typ);
+                let underlying_span = typ.span;
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::REF_MUT,
+                    push(&mut types.refs_mut, typ),
+                    span.merged_with(&underlying_span),
+                )
+            }
+            nazmc_ast::Type::Lambda(params, return_type) => {
+                let params: ThinVec<_> = params.iter().map(|typ| self.lower_type(typ)).collect();
+                let return_type = self.lower_type(return_type);
+                let span = match params.first() {
+                    Some(first) => first.span.merged_with(&return_type.span),
+                    None => return_type.span,
+                };
+                let lambda = LambdaType {
+                    params,
+                    return_type,
+                };
+                let types = &mut self.nir.types;
+                (
+                    TypeKindAndIndex::LAMBDA,
+                    push(&mut types.lambdas, lambda),
+                    span,
+                )
+            }
+        };
+
+        Type {
+            kind_and_index: TypeKindAndIndex::new(kind, index),
+            span,
+        }
+    }
+
+    /// Lowers a path in an expression, which may be bound to a local
+    fn lower_value_path(&mut self, path: &nazmc_ast::PkgPathWithItem) -> ModPathWithItem {
+        if path.pkg_path.ids.is_empty() {
+            if let Some(local_idx) = self.find_local(path.item.id) {
+                return lower_path(path, ResolvedPath::Local(local_idx));
+            }
+
+            if self.find_item(path).is_none() {
+                if let Some(builtin) = BuiltinFn::from_id(path.item.id) {
+                    return lower_path(path, ResolvedPath::Builtin(builtin));
+                }
+            }
+        }
+
+        self.lower_item_path(path)
+    }
+
+    /// Lowers a path that could only be bound to an item
+    fn lower_item_path(&mut self, path: &nazmc_ast::PkgPathWithItem) -> ModPathWithItem {
+        let resolved = match self.find_item(path) {
+            Some(item) => item_to_resolved_path(item.kind_and_idx),
+            None => {
+                self.add_unresolved_path_err(path);
+                ResolvedPath::Fn(0) // A dummy value as the diagnostics will be reported
+            }
+        };
+
+        lower_path(path, resolved)
+    }
+
+    /// Lowers a path in a struct expression or a pattern, which may be bound to an enum variant
+    fn lower_struct_path(&mut self, path: &nazmc_ast::PkgPathWithItem) -> ModPathWithItem {
+        let Some((_, enum_item)) = self.nrt.find_variant_enum(
+            self.parsed_files,
+            self.packages,
+            self.current_pkg_idx,
+            self.current_file_idx,
+            path,
+        ) else {
+            return self.lower_item_path(path);
+        };
+
+        let Some(variant_idx) = find_variant(self.parsed_files, enum_item, path.item.id) else {
+            self.add_unresolved_path_err(path);
+            return lower_path(path, ResolvedPath::Fn(0)); // A dummy value as the diagnostics will be reported
+        };
+
+        lower_path(
+            path,
+            ResolvedPath::Variant(enum_item.kind_and_idx.index(), variant_idx),
+        )
+    }
+
+    fn add_unresolved_path_err(&mut self, path: &nazmc_ast::PkgPathWithItem) {
+        let name = &self.id_pool[path.item.id];
+        let msg = format!("لم يتم العثور على الاسم `{}` في هذا النطاق", name);
+        let span = path_span(path);
+
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_error(span, vec!["هذا الاسم غير معرّف في هذا النطاق".to_string()]);
+
+        self.diagnostics
+            .push(Diagnostic::error_with_code(306, msg, vec![code_window]));
+    }
+
+    fn find_item(&self, path: &nazmc_ast::PkgPathWithItem) -> Option<ItemInFile> {
+        if path.pkg_path.ids.is_empty() {
+            return self.nrt.find_item_in_file_scope(
+                self.parsed_files,
+                self.current_pkg_idx,
+                self.current_file_idx,
+                path.item.id,
+            );
+        }
+
+        let pkg_idx = self.packages.get(&path.pkg_path.ids)?;
+        self.nrt.packages_to_items[*pkg_idx]
+            .get(&path.item.id)
+            .copied()
+    }
+}
+
+#[inline]
+fn push<T>(arena: &mut ThinVec<T>, node: T) -> usize {
+    let index = arena.len();
+    arena.push(node);
+    index
+}
+
+#[inline]
+fn path_span(path: &nazmc_ast::PkgPathWithItem) -> Span {
+    match path.pkg_path.spans.first() {
+        Some(first) => first.merged_with(&path.item.span),
+        None => path.item.span,
+    }
+}
+
+#[inline]
+fn item_to_resolved_path(kind_and_idx: FileItemKindAndIdx) -> ResolvedPath {
+    let index = kind_and_idx.index();
+    match kind_and_idx.kind() {
+        FileItemKindAndIdx::UNIT_STRUCT => ResolvedPath::UnitStruct(index),
+        FileItemKindAndIdx::TUPLE_STRUCT => ResolvedPath::TupleStruct(index),
+        FileItemKindAndIdx::FIELDS_STRUCT => ResolvedPath::FieldsStruct(index),
+        FileItemKindAndIdx::ENUM => ResolvedPath::Enum(index),
+        FileItemKindAndIdx::CONST => ResolvedPath::Const(index),
+        FileItemKindAndIdx::STATIC => ResolvedPath::Static(index),
+        FileItemKindAndIdx::FN => ResolvedPath::Fn(index),
+        _ => unreachable!(),
+    }
+}
+
+#[inline]
+fn lower_path(path: &nazmc_ast::PkgPathWithItem, resolved: ResolvedPath) -> ModPathWithItem {
+    ModPathWithItem {
+        mod_path: ModPath {
+            ids: path.pkg_path.ids.clone(),
+            spans: path.pkg_path.spans.clone(),
+        },
+        item: lower_id(path.item),
+        resolved,
+    }
+}
+
+#[inline]
+/// Checks if the only param of the lambda is the implicit `س` added by the parser,
+/// it has the span of the opening curly brace which the lambda span starts with
+fn is_implicit_param(lambda: &nazmc_ast::LambdaExpr, lambda_span: Span) -> bool {
+    match lambda.params.as_slice() {
+        [nazmc_ast::Binding {
+            kind: nazmc_ast::BindingKind::Id(id),
+            typ: None,
+        }] => id.id == PoolIdx::LAMBDA_IMPLICIT_PARAM && id.span.lo == lambda_span.lo,
+        _ => false,
+    }
+}
+
+fn lower_id(id: nazmc_ast::ASTId) -> NIRId {
+    NIRId {
+        span: id.span,
+        id: id.id,
+    }
+}
+
+#[inline]
+fn lower_vis(vis: nazmc_ast::VisModifier) -> VisModifier {
+    match vis {
+        nazmc_ast::VisModifier::Default => VisModifier::Default,
+        nazmc_ast::VisModifier::Public => VisModifier::Public,
+        nazmc_ast::VisModifier::Private => VisModifier::Private,
+    }
+}
+
+fn lower_literal(literal: &nazmc_ast::LiteralExpr) -> LiteralExpr {
+    match literal {
+        nazmc_ast::LiteralExpr::Str(pool_idx) => LiteralExpr::Str(*pool_idx),
+        nazmc_ast::LiteralExpr::Char(ch) => LiteralExpr::Char(*ch),
+        nazmc_ast::LiteralExpr::Bool(b) => LiteralExpr::Bool(*b),
+        nazmc_ast::LiteralExpr::Num(num_kind) => LiteralExpr::Num(match *num_kind {
+            nazmc_ast::NumKind::F4(f4) => NumKind::F4(f4),
+            nazmc_ast::NumKind::F8(f8) => NumKind::F8(f8),
+            nazmc_ast::NumKind::I(i) => NumKind::I(i),
+            nazmc_ast::NumKind::I1(i1) => NumKind::I1(i1),
+            nazmc_ast::NumKind::I2(i2) => NumKind::I2(i2),
+            nazmc_ast::NumKind::I4(i4) => NumKind::I4(i4),
+            nazmc_ast::NumKind::I8(i8) => NumKind::I8(i8),
+            nazmc_ast::NumKind::U(u) => NumKind::U(u),
+            nazmc_ast::NumKind::U1(u1) => NumKind::U1(u1),
+            nazmc_ast::NumKind::U2(u2) => NumKind::U2(u2),
+            nazmc_ast::NumKind::U4(u4) => NumKind::U4(u4),
+            nazmc_ast::NumKind::U8(u8) => NumKind::U8(u8),
+            nazmc_ast::NumKind::UnspecifiedInt(i) => NumKind::UnspecifiedInt(i),
+            nazmc_ast::NumKind::UnspecifiedFloat(f) => NumKind::UnspecifiedFloat(f),
+        }),
+    }
+}
+
+#[inline]
+fn lower_unary_op(op: &nazmc_ast::UnaryOp) -> UnaryOp {
+    match op {
+        nazmc_ast::UnaryOp::Minus => UnaryOp::Minus,
+        nazmc_ast::UnaryOp::LNot => UnaryOp::LNot,
+        nazmc_ast::UnaryOp::BNot => UnaryOp::BNot,
+        nazmc_ast::UnaryOp::Deref => UnaryOp::Deref,
+        nazmc_ast::UnaryOp::Borrow => UnaryOp::Borrow,
+        nazmc_ast::UnaryOp::BorrowMut => UnaryOp::BorrowMut,
+    }
+}
+
+#[inline]
+fn lower_bin_op(op: &nazmc_ast::BinOp) -> BinOp {
+    match op {
+        nazmc_ast::BinOp::LOr => BinOp::LOr,
+        nazmc_ast::BinOp::LAnd => BinOp::LAnd,
+        nazmc_ast::BinOp::EqualEqual => BinOp::EqualEqual,
+        nazmc_ast::BinOp::NotEqual => BinOp::NotEqual,
+        nazmc_ast::BinOp::GE => BinOp::GE,
+        nazmc_ast::BinOp::GT => BinOp::GT,
+        nazmc_ast::BinOp::LE => BinOp::LE,
+        nazmc_ast::BinOp::LT => BinOp::LT,
+        nazmc_ast::BinOp::OpenOpenRange => BinOp::OpenOpenRange,
+        nazmc_ast::BinOp::CloseOpenRange => BinOp::CloseOpenRange,
+        nazmc_ast::BinOp::OpenCloseRange => BinOp::OpenCloseRange,
+        nazmc_ast::BinOp::CloseCloseRange => BinOp::CloseCloseRange,
+        nazmc_ast::BinOp::BOr => BinOp::BOr,
+        nazmc_ast::BinOp::Xor => BinOp::Xor,
+        nazmc_ast::BinOp::BAnd => BinOp::BAnd,
+        nazmc_ast::BinOp::Shr => BinOp::Shr,
+        nazmc_ast::BinOp::Shl => BinOp::Shl,
+        nazmc_ast::BinOp::Plus => BinOp::Plus,
+        nazmc_ast::BinOp::Minus => BinOp::Minus,
+        nazmc_ast::BinOp::Times => BinOp::Times,
+        nazmc_ast::BinOp::Div => BinOp::Div,
+        nazmc_ast::BinOp::Mod => BinOp::Mod,
+        nazmc_ast::BinOp::Assign => BinOp::Assign,
+        nazmc_ast::BinOp::PlusAssign => BinOp::PlusAssign,
+        nazmc_ast::BinOp::MinusAssign => BinOp::MinusAssign,
+        nazmc_ast::BinOp::TimesAssign => BinOp::TimesAssign,
+        nazmc_ast::BinOp::DivAssign => BinOp::DivAssign,
+        nazmc_ast::BinOp::ModAssign => BinOp::ModAssign,
+        nazmc_ast::BinOp::BAndAssign => BinOp::BAndAssign,
+        nazmc_ast::BinOp::BOrAssign => BinOp::BOrAssign,
+        nazmc_ast::BinOp::XorAssign => BinOp::XorAssign,
+        nazmc_ast::BinOp::ShlAssign => BinOp::ShlAssign,
+        nazmc_ast::BinOp::ShrAssign => BinOp::ShrAssign,
+    }
+}
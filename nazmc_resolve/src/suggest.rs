@@ -0,0 +1,110 @@
+use nazmc_data_pool::{Built, DataPool, PoolIdx};
+
+/// Normalizes the Arabic letters that are commonly confused when typing, so they are compared as
+/// the same letter: the hamza forms of alef, waw and yaa, taa marbuta and haa, alef maqsura and yaa.
+/// The diacritics and the tatweel are removed as they don't change the name to the reader
+pub(crate) fn normalize_arabic(name: &str) -> String {
+    name.chars()
+        .filter_map(|ch| match ch {
+            'أ' | 'إ' | 'آ' | 'ٱ' => Some('ا'),
+            'ؤ' => Some('و'),
+            'ئ' | 'ى' => Some('ي'),
+            'ة' => Some('ه'),
+            'ء' | 'ـ' | '\u{064B}'..='\u{0652}' | '\u{0670}' => None,
+            _ => Some(ch),
+        })
+        .collect()
+}
+
+/// The Levenshtein distance between the two names counted in chars
+pub(crate) fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+    let mut row = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev_row[j] + (ca != cb) as usize;
+            row[j + 1] = substitution.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev_row, &mut row);
+    }
+
+    prev_row[b.len()]
+}
+
+/// Finds the candidate which is the most similar to the name after normalizing both of them,
+/// a candidate is similar if it is at most one edit away for each three letters of the name
+pub(crate) fn find_similar_name(
+    id_pool: &DataPool<Built>,
+    id: PoolIdx,
+    candidates: impl IntoIterator<Item = PoolIdx>,
+) -> Option<PoolIdx> {
+    let name = normalize_arabic(&id_pool[id]).chars().collect::<Vec<_>>();
+    let max_distance = (name.len() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != id && *candidate != PoolIdx::UNDERSCORE)
+        .filter_map(|candidate| {
+            let candidate_name = normalize_arabic(&id_pool[candidate])
+                .chars()
+                .collect::<Vec<_>>();
+            let distance = edit_distance(&name, &candidate_name);
+            (distance <= max_distance).then_some((distance, &id_pool[candidate], candidate))
+        })
+        .min()
+        .map(|(_, _, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &str, b: &str) -> usize {
+        let a = normalize_arabic(a).chars().collect::<Vec<_>>();
+        let b = normalize_arabic(b).chars().collect::<Vec<_>>();
+        edit_distance(&a, &b)
+    }
+
+    #[test]
+    fn test_normalize_arabic() {
+        assert_eq!(normalize_arabic("أحمد"), normalize_arabic("احمد"));
+        assert_eq!(normalize_arabic("إسلام"), "اسلام");
+        assert_eq!(normalize_arabic("مدرسة"), "مدرسه");
+        assert_eq!(normalize_arabic("مستشفى"), "مستشفي");
+        assert_eq!(normalize_arabic("مسؤول"), "مسوول");
+        assert_eq!(normalize_arabic("شيء"), normalize_arabic("شئ"));
+        assert_eq!(normalize_arabic("كَتَبَ"), "كتب");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(distance("اجمع", "اجمع"), 0);
+        assert_eq!(distance("أجمع", "اجمع"), 0);
+        assert_eq!(distance("اجمع", "اطرح"), 3);
+        assert_eq!(distance("اجمع", "اجع"), 1);
+        assert_eq!(distance("", "نظم"), 3);
+    }
+
+    #[test]
+    fn test_find_similar_name() {
+        let mut pool = DataPool::new();
+        // The reserved ids
+        for name in ["البداية", "س", "اظهر", "اظهر_", "_"] {
+            pool.get(name);
+        }
+        let typo = pool.get("مساحه");
+        let area = pool.get("مساحة");
+        let circle = pool.get("دائرة");
+        let other = pool.get("عنصر_بعيد");
+        let pool = pool.build();
+
+        assert_eq!(
+            find_similar_name(&pool, typo, [circle, area, other]),
+            Some(area)
+        );
+        assert_eq!(find_similar_name(&pool, typo, [circle, other]), None);
+        assert_eq!(find_similar_name(&pool, area, [area]), None);
+    }
+}
@@ -0,0 +1,485 @@
+use crate::{ty::TyVarKind, Ty, TypeChecker};
+use nazmc_data_pool::PoolIdx;
+use nazmc_diagnostics::span::Span;
+use nazmc_nir::*;
+use std::cmp::Ordering;
+use thin_vec::ThinVec;
+
+/// The value of a constant or a static which is computed before running the program
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConstValue {
+    Unit,
+    /// The value is stored in a wider int, and it is always kept in the range of its type
+    Int(i128, PrimitiveType),
+    /// The value of `ع4` is stored in a wider float, and it is rounded after each operation
+    Float(f64, PrimitiveType),
+    Bool(bool),
+    Char(char),
+    Str(PoolIdx),
+    Tuple(ThinVec<ConstValue>),
+    Array(ThinVec<ConstValue>),
+    /// The value of an invalid expression which was already reported
+    Error,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum EvalState {
+    NotEvaluated,
+    /// The item is being evaluated, so using it again means it depends on itself
+    InProgress,
+    Evaluated,
+}
+
+impl<'a> TypeChecker<'a> {
+    /// Evaluates the constant or the static if it is not evaluated yet, and returns its type.
+    /// The path is where it's used, it's given when it's used by another item to report the cycles
+    pub(crate) fn eval_const_item(
+        &mut self,
+        item: ResolvedPath,
+        used_at: Option<&ModPathWithItem>,
+    ) -> Ty {
+        let (decl, state, is_static) = match item {
+            ResolvedPath::Const(idx) => (&self.nir.consts[idx], self.consts_states[idx], false),
+            ResolvedPath::Static(idx) => (&self.nir.statics[idx], self.statics_states[idx], true),
+            _ => unreachable!(),
+        };
+
+        match state {
+            EvalState::Evaluated => return self.const_item_ty(item),
+            EvalState::InProgress => {
+                if let Some(path) = used_at {
+                    let kind_str = if is_static {
+                        "المتغير المشترك"
+                    } else {
+                        "الثابت"
+                    };
+                    let msg = format!(
+                        "تعريف {} `{}` يعتمد على نفسه",
+                        kind_str, &self.id_pool[decl.name.id]
+                    );
+                    self.add_err(msg, path.item.span, vec![]);
+                }
+                return Ty::Error;
+            }
+            EvalState::NotEvaluated => {}
+        }
+
+        self.set_const_item_state(item, EvalState::InProgress);
+
+        let file_idx = self.current_file_idx;
+        self.current_file_idx = decl.file_idx;
+
+        let ty = self.lower_type(&decl.typ);
+        self.set_const_item_ty(item, ty.clone());
+
+        let value = match self.const_expr_err(&decl.expr, is_static) {
+            Some((span, msg)) => {
+                self.add_err(msg, span, vec![]);
+                ConstValue::Error
+            }
+            None => {
+                self.check_expr_against(&decl.expr, &ty);
+                self.eval_const_expr(&decl.expr)
+            }
+        };
+
+        match item {
+            ResolvedPath::Const(idx) => {
+                self.table.consts_values[idx] = value;
+                self.consts_states[idx] = EvalState::Evaluated;
+            }
+            ResolvedPath::Static(idx) => {
+                self.table.statics_values[idx] = value;
+                self.statics_states[idx] = EvalState::Evaluated;
+            }
+            _ => unreachable!(),
+        }
+
+        self.current_file_idx = file_idx;
+
+        ty
+    }
+
+    fn const_item_ty(&self, item: ResolvedPath) -> Ty {
+        match item {
+            ResolvedPath::Const(idx) => self.table.consts[idx].clone(),
+            ResolvedPath::Static(idx) => self.table.statics[idx].clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_const_item_ty(&mut self, item: ResolvedPath, ty: Ty) {
+        match item {
+            ResolvedPath::Const(idx) => self.table.consts[idx] = ty,
+            ResolvedPath::Static(idx) => self.table.statics[idx] = ty,
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_const_item_state(&mut self, item: ResolvedPath, state: EvalState) {
+        match item {
+            ResolvedPath::Const(idx) => self.consts_states[idx] = state,
+            ResolvedPath::Static(idx) => self.statics_states[idx] = state,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Array sizes must be known before running the program,
+    /// so they accept only the expressions which could be computed at compile time
+    pub(crate) fn eval_array_size(&mut self, size: &Expr) -> u64 {
+        if self.const_expr_err(size, false).is_some() {
+            self.add_err(
+                "يجب أن يكون حجم المصفوفة عددًا طبيعيًا ثابتًا".to_string(),
+                size.span,
+                vec![],
+            );
+            self.table.exprs.insert(size.kind_and_index, Ty::Error);
+            return 0;
+        }
+
+        self.check_expr_against(size, &Ty::Primitive(PrimitiveType::U));
+
+        match self.eval_const_expr(size) {
+            ConstValue::Int(value, _) => value as u64,
+            _ => 0,
+        }
+    }
+
+    /// Returns the span and the error message of the first part of the expression
+    /// which could not be computed at compile time
+    fn const_expr_err(&self, expr: &Expr, allow_statics: bool) -> Option<(Span, String)> {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        let not_const = || {
+            Some((
+                expr.span,
+                "لا يمكن حساب هذا التعبير أثناء الترجمة".to_string(),
+            ))
+        };
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT | ExprKindAndIndex::LITERAL => None,
+            ExprKindAndIndex::PARENS => {
+                self.const_expr_err(&exprs.parens[index].expr, allow_statics)
+            }
+            ExprKindAndIndex::PATH => {
+                let path = &exprs.paths[index];
+                match path.resolved {
+                    ResolvedPath::Const(_) => None,
+                    ResolvedPath::Static(_) if allow_statics => None,
+                    ResolvedPath::Static(_) => Some((
+                        path.item.span,
+                        format!(
+                            "لا يمكن استخدام المتغير المشترك `{}` في تعريف ثابت",
+                            &self.id_pool[path.item.id]
+                        ),
+                    )),
+                    _ => not_const(),
+                }
+            }
+            ExprKindAndIndex::TUPLE_EXPR => exprs.tuples[index]
+                .elements
+                .iter()
+                .find_map(|element| self.const_expr_err(element, allow_statics)),
+            ExprKindAndIndex::ARRAY_ELEMENTS => exprs.array_elements[index]
+                .elements
+                .iter()
+                .find_map(|element| self.const_expr_err(element, allow_statics)),
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                self.const_expr_err(&exprs.array_elements_sized[index].repeat, allow_statics)
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                match unary.op {
+                    UnaryOp::Minus | UnaryOp::LNot | UnaryOp::BNot => {
+                        self.const_expr_err(&unary.expr, allow_statics)
+                    }
+                    _ => not_const(),
+                }
+            }
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                match bin.op {
+                    BinOp::LOr
+                    | BinOp::LAnd
+                    | BinOp::EqualEqual
+                    | BinOp::NotEqual
+                    | BinOp::GE
+                    | BinOp::GT
+                    | BinOp::LE
+                    | BinOp::LT
+                    | BinOp::BOr
+                    | BinOp::Xor
+                    | BinOp::BAnd
+                    | BinOp::Shr
+                    | BinOp::Shl
+                    | BinOp::Plus
+                    | BinOp::Minus
+                    | BinOp::Times
+                    | BinOp::Div
+                    | BinOp::Mod => self
+                        .const_expr_err(&bin.left, allow_statics)
+                        .or_else(|| self.const_expr_err(&bin.right, allow_statics)),
+                    _ => not_const(),
+                }
+            }
+            _ => not_const(),
+        }
+    }
+
+    /// Computes the value of a checked expression which is accepted by `const_expr_err`
+    fn eval_const_expr(&mut self, expr: &Expr) -> ConstValue {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT => ConstValue::Unit,
+            ExprKindAndIndex::LITERAL => match &exprs.literals[index] {
+                LiteralExpr::Str(idx) => ConstValue::Str(*idx),
+                LiteralExpr::Char(c) => ConstValue::Char(*c),
+                LiteralExpr::Bool(b) => ConstValue::Bool(*b),
+                LiteralExpr::Num(num_kind) => match *num_kind {
+                    NumKind::F4(f) => ConstValue::Float(f as f64, PrimitiveType::F4),
+                    NumKind::F8(f) => ConstValue::Float(f, PrimitiveType::F8),
+                    NumKind::I(i) => ConstValue::Int(i as i128, PrimitiveType::I),
+                    NumKind::I1(i) => ConstValue::Int(i as i128, PrimitiveType::I1),
+                    NumKind::I2(i) => ConstValue::Int(i as i128, PrimitiveType::I2),
+                    NumKind::I4(i) => ConstValue::Int(i as i128, PrimitiveType::I4),
+                    NumKind::I8(i) => ConstValue::Int(i as i128, PrimitiveType::I8),
+                    NumKind::U(u) => ConstValue::Int(u as i128, PrimitiveType::U),
+                    NumKind::U1(u) => ConstValue::Int(u as i128, PrimitiveType::U1),
+                    NumKind::U2(u) => ConstValue::Int(u as i128, PrimitiveType::U2),
+                    NumKind::U4(u) => ConstValue::Int(u as i128, PrimitiveType::U4),
+                    NumKind::U8(u) => ConstValue::Int(u as i128, PrimitiveType::U8),
+                    // The values which do not fit in their types are reported after inference
+                    NumKind::UnspecifiedInt(u) => match self.const_primitive(expr) {
+                        Some(p) if p.is_float() => ConstValue::Float(fit_float(u as f64, p), p),
+                        Some(p) => ConstValue::Int(wrap_int(u as i128, p), p),
+                        None => ConstValue::Error,
+                    },
+                    NumKind::UnspecifiedFloat(f) => match self.const_primitive(expr) {
+                        Some(p) => ConstValue::Float(fit_float(f, p), p),
+                        None => ConstValue::Error,
+                    },
+                },
+            },
+            ExprKindAndIndex::PARENS => self.eval_const_expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => match exprs.paths[index].resolved {
+                // The items which depend on themselves are not evaluated yet
+                ResolvedPath::Const(idx) if self.consts_states[idx] == EvalState::Evaluated => {
+                    self.table.consts_values[idx].clone()
+                }
+                ResolvedPath::Static(idx) if self.statics_states[idx] == EvalState::Evaluated => {
+                    self.table.statics_values[idx].clone()
+                }
+                _ => ConstValue::Error,
+            },
+            ExprKindAndIndex::TUPLE_EXPR => ConstValue::Tuple(
+                exprs.tuples[index]
+                    .elements
+                    .iter()
+                    .map(|element| self.eval_const_expr(element))
+                    .collect(),
+            ),
+            ExprKindAndIndex::ARRAY_ELEMENTS => ConstValue::Array(
+                exprs.array_elements[index]
+                    .elements
+                    .iter()
+                    .map(|element| self.eval_const_expr(element))
+                    .collect(),
+            ),
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let repeat = self.eval_const_expr(&exprs.array_elements_sized[index].repeat);
+                match self.resolve(self.table.expr_ty(expr)) {
+                    Ty::Array(_, size) => {
+                        ConstValue::Array((0..size).map(|_| repeat.clone()).collect())
+                    }
+                    _ => ConstValue::Error,
+                }
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                match (unary.op, self.eval_const_expr(&unary.expr)) {
+                    (UnaryOp::Minus, ConstValue::Int(i, p)) => {
+                        self.check_const_int(-i, p, expr.span)
+                    }
+                    (UnaryOp::Minus, ConstValue::Float(f, p)) => ConstValue::Float(-f, p),
+                    (UnaryOp::LNot, ConstValue::Bool(b)) => ConstValue::Bool(!b),
+                    (UnaryOp::BNot, ConstValue::Int(i, p)) => ConstValue::Int(wrap_int(!i, p), p),
+                    _ => ConstValue::Error,
+                }
+            }
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                let left = self.eval_const_expr(&bin.left);
+                let right = self.eval_const_expr(&bin.right);
+                self.eval_const_bin(bin.op, left, right, expr.span)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn eval_const_bin(
+        &mut self,
+        op: BinOp,
+        left: ConstValue,
+        right: ConstValue,
+        span: Span,
+    ) -> ConstValue {
+        if left == ConstValue::Error || right == ConstValue::Error {
+            return ConstValue::Error;
+        }
+
+        match op {
+            BinOp::LOr | BinOp::LAnd => match (left, right) {
+                (ConstValue::Bool(l), ConstValue::Bool(r)) if op == BinOp::LOr => {
+                    ConstValue::Bool(l || r)
+                }
+                (ConstValue::Bool(l), ConstValue::Bool(r)) => ConstValue::Bool(l && r),
+                _ => ConstValue::Error,
+            },
+            BinOp::EqualEqual | BinOp::NotEqual | BinOp::GE | BinOp::GT | BinOp::LE | BinOp::LT => {
+                let ordering = match (&left, &right) {
+                    (ConstValue::Int(l, _), ConstValue::Int(r, _)) => l.partial_cmp(r),
+                    (ConstValue::Float(l, _), ConstValue::Float(r, _)) => l.partial_cmp(r),
+                    (ConstValue::Bool(l), ConstValue::Bool(r)) => l.partial_cmp(r),
+                    (ConstValue::Char(l), ConstValue::Char(r)) => l.partial_cmp(r),
+                    (ConstValue::Unit, ConstValue::Unit) => Some(Ordering::Equal),
+                    _ => {
+                        self.add_err(
+                            "لا يمكن حساب هذا التعبير أثناء الترجمة".to_string(),
+                            span,
+                            vec![],
+                        );
+                        return ConstValue::Error;
+                    }
+                };
+                let result = match op {
+                    BinOp::EqualEqual => ordering == Some(Ordering::Equal),
+                    BinOp::NotEqual => ordering != Some(Ordering::Equal),
+                    BinOp::GE => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    BinOp::GT => ordering == Some(Ordering::Greater),
+                    BinOp::LE => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    _ => ordering == Some(Ordering::Less),
+                };
+                ConstValue::Bool(result)
+            }
+            _ => match (left, right) {
+                (ConstValue::Int(l, p), ConstValue::Int(r, _)) => match op {
+                    BinOp::Plus => self.check_const_int(l + r, p, span),
+                    BinOp::Minus => self.check_const_int(l - r, p, span),
+                    BinOp::Times => self.check_const_int(l * r, p, span),
+                    BinOp::Div | BinOp::Mod if r == 0 => {
+                        self.add_err("لا يمكن القسمة على صفر".to_string(), span, vec![]);
+                        ConstValue::Error
+                    }
+                    BinOp::Div => self.check_const_int(l / r, p, span),
+                    BinOp::Mod => self.check_const_int(l % r, p, span),
+                    BinOp::BAnd => ConstValue::Int(l & r, p),
+                    BinOp::BOr => ConstValue::Int(l | r, p),
+                    BinOp::Xor => ConstValue::Int(l ^ r, p),
+                    BinOp::Shl | BinOp::Shr => {
+                        if r < 0 || r >= int_bits(p) as i128 {
+                            let msg = format!(
+                                "لا يمكن إزاحة النوع `{}` بمقدار {} من البتات",
+                                p.name(),
+                                self.numerals.fmt(r)
+                            );
+                            self.add_err(msg, span, vec![]);
+                            ConstValue::Error
+                        } else if op == BinOp::Shl {
+                            ConstValue::Int(wrap_int(l << r, p), p)
+                        } else {
+                            ConstValue::Int(l >> r, p)
+                        }
+                    }
+                    _ => unreachable!(),
+                },
+                (ConstValue::Float(l, p), ConstValue::Float(r, _)) => {
+                    let value = match op {
+                        BinOp::Plus => l + r,
+                        BinOp::Minus => l - r,
+                        BinOp::Times => l * r,
+                        BinOp::Div => l / r,
+                        BinOp::Mod => l % r,
+                        _ => return ConstValue::Error,
+                    };
+                    ConstValue::Float(fit_float(value, p), p)
+                }
+                _ => ConstValue::Error,
+            },
+        }
+    }
+
+    /// Reports the overflow if the value does not fit in the int type
+    fn check_const_int(&mut self, value: i128, typ: PrimitiveType, span: Span) -> ConstValue {
+        let (min, max) = int_bounds(typ);
+        if value < min || value > max {
+            let msg = format!("حدث فائض في العملية الحسابية للنوع `{}`", typ.name());
+            self.add_err(msg, span, vec![]);
+            return ConstValue::Error;
+        }
+        ConstValue::Int(value, typ)
+    }
+
+    /// The primitive type of the expression, the unbound int and float variables
+    /// will have the default types after inference
+    fn const_primitive(&self, expr: &Expr) -> Option<PrimitiveType> {
+        match self.resolve(self.table.expr_ty(expr)) {
+            Ty::Primitive(p) => Some(p),
+            Ty::Infer(var) => match self.vars[var].kind {
+                TyVarKind::Int => Some(PrimitiveType::I4),
+                TyVarKind::Float => Some(PrimitiveType::F8),
+                TyVarKind::Any => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The bounds of the int types, `ص` and `م` are 64-bit wide
+pub(crate) fn int_bounds(typ: PrimitiveType) -> (i128, i128) {
+    match typ {
+        PrimitiveType::I1 => (i8::MIN as i128, i8::MAX as i128),
+        PrimitiveType::I2 => (i16::MIN as i128, i16::MAX as i128),
+        PrimitiveType::I4 => (i32::MIN as i128, i32::MAX as i128),
+        PrimitiveType::I | PrimitiveType::I8 => (i64::MIN as i128, i64::MAX as i128),
+        PrimitiveType::U1 => (0, u8::MAX as i128),
+        PrimitiveType::U2 => (0, u16::MAX as i128),
+        PrimitiveType::U4 => (0, u32::MAX as i128),
+        PrimitiveType::U | PrimitiveType::U8 => (0, u64::MAX as i128),
+        _ => unreachable!("Not an int type"),
+    }
+}
+
+/// The count of bits in the int type
+fn int_bits(typ: PrimitiveType) -> u32 {
+    match typ {
+        PrimitiveType::I1 | PrimitiveType::U1 => 8,
+        PrimitiveType::I2 | PrimitiveType::U2 => 16,
+        PrimitiveType::I4 | PrimitiveType::U4 => 32,
+        _ => 64,
+    }
+}
+
+/// Truncates the value to the bits of the int type, like casting in C
+fn wrap_int(value: i128, typ: PrimitiveType) -> i128 {
+    let bits = int_bits(typ);
+    let truncated = value & ((1i128 << bits) - 1);
+    if typ.is_signed_int() && truncated >> (bits - 1) == 1 {
+        truncated - (1i128 << bits)
+    } else {
+        truncated
+    }
+}
+
+/// Rounds the value of `ع4` to the nearest 32-bit float
+#[inline]
+fn fit_float(value: f64, typ: PrimitiveType) -> f64 {
+    if typ == PrimitiveType::F4 {
+        value as f32 as f64
+    } else {
+        value
+    }
+}
@@ -0,0 +1,759 @@
+use crate::{
+    ty::{NumClass, TyVarKind},
+    Ty, TypeChecker,
+};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic};
+use nazmc_nir::*;
+use thin_vec::ThinVec;
+
+impl<'a> TypeChecker<'a> {
+    /// Checks the expression and stores its type in the table
+    pub(crate) fn check_expr(&mut self, expr: &Expr) -> Ty {
+        let ty = self.infer_expr(expr);
+        self.table.exprs.insert(expr.kind_and_index, ty.clone());
+        ty
+    }
+
+    pub(crate) fn check_expr_against(&mut self, expr: &Expr, expected: &Ty) -> Ty {
+        let ty = self.check_expr(expr);
+        self.expect_ty(&ty, expected, expr.span);
+        ty
+    }
+
+    fn infer_expr(&mut self, expr: &Expr) -> Ty {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT => Ty::Unit,
+            ExprKindAndIndex::LITERAL => self.infer_literal(&exprs.literals[index], expr.span),
+            ExprKindAndIndex::PARENS => self.check_expr(&exprs.parens[index].expr),
+            ExprKindAndIndex::PATH => self.infer_path(&exprs.paths[index]),
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                if let ResolvedPath::Fn(fn_idx) = call.path.resolved {
+                    let (params, return_type) = self.table.fns_signatures[fn_idx].clone();
+                    self.check_args(&params, &call.args, call.parens_span);
+                    return_type
+                } else if let ResolvedPath::Builtin(_) = call.path.resolved {
+                    // The output fns accept a value of any type
+                    let param = self.new_var(TyVarKind::Any, call.parens_span);
+                    self.check_args(&[param], &call.args, call.parens_span);
+                    Ty::Unit
+                } else {
+                    let on = self.infer_path(&call.path);
+                    self.check_call_on(on, &call.args, call.parens_span)
+                }
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                let on = self.check_expr(&call.on);
+                self.check_call_on(on, &call.args, call.parens_span)
+            }
+            ExprKindAndIndex::UNIT_STRUCT => {
+                let path = &exprs.unit_structs[index];
+                match path.resolved {
+                    ResolvedPath::UnitStruct(idx) => Ty::UnitStruct(idx),
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Unit
+                        ) =>
+                    {
+                        Ty::Enum(enum_idx)
+                    }
+                    _ => self.add_struct_kind_err(path, "إنشاء"),
+                }
+            }
+            ExprKindAndIndex::TUPLE_STRUCT => {
+                let tuple_struct = &exprs.tuple_structs[index];
+                match tuple_struct.path.resolved {
+                    ResolvedPath::TupleStruct(idx) => {
+                        let types = self.table.tuple_structs[idx].clone();
+                        self.check_args(&types, &tuple_struct.args, expr.span);
+                        Ty::TupleStruct(idx)
+                    }
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Tuple(_)
+                        ) =>
+                    {
+                        let types = self.table.enums[enum_idx][variant_idx].clone();
+                        self.check_args(&types, &tuple_struct.args, expr.span);
+                        Ty::Enum(enum_idx)
+                    }
+                    _ => {
+                        for arg in &tuple_struct.args {
+                            self.check_expr(arg);
+                        }
+                        self.add_struct_kind_err(&tuple_struct.path, "إنشاء")
+                    }
+                }
+            }
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                let fields_struct = &exprs.fields_structs[index];
+                match fields_struct.path.resolved {
+                    ResolvedPath::FieldsStruct(idx) => {
+                        self.check_fields_struct_expr(fields_struct);
+                        Ty::FieldsStruct(idx)
+                    }
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Fields(_)
+                        ) =>
+                    {
+                        self.check_fields_struct_expr(fields_struct);
+                        Ty::Enum(enum_idx)
+                    }
+                    _ => {
+                        for field in &fields_struct.fields {
+                            self.check_expr(&field.expr);
+                        }
+                        self.add_struct_kind_err(&fields_struct.path, "إنشاء")
+                    }
+                }
+            }
+            ExprKindAndIndex::FIELD => {
+                let field = &exprs.fields[index];
+                let on = self.check_expr(&field.on);
+                self.infer_field(on, field)
+            }
+            ExprKindAndIndex::INDEX => {
+                let index = &exprs.indexes[index];
+                let on = self.check_expr(&index.on);
+                let idx_ty = self.new_var(TyVarKind::Int, index.idx.span);
+                self.check_expr_against(&index.idx, &idx_ty);
+
+                match self.auto_deref(on) {
+                    Ty::Array(ty, _) | Ty::Slice(ty) => *ty,
+                    Ty::Error => Ty::Error,
+                    found => {
+                        let msg = format!(
+                            "لا يمكن الوصول إلى عناصر النوع `{}` باستخدام الفهرس",
+                            self.fmt_ty(&found)
+                        );
+                        self.add_err(msg, index.on.span, vec![]);
+                        Ty::Error
+                    }
+                }
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => {
+                let elements = &exprs.array_elements[index].elements;
+                let ty = self.new_var(TyVarKind::Any, expr.span);
+                for element in elements {
+                    self.check_expr_against(element, &ty);
+                }
+                Ty::Array(Box::new(ty), elements.len() as u64)
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let array = &exprs.array_elements_sized[index];
+                let ty = self.check_expr(&array.repeat);
+                let size = self.eval_array_size(&array.size);
+                Ty::Array(Box::new(ty), size)
+            }
+            ExprKindAndIndex::TUPLE_EXPR => Ty::Tuple(
+                exprs.tuples[index]
+                    .elements
+                    .iter()
+                    .map(|element| self.check_expr(element))
+                    .collect(),
+            ),
+            ExprKindAndIndex::RETURN_WITH_VALUE => {
+                let return_type = self.return_types.last().unwrap().clone();
+                self.check_expr_against(&exprs.returns[index].expr_to_return, &return_type);
+                Ty::Never
+            }
+            ExprKindAndIndex::RETURN => {
+                let return_type = self.return_types.last().unwrap().clone();
+                if !self.unify(&Ty::Unit, &return_type) {
+                    let msg = format!("يُتوقع إرجاع قيمة من النوع `{}`", self.fmt_ty(&return_type));
+                    self.add_err(msg, expr.span, vec![]);
+                }
+                Ty::Never
+            }
+            ExprKindAndIndex::BREAK | ExprKindAndIndex::CONTINUE => {
+                self.check_in_loop(expr);
+                Ty::Never
+            }
+            ExprKindAndIndex::BREAK_WITH_VALUE => {
+                self.check_expr(&exprs.breaks[index].expr_to_break);
+                if self.check_in_loop(expr) {
+                    self.add_err(
+                        "لا يمكن إرجاع قيمة من حلقة `طالما` باستخدام `قطع`".to_string(),
+                        expr.span,
+                        vec![],
+                    );
+                }
+                Ty::Never
+            }
+            ExprKindAndIndex::IF_EXPR => self.check_if(&exprs.ifs[index], Some(expr.span)),
+            ExprKindAndIndex::WHEN_EXPR => self.check_when(&exprs.whens[index], Some(expr.span)),
+            ExprKindAndIndex::LAMBDA_EXPR => {
+                let lambda = &exprs.lambdas[index];
+
+                let params = lambda
+                    .params
+                    .iter()
+                    .map(|binding| {
+                        let ty = match &binding.typ {
+                            Some(typ) => self.lower_type(typ),
+                            None => {
+                                let span = self.binding_kind_span(&binding.kind);
+                                self.new_var(TyVarKind::Any, span)
+                            }
+                        };
+                        self.bind(&binding.kind, ty.clone());
+                        ty
+                    })
+                    .collect();
+
+                let return_type = self.new_var(TyVarKind::Any, expr.span);
+
+                self.return_types.push(return_type.clone());
+                self.loops_depths.push(0);
+
+                self.check_scope_against(lambda.body, &return_type, expr.span);
+
+                self.return_types.pop();
+                self.loops_depths.pop();
+
+                Ty::Lambda(params, Box::new(return_type))
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                self.infer_unary_expr(unary)
+            }
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                self.infer_bin_expr(bin, expr.span)
+            }
+            ExprKindAndIndex::ON => {
+                self.add_err(
+                    "لا يمكن استخدام `على` خارج الدوال التابعة للأنواع".to_string(),
+                    expr.span,
+                    vec![],
+                );
+                Ty::Error
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn infer_literal(&mut self, literal: &LiteralExpr, span: Span) -> Ty {
+        let p = match literal {
+            LiteralExpr::Str(_) => return Ty::Ref(Box::new(Ty::Primitive(PrimitiveType::Str))),
+            LiteralExpr::Char(_) => PrimitiveType::Char,
+            LiteralExpr::Bool(_) => PrimitiveType::Bool,
+            LiteralExpr::Num(num_kind) => match num_kind {
+                NumKind::F4(_) => PrimitiveType::F4,
+                NumKind::F8(_) => PrimitiveType::F8,
+                NumKind::I(_) => PrimitiveType::I,
+                NumKind::I1(_) => PrimitiveType::I1,
+                NumKind::I2(_) => PrimitiveType::I2,
+                NumKind::I4(_) => PrimitiveType::I4,
+                NumKind::I8(_) => PrimitiveType::I8,
+                NumKind::U(_) => PrimitiveType::U,
+                NumKind::U1(_) => PrimitiveType::U1,
+                NumKind::U2(_) => PrimitiveType::U2,
+                NumKind::U4(_) => PrimitiveType::U4,
+                NumKind::U8(_) => PrimitiveType::U8,
+                NumKind::UnspecifiedInt(value) => {
+                    let ty = self.new_var(TyVarKind::Int, span);
+                    self.unspecified_ints.push((
+                        *value,
+                        false,
+                        ty.clone(),
+                        self.current_file_idx,
+                        span,
+                    ));
+                    return ty;
+                }
+                NumKind::UnspecifiedFloat(_) => return self.new_var(TyVarKind::Float, span),
+            },
+        };
+
+        Ty::Primitive(p)
+    }
+
+    fn infer_path(&mut self, path: &ModPathWithItem) -> Ty {
+        match path.resolved {
+            ResolvedPath::Local(local_idx) => self.table.locals[local_idx].clone(),
+            ResolvedPath::Fn(fn_idx) => {
+                let (params, return_type) = self.table.fns_signatures[fn_idx].clone();
+                Ty::Lambda(params, Box::new(return_type))
+            }
+            ResolvedPath::UnitStruct(idx) => Ty::UnitStruct(idx),
+            ResolvedPath::Const(_) | ResolvedPath::Static(_) => {
+                self.eval_const_item(path.resolved, Some(path))
+            }
+            ResolvedPath::Builtin(_) => {
+                let name = &self.id_pool[path.item.id];
+                let msg = format!("لا يمكن استخدام الدالة المدمجة `{}` إلا باستدعائها", name);
+                self.add_err(msg, path.item.span, vec![]);
+                Ty::Error
+            }
+            ResolvedPath::TupleStruct(_) | ResolvedPath::FieldsStruct(_) => {
+                let name = &self.id_pool[path.item.id];
+                let msg = format!("لا يمكن استخدام الهيكل `{}` كقيمة", name);
+                self.add_err(msg, path.item.span, vec![]);
+                Ty::Error
+            }
+            ResolvedPath::Enum(_) => {
+                let name = &self.id_pool[path.item.id];
+                let msg = format!("لا يمكن استخدام التصنيف `{}` كقيمة", name);
+                self.add_err(msg, path.item.span, vec![]);
+                Ty::Error
+            }
+            ResolvedPath::Variant(_, _) => {
+                unreachable!("The resolver only accepts variants in struct expressions and patterns")
+            }
+        }
+    }
+
+    fn check_call_on(&mut self, on: Ty, args: &[Expr], parens_span: Span) -> Ty {
+        match self.resolve(&on) {
+            Ty::Lambda(params, return_type) => {
+                self.check_args(&params, args, parens_span);
+                *return_type
+            }
+            Ty::Infer(var) if self.vars[var].kind == TyVarKind::Any => {
+                let params = args
+                    .iter()
+                    .map(|arg| self.check_expr(arg))
+                    .collect::<ThinVec<_>>();
+                let return_type = self.new_var(TyVarKind::Any, parens_span);
+                self.unify(&on, &Ty::Lambda(params, Box::new(return_type.clone())));
+                return_type
+            }
+            Ty::Error => {
+                for arg in args {
+                    self.check_expr(arg);
+                }
+                Ty::Error
+            }
+            found => {
+                for arg in args {
+                    self.check_expr(arg);
+                }
+                let msg = format!("لا يمكن استدعاء قيمة من النوع `{}`", self.fmt_ty(&found));
+                self.add_err(msg, parens_span, vec![]);
+                Ty::Error
+            }
+        }
+    }
+
+    fn check_args(&mut self, params: &[Ty], args: &[Expr], span: Span) {
+        if params.len() != args.len() {
+            let msg = format!(
+                "يُتوقع {} من المُعاملات، ولكن تم العثور على {}",
+                self.numerals.fmt(params.len()),
+                self.numerals.fmt(args.len())
+            );
+            self.add_err(msg, span, vec![]);
+        }
+
+        for (i, arg) in args.iter().enumerate() {
+            match params.get(i) {
+                Some(param) => self.check_expr_against(arg, param),
+                None => self.check_expr(arg),
+            };
+        }
+    }
+
+    fn check_fields_struct_expr(&mut self, fields_struct: &FieldsStructExpr) {
+        let (names, types) = self.fields_of(fields_struct.path.resolved);
+        let (owner, verb) = self.fields_owner(&fields_struct.path);
+        let mut initialized: Vec<Option<Span>> = vec![None; names.len()];
+
+        for field in &fields_struct.fields {
+            let Some(field_idx) = names.iter().position(|name| name.id == field.name.id) else {
+                self.check_expr(&field.expr);
+                let msg = format!(
+                    "{} لا {} على حقل باسم `{}`",
+                    owner,
+                    verb,
+                    &self.id_pool[field.name.id]
+                );
+                self.add_err(msg, field.name.span, vec![]);
+                continue;
+            };
+
+            self.check_expr_against(&field.expr, &types[field_idx]);
+
+            if let Some(first_span) = initialized[field_idx] {
+                let msg = format!(
+                    "تم تحديد قيمة الحقل `{}` أكثر من مرة",
+                    &self.id_pool[field.name.id]
+                );
+                let mut code_window = CodeWindow::new(first_span);
+                code_window.mark_secondary(first_span, vec!["أول قيمة للحقل".to_string()]);
+                code_window.mark_error(field.name.span, vec!["قيمة أخرى للحقل".to_string()]);
+                self.diagnostics
+                    .push(Diagnostic::error(msg, vec![code_window]));
+            } else {
+                initialized[field_idx] = Some(field.name.span);
+            }
+        }
+
+        let missing = names
+            .iter()
+            .zip(initialized)
+            .filter(|(_, span)| span.is_none())
+            .map(|(name, _)| format!("`{}`", &self.id_pool[name.id]))
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            let msg = format!(
+                "لم يتم تحديد قيم بعض حقول {}: {}",
+                owner,
+                missing.join("، ")
+            );
+            self.add_err(msg, fields_struct.path.item.span, vec![]);
+        }
+    }
+
+    /// Returns the names and the types of the fields of the fields struct or the fields variant
+    pub(crate) fn fields_of(&self, resolved: ResolvedPath) -> (Vec<NIRId>, ThinVec<Ty>) {
+        match resolved {
+            ResolvedPath::FieldsStruct(idx) => (
+                self.nir.fields_structs[idx]
+                    .fields
+                    .iter()
+                    .map(|(_, name, _)| *name)
+                    .collect(),
+                self.table.fields_structs[idx].clone(),
+            ),
+            ResolvedPath::Variant(enum_idx, variant_idx) => {
+                let VariantKind::Fields(fields) =
+                    &self.nir.enums[enum_idx].variants[variant_idx].kind
+                else {
+                    unreachable!()
+                };
+                (
+                    fields.iter().map(|(name, _)| *name).collect(),
+                    self.table.enums[enum_idx][variant_idx].clone(),
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the description of the fields struct or the fields variant used in the errors
+    /// with the verb that agrees with it
+    pub(crate) fn fields_owner(&self, path: &ModPathWithItem) -> (String, &'static str) {
+        match path.resolved {
+            ResolvedPath::Variant(enum_idx, variant_idx) => (
+                format!("الحالة `{}`", self.fmt_variant(enum_idx, variant_idx)),
+                "تحتوي",
+            ),
+            _ => (
+                format!("الهيكل `{}`", &self.id_pool[path.item.id]),
+                "يحتوي",
+            ),
+        }
+    }
+
+    fn infer_field(&mut self, on: Ty, field: &FieldExpr) -> Ty {
+        let field_name = &self.id_pool[field.name.id];
+
+        match self.auto_deref(on) {
+            Ty::FieldsStruct(struct_idx) => {
+                let struct_fields = &self.nir.fields_structs[struct_idx].fields;
+                match struct_fields
+                    .iter()
+                    .position(|(_, name, _)| name.id == field.name.id)
+                {
+                    Some(field_idx) => self.table.fields_structs[struct_idx][field_idx].clone(),
+                    None => {
+                        let msg = format!(
+                            "الهيكل `{}` لا يحتوي على حقل باسم `{}`",
+                            self.fmt_ty(&Ty::FieldsStruct(struct_idx)),
+                            field_name
+                        );
+                        self.add_err(msg, field.name.span, vec![]);
+                        Ty::Error
+                    }
+                }
+            }
+            Ty::Error => Ty::Error,
+            Ty::Infer(_) => {
+                self.add_err(
+                    "يجب معرفة النوع قبل الوصول إلى حقوله".to_string(),
+                    field.on.span,
+                    vec!["يجب تحديد النوع".to_string()],
+                );
+                Ty::Error
+            }
+            found => {
+                let msg = format!(
+                    "لا يمكن الوصول إلى الحقل `{}` في النوع `{}`",
+                    field_name,
+                    self.fmt_ty(&found)
+                );
+                self.add_err(msg, field.name.span, vec![]);
+                Ty::Error
+            }
+        }
+    }
+
+    /// Follows the pointers and references to their underlying type
+    fn auto_deref(&self, ty: Ty) -> Ty {
+        let mut ty = self.resolve(&ty);
+        while let Some(pointee) = ty.pointee() {
+            ty = self.resolve(pointee);
+        }
+        ty
+    }
+
+    fn infer_unary_expr(&mut self, unary: &UnaryExpr) -> Ty {
+        match unary.op {
+            UnaryOp::Minus => {
+                let ints_len = self.unspecified_ints.len();
+                let ty = self.check_expr(&unary.expr);
+                self.expect_num_class(&ty, NumClass::Signed, unary.expr.span);
+
+                // The negated literal is checked against the min value of its type instead of the max
+                if unary.expr.kind_and_index.kind() == ExprKindAndIndex::LITERAL
+                    && self.unspecified_ints.len() > ints_len
+                {
+                    self.unspecified_ints.last_mut().unwrap().1 = true;
+                }
+
+                ty
+            }
+            UnaryOp::LNot => self.check_expr_against(&unary.expr, &Ty::bool()),
+            UnaryOp::BNot => {
+                let ty = self.check_expr(&unary.expr);
+                self.expect_num_class(&ty, NumClass::Int, unary.expr.span);
+                ty
+            }
+            UnaryOp::Deref => {
+                let ty = self.check_expr(&unary.expr);
+                match self.resolve(&ty) {
+                    Ty::Error => Ty::Error,
+                    resolved => match resolved.pointee() {
+                        Some(pointee) => pointee.clone(),
+                        None => {
+                            let msg = format!(
+                                "لا يمكن الوصول إلى القيمة التي يشير إليها النوع `{}` لأنه ليس مؤشرًا أو مرجعًا",
+                                self.fmt_ty(&resolved)
+                            );
+                            self.add_err(msg, unary.expr.span, vec![]);
+                            Ty::Error
+                        }
+                    },
+                }
+            }
+            UnaryOp::Borrow => Ty::Ref(Box::new(self.check_expr(&unary.expr))),
+            UnaryOp::BorrowMut => Ty::RefMut(Box::new(self.check_expr(&unary.expr))),
+        }
+    }
+
+    fn infer_bin_expr(&mut self, bin: &BinExpr, span: Span) -> Ty {
+        match bin.op {
+            BinOp::LOr | BinOp::LAnd => {
+                self.check_expr_against(&bin.left, &Ty::bool());
+                self.check_expr_against(&bin.right, &Ty::bool());
+                Ty::bool()
+            }
+            BinOp::EqualEqual | BinOp::NotEqual | BinOp::GE | BinOp::GT | BinOp::LE | BinOp::LT => {
+                let left = self.check_expr(&bin.left);
+                self.check_expr_against(&bin.right, &left);
+                Ty::bool()
+            }
+            BinOp::OpenOpenRange
+            | BinOp::CloseOpenRange
+            | BinOp::OpenCloseRange
+            | BinOp::CloseCloseRange => {
+                let left = self.check_expr(&bin.left);
+                self.check_expr_against(&bin.right, &left);
+                self.add_err("لا يمكن استخدام النطاقات كقيم".to_string(), span, vec![]);
+                Ty::Error
+            }
+            BinOp::Plus | BinOp::Minus | BinOp::Times | BinOp::Div | BinOp::Mod => {
+                self.check_arithmetic(bin, NumClass::Numeric)
+            }
+            BinOp::BOr | BinOp::Xor | BinOp::BAnd => self.check_arithmetic(bin, NumClass::Int),
+            BinOp::Shr | BinOp::Shl => self.check_shift(bin),
+            BinOp::Assign => {
+                self.check_place(&bin.left);
+                let left = self.check_expr(&bin.left);
+                self.check_expr_against(&bin.right, &left);
+                Ty::Unit
+            }
+            BinOp::PlusAssign
+            | BinOp::MinusAssign
+            | BinOp::TimesAssign
+            | BinOp::DivAssign
+            | BinOp::ModAssign => {
+                self.check_place(&bin.left);
+                self.check_arithmetic(bin, NumClass::Numeric);
+                Ty::Unit
+            }
+            BinOp::BAndAssign | BinOp::BOrAssign | BinOp::XorAssign => {
+                self.check_place(&bin.left);
+                self.check_arithmetic(bin, NumClass::Int);
+                Ty::Unit
+            }
+            BinOp::ShlAssign | BinOp::ShrAssign => {
+                self.check_place(&bin.left);
+                self.check_shift(bin);
+                Ty::Unit
+            }
+        }
+    }
+
+    fn check_arithmetic(&mut self, bin: &BinExpr, class: NumClass) -> Ty {
+        let left = self.check_expr(&bin.left);
+        self.check_expr_against(&bin.right, &left);
+        self.expect_num_class(&left, class, bin.left.span);
+        left
+    }
+
+    fn check_shift(&mut self, bin: &BinExpr) -> Ty {
+        let left = self.check_expr(&bin.left);
+        let right = self.check_expr(&bin.right);
+        self.expect_num_class(&left, NumClass::Int, bin.left.span);
+        self.expect_num_class(&right, NumClass::Int, bin.right.span);
+        left
+    }
+
+    fn expect_num_class(&mut self, ty: &Ty, class: NumClass, span: Span) {
+        let accepted = match self.resolve(ty) {
+            Ty::Error | Ty::Never => true,
+            Ty::Infer(var) => {
+                let accepted = match self.vars[var].kind {
+                    TyVarKind::Any | TyVarKind::Int => true,
+                    TyVarKind::Float => class != NumClass::Int,
+                };
+                // The variable may be bound later to a type which is not in the class
+                if accepted {
+                    self.vars[var]
+                        .num_uses
+                        .push((class, self.current_file_idx, span));
+                }
+                accepted
+            }
+            Ty::Primitive(p) => class.accepts(p),
+            _ => false,
+        };
+
+        if !accepted {
+            self.add_num_class_err(ty, span);
+        }
+    }
+
+    pub(crate) fn add_num_class_err(&mut self, ty: &Ty, span: Span) {
+        let msg = format!("لا يمكن استخدام هذه العملية مع النوع `{}`", self.fmt_ty(ty));
+        self.add_err(msg, span, vec![]);
+    }
+
+    /// Checks that the expression refers to a memory location that could be assigned
+    fn check_place(&mut self, expr: &Expr) {
+        if !self.is_place(expr) {
+            self.add_err(
+                "لا يمكن الإسناد إلى هذا التعبير".to_string(),
+                expr.span,
+                vec!["ليس متغيرًا أو حقلًا أو عنصرًا في مصفوفة".to_string()],
+            );
+        }
+    }
+
+    fn is_place(&self, expr: &Expr) -> bool {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            // Assigning to the statics is reported by the borrow checker
+            ExprKindAndIndex::PATH => matches!(
+                exprs.paths[index].resolved,
+                ResolvedPath::Local(_) | ResolvedPath::Static(_)
+            ),
+            ExprKindAndIndex::FIELD | ExprKindAndIndex::INDEX => true,
+            ExprKindAndIndex::UNARY_EXPR => exprs.unary_exprs[index].op == UnaryOp::Deref,
+            ExprKindAndIndex::PARENS => self.is_place(&exprs.parens[index].expr),
+            _ => false,
+        }
+    }
+
+    /// Reports `قطع` and `وصل` outside loops, and returns whether the expression is inside a loop
+    fn check_in_loop(&mut self, expr: &Expr) -> bool {
+        if *self.loops_depths.last().unwrap() > 0 {
+            return true;
+        }
+
+        let keyword = if expr.kind_and_index.kind() == ExprKindAndIndex::CONTINUE {
+            "وصل"
+        } else {
+            "قطع"
+        };
+
+        let msg = format!("لا يمكن استخدام `{}` خارج حلقة تكرارية", keyword);
+        self.add_err(msg, expr.span, vec![]);
+
+        false
+    }
+
+    /// Reports a struct or a variant path used with a syntax of another kind of structs,
+    /// the action is what is done with the struct (i.e. creating or matching it)
+    pub(crate) fn add_struct_kind_err(&mut self, path: &ModPathWithItem, action: &str) -> Ty {
+        let (name_span, kind_str) = match path.resolved {
+            ResolvedPath::UnitStruct(idx) => {
+                let s = &self.nir.unit_structs[idx];
+                (s.name.span, "هيكل بدون حقول")
+            }
+            ResolvedPath::TupleStruct(idx) => {
+                let s = &self.nir.tuple_structs[idx];
+                (s.name.span, "هيكل بحقول غير مسماة")
+            }
+            ResolvedPath::FieldsStruct(idx) => {
+                let s = &self.nir.fields_structs[idx];
+                (s.name.span, "هيكل بحقول مسماة")
+            }
+            ResolvedPath::Variant(enum_idx, variant_idx) => {
+                let e = &self.nir.enums[enum_idx];
+                let variant = &e.variants[variant_idx];
+                let kind_str = match variant.kind {
+                    VariantKind::Unit => "حالة بدون حقول",
+                    VariantKind::Tuple(_) => "حالة بحقول غير مسماة",
+                    VariantKind::Fields(_) => "حالة بحقول مسماة",
+                };
+                (variant.name.span, kind_str)
+            }
+            ResolvedPath::Local(_)
+            | ResolvedPath::Fn(_)
+            | ResolvedPath::Builtin(_)
+            | ResolvedPath::Enum(_)
+            | ResolvedPath::Const(_)
+            | ResolvedPath::Static(_) => {
+                unreachable!("The resolver only accepts structs and variants in struct expressions and patterns")
+            }
+        };
+
+        let (item_str, pronoun, name) = match path.resolved {
+            ResolvedPath::Variant(enum_idx, variant_idx) => {
+                ("الحالة", "هي", self.fmt_variant(enum_idx, variant_idx))
+            }
+            _ => ("الهيكل", "هو", self.id_pool[path.item.id].to_string()),
+        };
+
+        let msg = format!("لا يمكن {} {} `{}` بهذه الصيغة", action, item_str, name);
+        let mut code_window = CodeWindow::new(path.item.span);
+        code_window.mark_error(path.item.span, vec![]);
+        let mut diagnostic = Diagnostic::error(msg, vec![code_window]);
+
+        let mut note_code_window = CodeWindow::new(name_span);
+        note_code_window.mark_note(name_span, vec![]);
+        let note = Diagnostic::note(
+            format!("{} `{}` {} {}", item_str, name, pronoun, kind_str),
+            vec![note_code_window],
+        );
+        diagnostic.chain(note);
+
+        self.diagnostics.push(diagnostic);
+
+        Ty::Error
+    }
+}
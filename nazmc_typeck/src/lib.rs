@@ -0,0 +1,775 @@
+use consts::EvalState;
+use nazmc_data_pool::{Built, DataPool};
+use nazmc_diagnostics::{span::Span, CodeWindow, Diagnostic, Numerals};
+use nazmc_nir::*;
+use std::collections::HashMap;
+use thin_vec::ThinVec;
+use ty::{TyVar, TyVarKind};
+
+mod consts;
+mod exprs;
+mod patterns;
+mod ty;
+
+pub use consts::ConstValue;
+pub use ty::Ty;
+
+/// The types of the NIR values after type checking
+#[derive(Default)]
+pub struct TypesTable {
+    /// The type of each local in `NIR.locals`
+    pub locals: ThinVec<Ty>,
+    /// The type of each expression by its kind and index in the NIR arenas
+    pub exprs: HashMap<ExprKindAndIndex, Ty>,
+    /// The params types and the return type of each function in `NIR.fns`
+    pub fns_signatures: ThinVec<(ThinVec<Ty>, Ty)>,
+    /// The types of each tuple struct in `NIR.tuple_structs`
+    pub tuple_structs: ThinVec<ThinVec<Ty>>,
+    /// The fields types of each fields struct in `NIR.fields_structs`
+    pub fields_structs: ThinVec<ThinVec<Ty>>,
+    /// The fields types of each variant of each enum in `NIR.enums`
+    pub enums: ThinVec<ThinVec<ThinVec<Ty>>>,
+    /// The type of each constant in `NIR.consts`
+    pub consts: ThinVec<Ty>,
+    /// The computed value of each constant in `NIR.consts`
+    pub consts_values: ThinVec<ConstValue>,
+    /// The type of each static in `NIR.statics`
+    pub statics: ThinVec<Ty>,
+    /// The computed initial value of each static in `NIR.statics`
+    pub statics_values: ThinVec<ConstValue>,
+}
+
+impl TypesTable {
+    #[inline]
+    pub fn expr_ty(&self, expr: &Expr) -> &Ty {
+        &self.exprs[&expr.kind_and_index]
+    }
+}
+
+pub struct TypeChecker<'a> {
+    /// The pool used to preserve ids string values
+    id_pool: &'a DataPool<Built>,
+    nir: &'a NIR,
+    /// The digits of the numbers in the errors
+    numerals: Numerals,
+    diagnostics: Vec<Diagnostic>,
+    table: TypesTable,
+    /// The inference variables of all bodies
+    vars: Vec<TyVar>,
+    /// The values of the unspecified int literals with whether they are negated, their types and
+    /// spans, they are checked to fit in their types after inference
+    unspecified_ints: Vec<(u64, bool, Ty, usize, Span)>,
+    /// The file index of the item being checked
+    current_file_idx: usize,
+    /// The expected return type of each nested function or lambda being checked
+    return_types: Vec<Ty>,
+    /// The count of nested loops in each nested function or lambda being checked
+    loops_depths: Vec<usize>,
+    /// The checked `عندما` expressions with their file indexes,
+    /// their exhaustiveness is checked after inferring the types of their values
+    whens: Vec<(usize, &'a WhenExpr)>,
+    /// The evaluation state of each constant in `NIR.consts`
+    consts_states: Vec<EvalState>,
+    /// The evaluation state of each static in `NIR.statics`
+    statics_states: Vec<EvalState>,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new(id_pool: &'a DataPool<Built>, nir: &'a NIR, numerals: Numerals) -> Self {
+        Self {
+            id_pool,
+            nir,
+            numerals,
+            diagnostics: vec![],
+            table: TypesTable::default(),
+            vars: vec![],
+            unspecified_ints: vec![],
+            current_file_idx: 0,
+            return_types: vec![],
+            loops_depths: vec![],
+            whens: vec![],
+            consts_states: vec![EvalState::NotEvaluated; nir.consts.len()],
+            statics_states: vec![EvalState::NotEvaluated; nir.statics.len()],
+        }
+    }
+
+    /// Checks the types and returns the table with the errors, the table is valid only if there is no error
+    pub fn check(mut self) -> (TypesTable, Vec<Diagnostic>) {
+        let nir = self.nir;
+
+        self.table.locals = nir.locals.iter().map(|_| Ty::Error).collect();
+        self.table.consts = nir.consts.iter().map(|_| Ty::Error).collect();
+        self.table.consts_values = nir.consts.iter().map(|_| ConstValue::Error).collect();
+        self.table.statics = nir.statics.iter().map(|_| Ty::Error).collect();
+        self.table.statics_values = nir.statics.iter().map(|_| ConstValue::Error).collect();
+
+        // Compute the constants and the statics first, so the array sizes in any type could use them
+        for idx in 0..nir.consts.len() {
+            self.eval_const_item(ResolvedPath::Const(idx), None);
+        }
+
+        for idx in 0..nir.statics.len() {
+            self.eval_const_item(ResolvedPath::Static(idx), None);
+        }
+
+        // Lower the items signatures first, so any body could use them
+        for tuple_struct in &nir.tuple_structs {
+            self.current_file_idx = tuple_struct.file_idx;
+            let types = tuple_struct
+                .types
+                .iter()
+                .map(|(_, typ)| self.lower_type(typ))
+                .collect();
+            self.table.tuple_structs.push(types);
+        }
+
+        for fields_struct in &nir.fields_structs {
+            self.current_file_idx = fields_struct.file_idx;
+            let types = fields_struct
+                .fields
+                .iter()
+                .map(|(_, _, typ)| self.lower_type(typ))
+                .collect();
+            self.table.fields_structs.push(types);
+        }
+
+        for e in &nir.enums {
+            self.current_file_idx = e.file_idx;
+            let variants = e
+                .variants
+                .iter()
+                .map(|variant| match &variant.kind {
+                    VariantKind::Unit => ThinVec::new(),
+                    VariantKind::Tuple(types) => {
+                        types.iter().map(|typ| self.lower_type(typ)).collect()
+                    }
+                    VariantKind::Fields(fields) => fields
+                        .iter()
+                        .map(|(_, typ)| self.lower_type(typ))
+                        .collect(),
+                })
+                .collect();
+            self.table.enums.push(variants);
+        }
+
+        for f in &nir.fns {
+            self.current_file_idx = f.file_idx;
+            let params = f
+                .params
+                .iter()
+                .map(|(local_idx, typ)| {
+                    let ty = self.lower_type(typ);
+                    self.table.locals[*local_idx] = ty.clone();
+                    ty
+                })
+                .collect();
+            let return_type = self.lower_type(&f.return_type);
+            self.table.fns_signatures.push((params, return_type));
+        }
+
+        for (fn_idx, f) in nir.fns.iter().enumerate() {
+            self.current_file_idx = f.file_idx;
+
+            let return_type = self.table.fns_signatures[fn_idx].1.clone();
+
+            self.return_types.push(return_type.clone());
+            self.loops_depths.push(0);
+
+            self.check_scope_against(f.body, &return_type, f.return_type.span);
+
+            self.return_types.pop();
+            self.loops_depths.pop();
+        }
+
+        self.finish_inference();
+
+        // The patterns of the invalid values may not match their types, so they are not checked
+        if self.diagnostics.is_empty() {
+            self.check_whens_exhaustiveness();
+        }
+
+        (self.table, self.diagnostics)
+    }
+
+    fn lower_type(&mut self, typ: &Type) -> Ty {
+        let types = &self.nir.types;
+        let index = typ.kind_and_index.index();
+
+        match typ.kind_and_index.kind() {
+            TypeKindAndIndex::UNIT => Ty::Unit,
+            TypeKindAndIndex::PRIMITIVE => Ty::Primitive(PrimitiveType::from_index(index)),
+            TypeKindAndIndex::PATH => match types.paths[index].resolved {
+                ResolvedPath::UnitStruct(idx) => Ty::UnitStruct(idx),
+                ResolvedPath::TupleStruct(idx) => Ty::TupleStruct(idx),
+                ResolvedPath::FieldsStruct(idx) => Ty::FieldsStruct(idx),
+                ResolvedPath::Enum(idx) => Ty::Enum(idx),
+                ResolvedPath::Local(_)
+                | ResolvedPath::Fn(_)
+                | ResolvedPath::Builtin(_)
+                | ResolvedPath::Variant(_, _)
+                | ResolvedPath::Const(_)
+                | ResolvedPath::Static(_) => {
+                    unreachable!("The resolver only accepts structs and enums in types")
+                }
+            },
+            TypeKindAndIndex::PTR => Ty::Ptr(Box::new(self.lower_type(&types.ptrs[index]))),
+            TypeKindAndIndex::REF => Ty::Ref(Box::new(self.lower_type(&types.refs[index]))),
+            TypeKindAndIndex::PTR_MUT => {
+                Ty::PtrMut(Box::new(self.lower_type(&types.ptrs_mut[index])))
+            }
+            TypeKindAndIndex::REF_MUT => {
+                Ty::RefMut(Box::new(self.lower_type(&types.refs_mut[index])))
+            }
+            TypeKindAndIndex::SLICE => Ty::Slice(Box::new(self.lower_type(&types.slices[index]))),
+            TypeKindAndIndex::TUPLE => Ty::Tuple(
+                types.tuples[index]
+                    .types
+                    .iter()
+                    .map(|typ| self.lower_type(typ))
+                    .collect(),
+            ),
+            TypeKindAndIndex::ARRAY => {
+                let array = &types.arrays[index];
+                let ty = self.lower_type(&array.typ);
+                let size = self.eval_array_size(&array.size);
+                Ty::Array(Box::new(ty), size)
+            }
+            TypeKindAndIndex::LAMBDA => {
+                let lambda = &types.lambdas[index];
+                let params = lambda
+                    .params
+                    .iter()
+                    .map(|typ| self.lower_type(typ))
+                    .collect();
+                let return_type = self.lower_type(&lambda.return_type);
+                Ty::Lambda(params, Box::new(return_type))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Checks the scope and returns its type, which is the type of its return expression if any.
+    /// Otherwise, it will be `()` or `!!` if it has a statement that never completes
+    fn check_scope(&mut self, scope: Scope) -> Ty {
+        let body = &self.nir.scopes[scope.index];
+        let mut diverges = false;
+
+        for stm in &body.stms {
+            diverges |= self.check_stm(stm);
+        }
+
+        match &body.return_expr {
+            Some(expr) => self.check_expr(expr),
+            None if diverges => Ty::Never,
+            None => Ty::Unit,
+        }
+    }
+
+    fn check_scope_against(&mut self, scope: Scope, expected: &Ty, fallback_span: Span) {
+        let ty = self.check_scope(scope);
+        let span = self.scope_span(scope, fallback_span);
+        self.expect_ty(&ty, expected, span);
+    }
+
+    /// The span of the return expression of the scope if any
+    #[inline]
+    fn scope_span(&self, scope: Scope, fallback_span: Span) -> Span {
+        self.nir.scopes[scope.index]
+            .return_expr
+            .as_ref()
+            .map_or(fallback_span, |expr| expr.span)
+    }
+
+    /// Checks the statement and returns whether it never completes
+    fn check_stm(&mut self, stm: &Stm) -> bool {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET => {
+                self.check_let_stm(&stms.lets[index]);
+                false
+            }
+            StmKindAndIndex::LET_MUT => {
+                self.check_let_stm(&stms.let_muts[index]);
+                false
+            }
+            StmKindAndIndex::IF => {
+                let ty = self.check_if(&stms.ifs[index], None);
+                self.resolve(&ty) == Ty::Never
+            }
+            StmKindAndIndex::WHEN => {
+                let ty = self.check_when(&stms.whens[index], None);
+                self.resolve(&ty) == Ty::Never
+            }
+            StmKindAndIndex::WHILE => {
+                let while_stm = &stms.whiles[index];
+                self.check_expr_against(&while_stm.condition, &Ty::bool());
+                *self.loops_depths.last_mut().unwrap() += 1;
+                self.check_scope(while_stm.scope);
+                *self.loops_depths.last_mut().unwrap() -= 1;
+                false
+            }
+            StmKindAndIndex::DO_WHILE => {
+                let do_while_stm = &stms.do_whiles[index];
+                *self.loops_depths.last_mut().unwrap() += 1;
+                self.check_scope(do_while_stm.scope);
+                *self.loops_depths.last_mut().unwrap() -= 1;
+                self.check_expr_against(&do_while_stm.condition, &Ty::bool());
+                false
+            }
+            StmKindAndIndex::EXPR => {
+                let ty = self.check_expr(&stms.exprs[index]);
+                self.resolve(&ty) == Ty::Never
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn check_let_stm(&mut self, let_stm: &LetStm) {
+        let ty = match &let_stm.binding.typ {
+            Some(typ) => self.lower_type(typ),
+            None => {
+                let span = self.binding_kind_span(&let_stm.binding.kind);
+                self.new_var(TyVarKind::Any, span)
+            }
+        };
+
+        if let Some(expr) = &let_stm.assign {
+            self.check_expr_against(expr, &ty);
+        }
+
+        self.bind(&let_stm.binding.kind, ty);
+    }
+
+    /// Sets the types of the locals of the binding
+    fn bind(&mut self, kind: &BindingKind, ty: Ty) {
+        match kind {
+            BindingKind::Local(local_idx) => self.table.locals[*local_idx] = ty,
+            BindingKind::TupleDestruction(kinds, span) => {
+                let types = match self.resolve(&ty) {
+                    Ty::Tuple(types) if types.len() == kinds.len() => types,
+                    Ty::Infer(var) if self.vars[var].kind == TyVarKind::Any => {
+                        let types: ThinVec<_> = kinds
+                            .iter()
+                            .map(|kind| {
+                                let span = self.binding_kind_span(kind);
+                                self.new_var(TyVarKind::Any, span)
+                            })
+                            .collect();
+                        self.unify(&ty, &Ty::Tuple(types.clone()));
+                        types
+                    }
+                    Ty::Error => kinds.iter().map(|_| Ty::Error).collect(),
+                    found => {
+                        let msg = format!(
+                            "يُتوقع مجموعة من {} عناصر، ولكن تم العثور على النوع `{}`",
+                            self.numerals.fmt(kinds.len()),
+                            self.fmt_ty(&found)
+                        );
+                        self.add_err(msg, *span, vec![]);
+                        kinds.iter().map(|_| Ty::Error).collect()
+                    }
+                };
+
+                for (kind, ty) in kinds.iter().zip(types) {
+                    self.bind(kind, ty);
+                }
+            }
+        }
+    }
+
+    fn binding_kind_span(&self, kind: &BindingKind) -> Span {
+        match kind {
+            BindingKind::Local(local_idx) => self.nir.locals[*local_idx].name.span,
+            BindingKind::TupleDestruction(_, span) => *span,
+        }
+    }
+
+    /// Checks the if expression and returns its type
+    ///
+    /// The span is given when the if is used as an expression,
+    /// so all its branches must have the same type and it must have an `وإلا` branch if that type is not `()`
+    fn check_if(&mut self, if_expr: &IfExpr, expr_span: Option<Span>) -> Ty {
+        let mut branches = Vec::with_capacity(if_expr.else_ifs.len() + 2);
+
+        for ConditionalScope { condition, scope } in
+            std::iter::once(&if_expr.if_).chain(&if_expr.else_ifs)
+        {
+            self.check_expr_against(condition, &Ty::bool());
+            let ty = self.check_scope(*scope);
+            branches.push((ty, *scope));
+        }
+
+        if let Some(scope) = if_expr.else_ {
+            let ty = self.check_scope(scope);
+            branches.push((ty, scope));
+        }
+
+        let Some(expr_span) = expr_span else {
+            let all_diverge = if_expr.else_.is_some()
+                && branches.iter().all(|(ty, _)| self.resolve(ty) == Ty::Never);
+            return if all_diverge { Ty::Never } else { Ty::Unit };
+        };
+
+        if if_expr.else_.is_none() {
+            for (ty, scope) in branches {
+                let span = self.scope_span(scope, expr_span);
+                if !self.unify(&ty, &Ty::Unit) {
+                    let msg = format!(
+                        "تعبير `لو` بدون `وإلا` يجب أن يكون من النوع `()`، ولكن تم العثور على النوع `{}`",
+                        self.fmt_ty(&ty)
+                    );
+                    self.add_err(msg, span, vec![]);
+                }
+            }
+            return Ty::Unit;
+        }
+
+        let mut if_ty = Ty::Never;
+
+        for (ty, scope) in branches {
+            if self.resolve(&if_ty) == Ty::Never {
+                if_ty = ty;
+            } else {
+                let span = self.scope_span(scope, expr_span);
+                self.expect_ty(&ty, &if_ty, span);
+            }
+        }
+
+        if_ty
+    }
+
+    /// Checks the `عندما` expression and returns its type
+    ///
+    /// The span is given when it is used as an expression, so all its arms must have the same type
+    fn check_when(&mut self, when_expr: &'a WhenExpr, expr_span: Option<Span>) -> Ty {
+        let ty = self.check_expr(&when_expr.expr);
+        let mut arms = Vec::with_capacity(when_expr.arms.len());
+
+        for arm in &when_expr.arms {
+            self.check_pattern(&arm.pattern, &ty);
+            if let Some(guard) = &arm.guard {
+                self.check_expr_against(guard, &Ty::bool());
+            }
+            let ty = self.check_scope(arm.body);
+            arms.push((ty, arm.body));
+        }
+
+        self.whens.push((self.current_file_idx, when_expr));
+
+        let Some(expr_span) = expr_span else {
+            let all_diverge = arms.iter().all(|(ty, _)| self.resolve(ty) == Ty::Never);
+            return if all_diverge { Ty::Never } else { Ty::Unit };
+        };
+
+        let mut when_ty = Ty::Never;
+
+        for (ty, scope) in arms {
+            if self.resolve(&when_ty) == Ty::Never {
+                when_ty = ty;
+            } else {
+                let span = self.scope_span(scope, expr_span);
+                self.expect_ty(&ty, &when_ty, span);
+            }
+        }
+
+        when_ty
+    }
+
+    fn new_var(&mut self, kind: TyVarKind, span: Span) -> Ty {
+        let var = self.vars.len();
+        self.vars.push(TyVar {
+            kind,
+            bound: None,
+            origin: (self.current_file_idx, span),
+            num_uses: vec![],
+        });
+        Ty::Infer(var)
+    }
+
+    /// Follows the bound inference variables until reaching a type which is not a bound variable
+    fn resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty;
+        while let Ty::Infer(var) = ty {
+            match &self.vars[*var].bound {
+                Some(bound) => ty = bound,
+                None => break,
+            }
+        }
+        ty.clone()
+    }
+
+    /// Replaces all the bound inference variables in the type
+    fn resolve_deep(&self, ty: &Ty) -> Ty {
+        let resolve_boxed = |ty: &Ty| Box::new(self.resolve_deep(ty));
+        match self.resolve(ty) {
+            Ty::Ptr(ty) => Ty::Ptr(resolve_boxed(&ty)),
+            Ty::Ref(ty) => Ty::Ref(resolve_boxed(&ty)),
+            Ty::PtrMut(ty) => Ty::PtrMut(resolve_boxed(&ty)),
+            Ty::RefMut(ty) => Ty::RefMut(resolve_boxed(&ty)),
+            Ty::Slice(ty) => Ty::Slice(resolve_boxed(&ty)),
+            Ty::Array(ty, size) => Ty::Array(resolve_boxed(&ty), size),
+            Ty::Tuple(types) => Ty::Tuple(types.iter().map(|ty| self.resolve_deep(ty)).collect()),
+            Ty::Lambda(params, return_type) => Ty::Lambda(
+                params.iter().map(|ty| self.resolve_deep(ty)).collect(),
+                resolve_boxed(&return_type),
+            ),
+            ty => ty,
+        }
+    }
+
+    /// Makes the two types equal by binding their inference variables, returns false if they cannot be equal
+    fn unify(&mut self, a: &Ty, b: &Ty) -> bool {
+        let a = self.resolve(a);
+        let b = self.resolve(b);
+
+        match (&a, &b) {
+            (Ty::Error, _) | (_, Ty::Error) | (Ty::Never, _) | (_, Ty::Never) => true,
+            (Ty::Infer(x), Ty::Infer(y)) if x == y => true,
+            (Ty::Infer(x), _) => self.bind_var(*x, &b),
+            (_, Ty::Infer(y)) => self.bind_var(*y, &a),
+            (Ty::Unit, Ty::Unit) => true,
+            (Ty::Primitive(p1), Ty::Primitive(p2)) => p1 == p2,
+            (Ty::UnitStruct(s1), Ty::UnitStruct(s2))
+            | (Ty::TupleStruct(s1), Ty::TupleStruct(s2))
+            | (Ty::FieldsStruct(s1), Ty::FieldsStruct(s2))
+            | (Ty::Enum(s1), Ty::Enum(s2)) => s1 == s2,
+            (Ty::Ptr(t1), Ty::Ptr(t2))
+            | (Ty::Ref(t1), Ty::Ref(t2))
+            | (Ty::PtrMut(t1), Ty::PtrMut(t2))
+            | (Ty::RefMut(t1), Ty::RefMut(t2))
+            | (Ty::Slice(t1), Ty::Slice(t2)) => self.unify(t1, t2),
+            (Ty::Array(t1, n1), Ty::Array(t2, n2)) => n1 == n2 && self.unify(t1, t2),
+            (Ty::Tuple(types1), Ty::Tuple(types2)) => {
+                types1.len() == types2.len()
+                    && types1
+                        .iter()
+                        .zip(types2)
+                        .fold(true, |unified, (t1, t2)| self.unify(t1, t2) && unified)
+            }
+            (Ty::Lambda(params1, ret1), Ty::Lambda(params2, ret2)) => {
+                params1.len() == params2.len()
+                    && params1
+                        .iter()
+                        .zip(params2)
+                        .fold(true, |unified, (t1, t2)| self.unify(t1, t2) && unified)
+                    && self.unify(ret1, ret2)
+            }
+            _ => false,
+        }
+    }
+
+    fn bind_var(&mut self, var: usize, ty: &Ty) -> bool {
+        let kind = self.vars[var].kind;
+
+        let can_bind = match (kind, ty) {
+            (TyVarKind::Any, _) => !self.occurs(var, ty),
+            (_, Ty::Infer(other)) => {
+                let other_kind = self.vars[*other].kind;
+                if other_kind == TyVarKind::Any {
+                    // Keep the more specific kind
+                    self.vars[*other].bound = Some(Ty::Infer(var));
+                    return true;
+                }
+                other_kind == kind
+            }
+            (TyVarKind::Int, Ty::Primitive(p)) => p.is_int(),
+            (TyVarKind::Float, Ty::Primitive(p)) => p.is_float(),
+            _ => false,
+        };
+
+        if can_bind {
+            self.vars[var].bound = Some(ty.clone());
+        }
+
+        can_bind
+    }
+
+    fn occurs(&self, var: usize, ty: &Ty) -> bool {
+        match self.resolve(ty) {
+            Ty::Infer(other) => other == var,
+            Ty::Ptr(ty) | Ty::Ref(ty) | Ty::PtrMut(ty) | Ty::RefMut(ty) | Ty::Slice(ty) => {
+                self.occurs(var, &ty)
+            }
+            Ty::Array(ty, _) => self.occurs(var, &ty),
+            Ty::Tuple(types) => types.iter().any(|ty| self.occurs(var, ty)),
+            Ty::Lambda(params, return_type) => {
+                params.iter().any(|ty| self.occurs(var, ty)) || self.occurs(var, &return_type)
+            }
+            _ => false,
+        }
+    }
+
+    /// Unifies the found type with the expected one and reports a mismatch if they cannot be unified
+    fn expect_ty(&mut self, found: &Ty, expected: &Ty, span: Span) -> bool {
+        if self.unify(found, expected) {
+            return true;
+        }
+
+        let expected = self.fmt_ty(expected);
+        let found = self.fmt_ty(found);
+        let msg = format!(
+            "يُتوقع النوع `{}`، ولكن تم العثور على النوع `{}`",
+            expected, found
+        );
+        self.add_err(msg, span, vec![format!("من النوع `{}`", found)]);
+
+        false
+    }
+
+    /// Applies the default types to the unbound int and float variables,
+    /// reports the types that could not be inferred and writes the final types to the table
+    fn finish_inference(&mut self) {
+        for var in 0..self.vars.len() {
+            if self.vars[var].bound.is_some() {
+                continue;
+            }
+
+            match self.vars[var].kind {
+                TyVarKind::Int => self.vars[var].bound = Some(Ty::Primitive(PrimitiveType::I4)),
+                TyVarKind::Float => self.vars[var].bound = Some(Ty::Primitive(PrimitiveType::F8)),
+                TyVarKind::Any => {
+                    let (file_idx, span) = self.vars[var].origin;
+                    self.current_file_idx = file_idx;
+                    self.add_err(
+                        "لا يمكن استنتاج النوع هنا".to_string(),
+                        span,
+                        vec!["يجب تحديد النوع".to_string()],
+                    );
+                    self.vars[var].bound = Some(Ty::Error);
+                }
+            }
+        }
+
+        for var in 0..self.vars.len() {
+            for (class, file_idx, span) in std::mem::take(&mut self.vars[var].num_uses) {
+                let ty = self.resolve(&Ty::Infer(var));
+                let accepted = match ty {
+                    Ty::Error | Ty::Never => true,
+                    Ty::Primitive(p) => class.accepts(p),
+                    _ => false,
+                };
+
+                if !accepted {
+                    self.current_file_idx = file_idx;
+                    self.add_num_class_err(&ty, span);
+                }
+            }
+        }
+
+        for (value, negated, ty, file_idx, span) in std::mem::take(&mut self.unspecified_ints) {
+            let Ty::Primitive(p) = self.resolve(&ty) else {
+                continue;
+            };
+
+            let max = match p {
+                PrimitiveType::I1 => i8::MAX as u64,
+                PrimitiveType::I2 => i16::MAX as u64,
+                PrimitiveType::I4 => i32::MAX as u64,
+                PrimitiveType::I8 => i64::MAX as u64,
+                PrimitiveType::I => isize::MAX as u64,
+                PrimitiveType::U1 => u8::MAX as u64,
+                PrimitiveType::U2 => u16::MAX as u64,
+                PrimitiveType::U4 => u32::MAX as u64,
+                PrimitiveType::U8 => u64::MAX,
+                PrimitiveType::U => usize::MAX as u64,
+                _ => continue,
+            };
+
+            // The magnitude of the min value of the signed ints is bigger than their max value by one
+            if negated && p.is_signed_int() {
+                if value > max + 1 {
+                    self.current_file_idx = file_idx;
+                    let msg = format!(
+                        "العدد -{} أصغر من أن يتسع في النوع `{}`",
+                        self.numerals.fmt(value),
+                        p.name()
+                    );
+                    let label = format!(
+                        "أصغر قيمة للنوع `{}` هى -{}",
+                        p.name(),
+                        self.numerals.fmt(max + 1)
+                    );
+                    self.add_err(msg, span, vec![label]);
+                }
+            } else if value > max {
+                self.current_file_idx = file_idx;
+                let msg = format!(
+                    "العدد {} أكبر من أن يتسع في النوع `{}`",
+                    self.numerals.fmt(value),
+                    p.name()
+                );
+                let label = format!(
+                    "أكبر قيمة للنوع `{}` هى {}",
+                    p.name(),
+                    self.numerals.fmt(max)
+                );
+                self.add_err(msg, span, vec![label]);
+            }
+        }
+
+        let locals = std::mem::take(&mut self.table.locals);
+        self.table.locals = locals.iter().map(|ty| self.resolve_deep(ty)).collect();
+
+        let exprs = std::mem::take(&mut self.table.exprs);
+        self.table.exprs = exprs
+            .into_iter()
+            .map(|(expr, ty)| (expr, self.resolve_deep(&ty)))
+            .collect();
+    }
+
+    fn fmt_ty(&self, ty: &Ty) -> String {
+        let fmt_list = |types: &[Ty]| {
+            types
+                .iter()
+                .map(|ty| self.fmt_ty(ty))
+                .collect::<Vec<_>>()
+                .join("، ")
+        };
+
+        match self.resolve(ty) {
+            Ty::Unit => "()".to_string(),
+            Ty::Never => "!!".to_string(),
+            Ty::Primitive(p) => p.name().to_string(),
+            Ty::UnitStruct(idx) => self.id_pool[self.nir.unit_structs[idx].name.id].to_string(),
+            Ty::TupleStruct(idx) => self.id_pool[self.nir.tuple_structs[idx].name.id].to_string(),
+            Ty::FieldsStruct(idx) => self.id_pool[self.nir.fields_structs[idx].name.id].to_string(),
+            Ty::Enum(idx) => self.id_pool[self.nir.enums[idx].name.id].to_string(),
+            Ty::Ptr(ty) => format!("*{}", self.fmt_ty(&ty)),
+            Ty::Ref(ty) => format!("#{}", self.fmt_ty(&ty)),
+            Ty::PtrMut(ty) => format!("*متغير {}", self.fmt_ty(&ty)),
+            Ty::RefMut(ty) => format!("#متغير {}", self.fmt_ty(&ty)),
+            Ty::Slice(ty) => format!("[{}]", self.fmt_ty(&ty)),
+            Ty::Array(ty, size) => format!("[{}؛ {}]", self.fmt_ty(&ty), size),
+            Ty::Tuple(types) if types.len() == 1 => format!("({}،)", self.fmt_ty(&types[0])),
+            Ty::Tuple(types) => format!("({})", fmt_list(&types)),
+            Ty::Lambda(params, return_type) => {
+                format!("({}) -> {}", fmt_list(&params), self.fmt_ty(&return_type))
+            }
+            Ty::Infer(var) => match self.vars[var].kind {
+                TyVarKind::Int => "{عدد صحيح}".to_string(),
+                TyVarKind::Float => "{عدد عشري}".to_string(),
+                TyVarKind::Any => "؟".to_string(),
+            },
+            Ty::Error => "؟".to_string(),
+        }
+    }
+
+    /// The name of the variant prefixed by the name of its enum
+    fn fmt_variant(&self, enum_idx: usize, variant_idx: usize) -> String {
+        let e = &self.nir.enums[enum_idx];
+        format!(
+            "{}::{}",
+            &self.id_pool[e.name.id],
+            &self.id_pool[e.variants[variant_idx].name.id]
+        )
+    }
+
+    fn add_err(&mut self, msg: String, span: Span, labels: Vec<String>) {
+        let mut code_window = CodeWindow::new(span);
+        code_window.mark_error(span, labels);
+        self.diagnostics
+            .push(Diagnostic::error(msg, vec![code_window]));
+    }
+}
@@ -0,0 +1,526 @@
+use crate::{consts::int_bounds, ty::TyVarKind, Ty, TypeChecker};
+use nazmc_diagnostics::span::Span;
+use nazmc_nir::*;
+use thin_vec::ThinVec;
+
+/// A pattern reduced to what the exhaustiveness checking cares about
+#[derive(Clone)]
+enum Pat {
+    /// `_` and the bindings
+    Wild,
+    Bool(bool),
+    /// The only constructor of `()`, the tuples and the structs with the patterns of their elements
+    Single(Vec<Pat>),
+    /// A variant of an enum by its index with the patterns of its fields
+    Variant(usize, Vec<Pat>),
+    /// The inclusive bounds of an int literal or an int range
+    IntRange(i128, i128),
+    /// The other literals and ranges, whose types have too many values to be listed
+    Opaque,
+}
+
+impl<'a> TypeChecker<'a> {
+    /// Checks that the pattern could match values of the type and sets the types of its bindings
+    pub(crate) fn check_pattern(&mut self, pattern: &Pattern, ty: &Ty) {
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::Binding(local_idx) => self.table.locals[*local_idx] = ty.clone(),
+            PatternKind::Literal(expr) => {
+                self.check_expr_against(expr, ty);
+            }
+            PatternKind::Range(range) => {
+                let start = self.check_expr(&range.start);
+                let end = self.check_expr(&range.end);
+
+                if !self.expect_ty(&start, ty, range.start.span)
+                    || !self.expect_ty(&end, ty, range.end.span)
+                {
+                    return;
+                }
+
+                let is_ordered = match self.resolve(ty) {
+                    Ty::Primitive(p) => p.is_int() || p == PrimitiveType::Char,
+                    Ty::Infer(var) => self.vars[var].kind == TyVarKind::Int,
+                    _ => true,
+                };
+
+                if !is_ordered {
+                    let msg = format!(
+                        "لا يمكن استخدام النطاقات في الأنماط إلا مع الأعداد الصحيحة والحروف، ولكن تم العثور على النوع `{}`",
+                        self.fmt_ty(ty)
+                    );
+                    self.add_err(msg, pattern.span, vec![]);
+                }
+            }
+            PatternKind::Tuple(patterns) => {
+                let types = self.tuple_pattern_types(patterns, ty, pattern.span);
+                for (pattern, ty) in patterns.iter().zip(types) {
+                    self.check_pattern(pattern, &ty);
+                }
+            }
+            PatternKind::UnitStruct(path) => {
+                let found = match path.resolved {
+                    ResolvedPath::UnitStruct(idx) => Ty::UnitStruct(idx),
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Unit
+                        ) =>
+                    {
+                        Ty::Enum(enum_idx)
+                    }
+                    _ => self.add_struct_kind_err(path, "مطابقة"),
+                };
+                self.expect_ty(&found, ty, pattern.span);
+            }
+            PatternKind::TupleStruct(path, patterns) => {
+                let (found, types) = match path.resolved {
+                    ResolvedPath::TupleStruct(idx) => {
+                        (Ty::TupleStruct(idx), self.table.tuple_structs[idx].clone())
+                    }
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Tuple(_)
+                        ) =>
+                    {
+                        (
+                            Ty::Enum(enum_idx),
+                            self.table.enums[enum_idx][variant_idx].clone(),
+                        )
+                    }
+                    _ => {
+                        self.add_struct_kind_err(path, "مطابقة");
+                        for pattern in patterns {
+                            self.check_pattern(pattern, &Ty::Error);
+                        }
+                        return;
+                    }
+                };
+
+                self.expect_ty(&found, ty, pattern.span);
+
+                if types.len() != patterns.len() {
+                    let (owner, _) = self.fields_owner(path);
+                    let msg = format!(
+                        "يُتوقع {} من الأنماط لحقول {}، ولكن تم العثور على {}",
+                        self.numerals.fmt(types.len()),
+                        owner,
+                        self.numerals.fmt(patterns.len())
+                    );
+                    self.add_err(msg, pattern.span, vec![]);
+                }
+
+                for (i, pattern) in patterns.iter().enumerate() {
+                    let ty = types.get(i).cloned().unwrap_or(Ty::Error);
+                    self.check_pattern(pattern, &ty);
+                }
+            }
+            PatternKind::FieldsStruct(path, fields) => {
+                let found = match path.resolved {
+                    ResolvedPath::FieldsStruct(idx) => Ty::FieldsStruct(idx),
+                    ResolvedPath::Variant(enum_idx, variant_idx)
+                        if matches!(
+                            self.nir.enums[enum_idx].variants[variant_idx].kind,
+                            VariantKind::Fields(_)
+                        ) =>
+                    {
+                        Ty::Enum(enum_idx)
+                    }
+                    _ => {
+                        self.add_struct_kind_err(path, "مطابقة");
+                        for (_, pattern) in fields {
+                            self.check_pattern(pattern, &Ty::Error);
+                        }
+                        return;
+                    }
+                };
+
+                self.expect_ty(&found, ty, pattern.span);
+                self.check_fields_pattern(path, fields);
+            }
+        }
+    }
+
+    /// Returns the types of the elements of a tuple pattern matching values of the type
+    fn tuple_pattern_types(&mut self, patterns: &[Pattern], ty: &Ty, span: Span) -> ThinVec<Ty> {
+        match self.resolve(ty) {
+            Ty::Tuple(types) if types.len() == patterns.len() => types,
+            Ty::Unit if patterns.is_empty() => ThinVec::new(),
+            Ty::Infer(var) if self.vars[var].kind == TyVarKind::Any => {
+                let types: ThinVec<_> = patterns
+                    .iter()
+                    .map(|pattern| self.new_var(TyVarKind::Any, pattern.span))
+                    .collect();
+                self.unify(ty, &Ty::Tuple(types.clone()));
+                types
+            }
+            Ty::Error => patterns.iter().map(|_| Ty::Error).collect(),
+            found => {
+                let msg = format!(
+                    "يُتوقع نمط من النوع `{}`، ولكن تم العثور على نمط مجموعة من {} عناصر",
+                    self.fmt_ty(&found),
+                    self.numerals.fmt(patterns.len())
+                );
+                self.add_err(msg, span, vec![]);
+                patterns.iter().map(|_| Ty::Error).collect()
+            }
+        }
+    }
+
+    /// The fields which are not written in the pattern could have any values
+    fn check_fields_pattern(&mut self, path: &ModPathWithItem, fields: &[(NIRId, Pattern)]) {
+        let (names, types) = self.fields_of(path.resolved);
+        let (owner, verb) = self.fields_owner(path);
+        let mut matched = vec![false; names.len()];
+
+        for (name, pattern) in fields {
+            let Some(field_idx) = names
+                .iter()
+                .position(|field_name| field_name.id == name.id)
+            else {
+                self.check_pattern(pattern, &Ty::Error);
+                let msg = format!(
+                    "{} لا {} على حقل باسم `{}`",
+                    owner,
+                    verb,
+                    &self.id_pool[name.id]
+                );
+                self.add_err(msg, name.span, vec![]);
+                continue;
+            };
+
+            self.check_pattern(pattern, &types[field_idx]);
+
+            if matched[field_idx] {
+                let msg = format!(
+                    "تم تحديد نمط الحقل `{}` أكثر من مرة",
+                    &self.id_pool[name.id]
+                );
+                self.add_err(msg, name.span, vec![]);
+            } else {
+                matched[field_idx] = true;
+            }
+        }
+    }
+
+    /// Reports the `عندما` expressions whose arms could not match some values
+    pub(crate) fn check_whens_exhaustiveness(&mut self) {
+        for (file_idx, when_expr) in std::mem::take(&mut self.whens) {
+            let rows = when_expr
+                .arms
+                .iter()
+                // The guarded arms may not match, so they don't cover any value
+                .filter(|arm| arm.guard.is_none())
+                .map(|arm| vec![self.lower_pat(&arm.pattern)])
+                .collect();
+
+            let ty = self.table.expr_ty(&when_expr.expr).clone();
+
+            if let Some(missing) = self.find_missing(rows, &[ty]) {
+                self.current_file_idx = file_idx;
+                self.add_err(
+                    "أنماط `عندما` لا تغطي كل القيم الممكنة".to_string(),
+                    when_expr.expr.span,
+                    vec![format!("النمط `{}` غير مغطى", missing[0])],
+                );
+            }
+        }
+    }
+
+    fn lower_pat(&self, pattern: &Pattern) -> Pat {
+        let ctor = |resolved: ResolvedPath, pats: Vec<Pat>| match resolved {
+            ResolvedPath::Variant(_, variant_idx) => Pat::Variant(variant_idx, pats),
+            _ => Pat::Single(pats),
+        };
+
+        let lower_pats = |patterns: &[Pattern]| {
+            patterns
+                .iter()
+                .map(|pattern| self.lower_pat(pattern))
+                .collect()
+        };
+
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Binding(_) => Pat::Wild,
+            PatternKind::Literal(expr) => {
+                if let Some(value) = self.int_value(expr) {
+                    Pat::IntRange(value, value)
+                } else if expr.kind_and_index.kind() == ExprKindAndIndex::LITERAL {
+                    match self.nir.exprs.literals[expr.kind_and_index.index()] {
+                        LiteralExpr::Bool(b) => Pat::Bool(b),
+                        _ => Pat::Opaque,
+                    }
+                } else {
+                    Pat::Opaque
+                }
+            }
+            PatternKind::Range(range) => {
+                match (self.int_value(&range.start), self.int_value(&range.end)) {
+                    (Some(start), Some(end)) => {
+                        let start = match range.op {
+                            BinOp::OpenOpenRange | BinOp::OpenCloseRange => start + 1,
+                            _ => start,
+                        };
+                        let end = match range.op {
+                            BinOp::OpenOpenRange | BinOp::CloseOpenRange => end - 1,
+                            _ => end,
+                        };
+                        Pat::IntRange(start, end)
+                    }
+                    _ => Pat::Opaque,
+                }
+            }
+            PatternKind::Tuple(patterns) => Pat::Single(lower_pats(patterns)),
+            PatternKind::UnitStruct(path) => ctor(path.resolved, vec![]),
+            PatternKind::TupleStruct(path, patterns) => ctor(path.resolved, lower_pats(patterns)),
+            PatternKind::FieldsStruct(path, fields) => {
+                let pats = self
+                    .fields_of(path.resolved)
+                    .0
+                    .iter()
+                    .map(|name| {
+                        fields
+                            .iter()
+                            .find(|(field_name, _)| field_name.id == name.id)
+                            .map_or(Pat::Wild, |(_, pattern)| self.lower_pat(pattern))
+                    })
+                    .collect();
+                ctor(path.resolved, pats)
+            }
+        }
+    }
+
+    /// Returns the value of the int literal used in a pattern, which may be negated
+    fn int_value(&self, expr: &Expr) -> Option<i128> {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        match expr.kind_and_index.kind() {
+            ExprKindAndIndex::LITERAL => match exprs.literals[index] {
+                LiteralExpr::Num(num_kind) => match num_kind {
+                    NumKind::I(i) => Some(i as i128),
+                    NumKind::I1(i) => Some(i as i128),
+                    NumKind::I2(i) => Some(i as i128),
+                    NumKind::I4(i) => Some(i as i128),
+                    NumKind::I8(i) => Some(i as i128),
+                    NumKind::U(u) => Some(u as i128),
+                    NumKind::U1(u) => Some(u as i128),
+                    NumKind::U2(u) => Some(u as i128),
+                    NumKind::U4(u) => Some(u as i128),
+                    NumKind::U8(u) => Some(u as i128),
+                    NumKind::UnspecifiedInt(u) => Some(u as i128),
+                    _ => None,
+                },
+                _ => None,
+            },
+            ExprKindAndIndex::UNARY_EXPR if exprs.unary_exprs[index].op == UnaryOp::Minus => self
+                .int_value(&exprs.unary_exprs[index].expr)
+                .map(|value| -value),
+            _ => None,
+        }
+    }
+
+    /// Returns values of the types which are not matched by any row of the patterns,
+    /// the values are formatted as patterns with `_` for the values that cannot be listed
+    ///
+    /// It's the usefulness algorithm of Maranget for a row of wildcards, which is useful
+    /// only if some values are not matched
+    fn find_missing(&self, rows: Vec<Vec<Pat>>, types: &[Ty]) -> Option<Vec<String>> {
+        let Some((ty, rest_types)) = types.split_first() else {
+            return if rows.is_empty() { Some(vec![]) } else { None };
+        };
+
+        match ty {
+            Ty::Primitive(PrimitiveType::Bool) => {
+                for b in [true, false] {
+                    let specialized = rows
+                        .iter()
+                        .filter(|row| match row[0] {
+                            Pat::Bool(value) => value == b,
+                            _ => true,
+                        })
+                        .map(|row| row[1..].to_vec())
+                        .collect();
+
+                    if let Some(mut missing) = self.find_missing(specialized, rest_types) {
+                        missing.insert(0, if b { "صحيح" } else { "فاسد" }.to_string());
+                        return Some(missing);
+                    }
+                }
+                None
+            }
+            Ty::Primitive(p) if p.is_int() => {
+                let (min, max) = int_bounds(*p);
+
+                // The values between each two boundaries are matched by the same rows,
+                // so only one value of them needs to be checked
+                let mut boundaries = vec![min, max + 1];
+                for row in &rows {
+                    if let Pat::IntRange(start, end) = row[0] {
+                        if start <= end {
+                            boundaries.push(start.clamp(min, max + 1));
+                            boundaries.push((end + 1).clamp(min, max + 1));
+                        }
+                    }
+                }
+                boundaries.sort_unstable();
+                boundaries.dedup();
+
+                for segment in boundaries.windows(2) {
+                    let (start, end) = (segment[0], segment[1] - 1);
+
+                    let specialized = rows
+                        .iter()
+                        .filter(|row| match row[0] {
+                            Pat::IntRange(lo, hi) => lo <= start && end <= hi,
+                            Pat::Wild => true,
+                            _ => false,
+                        })
+                        .map(|row| row[1..].to_vec())
+                        .collect();
+
+                    if let Some(mut missing) = self.find_missing(specialized, rest_types) {
+                        let range = if (start, end) == (min, max) {
+                            "_".to_string()
+                        } else if start == end {
+                            self.numerals.fmt(start)
+                        } else {
+                            format!("{}..{}", self.numerals.fmt(start), self.numerals.fmt(end))
+                        };
+                        missing.insert(0, range);
+                        return Some(missing);
+                    }
+                }
+                None
+            }
+            Ty::Unit
+            | Ty::UnitStruct(_)
+            | Ty::Tuple(_)
+            | Ty::TupleStruct(_)
+            | Ty::FieldsStruct(_) => {
+                let mut types = self.elements_types(ty);
+                let arity = types.len();
+                types.extend_from_slice(rest_types);
+
+                let specialized = rows
+                    .iter()
+                    .map(|row| {
+                        let mut specialized_row = match &row[0] {
+                            Pat::Single(pats) => pats.clone(),
+                            _ => vec![Pat::Wild; arity],
+                        };
+                        specialized_row.extend_from_slice(&row[1..]);
+                        specialized_row
+                    })
+                    .collect();
+
+                let mut missing = self.find_missing(specialized, &types)?;
+                let rest = missing.split_off(arity);
+                missing = [vec![self.fmt_missing(ty, missing)], rest].concat();
+                Some(missing)
+            }
+            Ty::Enum(enum_idx) => {
+                for (variant_idx, variant_types) in self.table.enums[*enum_idx].iter().enumerate() {
+                    let arity = variant_types.len();
+                    let mut types = variant_types.to_vec();
+                    types.extend_from_slice(rest_types);
+
+                    let specialized = rows
+                        .iter()
+                        .filter_map(|row| {
+                            let mut specialized_row = match &row[0] {
+                                Pat::Variant(idx, pats) if *idx == variant_idx => pats.clone(),
+                                Pat::Variant(_, _) => return None,
+                                _ => vec![Pat::Wild; arity],
+                            };
+                            specialized_row.extend_from_slice(&row[1..]);
+                            Some(specialized_row)
+                        })
+                        .collect();
+
+                    if let Some(mut missing) = self.find_missing(specialized, &types) {
+                        let rest = missing.split_off(arity);
+                        let variant = self.fmt_missing_variant(*enum_idx, variant_idx, missing);
+                        return Some([vec![variant], rest].concat());
+                    }
+                }
+                None
+            }
+            _ => {
+                // Only the wildcards could cover all the values
+                let default = rows
+                    .iter()
+                    .filter(|row| matches!(row[0], Pat::Wild))
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+
+                let mut missing = self.find_missing(default, rest_types)?;
+                missing.insert(0, "_".to_string());
+                Some(missing)
+            }
+        }
+    }
+
+    fn elements_types(&self, ty: &Ty) -> Vec<Ty> {
+        match ty {
+            Ty::Tuple(types) => types.to_vec(),
+            Ty::TupleStruct(idx) => self.table.tuple_structs[*idx].to_vec(),
+            Ty::FieldsStruct(idx) => self.table.fields_structs[*idx].to_vec(),
+            _ => vec![],
+        }
+    }
+
+    fn fmt_missing(&self, ty: &Ty, elements: Vec<String>) -> String {
+        match ty {
+            Ty::UnitStruct(idx) => {
+                format!(".{}", &self.id_pool[self.nir.unit_structs[*idx].name.id])
+            }
+            Ty::Tuple(_) if elements.len() == 1 => format!("({}،)", elements[0]),
+            Ty::Tuple(_) => format!("({})", elements.join("، ")),
+            Ty::TupleStruct(idx) => format!(
+                ".{}({})",
+                &self.id_pool[self.nir.tuple_structs[*idx].name.id],
+                elements.join("، ")
+            ),
+            Ty::FieldsStruct(idx) => {
+                let fields_struct = &self.nir.fields_structs[*idx];
+                let fields = fields_struct
+                    .fields
+                    .iter()
+                    .zip(elements)
+                    .map(|((_, name, _), element)| {
+                        format!("{} = {}", &self.id_pool[name.id], element)
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    ".{} {{ {} }}",
+                    &self.id_pool[fields_struct.name.id],
+                    fields.join("، ")
+                )
+            }
+            _ => "()".to_string(),
+        }
+    }
+
+    fn fmt_missing_variant(
+        &self,
+        enum_idx: usize,
+        variant_idx: usize,
+        elements: Vec<String>,
+    ) -> String {
+        let name = self.fmt_variant(enum_idx, variant_idx);
+        match &self.nir.enums[enum_idx].variants[variant_idx].kind {
+            VariantKind::Unit => format!(".{}", name),
+            VariantKind::Tuple(_) => format!(".{}({})", name, elements.join("، ")),
+            VariantKind::Fields(fields) => {
+                let fields = fields
+                    .iter()
+                    .zip(elements)
+                    .map(|((name, _), element)| format!("{} = {}", &self.id_pool[name.id], element))
+                    .collect::<Vec<_>>();
+                format!(".{} {{ {} }}", name, fields.join("، "))
+            }
+        }
+    }
+}
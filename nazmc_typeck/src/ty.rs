@@ -0,0 +1,84 @@
+use nazmc_nir::PrimitiveType;
+use thin_vec::ThinVec;
+
+/// The type of a value after lowering the NIR types and inferring the unspecified ones
+#[derive(Clone, PartialEq, Debug)]
+pub enum Ty {
+    Unit,
+    /// The type of the expressions that never complete (e.g. `أرجع`, `قطع` and `وصل`)
+    Never,
+    Primitive(PrimitiveType),
+    UnitStruct(usize),
+    TupleStruct(usize),
+    FieldsStruct(usize),
+    Enum(usize),
+    Ptr(Box<Ty>),
+    Ref(Box<Ty>),
+    PtrMut(Box<Ty>),
+    RefMut(Box<Ty>),
+    Slice(Box<Ty>),
+    Tuple(ThinVec<Ty>),
+    Array(Box<Ty>, u64),
+    Lambda(ThinVec<Ty>, Box<Ty>),
+    /// The index of an inference variable, no type will contain it after checking
+    Infer(usize),
+    /// The type of an invalid expression which was already reported,
+    /// it is compatible with all types so it will not cause more errors
+    Error,
+}
+
+impl Ty {
+    #[inline]
+    pub fn bool() -> Self {
+        Self::Primitive(PrimitiveType::Bool)
+    }
+
+    /// Returns the underlying type of pointers and references
+    #[inline]
+    pub fn pointee(&self) -> Option<&Ty> {
+        match self {
+            Self::Ptr(ty) | Self::Ref(ty) | Self::PtrMut(ty) | Self::RefMut(ty) => Some(ty),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of the values which an inference variable could be bound to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum TyVarKind {
+    Any,
+    /// The type of an unspecified int literal, it defaults to `ص4`
+    Int,
+    /// The type of an unspecified float literal, it defaults to `ع8`
+    Float,
+}
+
+/// The types accepted by the arithmetic and bitwise operators
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum NumClass {
+    /// Any int or float
+    Numeric,
+    /// Signed ints and floats
+    Signed,
+    Int,
+}
+
+impl NumClass {
+    pub(crate) fn accepts(self, p: PrimitiveType) -> bool {
+        match self {
+            NumClass::Numeric => p.is_int() || p.is_float(),
+            NumClass::Signed => p.is_signed_int() || p.is_float(),
+            NumClass::Int => p.is_int(),
+        }
+    }
+}
+
+pub(crate) struct TyVar {
+    pub(crate) kind: TyVarKind,
+    pub(crate) bound: Option<Ty>,
+    /// The file index and the span of the expression or the binding which created this variable
+    pub(crate) origin: (usize, nazmc_diagnostics::span::Span),
+    /// The operators used on the values of this variable before it was bound with their file indexes
+    /// and spans, they are checked against the inferred type after inference
+    pub(crate) num_uses: Vec<(NumClass, usize, nazmc_diagnostics::span::Span)>,
+}
@@ -1,74 +1,249 @@
-use std::{fs, path::PathBuf, process::exit};
-
 use bpaf::Bpaf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+    str::FromStr,
+};
 
+use nazmc_diagnostics::{
+    eprint_diagnostics, explanation, Diagnostic, Emitter, ErrorFormat, Lint, LintLevel, LintLevels,
+    Numerals, SourceMap,
+};
 use owo_colors::OwoColorize;
 
 #[derive(Clone, Debug, Bpaf)]
 #[bpaf(options, version)]
 /// The official compiler of Nazm programming language
-struct Cli {
-    #[bpaf(positional("FILE"))]
-    /// Nazm file to compile (with *.نظم extension)
-    file: PathBuf,
+pub struct Cli {
+    #[bpaf(argument("FORMAT"), fallback(ErrorFormat::Human))]
+    /// Print the diagnostics as colored code windows or as JSON objects, FORMAT is `human` or `json`
+    pub error_format: ErrorFormat,
+    #[bpaf(argument("NUMERALS"), optional)]
+    /// Print the numbers of the diagnostics and the programs output with the digits `لاتينية` (0-9) or `مشرقية` (٠-٩),
+    /// it overrides `الأرقام` in `nazm.yaml`
+    pub numerals: Option<Numerals>,
+    #[bpaf(external)]
+    pub subcommand: Subcommand,
 }
 
-#[inline]
-pub fn format_err(msg: String) -> String {
-    let err = "خطأ".bold();
-    let err_col = ":".bold();
-    format!("{}{} {}", err.bright_red(), err_col, msg)
+#[derive(Clone, Debug, Bpaf)]
+pub enum Subcommand {
+    #[bpaf(command("check"))]
+    /// Check the package for errors without running or compiling it
+    Check {
+        #[bpaf(argument("PATH"), optional)]
+        /// The path of `nazm.yaml` or the directory containing it, default to the current directory
+        manifest_path: Option<PathBuf>,
+        #[bpaf(argument("KIND"), optional)]
+        /// Print the tokens, the AST or the NIR of the package then stop, KIND is `tokens`, `ast` or `nir`
+        emit: Option<Emit>,
+        /// Print the emitted representation as JSON
+        json: bool,
+        #[bpaf(external)]
+        lint_options: LintOptions,
+    },
+    #[bpaf(command("run"))]
+    /// Check the package then run its `البداية` function
+    Run {
+        #[bpaf(argument("PATH"), optional)]
+        /// The path of `nazm.yaml` or the directory containing it, default to the current directory
+        manifest_path: Option<PathBuf>,
+        #[bpaf(external)]
+        lint_options: LintOptions,
+    },
+    #[bpaf(command("build"))]
+    /// Check the package then compile it to an executable in `build` directory
+    Build {
+        #[bpaf(argument("PATH"), optional)]
+        /// The path of `nazm.yaml` or the directory containing it, default to the current directory
+        manifest_path: Option<PathBuf>,
+        #[bpaf(external)]
+        lint_options: LintOptions,
+    },
+    #[bpaf(command("fix"))]
+    /// Apply the suggestions of the errors which could be fixed automatically to the package files
+    Fix {
+        #[bpaf(argument("PATH"), optional)]
+        /// The path of `nazm.yaml` or the directory containing it, default to the current directory
+        manifest_path: Option<PathBuf>,
+    },
+    #[bpaf(command("fmt"))]
+    /// Format the package files with the canonical style of Nazm
+    Fmt {
+        #[bpaf(argument("PATH"), optional)]
+        /// The path of `nazm.yaml` or the directory containing it, default to the current directory
+        manifest_path: Option<PathBuf>,
+        /// Don't write the files, print the files which are not formatted and fail if there is any
+        check: bool,
+    },
+    #[bpaf(command("lex"))]
+    /// Print the tokens of a single file
+    Lex {
+        /// Print the tokens as JSON
+        json: bool,
+        #[bpaf(positional("FILE"))]
+        /// The path of the `.نظم` file
+        file: PathBuf,
+    },
+    #[bpaf(command("parse"))]
+    /// Check the syntax of a single file and print its items
+    Parse {
+        #[bpaf(positional("FILE"))]
+        /// The path of the `.نظم` file
+        file: PathBuf,
+    },
+    #[bpaf(command("explain"))]
+    /// Print a detailed explanation of an error code with an example
+    Explain {
+        #[bpaf(positional("CODE"))]
+        /// The code of the error, e.g. `306` of `خطأ[306]`
+        code: usize,
+    },
 }
 
-#[inline]
-pub fn print_err(msg: String) {
-    eprintln!("{}", format_err(msg));
+/// The levels of the lints from the command line, they override the levels in `nazm.yaml`
+#[derive(Clone, Debug, Bpaf)]
+pub struct LintOptions {
+    /// Report the warnings of all the lints as errors
+    deny_warnings: bool,
+    #[bpaf(argument("LINT"), many)]
+    /// Don't report the lint, e.g. `--allow متغير_غير_ضروري`
+    allow: Vec<Lint>,
+    #[bpaf(argument("LINT"), many)]
+    /// Report the lint as a warning
+    warn: Vec<Lint>,
+    #[bpaf(argument("LINT"), many)]
+    /// Report the lint as an error
+    deny: Vec<Lint>,
 }
 
-pub fn read_file() -> (PathBuf, String) {
-    let file_path = cli().fallback_to_usage().run().file;
+impl LintOptions {
+    /// Sets the levels of the given lints over the levels read from `nazm.yaml`
+    pub fn apply(&self, levels: &mut LintLevels) {
+        for (lints, level) in [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warn),
+            (&self.deny, LintLevel::Deny),
+        ] {
+            for lint in lints {
+                levels.set(*lint, level);
+            }
+        }
+
+        if self.deny_warnings {
+            levels.deny_warnings();
+        }
+    }
+}
 
-    let err_msg_str = format!(
-        "{} {}{}",
-        "يُتوقع ملف بامتداد".bold(),
-        "*.نظم".bright_yellow().bold(),
-        "، ولكن تم العثور على".bold()
-    );
+/// The intermediate representations that could be printed by `check --emit`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Emit {
+    Tokens,
+    Ast,
+    Nir,
+}
 
-    let file_path_str = file_path.to_str().unwrap().to_string();
+impl FromStr for Emit {
+    type Err = String;
 
-    match file_path.extension() {
-        Some(ext) => {
-            if ext != "نظم" {
-                print_err(format!(
-                    "{} {}",
-                    err_msg_str,
-                    file_path_str.bright_red().bold()
-                ));
-                exit(1);
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tokens" => Ok(Self::Tokens),
+            "ast" => Ok(Self::Ast),
+            "nir" => Ok(Self::Nir),
+            _ => Err(format!("`{s}` is not one of `tokens`, `ast` or `nir`")),
         }
+    }
+}
 
+/// Prints the explanation of the error code or exits after printing an error if it has no explanation
+pub fn explain(emitter: &dyn Emitter, code: usize) {
+    match explanation(code) {
+        Some(text) => print!("{text}"),
         None => {
-            print_err(format!(
-                "{} {}",
-                err_msg_str,
-                file_path_str.bright_red().bold()
-            ));
-            exit(1);
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "لا يوجد شرح لرمز الخطأ".bold(),
+                    code.bright_red().bold(),
+                ),
+            );
+            exit(1)
         }
     }
+}
+
+/// Exits after printing an error if the file doesn't have the `.نظم` extension
+pub fn check_extension(emitter: &dyn Emitter, path: &Path) {
+    if path.extension().is_some_and(|ext| ext == "نظم") {
+        return;
+    }
+
+    print_err(
+        emitter,
+        format!(
+            "{} {}{} {}",
+            "يُتوقع ملف بامتداد".bold(),
+            "*.نظم".bright_yellow().bold(),
+            "، ولكن تم العثور على".bold(),
+            path.display().bright_red().bold()
+        ),
+    );
+    exit(1)
+}
 
-    match fs::read_to_string(&file_path) {
-        Ok(content) => (file_path, content),
+/// Reads the file or exits after printing an error if it cannot be read
+pub fn read_file(emitter: &dyn Emitter, path: &Path) -> String {
+    match fs::read_to_string(path) {
+        Ok(content) => content,
         Err(_) => {
-            print_err(format!(
-                "{} {} {}",
-                "لا يمكن قراءة الملف".bold(),
-                file_path_str.bright_red().bold(),
-                "أو أنه غير موجود".bold()
-            ));
-            exit(1);
+            print_err(
+                emitter,
+                format!(
+                    "{} {}{}",
+                    "لا يمكن قراءة الملف".bold(),
+                    path.display().bright_red().bold(),
+                    " أو أنه غير موجود".bold()
+                ),
+            );
+            exit(1)
+        }
+    }
+}
+
+#[inline]
+pub fn format_err(msg: String) -> String {
+    let err = "خطأ".bold();
+    let err_col = ":".bold();
+    format!("{}{} {}", err.bright_red(), err_col, msg)
+}
+
+#[inline]
+pub fn print_err(emitter: &dyn Emitter, msg: String) {
+    if emitter.format() == ErrorFormat::Json {
+        eprint_diagnostics(
+            emitter,
+            &SourceMap::new(),
+            vec![Diagnostic::error(strip_styles(&msg), vec![])],
+        );
+    } else {
+        eprintln!("{}", format_err(msg));
+    }
+}
+
+/// Removes the terminal escape sequences of the colors and the styles
+fn strip_styles(msg: &str) -> String {
+    let mut stripped = String::with_capacity(msg.len());
+    let mut chars = msg.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            stripped.push(c);
         }
     }
+    stripped
 }
@@ -0,0 +1,365 @@
+use super::Node;
+use nazmc_ast::*;
+use nazmc_data_pool::{Built, DataPool};
+
+/// Converts the AST of a file to a readable tree with the names taken from the pools
+pub(crate) struct ASTDumper<'a> {
+    pub(crate) id_pool: &'a DataPool<Built>,
+    pub(crate) str_pool: &'a DataPool<Built>,
+}
+
+impl<'a> ASTDumper<'a> {
+    pub(crate) fn file(&self, path: &str, file: &File) -> Node {
+        let imports = file.imports.iter().map(|(import, alias)| {
+            Node::new("Import")
+                .name(self.path(import))
+                .span(import.item.span)
+                .child("alias", self.id(alias))
+        });
+
+        let star_imports = file.star_imports.iter().map(|pkg_path| {
+            let node = Node::new("StarImport").name(self.pkg_path(pkg_path));
+            match (pkg_path.spans.first(), pkg_path.spans.last()) {
+                (Some(first), Some(last)) => node.span(first.merged_with(last)),
+                _ => node,
+            }
+        });
+
+        Node::new("File")
+            .name(path)
+            .children("import", imports)
+            .children("import", star_imports)
+            .children("item", file.items.iter().map(|item| self.item(item)))
+    }
+
+    fn id(&self, id: &ASTId) -> Node {
+        Node::new("Id").name(&self.id_pool[id.id]).span(id.span)
+    }
+
+    fn pkg_path(&self, pkg_path: &PkgPath) -> String {
+        pkg_path
+            .ids
+            .iter()
+            .map(|id| &self.id_pool[*id])
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    fn path(&self, path: &PkgPathWithItem) -> String {
+        let item = &self.id_pool[path.item.id];
+        if path.pkg_path.ids.is_empty() {
+            item.to_string()
+        } else {
+            format!("{}::{}", self.pkg_path(&path.pkg_path), item)
+        }
+    }
+
+    fn path_node(&self, kind: &str, path: &PkgPathWithItem) -> Node {
+        Node::new(kind).name(self.path(path)).span(path.item.span)
+    }
+
+    fn item(&self, item: &Item) -> Node {
+        let node = |kind: &str| {
+            Node::new(format!("{kind}({:?})", item.vis))
+                .name(&self.id_pool[item.name.id])
+                .span(item.name.span)
+        };
+
+        match &item.kind {
+            ItemKind::UnitStruct => node("UnitStruct"),
+            ItemKind::TupleStruct(tuple_struct) => node("TupleStruct").children(
+                "field",
+                tuple_struct.types.iter().map(|(vis, typ)| {
+                    Node::new(format!("Field({vis:?})")).child("type", self.typ(typ))
+                }),
+            ),
+            ItemKind::FieldsStruct(fields_struct) => node("FieldsStruct").children(
+                "field",
+                fields_struct.fields.iter().map(|(vis, name, typ)| {
+                    Node::new(format!("Field({vis:?})"))
+                        .name(&self.id_pool[name.id])
+                        .span(name.span)
+                        .child("type", self.typ(typ))
+                }),
+            ),
+            ItemKind::Enum(enum_) => node("Enum").children(
+                "variant",
+                enum_.variants.iter().map(|variant| {
+                    let node = Node::new("Variant")
+                        .name(&self.id_pool[variant.name.id])
+                        .span(variant.name.span);
+                    match &variant.kind {
+                        VariantKind::Unit => node,
+                        VariantKind::Tuple(types) => {
+                            node.children("type", types.iter().map(|typ| self.typ(typ)))
+                        }
+                        VariantKind::Fields(fields) => node.children(
+                            "field",
+                            fields.iter().map(|(name, typ)| {
+                                Node::new("Field")
+                                    .name(&self.id_pool[name.id])
+                                    .span(name.span)
+                                    .child("type", self.typ(typ))
+                            }),
+                        ),
+                    }
+                }),
+            ),
+            ItemKind::Const(const_) => node("Const")
+                .child("type", self.typ(&const_.typ))
+                .child("value", self.expr(&const_.expr)),
+            ItemKind::Static(static_) => node("Static")
+                .child("type", self.typ(&static_.typ))
+                .child("value", self.expr(&static_.expr)),
+            ItemKind::Fn(fn_) => node("Fn")
+                .children(
+                    "param",
+                    fn_.params.iter().map(|(name, typ)| {
+                        Node::new("Param")
+                            .name(&self.id_pool[name.id])
+                            .span(name.span)
+                            .child("type", self.typ(typ))
+                    }),
+                )
+                .child("return_type", self.typ(&fn_.return_type))
+                .child("body", self.scope(&fn_.body)),
+        }
+    }
+
+    fn typ(&self, typ: &Type) -> Node {
+        match typ {
+            Type::Path(path) => self.path_node("PathType", path),
+            Type::Unit(span) => {
+                let node = Node::new("UnitType");
+                match span {
+                    Some(span) => node.span(*span),
+                    None => node,
+                }
+            }
+            Type::Tuple(types, span) => Node::new("TupleType")
+                .span(*span)
+                .children("type", types.iter().map(|typ| self.typ(typ))),
+            Type::Paren(typ, span) => Node::new("ParenType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::Slice(typ, span) => Node::new("SliceType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::Array(typ, size, span) => Node::new("ArrayType")
+                .span(*span)
+                .child("type", self.typ(typ))
+                .child("size", self.expr(size)),
+            Type::Ptr(typ, span) => Node::new("PtrType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::Ref(typ, span) => Node::new("RefType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::PtrMut(typ, span) => Node::new("PtrMutType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::RefMut(typ, span) => Node::new("RefMutType")
+                .span(*span)
+                .child("type", self.typ(typ)),
+            Type::Lambda(params, return_type) => Node::new("LambdaType")
+                .children("param", params.iter().map(|typ| self.typ(typ)))
+                .child("return_type", self.typ(return_type)),
+        }
+    }
+
+    fn binding(&self, binding: &Binding) -> Node {
+        Node::new("Binding")
+            .child("kind", self.binding_kind(&binding.kind))
+            .opt_child("type", binding.typ.as_ref().map(|typ| self.typ(typ)))
+    }
+
+    fn binding_kind(&self, kind: &BindingKind) -> Node {
+        match kind {
+            BindingKind::Id(id) => self.id(id),
+            BindingKind::Tuple(kinds, span) => Node::new("TupleDestruction")
+                .span(*span)
+                .children("binding", kinds.iter().map(|kind| self.binding_kind(kind))),
+        }
+    }
+
+    fn scope(&self, scope: &Scope) -> Node {
+        Node::new("Scope")
+            .children("stm", scope.stms.iter().map(|stm| self.stm(stm)))
+            .opt_child(
+                "return",
+                scope.return_expr.as_ref().map(|expr| self.expr(expr)),
+            )
+    }
+
+    fn stm(&self, stm: &Stm) -> Node {
+        match stm {
+            Stm::Let(let_stm) => self.let_stm("Let", let_stm),
+            Stm::LetMut(let_stm) => self.let_stm("LetMut", let_stm),
+            Stm::While(while_stm) => Node::new("While")
+                .child("condition", self.expr(&while_stm.0))
+                .child("body", self.scope(&while_stm.1)),
+            Stm::DoWhile(do_while_stm) => Node::new("DoWhile")
+                .child("body", self.scope(&do_while_stm.0))
+                .child("condition", self.expr(&do_while_stm.1)),
+            Stm::If(if_expr) => self.if_expr(if_expr),
+            Stm::When(when_expr) => self.when_expr(when_expr),
+            Stm::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn let_stm(&self, kind: &str, let_stm: &LetStm) -> Node {
+        Node::new(kind)
+            .child("binding", self.binding(&let_stm.binding))
+            .opt_child(
+                "assign",
+                let_stm.assign.as_ref().map(|expr| self.expr(expr)),
+            )
+    }
+
+    fn if_expr(&self, if_expr: &IfExpr) -> Node {
+        let else_ifs = if_expr.else_ifs.iter().map(|(condition, scope)| {
+            Node::new("ElseIf")
+                .child("condition", self.expr(condition))
+                .child("body", self.scope(scope))
+        });
+
+        Node::new("If")
+            .child("condition", self.expr(&if_expr.if_.0))
+            .child("body", self.scope(&if_expr.if_.1))
+            .children("else_if", else_ifs)
+            .opt_child(
+                "else",
+                if_expr.else_.as_ref().map(|scope| self.scope(scope)),
+            )
+    }
+
+    fn when_expr(&self, when_expr: &WhenExpr) -> Node {
+        let arms = when_expr.arms.iter().map(|arm| {
+            Node::new("Arm")
+                .child("pattern", self.pattern(&arm.pattern))
+                .opt_child("guard", arm.guard.as_ref().map(|guard| self.expr(guard)))
+                .child("body", self.scope(&arm.body))
+        });
+
+        Node::new("When")
+            .child("expr", self.expr(&when_expr.expr))
+            .children("arm", arms)
+    }
+
+    fn pattern(&self, pattern: &Pattern) -> Node {
+        let node = match &pattern.kind {
+            PatternKind::Wildcard => Node::new("WildcardPattern"),
+            PatternKind::Binding(id) => Node::new("BindingPattern").name(&self.id_pool[id.id]),
+            PatternKind::Literal(expr) => {
+                Node::new("LiteralPattern").child("expr", self.expr(expr))
+            }
+            PatternKind::Range(range) => Node::new(format!("RangePattern({:?})", range.op))
+                .child("start", self.expr(&range.start))
+                .child("end", self.expr(&range.end)),
+            PatternKind::Tuple(patterns) => Node::new("TuplePattern").children(
+                "pattern",
+                patterns.iter().map(|pattern| self.pattern(pattern)),
+            ),
+            PatternKind::UnitStruct(path) => Node::new("UnitStructPattern").name(self.path(path)),
+            PatternKind::TupleStruct(tuple_struct) => Node::new("TupleStructPattern")
+                .name(self.path(&tuple_struct.path))
+                .children(
+                    "pattern",
+                    tuple_struct
+                        .patterns
+                        .iter()
+                        .map(|pattern| self.pattern(pattern)),
+                ),
+            PatternKind::FieldsStruct(fields_struct) => Node::new("FieldsStructPattern")
+                .name(self.path(&fields_struct.path))
+                .children(
+                    "field",
+                    fields_struct.fields.iter().map(|(name, pattern)| {
+                        Node::new("Field")
+                            .name(&self.id_pool[name.id])
+                            .span(name.span)
+                            .child("pattern", self.pattern(pattern))
+                    }),
+                ),
+        };
+
+        node.span(pattern.span)
+    }
+
+    fn exprs<'b>(&'b self, exprs: &'b [Expr]) -> impl Iterator<Item = Node> + 'b {
+        exprs.iter().map(|expr| self.expr(expr))
+    }
+
+    fn expr(&self, expr: &Expr) -> Node {
+        let node = match &expr.kind {
+            ExprKind::Literal(literal) => {
+                let value = match literal {
+                    LiteralExpr::Str(idx) => format!("{:?}", &self.str_pool[*idx]),
+                    LiteralExpr::Char(ch) => format!("{ch:?}"),
+                    LiteralExpr::Bool(b) => format!("{b}"),
+                    LiteralExpr::Num(num) => format!("{num:?}"),
+                };
+                Node::new("Literal").name(value)
+            }
+            ExprKind::Parens(expr) => Node::new("Parens").child("expr", self.expr(expr)),
+            ExprKind::Path(path) => Node::new("Path").name(self.path(path)),
+            ExprKind::Call(call) => Node::new("Call")
+                .child("on", self.expr(&call.on))
+                .children("arg", self.exprs(&call.args)),
+            ExprKind::UnitStruct(path) => Node::new("UnitStruct").name(self.path(path)),
+            ExprKind::TupleStruct(tuple_struct) => Node::new("TupleStruct")
+                .name(self.path(&tuple_struct.path))
+                .children("arg", self.exprs(&tuple_struct.args)),
+            ExprKind::FieldsStruct(fields_struct) => Node::new("FieldsStruct")
+                .name(self.path(&fields_struct.path))
+                .children(
+                    "field",
+                    fields_struct.fields.iter().map(|(name, expr)| {
+                        Node::new("Field")
+                            .name(&self.id_pool[name.id])
+                            .span(name.span)
+                            .child("expr", self.expr(expr))
+                    }),
+                ),
+            ExprKind::Field(field) => Node::new("Field")
+                .name(&self.id_pool[field.name.id])
+                .child("on", self.expr(&field.on)),
+            ExprKind::Index(index) => Node::new("Index")
+                .child("on", self.expr(&index.on))
+                .child("index", self.expr(&index.index)),
+            ExprKind::Tuple(elements) => {
+                Node::new("Tuple").children("element", self.exprs(elements))
+            }
+            ExprKind::ArrayElemnts(elements) => {
+                Node::new("ArrayElements").children("element", self.exprs(elements))
+            }
+            ExprKind::ArrayElemntsSized(array) => Node::new("ArrayElementsSized")
+                .child("repeat", self.expr(&array.repeat))
+                .child("size", self.expr(&array.size)),
+            ExprKind::If(if_expr) => self.if_expr(if_expr),
+            ExprKind::When(when_expr) => self.when_expr(when_expr),
+            ExprKind::Lambda(lambda) => Node::new("Lambda")
+                .children(
+                    "param",
+                    lambda.params.iter().map(|param| self.binding(param)),
+                )
+                .child("body", self.scope(&lambda.body)),
+            ExprKind::UnaryOp(unary) => {
+                Node::new(format!("UnaryOp({:?})", unary.op)).child("expr", self.expr(&unary.expr))
+            }
+            ExprKind::BinaryOp(bin) => Node::new(format!("BinaryOp({:?})", bin.op))
+                .child("left", self.expr(&bin.left))
+                .child("right", self.expr(&bin.right)),
+            ExprKind::Return(expr) => {
+                Node::new("Return").opt_child("expr", expr.as_ref().map(|expr| self.expr(expr)))
+            }
+            ExprKind::Break(expr) => {
+                Node::new("Break").opt_child("expr", expr.as_ref().map(|expr| self.expr(expr)))
+            }
+            ExprKind::Continue => Node::new("Continue"),
+            ExprKind::On => Node::new("On"),
+        };
+
+        node.span(expr.span)
+    }
+}
@@ -0,0 +1,169 @@
+mod ast;
+mod nir;
+mod tokens;
+
+pub(crate) use ast::ASTDumper;
+pub(crate) use nir::NIRDumper;
+pub(crate) use tokens::{print_tokens, FileTokens};
+
+use nazmc_diagnostics::{span::Span, SourceFile, SourceMap};
+use serde::Serialize;
+
+/// A node in the dumped tree of the AST or the NIR, it is printed as indented lines or as JSON
+#[derive(Serialize)]
+pub(crate) struct Node {
+    /// The role of the node in its parent (e.g. `condition` or `body`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<&'static str>,
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// The arena of the NIR where the node is stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arena: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    #[serde(skip)]
+    span: Option<Span>,
+    /// The lines and the columns of the span, they are computed from its file before printing
+    #[serde(rename = "span", skip_serializing_if = "Option::is_none")]
+    location: Option<NodeSpan>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<Node>,
+}
+
+/// The lines and the columns of a span starting from 1
+#[derive(Serialize)]
+pub(crate) struct NodeSpan {
+    start: [usize; 2],
+    end: [usize; 2],
+}
+
+impl NodeSpan {
+    pub(crate) fn new(file: &SourceFile, span: Span) -> Self {
+        let (start, end) = file.span_cursors(span);
+
+        Self {
+            start: [start.line + 1, start.col + 1],
+            end: [end.line + 1, end.col + 1],
+        }
+    }
+}
+
+impl std::fmt::Display for NodeSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start[0], self.start[1], self.end[0], self.end[1]
+        )
+    }
+}
+
+impl Node {
+    pub(crate) fn new(kind: impl Into<String>) -> Self {
+        Self {
+            label: None,
+            kind: kind.into(),
+            name: None,
+            arena: None,
+            index: None,
+            span: None,
+            location: None,
+            children: vec![],
+        }
+    }
+
+    pub(crate) fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub(crate) fn span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub(crate) fn arena(mut self, arena: &'static str, index: usize) -> Self {
+        self.arena = Some(arena);
+        self.index = Some(index);
+        self
+    }
+
+    pub(crate) fn child(mut self, label: &'static str, mut node: Node) -> Self {
+        node.label = Some(label);
+        self.children.push(node);
+        self
+    }
+
+    pub(crate) fn opt_child(self, label: &'static str, node: Option<Node>) -> Self {
+        match node {
+            Some(node) => self.child(label, node),
+            None => self,
+        }
+    }
+
+    pub(crate) fn children(
+        mut self,
+        label: &'static str,
+        nodes: impl IntoIterator<Item = Node>,
+    ) -> Self {
+        for node in nodes {
+            self = self.child(label, node);
+        }
+        self
+    }
+
+    fn locate(&mut self, source_map: &SourceMap) {
+        self.location = self
+            .span
+            .map(|span| NodeSpan::new(&source_map[span.file_id], span));
+
+        for child in &mut self.children {
+            child.locate(source_map);
+        }
+    }
+
+    fn write_tree(&self, depth: usize, out: &mut String) {
+        for _ in 0..depth {
+            out.push_str("  ");
+        }
+        if let Some(label) = self.label {
+            out.push_str(label);
+            out.push_str(": ");
+        }
+        out.push_str(&self.kind);
+        if let Some(name) = &self.name {
+            out.push(' ');
+            out.push_str(name);
+        }
+        if let (Some(arena), Some(index)) = (self.arena, self.index) {
+            out.push_str(&format!(" {arena}[{index}]"));
+        }
+        if let Some(location) = &self.location {
+            out.push_str(&format!(" @{location}"));
+        }
+        out.push('\n');
+
+        for child in &self.children {
+            child.write_tree(depth + 1, out);
+        }
+    }
+}
+
+/// Prints the trees as indented lines or as a JSON array
+pub(crate) fn print_nodes(source_map: &SourceMap, mut nodes: Vec<Node>, json: bool) {
+    for node in &mut nodes {
+        node.locate(source_map);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&nodes).unwrap());
+    } else {
+        let mut out = String::new();
+        for node in nodes {
+            node.write_tree(0, &mut out);
+        }
+        print!("{out}");
+    }
+}
@@ -0,0 +1,455 @@
+use super::Node;
+use nazmc_data_pool::{Built, DataPool};
+use nazmc_nir::*;
+
+/// Converts the NIR to a readable tree, each expression, statement, type and scope is shown with
+/// the arena where it is stored and its index in it
+pub(crate) struct NIRDumper<'a> {
+    pub(crate) id_pool: &'a DataPool<Built>,
+    pub(crate) str_pool: &'a DataPool<Built>,
+    pub(crate) nir: &'a NIR,
+}
+
+impl<'a> NIRDumper<'a> {
+    pub(crate) fn dump(&self) -> Node {
+        let nir = self.nir;
+
+        let unit_structs = nir.unit_structs.iter().enumerate().map(|(i, s)| {
+            self.item("UnitStruct", s.vis, &s.name)
+                .arena("unit_structs", i)
+        });
+
+        let tuple_structs = nir.tuple_structs.iter().enumerate().map(|(i, s)| {
+            self.item("TupleStruct", s.vis, &s.name)
+                .arena("tuple_structs", i)
+                .children(
+                    "field",
+                    s.types.iter().map(|(vis, typ)| {
+                        Node::new(format!("Field({vis:?})")).child("type", self.typ(typ))
+                    }),
+                )
+        });
+
+        let fields_structs = nir.fields_structs.iter().enumerate().map(|(i, s)| {
+            self.item("FieldsStruct", s.vis, &s.name)
+                .arena("fields_structs", i)
+                .children(
+                    "field",
+                    s.fields.iter().map(|(vis, name, typ)| {
+                        self.id(&format!("Field({vis:?})"), name)
+                            .child("type", self.typ(typ))
+                    }),
+                )
+        });
+
+        let enums = nir.enums.iter().enumerate().map(|(i, e)| {
+            let variants = e.variants.iter().map(|variant| {
+                let node = self.id("Variant", &variant.name);
+                match &variant.kind {
+                    VariantKind::Unit => node,
+                    VariantKind::Tuple(types) => {
+                        node.children("type", types.iter().map(|typ| self.typ(typ)))
+                    }
+                    VariantKind::Fields(fields) => node.children(
+                        "field",
+                        fields
+                            .iter()
+                            .map(|(name, typ)| self.id("Field", name).child("type", self.typ(typ))),
+                    ),
+                }
+            });
+
+            self.item("Enum", e.vis, &e.name)
+                .arena("enums", i)
+                .children("variant", variants)
+        });
+
+        let consts = nir.consts.iter().enumerate().map(|(i, c)| {
+            self.item("Const", c.vis, &c.name)
+                .arena("consts", i)
+                .child("type", self.typ(&c.typ))
+                .child("value", self.expr(&c.expr))
+        });
+
+        let statics = nir.statics.iter().enumerate().map(|(i, s)| {
+            self.item("Static", s.vis, &s.name)
+                .arena("statics", i)
+                .child("type", self.typ(&s.typ))
+                .child("value", self.expr(&s.expr))
+        });
+
+        let fns = nir.fns.iter().enumerate().map(|(i, f)| {
+            self.item("Fn", f.vis, &f.name)
+                .arena("fns", i)
+                .children(
+                    "param",
+                    f.params.iter().map(|(local_idx, typ)| {
+                        self.local(*local_idx).child("type", self.typ(typ))
+                    }),
+                )
+                .child("return_type", self.typ(&f.return_type))
+                .child("body", self.scope(f.body))
+        });
+
+        Node::new("NIR")
+            .children("item", unit_structs)
+            .children("item", tuple_structs)
+            .children("item", fields_structs)
+            .children("item", enums)
+            .children("item", consts)
+            .children("item", statics)
+            .children("item", fns)
+    }
+
+    fn id(&self, kind: &str, id: &NIRId) -> Node {
+        Node::new(kind).name(&self.id_pool[id.id]).span(id.span)
+    }
+
+    fn item(&self, kind: &str, vis: VisModifier, name: &NIRId) -> Node {
+        self.id(&format!("{kind}({vis:?})"), name)
+    }
+
+    fn local(&self, local_idx: usize) -> Node {
+        let local = &self.nir.locals[local_idx];
+        let kind = if local.is_mut { "LocalMut" } else { "Local" };
+        self.id(kind, &local.name).arena("locals", local_idx)
+    }
+
+    fn path(&self, kind: &str, path: &ModPathWithItem) -> Node {
+        let mut name = path
+            .mod_path
+            .ids
+            .iter()
+            .map(|id| &self.id_pool[*id])
+            .collect::<Vec<_>>();
+        name.push(&self.id_pool[path.item.id]);
+
+        Node::new(kind)
+            .name(name.join("::"))
+            .child("resolved", Node::new(format!("{:?}", path.resolved)))
+    }
+
+    fn typ(&self, typ: &Type) -> Node {
+        let types = &self.nir.types;
+        let index = typ.kind_and_index.index();
+
+        let node = match typ.kind_and_index.kind() {
+            TypeKindAndIndex::UNIT => Node::new("UnitType"),
+            TypeKindAndIndex::PRIMITIVE => {
+                Node::new("PrimitiveType").name(PrimitiveType::from_index(index).name())
+            }
+            TypeKindAndIndex::PATH => self
+                .path("PathType", &types.paths[index])
+                .arena("types.paths", index),
+            TypeKindAndIndex::PTR => Node::new("PtrType")
+                .arena("types.ptrs", index)
+                .child("type", self.typ(&types.ptrs[index])),
+            TypeKindAndIndex::REF => Node::new("RefType")
+                .arena("types.refs", index)
+                .child("type", self.typ(&types.refs[index])),
+            TypeKindAndIndex::PTR_MUT => Node::new("PtrMutType")
+                .arena("types.ptrs_mut", index)
+                .child("type", self.typ(&types.ptrs_mut[index])),
+            TypeKindAndIndex::REF_MUT => Node::new("RefMutType")
+                .arena("types.refs_mut", index)
+                .child("type", self.typ(&types.refs_mut[index])),
+            TypeKindAndIndex::SLICE => Node::new("SliceType")
+                .arena("types.slices", index)
+                .child("type", self.typ(&types.slices[index])),
+            TypeKindAndIndex::TUPLE => Node::new("TupleType")
+                .arena("types.tuples", index)
+                .children(
+                    "type",
+                    types.tuples[index].types.iter().map(|typ| self.typ(typ)),
+                ),
+            TypeKindAndIndex::ARRAY => {
+                let array = &types.arrays[index];
+                Node::new("ArrayType")
+                    .arena("types.arrays", index)
+                    .child("type", self.typ(&array.typ))
+                    .child("size", self.expr(&array.size))
+            }
+            TypeKindAndIndex::LAMBDA => {
+                let lambda = &types.lambdas[index];
+                Node::new("LambdaType")
+                    .arena("types.lambdas", index)
+                    .children("param", lambda.params.iter().map(|typ| self.typ(typ)))
+                    .child("return_type", self.typ(&lambda.return_type))
+            }
+            _ => unreachable!(),
+        };
+
+        node.span(typ.span)
+    }
+
+    fn binding(&self, binding: &Binding) -> Node {
+        Node::new("Binding")
+            .child("kind", self.binding_kind(&binding.kind))
+            .opt_child("type", binding.typ.as_ref().map(|typ| self.typ(typ)))
+    }
+
+    fn binding_kind(&self, kind: &BindingKind) -> Node {
+        match kind {
+            BindingKind::Local(local_idx) => self.local(*local_idx),
+            BindingKind::TupleDestruction(kinds, span) => Node::new("TupleDestruction")
+                .span(*span)
+                .children("binding", kinds.iter().map(|kind| self.binding_kind(kind))),
+        }
+    }
+
+    fn scope(&self, scope: Scope) -> Node {
+        let body = &self.nir.scopes[scope.index];
+
+        Node::new("Scope")
+            .arena("scopes", scope.index)
+            .children("stm", body.stms.iter().map(|stm| self.stm(stm)))
+            .opt_child(
+                "return",
+                body.return_expr.as_ref().map(|expr| self.expr(expr)),
+            )
+    }
+
+    fn conditional_scope(&self, kind: &str, conditional_scope: &ConditionalScope) -> Node {
+        Node::new(kind)
+            .child("condition", self.expr(&conditional_scope.condition))
+            .child("body", self.scope(conditional_scope.scope))
+    }
+
+    fn stm(&self, stm: &Stm) -> Node {
+        let stms = &self.nir.stms;
+        let index = stm.kind_and_index.index();
+
+        match stm.kind_and_index.kind() {
+            StmKindAndIndex::LET => self
+                .let_stm("Let", &stms.lets[index])
+                .arena("stms.lets", index),
+            StmKindAndIndex::LET_MUT => self
+                .let_stm("LetMut", &stms.let_muts[index])
+                .arena("stms.let_muts", index),
+            StmKindAndIndex::IF => self.if_expr(&stms.ifs[index]).arena("stms.ifs", index),
+            StmKindAndIndex::WHILE => self
+                .conditional_scope("While", &stms.whiles[index])
+                .arena("stms.whiles", index),
+            StmKindAndIndex::DO_WHILE => self
+                .conditional_scope("DoWhile", &stms.do_whiles[index])
+                .arena("stms.do_whiles", index),
+            StmKindAndIndex::WHEN => self
+                .when_expr(&stms.whens[index])
+                .arena("stms.whens", index),
+            StmKindAndIndex::EXPR => Node::new("ExprStm")
+                .arena("stms.exprs", index)
+                .child("expr", self.expr(&stms.exprs[index])),
+            _ => unreachable!(),
+        }
+    }
+
+    fn let_stm(&self, kind: &str, let_stm: &LetStm) -> Node {
+        Node::new(kind)
+            .child("binding", self.binding(&let_stm.binding))
+            .opt_child(
+                "assign",
+                let_stm.assign.as_ref().map(|expr| self.expr(expr)),
+            )
+    }
+
+    fn if_expr(&self, if_expr: &IfExpr) -> Node {
+        Node::new("If")
+            .child("if", self.conditional_scope("Then", &if_expr.if_))
+            .children(
+                "else_if",
+                if_expr
+                    .else_ifs
+                    .iter()
+                    .map(|else_if| self.conditional_scope("ElseIf", else_if)),
+            )
+            .opt_child("else", if_expr.else_.map(|scope| self.scope(scope)))
+    }
+
+    fn when_expr(&self, when_expr: &WhenExpr) -> Node {
+        let arms = when_expr.arms.iter().map(|arm| {
+            Node::new("Arm")
+                .child("pattern", self.pattern(&arm.pattern))
+                .opt_child("guard", arm.guard.as_ref().map(|guard| self.expr(guard)))
+                .child("body", self.scope(arm.body))
+        });
+
+        Node::new("When")
+            .child("expr", self.expr(&when_expr.expr))
+            .children("arm", arms)
+    }
+
+    fn pattern(&self, pattern: &Pattern) -> Node {
+        let node = match &pattern.kind {
+            PatternKind::Wildcard => Node::new("WildcardPattern"),
+            PatternKind::Binding(local_idx) => {
+                Node::new("BindingPattern").child("local", self.local(*local_idx))
+            }
+            PatternKind::Literal(expr) => {
+                Node::new("LiteralPattern").child("expr", self.expr(expr))
+            }
+            PatternKind::Range(range) => Node::new(format!("RangePattern({:?})", range.op))
+                .child("start", self.expr(&range.start))
+                .child("end", self.expr(&range.end)),
+            PatternKind::Tuple(patterns) => Node::new("TuplePattern").children(
+                "pattern",
+                patterns.iter().map(|pattern| self.pattern(pattern)),
+            ),
+            PatternKind::UnitStruct(path) => self.path("UnitStructPattern", path),
+            PatternKind::TupleStruct(path, patterns) => {
+                self.path("TupleStructPattern", path).children(
+                    "pattern",
+                    patterns.iter().map(|pattern| self.pattern(pattern)),
+                )
+            }
+            PatternKind::FieldsStruct(path, fields) => {
+                self.path("FieldsStructPattern", path).children(
+                    "field",
+                    fields.iter().map(|(name, pattern)| {
+                        self.id("Field", name)
+                            .child("pattern", self.pattern(pattern))
+                    }),
+                )
+            }
+        };
+
+        node.span(pattern.span)
+    }
+
+    fn exprs<'b>(&'b self, exprs: &'b [Expr]) -> impl Iterator<Item = Node> + 'b {
+        exprs.iter().map(|expr| self.expr(expr))
+    }
+
+    fn expr(&self, expr: &Expr) -> Node {
+        let exprs = &self.nir.exprs;
+        let index = expr.kind_and_index.index();
+
+        let node = match expr.kind_and_index.kind() {
+            ExprKindAndIndex::UNIT => Node::new("Unit"),
+            ExprKindAndIndex::LITERAL => {
+                let value = match exprs.literals[index] {
+                    LiteralExpr::Str(idx) => format!("{:?}", &self.str_pool[idx]),
+                    LiteralExpr::Char(ch) => format!("{ch:?}"),
+                    LiteralExpr::Bool(b) => format!("{b}"),
+                    LiteralExpr::Num(num) => format!("{num:?}"),
+                };
+                Node::new("Literal")
+                    .name(value)
+                    .arena("exprs.literals", index)
+            }
+            ExprKindAndIndex::PARENS => Node::new("Parens")
+                .arena("exprs.parens", index)
+                .child("expr", self.expr(&exprs.parens[index].expr)),
+            ExprKindAndIndex::PATH => self
+                .path("Path", &exprs.paths[index])
+                .arena("exprs.paths", index),
+            ExprKindAndIndex::PATH_CALL => {
+                let call = &exprs.path_calls[index];
+                self.path("PathCall", &call.path)
+                    .arena("exprs.path_calls", index)
+                    .children("arg", self.exprs(&call.args))
+            }
+            ExprKindAndIndex::METHOD_CALL => {
+                let call = &exprs.method_calls[index];
+                Node::new("MethodCall")
+                    .arena("exprs.method_calls", index)
+                    .child("on", self.expr(&call.on))
+                    .children("arg", self.exprs(&call.args))
+            }
+            ExprKindAndIndex::CALL => {
+                let call = &exprs.calls[index];
+                Node::new("Call")
+                    .arena("exprs.calls", index)
+                    .child("on", self.expr(&call.on))
+                    .children("arg", self.exprs(&call.args))
+            }
+            ExprKindAndIndex::UNIT_STRUCT => self
+                .path("UnitStruct", &exprs.unit_structs[index])
+                .arena("exprs.unit_structs", index),
+            ExprKindAndIndex::TUPLE_STRUCT => {
+                let tuple_struct = &exprs.tuple_structs[index];
+                self.path("TupleStruct", &tuple_struct.path)
+                    .arena("exprs.tuple_structs", index)
+                    .children("arg", self.exprs(&tuple_struct.args))
+            }
+            ExprKindAndIndex::FIELDS_STRUCT => {
+                let fields_struct = &exprs.fields_structs[index];
+                self.path("FieldsStruct", &fields_struct.path)
+                    .arena("exprs.fields_structs", index)
+                    .children(
+                        "field",
+                        fields_struct.fields.iter().map(|field| {
+                            self.id("Field", &field.name)
+                                .child("expr", self.expr(&field.expr))
+                        }),
+                    )
+            }
+            ExprKindAndIndex::FIELD => {
+                let field = &exprs.fields[index];
+                Node::new("Field")
+                    .name(&self.id_pool[field.name.id])
+                    .arena("exprs.fields", index)
+                    .child("on", self.expr(&field.on))
+            }
+            ExprKindAndIndex::INDEX => {
+                let idx = &exprs.indexes[index];
+                Node::new("Index")
+                    .arena("exprs.indexes", index)
+                    .child("on", self.expr(&idx.on))
+                    .child("index", self.expr(&idx.idx))
+            }
+            ExprKindAndIndex::ARRAY_ELEMENTS => Node::new("ArrayElements")
+                .arena("exprs.array_elements", index)
+                .children("element", self.exprs(&exprs.array_elements[index].elements)),
+            ExprKindAndIndex::ARRAY_ELEMENTS_SIZED => {
+                let array = &exprs.array_elements_sized[index];
+                Node::new("ArrayElementsSized")
+                    .arena("exprs.array_elements_sized", index)
+                    .child("repeat", self.expr(&array.repeat))
+                    .child("size", self.expr(&array.size))
+            }
+            ExprKindAndIndex::TUPLE_EXPR => Node::new("Tuple")
+                .arena("exprs.tuples", index)
+                .children("element", self.exprs(&exprs.tuples[index].elements)),
+            ExprKindAndIndex::RETURN_WITH_VALUE => Node::new("Return")
+                .arena("exprs.returns", index)
+                .child("expr", self.expr(&exprs.returns[index].expr_to_return)),
+            ExprKindAndIndex::RETURN => Node::new("Return"),
+            ExprKindAndIndex::BREAK_WITH_VALUE => Node::new("Break")
+                .arena("exprs.breaks", index)
+                .child("expr", self.expr(&exprs.breaks[index].expr_to_break)),
+            ExprKindAndIndex::BREAK => Node::new("Break"),
+            ExprKindAndIndex::CONTINUE => Node::new("Continue"),
+            ExprKindAndIndex::ON => Node::new("On"),
+            ExprKindAndIndex::IF_EXPR => self.if_expr(&exprs.ifs[index]).arena("exprs.ifs", index),
+            ExprKindAndIndex::WHEN_EXPR => self
+                .when_expr(&exprs.whens[index])
+                .arena("exprs.whens", index),
+            ExprKindAndIndex::LAMBDA_EXPR => {
+                let lambda = &exprs.lambdas[index];
+                Node::new("Lambda")
+                    .arena("exprs.lambdas", index)
+                    .children(
+                        "param",
+                        lambda.params.iter().map(|param| self.binding(param)),
+                    )
+                    .child("body", self.scope(lambda.body))
+            }
+            ExprKindAndIndex::UNARY_EXPR => {
+                let unary = &exprs.unary_exprs[index];
+                Node::new(format!("UnaryOp({:?})", unary.op))
+                    .arena("exprs.unary_exprs", index)
+                    .child("expr", self.expr(&unary.expr))
+            }
+            ExprKindAndIndex::BIN_EXPR => {
+                let bin = &exprs.bin_exprs[index];
+                Node::new(format!("BinaryOp({:?})", bin.op))
+                    .arena("exprs.bin_exprs", index)
+                    .child("left", self.expr(&bin.left))
+                    .child("right", self.expr(&bin.right))
+            }
+            _ => unreachable!(),
+        };
+
+        node.span(expr.span)
+    }
+}
@@ -0,0 +1,58 @@
+use super::NodeSpan;
+use nazmc_diagnostics::SourceFile;
+use nazmc_lexer::{Token, TokenKind};
+use serde::Serialize;
+
+/// The tokens of a single file
+#[derive(Serialize)]
+pub(crate) struct FileTokens {
+    file: String,
+    tokens: Vec<TokenEntry>,
+}
+
+#[derive(Serialize)]
+struct TokenEntry {
+    kind: String,
+    text: String,
+    span: NodeSpan,
+    /// The start and the end bytes of the token in the file
+    bytes: [u32; 2],
+}
+
+impl FileTokens {
+    pub(crate) fn new(file: &SourceFile, tokens: &[Token]) -> Self {
+        let tokens = tokens
+            .iter()
+            .take_while(|token| !matches!(token.kind, TokenKind::Eof))
+            .map(|token| TokenEntry {
+                kind: format!("{:?}", token.kind),
+                text: file.content()[token.span.byte_range()].to_string(),
+                span: NodeSpan::new(file, token.span),
+                bytes: [token.span.lo, token.span.hi],
+            })
+            .collect();
+
+        Self {
+            file: file.path().to_string(),
+            tokens,
+        }
+    }
+}
+
+/// Prints the tokens of each file, one token per line, or as a JSON array
+pub(crate) fn print_tokens(files: &[FileTokens], json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(files).unwrap());
+        return;
+    }
+
+    for file in files {
+        println!("{}:", file.file);
+        for token in &file.tokens {
+            println!(
+                "  {}\t{}..{}\t{}\t{:?}",
+                token.span, token.bytes[0], token.bytes[1], token.kind, token.text
+            );
+        }
+    }
+}
@@ -1,231 +1,499 @@
 mod cli;
-use cli::print_err;
+mod emit;
+use cli::{check_extension, explain, print_err, read_file, Emit, Subcommand};
+use emit::{print_nodes, print_tokens, ASTDumper, FileTokens, NIRDumper};
 use nazmc_data_pool::DataPool;
-use nazmc_data_pool::PoolIdx;
-use nazmc_diagnostics::span::Span;
+use nazmc_diagnostics::{
+    apply_fixes, collect_fixes, eprint_diagnostics, Diagnostic, Emitter, ErrorFormat, Fix,
+};
+use nazmc_driver::{read_sources, Config, FileId, Manifest, PhaseResult, Session, SourceMap};
 use nazmc_lexer::LexerIter;
-use nazmc_parser::parse;
+use nazmc_parser::{check_lexer_errors, format_file, parse};
 use owo_colors::OwoColorize;
-use serde::Deserialize;
-use serde_yaml::Value;
 use std::io;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::{
-    collections::HashMap,
-    fs, panic,
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
     process::{exit, Command},
 };
-use thin_vec::ThinVec;
-
-fn collect_paths(paths: Vec<Value>, prefix: &str, collected_paths: &mut Vec<String>) {
-    for path in paths {
-        match path {
-            Value::String(s) => {
-                if prefix.is_empty() {
-                    collected_paths.push(s.clone());
-                } else {
-                    collected_paths.push(format!("{}/{}", prefix, s));
-                }
-            }
-            Value::Mapping(mapping) => {
-                for (key, value) in mapping {
-                    if let Value::String(key_str) = key {
-                        let new_prefix = if prefix.is_empty() {
-                            key_str.clone()
-                        } else {
-                            format!("{}/{}", prefix, key_str)
-                        };
-
-                        if let Value::Sequence(nested_paths) = value {
-                            collect_paths(nested_paths, &new_prefix, collected_paths);
-                        }
-                    }
-                }
-            }
-            s => todo!("{:?}", s),
+
+/// Reads the package manifest, its path may be the file itself or the directory containing it,
+/// then the current directory is changed to the package directory
+fn read_manifest(emitter: &dyn Emitter, manifest_path: Option<&Path>) -> Manifest {
+    let manifest_path = match manifest_path {
+        Some(path) if path.is_dir() => path.join("nazm.yaml"),
+        Some(path) => path.to_path_buf(),
+        None => PathBuf::from("nazm.yaml"),
+    };
+
+    let manifest = match Manifest::read(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(d) => exit_with_diagnostics(emitter, &SourceMap::new(), vec![d]),
+    };
+
+    if let Some(dir) = manifest_path.parent() {
+        if !dir.as_os_str().is_empty() && env::set_current_dir(dir).is_err() {
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "لا يمكن الانتقال إلى المجلد".bold(),
+                    dir.display().bright_red().bold()
+                ),
+            );
+            exit(1);
         }
     }
+
+    manifest
+}
+
+/// Prints the diagnostics of the files in the source map then exits with a failure
+fn exit_with_diagnostics(
+    emitter: &dyn Emitter,
+    source_map: &SourceMap,
+    diagnostics: Vec<Diagnostic>,
+) -> ! {
+    eprint_diagnostics(emitter, source_map, diagnostics);
+    exit(1)
+}
+
+/// Reads the file to the source map, or exits if it can't be read or it is too large
+fn add_file(emitter: &dyn Emitter, source_map: &mut SourceMap, path: &Path) -> FileId {
+    check_extension(emitter, path);
+
+    match source_map.add(path.display().to_string(), read_file(emitter, path)) {
+        Ok(file_id) => file_id,
+        Err(d) => exit_with_diagnostics(emitter, &SourceMap::new(), vec![d]),
+    }
 }
 
-#[derive(Deserialize)]
-struct NazmYaml {
-    الاسم: Option<String>,
-    الإصدار: Option<String>,
-    المسارات: Vec<Value>,
+/// Prints the warnings of the phase and returns its output, or prints its errors and exits
+fn phase_output<T>(emitter: &dyn Emitter, source_map: &SourceMap, result: PhaseResult<T>) -> T {
+    match result {
+        Ok((output, warnings)) => {
+            if !warnings.is_empty() {
+                eprint_diagnostics(emitter, source_map, warnings);
+            }
+            output
+        }
+        Err(diagnostics) => exit_with_diagnostics(emitter, source_map, diagnostics),
+    }
 }
 
-fn get_file_paths() -> Vec<String> {
-    let nazm_yaml = match fs::read_to_string("nazm.yaml") {
-        Ok(content) => content,
+/// Writes the generated C code to `build` directory and compiles it with the C compiler in `CC`
+fn build_c(emitter: &dyn Emitter, package_name: &str, c_code: String) {
+    let c_path = format!("build/{package_name}.c");
+    let exe_path = format!("build/{package_name}");
+
+    if fs::create_dir_all("build").is_err() || fs::write(&c_path, c_code).is_err() {
+        print_err(
+            emitter,
+            format!(
+                "{} {}",
+                "لا يمكن كتابة الملف".bold(),
+                c_path.bright_red().bold()
+            ),
+        );
+        exit(1)
+    }
+
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(&cc)
+        .args(["-O2", "-o", &exe_path, &c_path, "-lm"])
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(_) => {
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "فشلت ترجمة الملف".bold(),
+                    c_path.bright_red().bold()
+                ),
+            );
+            exit(1)
+        }
         Err(_) => {
-            print_err(format!("{}", "لم يتم العثور على ملف nazm.yaml".bold(),));
-            exit(1);
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "لا يمكن تشغيل مترجم لغة C".bold(),
+                    cc.bright_red().bold()
+                ),
+            );
+            exit(1)
         }
-    };
+    }
+}
 
-    let mut val = serde_yaml::from_str::<Value>(&nazm_yaml).unwrap();
+/// Prints the tokens of the file with their positions, one token per line or as JSON
+fn lex_file(emitter: &dyn Emitter, path: &Path, json: bool) {
+    let mut source_map = SourceMap::new();
+    let file_id = add_file(emitter, &mut source_map, path);
+    let file = &source_map[file_id];
+    let mut id_pool = DataPool::new();
+    let mut str_pool = DataPool::new();
 
-    val.apply_merge().unwrap();
+    let (tokens, lexer_errors) =
+        LexerIter::new(file.content(), file_id, &mut id_pool, &mut str_pool).collect_all();
 
-    let Ok(NazmYaml {
-        الاسم,
-        الإصدار,
-        المسارات,
-    }) = serde_yaml::from_value(val)
-    else {
-        print_err(format!(
-            "{}",
-            "ملف nazm.yaml يجب أن يحتوي على خاصية `المسارات` مع مسار ملف واحد على الأقل".bold(),
-        ));
-        exit(1)
-    };
+    if let Err(d) = check_lexer_errors(&tokens, file.content(), lexer_errors) {
+        exit_with_diagnostics(emitter, &source_map, d)
+    }
 
-    let mut collected_paths = Vec::new();
+    print_tokens(&[FileTokens::new(file, &tokens)], json);
+}
 
-    collect_paths(المسارات, "", &mut collected_paths);
+/// Checks the syntax of the file then prints its items
+fn parse_file(emitter: &dyn Emitter, path: &Path) {
+    let mut source_map = SourceMap::new();
+    let file_id = add_file(emitter, &mut source_map, path);
+    let file = &source_map[file_id];
+    let mut id_pool = DataPool::new();
+    let mut str_pool = DataPool::new();
 
-    if collected_paths.is_empty() {
-        print_err(format!(
-            "{}",
-            "ملف nazm.yaml يجب أن يحتوي على خاصية `المسارات` مع مسار ملف واحد على الأقل".bold(),
-        ));
-        exit(1)
+    let (tokens, lexer_errors) =
+        LexerIter::new(file.content(), file_id, &mut id_pool, &mut str_pool).collect_all();
+
+    let ast = match parse(tokens, file.content(), lexer_errors) {
+        Ok(ast) => ast,
+        Err(d) => exit_with_diagnostics(emitter, &source_map, d),
     };
 
-    // println!("الاسم: {}", الاسم.unwrap_or("".to_string()));
-    // println!("الإصدار: {}", الإصدار.unwrap_or("".to_string()));
-    // println!("المسارات:");
-    // for path in &collected_paths {
-    //     println!("\t{}", path);
-    // }
+    let id_pool = id_pool.build();
 
-    collected_paths
+    for item in &ast.items {
+        let kind = match &item.kind {
+            nazmc_ast::ItemKind::UnitStruct
+            | nazmc_ast::ItemKind::TupleStruct(_)
+            | nazmc_ast::ItemKind::FieldsStruct(_) => "هيكل",
+            nazmc_ast::ItemKind::Enum(_) => "تصنيف",
+            nazmc_ast::ItemKind::Const(_) => "ثابت",
+            nazmc_ast::ItemKind::Static(_) => "مشترك",
+            nazmc_ast::ItemKind::Fn(_) => "دالة",
+        };
+        let cursor = file.cursor(item.name.span.lo);
+        println!(
+            "{}:{}\t{} {}",
+            cursor.line + 1,
+            cursor.col + 1,
+            kind,
+            &id_pool[item.name.id]
+        );
+    }
 }
 
-fn main() {
-    // RTL printing
-    let output = Command::new("printf").arg(r#""\e[2 k""#).output().unwrap();
-    let output = &output.stdout[1..output.stdout.len() - 1];
-    io::stdout().write_all(output).unwrap();
-    io::stderr().write_all(output).unwrap();
+/// Returns the diagnostics of the phases after parsing until the first phase which has errors
+fn phases_diagnostics(session: &Session) -> Vec<Diagnostic> {
+    fn collect<T>(result: PhaseResult<T>, diagnostics: &mut Vec<Diagnostic>) -> Option<T> {
+        match result {
+            Ok((output, warnings)) => {
+                diagnostics.extend(warnings);
+                Some(output)
+            }
+            Err(errors) => {
+                diagnostics.extend(errors);
+                None
+            }
+        }
+    }
 
-    let files_paths = get_file_paths();
-    let mut id_pool = DataPool::new();
-    let mut str_pool = DataPool::new();
-    let mut packages = HashMap::new();
-    let mut parsed_files = vec![];
-    let mut packages_to_parsed_files = vec![];
-    let mut diagnostics: Vec<String> = vec![];
-    let mut fail_after_parsing = false;
+    let mut diagnostics = vec![];
 
-    // Register the main fn id to index 0 and the implicit lambda param name to index 1
-    id_pool.get("البداية");
-    id_pool.get("س");
+    let Some(nrt) = collect(session.resolve(), &mut diagnostics) else {
+        return diagnostics;
+    };
+    let Some(nir) = collect(session.build_nir(&nrt), &mut diagnostics) else {
+        return diagnostics;
+    };
+    let Some(types) = collect(session.typeck(&nir), &mut diagnostics) else {
+        return diagnostics;
+    };
+    if collect(session.borrowck(&nir, &types), &mut diagnostics).is_some() {
+        collect(session.lint(&nir), &mut diagnostics);
+    }
 
-    files_paths.into_iter().for_each(|file_path| {
-        let mut package_path = file_path
-            .split_terminator('/')
-            .map(|s| id_pool.get(s))
-            .collect::<ThinVec<_>>();
+    diagnostics
+}
 
-        package_path.pop(); // remove the actual file
+/// Applies the fixes of the diagnostics of the package files and prints their count for each file
+fn fix_files(emitter: &dyn Emitter, fixes: Vec<Fix>, has_errors: bool) {
+    let mut files_fixes: BTreeMap<String, Vec<Fix>> = BTreeMap::new();
+    for fix in fixes {
+        files_fixes
+            .entry(fix.file_path.clone())
+            .or_default()
+            .push(fix);
+    }
 
-        let package_idx = packages.len();
-        let package_idx = *packages.entry(package_path).or_insert(package_idx);
+    if files_fixes.is_empty() {
+        println!("{}", "لا توجد أخطاء يمكن إصلاحها تلقائيا".bold());
+    }
 
-        let path = format!("{file_path}.نظم");
-        let Ok(file_content) = fs::read_to_string(&path) else {
-            panic::set_hook(Box::new(|_| {}));
-            print_err(format!(
-                "{} {}{}",
-                "لا يمكن قراءة الملف".bold(),
-                path.bright_red().bold(),
-                " أو أنه غير موجود".bold()
-            ));
-            panic!()
-        };
+    for (path, fixes) in files_fixes {
+        let (fixed_content, applied) = apply_fixes(&read_file(emitter, Path::new(&path)), &fixes);
+
+        if fs::write(&path, fixed_content).is_err() {
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "لا يمكن الكتابة في الملف".bold(),
+                    path.bright_red().bold()
+                ),
+            );
+            exit(1)
+        }
+
+        println!(
+            "{} {} {}",
+            "تم إصلاح".bold(),
+            applied.bold().green(),
+            format!("من الأخطاء في الملف {path}").bold()
+        );
+    }
 
-        let (tokens, lines, lexer_errors) =
-            LexerIter::new(&file_content, &mut id_pool, &mut str_pool).collect_all();
+    if has_errors {
+        println!(
+            "{}",
+            "قد توجد أخطاء لا يمكن إصلاحها تلقائيا، نفّذ `nazmc check` لعرضها".bold()
+        );
+    }
+}
 
-        let ast = parse(tokens, &path, &file_content, &lines, lexer_errors);
+/// Formats the package files and writes the changed ones, or only prints them in the check mode
+/// then exits with a failure if there is any
+fn fmt_files(emitter: &dyn Emitter, files_paths: Vec<String>, check: bool) {
+    let mut has_errors = false;
+    let mut unformatted_files = 0;
 
-        match ast {
-            Ok(ast) => {
-                if package_idx >= packages_to_parsed_files.len() {
-                    packages_to_parsed_files.resize(package_idx + 1, vec![]);
-                }
+    for file_path in files_paths {
+        let path = format!("{file_path}.نظم");
+        let mut source_map = SourceMap::new();
+        let file_id = add_file(emitter, &mut source_map, Path::new(&path));
+        let file = &source_map[file_id];
+        let mut id_pool = DataPool::new();
+        let mut str_pool = DataPool::new();
 
-                let file_idx = parsed_files.len();
-                parsed_files.push(nazmc_resolve::ParsedFile { path, lines, ast });
-                packages_to_parsed_files[package_idx].push(file_idx);
-            }
+        let (tokens, lexer_errors) =
+            LexerIter::new(file.content(), file_id, &mut id_pool, &mut str_pool).collect_all();
+
+        let formatted = match format_file(&tokens, &path, file.content(), lexer_errors) {
+            Ok(formatted) => formatted,
             Err(d) => {
-                diagnostics.push(d);
-                fail_after_parsing = true;
+                eprint_diagnostics(emitter, &source_map, d);
+                has_errors = true;
+                continue;
             }
+        };
+
+        if formatted == file.content() {
+            continue;
         }
-    });
-
-    if fail_after_parsing {
-        let last_idx = diagnostics.len() - 1;
-        for (i, d) in diagnostics.iter().enumerate() {
-            eprint!("{d}");
-            if i != last_idx {
-                eprintln!();
-            }
+
+        unformatted_files += 1;
+
+        if check {
+            println!("{} {}", "الملف غير مُنسّق:".bold(), path.bright_red().bold());
+        } else if fs::write(&path, formatted).is_err() {
+            print_err(
+                emitter,
+                format!(
+                    "{} {}",
+                    "لا يمكن الكتابة في الملف".bold(),
+                    path.bright_red().bold()
+                ),
+            );
+            exit(1)
+        } else {
+            println!("{} {}", "تم تنسيق الملف".bold(), path.green().bold());
         }
+    }
+
+    if has_errors || check && unformatted_files > 0 {
         exit(1)
     }
+}
 
-    let id_pool = id_pool.build();
-    let str_pool = str_pool.build();
+fn main() {
+    let cli = cli::cli().run();
 
-    let mut packages_names = ThinVec::with_capacity(packages.len());
-    for (pkg, idx) in &packages {
-        if *idx >= packages_names.len() {
-            for _ in packages_names.len()..=*idx {
-                packages_names.push(ThinVec::default());
-            }
+    // The numerals of the manifest are not known before reading it
+    let emitter = &*cli.error_format.emitter(cli.numerals.unwrap_or_default());
+
+    // RTL printing of the diagnostics, it is only printed to terminals
+    // to keep the piped output and the JSON diagnostics valid
+    if cli.error_format == ErrorFormat::Human && io::stderr().is_terminal() {
+        let output = Command::new("printf").arg(r#""\e[2 k""#).output().unwrap();
+        let output = &output.stdout[1..output.stdout.len() - 1];
+        io::stderr().write_all(output).unwrap();
+    }
+
+    let (manifest_path, emit, json, lint_options) = match &cli.subcommand {
+        Subcommand::Lex { file, json } => return lex_file(emitter, file, *json),
+        Subcommand::Parse { file } => return parse_file(emitter, file),
+        Subcommand::Explain { code } => return explain(emitter, *code),
+        Subcommand::Fmt {
+            manifest_path,
+            check,
+        } => {
+            let manifest = read_manifest(emitter, manifest_path.as_deref());
+            let numerals = cli.numerals.or(manifest.numerals).unwrap_or_default();
+            let emitter = &*cli.error_format.emitter(numerals);
+            return fmt_files(emitter, manifest.files_paths, *check);
+        }
+        Subcommand::Check {
+            manifest_path,
+            emit,
+            json,
+            lint_options,
+        } => (manifest_path.as_deref(), *emit, *json, Some(lint_options)),
+        Subcommand::Run {
+            manifest_path,
+            lint_options,
         }
-        packages_names[*idx] = pkg.clone();
+        | Subcommand::Build {
+            manifest_path,
+            lint_options,
+        } => (manifest_path.as_deref(), None, false, Some(lint_options)),
+        Subcommand::Fix { manifest_path } => (manifest_path.as_deref(), None, false, None),
+    };
+
+    let Manifest {
+        name: package_name,
+        files_paths,
+        mut lint_levels,
+        numerals,
+    } = read_manifest(emitter, manifest_path);
+
+    // The command line option overrides the manifest
+    let numerals = cli.numerals.or(numerals).unwrap_or_default();
+    let emitter = &*cli.error_format.emitter(numerals);
+
+    if let Some(lint_options) = lint_options {
+        lint_options.apply(&mut lint_levels);
+    }
+
+    let source_map = match read_sources(Path::new(""), &files_paths) {
+        Ok(source_map) => source_map,
+        Err(d) => exit_with_diagnostics(emitter, &SourceMap::new(), d),
+    };
+
+    if emit == Some(Emit::Tokens) {
+        let mut id_pool = DataPool::new();
+        let mut str_pool = DataPool::new();
+        let mut diagnostics = vec![];
+        let files_tokens = source_map
+            .files()
+            .map(|(file_id, file)| {
+                let (tokens, lexer_errors) =
+                    LexerIter::new(file.content(), file_id, &mut id_pool, &mut str_pool)
+                        .collect_all();
+                if let Err(d) = check_lexer_errors(&tokens, file.content(), lexer_errors) {
+                    diagnostics.extend(d);
+                }
+                FileTokens::new(file, &tokens)
+            })
+            .collect::<Vec<_>>();
+
+        if !diagnostics.is_empty() {
+            exit_with_diagnostics(emitter, &source_map, diagnostics)
+        }
+
+        print_tokens(&files_tokens, json);
+        return;
     }
 
-    let resolver = nazmc_resolve::NameResolver::new(
-        &id_pool,
-        &packages,
-        &packages_names,
-        &packages_to_parsed_files,
-        &parsed_files,
+    let (session, diagnostics) = Session::new(
+        &source_map,
+        Config {
+            lint_levels,
+            numerals,
+        },
     );
 
-    let mut nrt = resolver.resolve();
+    if let Subcommand::Fix { .. } = cli.subcommand {
+        let diagnostics = if diagnostics.is_empty() {
+            phases_diagnostics(&session)
+        } else {
+            diagnostics
+        };
+        let has_errors = diagnostics.iter().any(Diagnostic::is_error);
+        return fix_files(
+            emitter,
+            collect_fixes(&source_map, &diagnostics),
+            has_errors,
+        );
+    }
 
-    // let (file_path, file_content) = cli::read_file();
+    if !diagnostics.is_empty() {
+        exit_with_diagnostics(emitter, &source_map, diagnostics)
+    }
+
+    if emit == Some(Emit::Ast) {
+        let dumper = ASTDumper {
+            id_pool: &session.id_pool,
+            str_pool: &session.str_pool,
+        };
+        let files = session
+            .parsed_files
+            .iter()
+            .map(|file| dumper.file(&file.path, &file.ast))
+            .collect::<Vec<_>>();
+        print_nodes(&source_map, files, json);
+        return;
+    }
 
-    // nazmc_parser::parse_file(&file_path, &file_content, &mut id_pool, &mut str_pool);
+    let nrt = phase_output(emitter, &source_map, session.resolve());
 
-    // for Token { span, val, kind } in tokens {
-    //     let color = match kind {
-    //         TokenKind::LineComment | TokenKind::DelimitedComment => XtermColors::BrightTurquoise,
-    //         TokenKind::Symbol(_) => XtermColors::UserBrightYellow,
-    //         TokenKind::Id => XtermColors::LightAnakiwaBlue,
-    //         TokenKind::Keyword(_) | TokenKind::Literal(LiteralKind::Bool(_)) => {
-    //             XtermColors::FlushOrange
-    //         }
-    //         TokenKind::Literal(LiteralKind::Str(_) | LiteralKind::Char(_)) => {
-    //             XtermColors::PinkSalmon
-    //         }
-    //         TokenKind::Literal(_) => XtermColors::ChelseaCucumber,
-    //         _ => XtermColors::UserWhite,
-    //     };
+    let nir = phase_output(emitter, &source_map, session.build_nir(&nrt));
+
+    if emit == Some(Emit::Nir) {
+        let dumper = NIRDumper {
+            id_pool: &session.id_pool,
+            str_pool: &session.str_pool,
+            nir: &nir,
+        };
+        print_nodes(&source_map, vec![dumper.dump()], json);
+        return;
+    }
 
-    //     let mut val = format!("{}", val.color(color));
+    let types = phase_output(emitter, &source_map, session.typeck(&nir));
 
-    //     if matches!(kind, TokenKind::Keyword(_) | TokenKind::Symbol(_)) {
-    //         val = format!("{}", val.bold());
-    //     }
+    phase_output(emitter, &source_map, session.borrowck(&nir, &types));
 
-    //     print!("{}", val);
-    // }
+    phase_output(emitter, &source_map, session.lint(&nir));
+
+    match cli.subcommand {
+        Subcommand::Run { .. } => {
+            if let Err(err) = session.run(&nir, &types) {
+                let exit_code = err.exit_code();
+                eprint_diagnostics(emitter, &source_map, vec![err.into_diagnostic()]);
+                exit(exit_code)
+            }
+        }
+        Subcommand::Build { .. } => {
+            let c_code = match session.generate_c(&nir, &types) {
+                Ok(c_code) => c_code,
+                Err(d) => exit_with_diagnostics(emitter, &source_map, d),
+            };
+            let package_name = package_name.unwrap_or_else(|| "برنامج".to_string());
+            build_c(emitter, &package_name, c_code);
+        }
+        Subcommand::Check { .. } => {}
+        Subcommand::Fix { .. }
+        | Subcommand::Fmt { .. }
+        | Subcommand::Lex { .. }
+        | Subcommand::Parse { .. }
+        | Subcommand::Explain { .. } => {
+            unreachable!()
+        }
+    }
 }
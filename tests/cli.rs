@@ -0,0 +1,560 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Creates a package with the given name in a new temporary directory which contains
+/// the manifest and the main file with the given content, and returns the directory path
+fn package(name: &str, content: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("nazmc-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("nazm.yaml"),
+        format!("الاسم: {}\nالمسارات:\n  - رئيسي\n", name),
+    )
+    .unwrap();
+    fs::write(dir.join("رئيسي.نظم"), content).unwrap();
+    dir
+}
+
+fn nazmc(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .args(args)
+        .arg("--manifest-path")
+        .arg(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_built_program_prints_like_the_interpreter() {
+    let dir = package(
+        "إرجاع",
+        "هيكل نقطة(ص4، ص4)\n\n\
+         تصنيف رسالة { خروج، كتابة(#متن)، }\n\n\
+         دالة تحية(ن: ص4): #متن {\n    لو ن > 0 {\n        أرجع \"مرحبا\"؛\n    }\n    أرجع \"وداعا\"؛\n}\n\n\
+         دالة نقطة_من(ن: ص4): نقطة {\n    أرجع .نقطة(ن، ن * 2)؛\n}\n\n\
+         دالة رسالة_من(ن: ص4): رسالة {\n    لو ن == 0 {\n        أرجع .رسالة::خروج؛\n    }\n    أرجع .رسالة::كتابة(\"نص\")؛\n}\n\n\
+         دالة مضاعف(ن: ص4): (ص4) -> ص4 {\n    أرجع { س * ن }؛\n}\n\n\
+         دالة البداية() {\n    اظهر_(تحية(1))؛\n    اظهر_(تحية(-1))؛\n    اظهر_(نقطة_من(3))؛\n    \
+         اظهر_(رسالة_من(0))؛\n    اظهر_(رسالة_من(1))؛\n    اظهر_(مضاعف(3)(5))؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert!(run.status.success());
+    assert_eq!(
+        String::from_utf8(run.stdout.clone()).unwrap(),
+        "مرحبا\nوداعا\nنقطة(3، 6)\nرسالة::خروج\nرسالة::كتابة(نص)\n15\n"
+    );
+
+    let build = nazmc(&["build"], &dir);
+    assert!(build.status.success());
+    let built = Command::new(dir.join("build").join("إرجاع"))
+        .output()
+        .unwrap();
+    assert!(built.status.success());
+    assert_eq!(built.stdout, run.stdout);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_min_values_of_signed_ints_are_printed_like_the_interpreter() {
+    let dir = package(
+        "أصغر_قيمة",
+        "دالة البداية() {\n    احجز أ: ص1 = -128؛\n    احجز ب: ص8 = -9223372036854775808؛\n    \
+         اظهر_(أ)؛\n    اظهر_(ب)؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert!(run.status.success());
+    assert_eq!(
+        String::from_utf8(run.stdout.clone()).unwrap(),
+        "-128\n-9223372036854775808\n"
+    );
+
+    let build = nazmc(&["build"], &dir);
+    assert!(build.status.success());
+    let built = Command::new(dir.join("build").join("أصغر_قيمة"))
+        .output()
+        .unwrap();
+    assert!(built.status.success());
+    assert_eq!(built.stdout, run.stdout);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fix_applies_the_machine_applicable_suggestions() {
+    let dir = package(
+        "إصلاح",
+        "دالة البداية() {\n    احجز أ = 1;\n    اظهر_(أ)؛\n}\n",
+    );
+
+    let fix = nazmc(&["fix"], &dir);
+    assert!(fix.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("رئيسي.نظم")).unwrap(),
+        "دالة البداية() {\n    احجز أ = 1؛\n    اظهر_(أ)؛\n}\n"
+    );
+    assert!(nazmc(&["check"], &dir).status.success());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fix_keeps_the_numbers_of_the_arguments_lists() {
+    let dir = package(
+        "وسائط",
+        "دالة جمع(أ: ص4، ب: ص4): ص4 { أ + ب }\n\n\
+         دالة البداية() {\n    اظهر_(جمع(1, 2))؛\n    اظهر_(جمع(1,000، 2))؛\n}\n",
+    );
+
+    assert!(nazmc(&["fix"], &dir).status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("رئيسي.نظم")).unwrap(),
+        "دالة جمع(أ: ص4، ب: ص4): ص4 { أ + ب }\n\n\
+         دالة البداية() {\n    اظهر_(جمع(1، 2))؛\n    اظهر_(جمع(1,000، 2))؛\n}\n"
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert_eq!(String::from_utf8(run.stdout).unwrap(), "3\n1002\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_numerals_option_overrides_the_manifest() {
+    let dir = package(
+        "أرقام",
+        "دالة البداية() {\n    اظهر_(12)؛\n    اظهر_(2.5)؛\n}\n",
+    );
+    let manifest = dir.join("nazm.yaml");
+    let content = fs::read_to_string(&manifest).unwrap();
+    fs::write(&manifest, content + "الأرقام: مشرقية\n").unwrap();
+
+    let run = nazmc(&["run"], &dir);
+    assert_eq!(String::from_utf8(run.stdout).unwrap(), "١٢\n٢٫٥\n");
+
+    let run = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .args(["--numerals", "لاتينية", "run", "--manifest-path"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(run.stdout).unwrap(), "12\n2.5\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_when_runs_the_first_matching_arm() {
+    let dir = package(
+        "عندما",
+        "تصنيف شكل { نقطة، دائرة(ص4)، مستطيل { عرض: ص4، طول: ص4 }، }\n\n\
+         دالة وصف(ش: شكل): #متن {\n    أرجع عندما ش {\n        \
+         .شكل::نقطة -> \"نقطة\"،\n        \
+         .شكل::دائرة(0) -> \"دائرة فارغة\"،\n        \
+         .شكل::دائرة(ن) لو ن > 10 -> \"دائرة كبيرة\"،\n        \
+         .شكل::مستطيل { طول = 1..5، عرض } -> { اظهر_(عرض)؛ \"مستطيل قصير\" }،\n        \
+         _ -> \"شكل آخر\"،\n    }؛\n}\n\n\
+         دالة مربع(ن: ص4): ص4 { ن * ن }\n\n\
+         دالة البداية() {\n    اظهر_(وصف(.شكل::نقطة))؛\n    اظهر_(وصف(.شكل::دائرة(0)))؛\n    \
+         اظهر_(وصف(.شكل::دائرة(20)))؛\n    اظهر_(وصف(.شكل::دائرة(5)))؛\n    \
+         اظهر_(وصف(.شكل::مستطيل { عرض = 7، طول = 3 }))؛\n    \
+         اظهر_(عندما مربع(3) { 9 -> \"تسعة\"، _ -> \"غير ذلك\" })؛\n    \
+         اظهر_(عندما (1، مربع(2)) { (1، ن) -> ن، _ -> 0 })؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert!(run.status.success());
+    assert_eq!(
+        String::from_utf8(run.stdout.clone()).unwrap(),
+        "نقطة\nدائرة فارغة\nدائرة كبيرة\nشكل آخر\n7\nمستطيل قصير\nتسعة\n4\n"
+    );
+
+    let build = nazmc(&["build"], &dir);
+    assert!(build.status.success());
+    let built = Command::new(dir.join("build").join("عندما"))
+        .output()
+        .unwrap();
+    assert_eq!(built.stdout, run.stdout);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_do_while_runs_its_block_before_checking_its_condition() {
+    let dir = package(
+        "افعل",
+        "دالة البداية() {\n    احجز متغير م = 0؛\n    افعل {\n        م += 1؛\n        \
+         لو م == 2 {\n            وصل؛\n        }\n        اظهر_(م)؛\n    } طالما م < 4؛\n\n    \
+         افعل {\n        اظهر_(\"مرة\")؛\n    } طالما فاسد؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert!(run.status.success());
+    assert_eq!(
+        String::from_utf8(run.stdout.clone()).unwrap(),
+        "1\n3\n4\nمرة\n"
+    );
+
+    let build = nazmc(&["build"], &dir);
+    assert!(build.status.success());
+    let built = Command::new(dir.join("build").join("افعل"))
+        .output()
+        .unwrap();
+    assert!(built.status.success());
+    assert_eq!(built.stdout, run.stdout);
+
+    fs::write(
+        dir.join("رئيسي.نظم"),
+        "دالة البداية() {\n    افعل { س -> اظهر_(س)؛ } طالما صحيح؛\n    افعل {} 1؛\n}\n",
+    )
+    .unwrap();
+    let check = nazmc(&["check"], &dir);
+    assert!(!check.status.success());
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    assert!(stderr.contains("يُتوقع محتوى `افعل` وليس مُعاملات لامدا"));
+    assert!(stderr.contains("رئيسي.نظم:2:12"));
+    assert!(stderr.contains("يُتوقع `طالما` بعد محتوى `افعل`، ولكن تم العثور على `1`"));
+
+    fs::write(
+        dir.join("رئيسي.نظم"),
+        "دالة البداية() {\n    افعل {} طالما 1؛\n}\n",
+    )
+    .unwrap();
+    let check = nazmc(&["check"], &dir);
+    assert!(!check.status.success());
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    assert!(stderr.contains("يُتوقع النوع `شرط`، ولكن تم العثور على النوع `{عدد صحيح}`"));
+    assert!(stderr.contains("رئيسي.نظم:2:19"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_run_executes_the_program_from_its_start() {
+    let dir = package(
+        "تشغيل",
+        "هيكل شخص { الاسم: #متن، العمر: ص4، }\n\n\
+         دالة البداية() {\n    احجز متغير مجموع = 0؛\n    احجز متغير م = 0؛\n    \
+         طالما صحيح {\n        م += 1؛\n        لو م % 2 == 0 {\n            وصل؛\n        }\n        \
+         لو م > 9 {\n            قطع؛\n        }\n        مجموع += م؛\n    }\n    اظهر_(مجموع)؛\n\n    \
+         احجز ش = .شخص { الاسم = \"علي\"، العمر = 30 }؛\n    اظهر_(ش.الاسم)؛\n    اظهر_(ش.العمر + 1)؛\n    \
+         اظهر_((1.5 * 2.0، 'ب'، !فاسد))؛\n\n    احجز أ = [10، 20، 30]؛\n    اظهر_(أ[1])؛\n\n    \
+         احجز ضعف: (ص4) -> ص4 = { س * 2 }؛\n    اظهر_(ضعف(21))؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert!(run.status.success());
+    assert_eq!(
+        String::from_utf8(run.stdout).unwrap(),
+        "25\nعلي\n31\n(3.0، ب، صحيح)\n20\n42\n"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_run_reports_the_runtime_errors() {
+    let dir = package(
+        "قسمة",
+        "دالة البداية() {\n    اظهر_(1)؛\n    احجز م = 0؛\n    اظهر_(10 / م)؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert_eq!(run.status.code(), Some(101));
+    assert_eq!(String::from_utf8(run.stdout).unwrap(), "1\n");
+    let stderr = String::from_utf8(run.stderr).unwrap();
+    assert!(stderr.contains("لا يمكن القسمة على صفر"));
+    assert!(stderr.contains("رئيسي.نظم:4:11"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_run_reports_the_stack_overflow() {
+    let dir = package(
+        "عودية",
+        "دالة عد(ن: ص4): ص4 {\n    لو ن == 0 {\n        أرجع 0؛\n    }\n    أرجع عد(ن - 1) + 1؛\n}\n\n\
+         دالة البداية() {\n    اظهر_(عد(500))؛\n    اظهر_(عد(100000))؛\n}\n",
+    );
+
+    let run = nazmc(&["run"], &dir);
+    assert_eq!(run.status.code(), Some(101));
+    assert_eq!(String::from_utf8(run.stdout).unwrap(), "500\n");
+    let stderr = String::from_utf8(run.stderr).unwrap();
+    assert!(stderr.contains("طفح المكدس، تجاوز عدد الاستدعاءات المتداخلة 1000 استدعاء"));
+    assert!(stderr.contains("رئيسي.نظم:5:10"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_deny_warnings_fails_on_the_lints_warnings() {
+    let dir = package("تحذيرات", "دالة البداية() {\n    احجز ل = 1؛\n}\n");
+
+    let check = nazmc(&["check"], &dir);
+    assert!(check.status.success());
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    assert!(stderr.lines().next().unwrap().contains("تحذير"));
+    assert!(stderr.contains("لم يتم استخدام `ل`"));
+
+    let check = nazmc(&["check", "--deny-warnings"], &dir);
+    assert!(!check.status.success());
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    assert!(stderr.lines().next().unwrap().contains("خطأ"));
+    assert!(stderr.contains("لم يتم استخدام `ل`"));
+    assert!(stderr.contains("هذا الخطأ بسبب منع التحذير `ربط_غير_مستخدم`"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_check_emits_the_intermediate_representations() {
+    let dir = package(
+        "إخراج",
+        "دالة البداية() {\n    احجز أ = 1 + 2؛\n    اظهر_(أ)؛\n}\n",
+    );
+
+    let tokens = nazmc(&["check", "--emit", "tokens"], &dir);
+    assert!(tokens.status.success());
+    let tokens = String::from_utf8(tokens.stdout).unwrap();
+    assert!(tokens.starts_with("رئيسي.نظم:\n  1:1-1:5\t0..8\tKeyword(Fn)\t\"دالة\"\n"));
+    assert!(tokens.contains("  2:16-2:17\t48..49\tSymbol(Plus)\t\"+\"\n"));
+
+    let ast = nazmc(&["check", "--emit", "ast"], &dir);
+    assert!(ast.status.success());
+    assert_eq!(
+        String::from_utf8(ast.stdout).unwrap(),
+        "File رئيسي.نظم\n  \
+         item: Fn(Default) البداية @1:6-1:13\n    \
+         return_type: UnitType\n    \
+         body: Scope\n      \
+         stm: Let\n        \
+         binding: Binding\n          \
+         kind: Id أ @2:10-2:11\n        \
+         assign: BinaryOp(Plus) @2:14-2:19\n          \
+         left: Literal UnspecifiedInt(1) @2:14-2:15\n          \
+         right: Literal UnspecifiedInt(2) @2:18-2:19\n      \
+         stm: Call @3:5-3:13\n        \
+         on: Path اظهر_ @3:5-3:10\n        \
+         arg: Path أ @3:11-3:12\n"
+    );
+
+    let nir = nazmc(&["check", "--emit", "nir"], &dir);
+    assert!(nir.status.success());
+    let nir = String::from_utf8(nir.stdout).unwrap();
+    assert!(nir.contains("assign: BinaryOp(Plus) exprs.bin_exprs[0] @2:14-2:19\n"));
+    assert!(nir.contains("resolved: Builtin(PrintLn)\n"));
+
+    let json = nazmc(&["check", "--emit", "nir", "--json"], &dir);
+    assert!(json.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&json.stdout).unwrap();
+    let item = &json[0]["children"][0];
+    assert_eq!(item["name"], "البداية");
+    assert_eq!(item["arena"], "fns");
+    assert_eq!(item["index"], 0);
+
+    let unknown = nazmc(&["check", "--emit", "hir"], &dir);
+    assert!(!unknown.status.success());
+
+    fs::write(
+        dir.join("رئيسي.نظم"),
+        "دالة البداية() {\n    احجز أ = \"نص؛\n}\n",
+    )
+    .unwrap();
+    let tokens = nazmc(&["check", "--emit", "tokens"], &dir);
+    assert_eq!(tokens.status.code(), Some(1));
+    assert!(tokens.stdout.is_empty());
+    let stderr = String::from_utf8(tokens.stderr).unwrap();
+    assert!(stderr.contains("علامة تنصيص مفقودة"));
+    assert!(stderr.contains("رئيسي.نظم:2:18"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_explain_prints_the_explanations_of_the_error_codes() {
+    let dir = package("شرح", "استيراد حساب::اضرب؛\n\nدالة البداية() {}\n");
+
+    let check = nazmc(&["check"], &dir);
+    assert!(!check.status.success());
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    assert!(stderr.contains("لمعرفة المزيد عن هذا الخطأ نفّذ `nazmc explain 302`"));
+
+    let explain = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .args(["explain", "302"])
+        .output()
+        .unwrap();
+    assert!(explain.status.success());
+    let explanation = String::from_utf8(explain.stdout).unwrap();
+    assert!(explanation.starts_with("لم يتم العثور على الاسم في المسار.\n"));
+    assert!(explanation.contains("مثال على الخطأ:"));
+    assert!(explanation.contains("التصحيح:"));
+
+    let explain = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .args(["--error-format", "json", "explain", "9999"])
+        .output()
+        .unwrap();
+    assert_eq!(explain.status.code(), Some(1));
+    let err: serde_json::Value = serde_json::from_slice(&explain.stderr).unwrap();
+    assert_eq!(err["message"], "لا يوجد شرح لرمز الخطأ 9999");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fmt_formats_the_files_and_checks_them() {
+    let unformatted = "دالة البداية(){\n// تعليق\nاحجز أ=1+2؛\n    اظهر_( أ )؛ /* آخر */\n}\n";
+    let formatted =
+        "دالة البداية() {\n    // تعليق\n    احجز أ = 1 + 2؛\n    اظهر_(أ)؛ /* آخر */\n}\n";
+    let dir = package("تنسيق", unformatted);
+    let main = dir.join("رئيسي.نظم");
+
+    let check = nazmc(&["fmt", "--check"], &dir);
+    assert_eq!(check.status.code(), Some(1));
+    assert!(String::from_utf8(check.stdout)
+        .unwrap()
+        .contains("رئيسي.نظم"));
+    assert_eq!(fs::read_to_string(&main).unwrap(), unformatted);
+
+    assert!(nazmc(&["fmt"], &dir).status.success());
+    assert_eq!(fs::read_to_string(&main).unwrap(), formatted);
+
+    assert!(nazmc(&["fmt", "--check"], &dir).status.success());
+    assert!(nazmc(&["fmt"], &dir).status.success());
+    assert_eq!(fs::read_to_string(&main).unwrap(), formatted);
+
+    let invalid = "دالة البداية( {\n}\n";
+    fs::write(&main, invalid).unwrap();
+    assert!(!nazmc(&["fmt"], &dir).status.success());
+    assert_eq!(fs::read_to_string(&main).unwrap(), invalid);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_lex_prints_the_tokens_of_the_file() {
+    let dir = package("تقطيع", "دالة البداية() {}\n");
+    let main = dir.join("رئيسي.نظم");
+
+    let lex = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .arg("lex")
+        .arg(&main)
+        .output()
+        .unwrap();
+    assert!(lex.status.success());
+    let tokens = String::from_utf8(lex.stdout).unwrap();
+    assert!(tokens.contains("1:1-1:5\t0..8\tKeyword(Fn)\t\"دالة\""));
+    assert!(tokens.contains("1:16-1:17\t26..27\tSymbol(OpenCurlyBrace)\t\"{\""));
+
+    let lex = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .args(["lex", "--json"])
+        .arg(&main)
+        .output()
+        .unwrap();
+    assert!(lex.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&lex.stdout).unwrap();
+    let first = &json[0]["tokens"][0];
+    assert_eq!(first["kind"], "Keyword(Fn)");
+    assert_eq!(first["text"], "دالة");
+    assert_eq!(first["bytes"], serde_json::json!([0, 8]));
+
+    let txt = dir.join("رئيسي.txt");
+    fs::copy(&main, &txt).unwrap();
+    let lex = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .arg("lex")
+        .arg(&txt)
+        .output()
+        .unwrap();
+    assert_eq!(lex.status.code(), Some(1));
+    assert!(String::from_utf8(lex.stderr)
+        .unwrap()
+        .contains("يُتوقع ملف بامتداد"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_parse_prints_the_items_of_the_file() {
+    let dir = package("تحليل", "هيكل نقطة(ص4، ص4)\n\nدالة البداية() {}\n");
+    let main = dir.join("رئيسي.نظم");
+
+    let parse = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .arg("parse")
+        .arg(&main)
+        .output()
+        .unwrap();
+    assert!(parse.status.success());
+    assert_eq!(
+        String::from_utf8(parse.stdout).unwrap(),
+        "1:6\tهيكل نقطة\n3:6\tدالة البداية\n"
+    );
+
+    fs::write(&main, "دالة أ( {}\n").unwrap();
+    let parse = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .arg("parse")
+        .arg(&main)
+        .output()
+        .unwrap();
+    assert_eq!(parse.status.code(), Some(1));
+    let stderr = String::from_utf8(parse.stderr).unwrap();
+    assert!(stderr.contains("يُتوقع مُعامِل دالة، ولكن تم العثور على `{`"));
+    assert!(stderr.contains("رئيسي.نظم:1:9"));
+
+    let parse = Command::new(env!("CARGO_BIN_EXE_nazmc"))
+        .arg("parse")
+        .arg(dir.join("nazm.yaml"))
+        .output()
+        .unwrap();
+    assert_eq!(parse.status.code(), Some(1));
+    assert!(String::from_utf8(parse.stderr)
+        .unwrap()
+        .contains("يُتوقع ملف بامتداد"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_json_error_format_prints_one_diagnostic_per_line_to_stderr() {
+    let dir = package("جيسون", "دالة البداية() {\n    احجز أ = \"نص؛\n}\n");
+
+    let check = nazmc(&["--error-format", "json", "check"], &dir);
+    assert_eq!(check.status.code(), Some(1));
+    assert!(check.stdout.is_empty());
+
+    let stderr = String::from_utf8(check.stderr).unwrap();
+    let diagnostics: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(diagnostics.len(), 2);
+
+    let missing_quote = &diagnostics[0];
+    assert_eq!(missing_quote["code"], 101);
+    assert_eq!(missing_quote["level"], "error");
+    assert_eq!(missing_quote["message"], "علامة تنصيص مفقودة");
+    let marks = &missing_quote["code_windows"][0]["marks"];
+    assert_eq!(marks[0]["kind"], "error");
+    assert_eq!(marks[1]["kind"], "secondary");
+    assert_eq!(marks[1]["span"]["start"], serde_json::json!([2, 14]));
+    assert_eq!(marks[1]["span"]["bytes"], serde_json::json!([46, 47]));
+    let suggestion = &missing_quote["suggestions"][0];
+    assert_eq!(suggestion["replacement"], "\"");
+    assert_eq!(suggestion["span"]["bytes"], serde_json::json!([53, 53]));
+    assert_eq!(suggestion["applicability"], "machine-applicable");
+
+    let missing_semicolon = &diagnostics[1];
+    assert_eq!(missing_semicolon["code"], 202);
+    assert_eq!(missing_semicolon["level"], "error");
+    assert_eq!(missing_semicolon["suggestions"][0]["replacement"], "؛");
+    assert_eq!(
+        missing_semicolon["suggestions"][0]["applicability"],
+        "maybe-incorrect"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}